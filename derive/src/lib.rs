@@ -0,0 +1,104 @@
+//! Derive macros for Plonky2.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields};
+
+/// Derives `CircuitVariable` for a struct with named fields, each of which must itself implement
+/// `CircuitVariable`.
+///
+/// For a struct `Foo`, this also generates a `FooValue<F>` struct holding the native values of the
+/// fields, and a `FooLayout` struct holding the layouts needed to allocate them.
+#[proc_macro_derive(CircuitVariable)]
+pub fn derive_circuit_variable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_circuit_variable(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_circuit_variable(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+    let vis = &input.vis;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "CircuitVariable cannot be derived for generic structs",
+        ));
+    }
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) if !fields.named.is_empty() => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    Span::call_site(),
+                    "CircuitVariable can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "CircuitVariable can only be derived for structs",
+            ))
+        }
+    };
+
+    let value_name = format_ident!("{}Value", name);
+    let layout_name = format_ident!("{}Layout", name);
+    let idents = fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let types = fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
+    let field_vis = fields.iter().map(|f| &f.vis).collect::<Vec<_>>();
+
+    let krate = quote!(::plonky2);
+    let variable = quote!(#krate::iop::variable::CircuitVariable);
+    let rich_field = quote!(#krate::hash::hash_types::RichField);
+
+    Ok(quote! {
+        #[derive(Clone, Debug, PartialEq)]
+        #vis struct #value_name<F: #rich_field> {
+            #( #field_vis #idents: <#types as #variable>::Value<F>, )*
+        }
+
+        #[derive(Clone, Debug, Default)]
+        #vis struct #layout_name {
+            #( #field_vis #idents: <#types as #variable>::Layout, )*
+        }
+
+        impl #variable for #name {
+            type Value<F: #rich_field> = #value_name<F>;
+            type Layout = #layout_name;
+
+            fn add_virtual<F: #rich_field + #krate::field::extension::Extendable<D>, const D: usize>(
+                builder: &mut #krate::plonk::circuit_builder::CircuitBuilder<F, D>,
+                layout: &Self::Layout,
+            ) -> Self {
+                Self {
+                    #( #idents: <#types as #variable>::add_virtual(builder, &layout.#idents), )*
+                }
+            }
+
+            fn targets(&self) -> #krate::iop::variable::Vec<#krate::iop::target::Target> {
+                [ #( #variable::targets(&self.#idents), )* ].concat()
+            }
+
+            fn set<F: #rich_field, W: #krate::iop::witness::WitnessWrite<F>>(
+                &self,
+                witness: &mut W,
+                value: &Self::Value<F>,
+            ) {
+                #( #variable::set(&self.#idents, witness, &value.#idents); )*
+            }
+
+            fn get<F: #rich_field, W: #krate::iop::witness::Witness<F>>(
+                &self,
+                witness: &W,
+            ) -> Self::Value<F> {
+                #value_name {
+                    #( #idents: #variable::get(&self.#idents, witness), )*
+                }
+            }
+        }
+    })
+}
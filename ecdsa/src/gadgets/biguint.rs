@@ -5,13 +5,17 @@ use core::marker::PhantomData;
 use num::{BigUint, Integer, Zero};
 use plonky2::field::extension::Extendable;
 use plonky2::field::types::{PrimeField, PrimeField64};
+use plonky2::gates::gate::Gate;
 use plonky2::hash::hash_types::RichField;
 use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
 use plonky2::iop::target::{BoolTarget, Target};
-use plonky2::iop::witness::{PartitionWitness, Witness};
+use plonky2::iop::variable::CircuitVariable;
+use plonky2::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use plonky2::plonk::circuit_builder::CircuitBuilder;
 use plonky2_u32::gadgets::arithmetic_u32::{CircuitBuilderU32, U32Target};
 use plonky2_u32::gadgets::multiple_comparison::list_le_u32_circuit;
+use plonky2_u32::gadgets::range_check::range_check_u32_circuit;
+use plonky2_u32::gates::range_check_u32::U32RangeCheckGate;
 use plonky2_u32::witness::{GeneratedValuesU32, WitnessU32};
 
 #[derive(Clone, Debug)]
@@ -29,6 +33,43 @@ impl BigUintTarget {
     }
 }
 
+impl CircuitVariable for BigUintTarget {
+    type Value<F: RichField> = BigUint;
+    /// The number of limbs.
+    type Layout = usize;
+
+    fn add_virtual<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        num_limbs: &usize,
+    ) -> Self {
+        let x = builder.add_virtual_biguint_target(*num_limbs);
+        // Each `U32RangeCheckGate` checks as many limbs as its wires allow.
+        let limbs_per_gate =
+            builder.config.num_wires / U32RangeCheckGate::<F, D>::new(1).num_wires();
+        for limbs in x.limbs.chunks(limbs_per_gate) {
+            range_check_u32_circuit(builder, limbs.to_vec());
+        }
+        x
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        self.limbs.iter().map(|limb| limb.0).collect()
+    }
+
+    fn set<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: &BigUint) {
+        let mut limbs = value.to_u32_digits();
+        assert!(self.num_limbs() >= limbs.len());
+        limbs.resize(self.num_limbs(), 0);
+        for (target, limb) in self.limbs.iter().zip(limbs) {
+            target.set(witness, &limb);
+        }
+    }
+
+    fn get<F: RichField, W: Witness<F>>(&self, witness: &W) -> BigUint {
+        witness.get_biguint_target(self.clone())
+    }
+}
+
 pub trait CircuitBuilderBiguint<F: RichField + Extendable<D>, const D: usize> {
     fn constant_biguint(&mut self, value: &BigUint) -> BigUintTarget;
 
@@ -348,14 +389,22 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F>
 mod tests {
     use anyhow::Result;
     use num::{BigUint, FromPrimitive, Integer};
+    use plonky2::iop::variable::CircuitVariable;
     use plonky2::iop::witness::PartialWitness;
     use plonky2::plonk::circuit_builder::CircuitBuilder;
     use plonky2::plonk::circuit_data::CircuitConfig;
     use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2_u32::gadgets::arithmetic_u32::U32Target;
     use rand::rngs::OsRng;
     use rand::Rng;
 
-    use crate::gadgets::biguint::{CircuitBuilderBiguint, WitnessBigUint};
+    use crate::gadgets::biguint::{BigUintTarget, CircuitBuilderBiguint, WitnessBigUint};
+
+    #[derive(Clone, Debug, CircuitVariable)]
+    struct ScaledBigUint {
+        value: BigUintTarget,
+        scale: U32Target,
+    }
 
     #[test]
     fn test_biguint_add() -> Result<()> {
@@ -505,4 +554,38 @@ mod tests {
         let proof = data.prove(pw).unwrap();
         data.verify(proof)
     }
+
+    #[test]
+    fn test_biguint_circuit_variable() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let mut rng = OsRng;
+
+        let value = ScaledBigUintValue {
+            value: BigUint::from_u128(rng.gen()).unwrap(),
+            scale: rng.gen(),
+        };
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let layout = ScaledBigUintLayout {
+            value: 4,
+            scale: (),
+        };
+        let x = ScaledBigUint::add_virtual(&mut builder, &layout);
+        let y = ScaledBigUint::add_virtual(&mut builder, &layout);
+        x.connect(&y, &mut builder);
+        y.register_public_inputs(&mut builder);
+
+        x.set(&mut pw, &value);
+        assert_eq!(x.get(&pw), value);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw).unwrap();
+        assert_eq!(proof.public_inputs.len(), 5);
+        data.verify(proof)
+    }
 }
@@ -67,18 +67,20 @@ impl<F: RichField, const D: usize> ArithmeticStark<F, D> {
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for ArithmeticStark<F, D> {
-    const COLUMNS: usize = columns::NUM_ARITH_COLUMNS;
+    fn num_columns(&self) -> usize {
+        columns::NUM_ARITH_COLUMNS
+    }
 
     fn eval_packed_generic<FE, P, const D2: usize>(
         &self,
-        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }>,
+        vars: StarkEvaluationVars<FE, P>,
         yield_constr: &mut ConstraintConsumer<P>,
     ) where
         FE: FieldExtension<D2, BaseField = F>,
         P: PackedField<Scalar = FE>,
     {
-        let lv = vars.local_values;
-        let nv = vars.next_values;
+        let lv: &[P; columns::NUM_ARITH_COLUMNS] = vars.local_values.try_into().unwrap();
+        let nv: &[P; columns::NUM_ARITH_COLUMNS] = vars.next_values.try_into().unwrap();
         add::eval_packed_generic(lv, yield_constr);
         sub::eval_packed_generic(lv, yield_constr);
         mul::eval_packed_generic(lv, yield_constr);
@@ -89,11 +91,11 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for ArithmeticSta
     fn eval_ext_circuit(
         &self,
         builder: &mut plonky2::plonk::circuit_builder::CircuitBuilder<F, D>,
-        vars: StarkEvaluationTargets<D, { Self::COLUMNS }>,
+        vars: StarkEvaluationTargets<D>,
         yield_constr: &mut RecursiveConstraintConsumer<F, D>,
     ) {
-        let lv = vars.local_values;
-        let nv = vars.next_values;
+        let lv: &[_; columns::NUM_ARITH_COLUMNS] = vars.local_values.try_into().unwrap();
+        let nv: &[_; columns::NUM_ARITH_COLUMNS] = vars.next_values.try_into().unwrap();
         add::eval_ext_circuit(builder, lv, yield_constr);
         sub::eval_ext_circuit(builder, lv, yield_constr);
         mul::eval_ext_circuit(builder, lv, yield_constr);
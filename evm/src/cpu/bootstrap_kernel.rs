@@ -57,11 +57,13 @@ pub(crate) fn generate_bootstrap_kernel<F: Field>(state: &mut GenerationState<F>
 }
 
 pub(crate) fn eval_bootstrap_kernel<F: Field, P: PackedField<Scalar = F>>(
-    vars: StarkEvaluationVars<F, P, NUM_CPU_COLUMNS>,
+    vars: StarkEvaluationVars<F, P>,
     yield_constr: &mut ConstraintConsumer<P>,
 ) {
-    let local_values: &CpuColumnsView<_> = vars.local_values.borrow();
-    let next_values: &CpuColumnsView<_> = vars.next_values.borrow();
+    let local_values: &[_; NUM_CPU_COLUMNS] = vars.local_values.try_into().unwrap();
+    let local_values: &CpuColumnsView<_> = local_values.borrow();
+    let next_values: &[_; NUM_CPU_COLUMNS] = vars.next_values.try_into().unwrap();
+    let next_values: &CpuColumnsView<_> = next_values.borrow();
 
     // IS_BOOTSTRAP_KERNEL must have an init value of 1, a final value of 0, and a delta in {0, -1}.
     let local_is_bootstrap = local_values.is_bootstrap_kernel;
@@ -99,11 +101,13 @@ pub(crate) fn eval_bootstrap_kernel<F: Field, P: PackedField<Scalar = F>>(
 
 pub(crate) fn eval_bootstrap_kernel_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    vars: StarkEvaluationTargets<D, NUM_CPU_COLUMNS>,
+    vars: StarkEvaluationTargets<D>,
     yield_constr: &mut RecursiveConstraintConsumer<F, D>,
 ) {
-    let local_values: &CpuColumnsView<_> = vars.local_values.borrow();
-    let next_values: &CpuColumnsView<_> = vars.next_values.borrow();
+    let local_values: &[_; NUM_CPU_COLUMNS] = vars.local_values.try_into().unwrap();
+    let local_values: &CpuColumnsView<_> = local_values.borrow();
+    let next_values: &[_; NUM_CPU_COLUMNS] = vars.next_values.try_into().unwrap();
+    let next_values: &CpuColumnsView<_> = next_values.borrow();
     let one = builder.one_extension();
 
     // IS_BOOTSTRAP_KERNEL must have an init value of 1, a final value of 0, and a delta in {0, -1}.
@@ -97,56 +97,48 @@ fn identity_operations(code: &mut Vec<Item>) {
 
 /// Remove no-op jumps: `[PUSH label, JUMP, label:] -> [label:]`.
 fn no_op_jumps(code: &mut Vec<Item>) {
-    replace_windows(code, |window| {
-        if let [Push(Label(l)), StandardOp(jump), decl] = window
-            && &jump == "JUMP"
-            && (decl == LocalLabelDeclaration(l.clone()) || decl == GlobalLabelDeclaration(l))
+    replace_windows(code, |window| match window {
+        [Push(Label(l)), StandardOp(jump), decl]
+            if &jump == "JUMP"
+                && (decl == LocalLabelDeclaration(l.clone())
+                    || decl == GlobalLabelDeclaration(l.clone())) =>
         {
             Some(vec![decl])
-        } else {
-            None
         }
+        _ => None,
     });
 }
 
 /// Remove swaps: `[PUSH x, PUSH y, SWAP1] -> [PUSH y, PUSH x]`.
 // Could be generalized to recognize more than two pushes.
 fn remove_swapped_pushes(code: &mut Vec<Item>) {
-    replace_windows(code, |window| {
-        if let [Push(x), Push(y), StandardOp(swap1)] = window
-            && &swap1 == "SWAP1" {
-            Some(vec![Push(y), Push(x)])
-        } else {
-            None
-        }
+    replace_windows(code, |window| match window {
+        [Push(x), Push(y), StandardOp(swap1)] if &swap1 == "SWAP1" => Some(vec![Push(y), Push(x)]),
+        _ => None,
     });
 }
 
 /// Remove SWAP1 before a commutative function.
 fn remove_swaps_commutative(code: &mut Vec<Item>) {
-    replace_windows(code, |window| {
-        if let [StandardOp(swap1), StandardOp(f)] = window && &swap1 == "SWAP1" {
+    replace_windows(code, |window| match window {
+        [StandardOp(swap1), StandardOp(f)] if &swap1 == "SWAP1" => {
             let commutative = matches!(f.as_str(), "ADD" | "MUL" | "AND" | "OR" | "XOR" | "EQ");
             commutative.then_some(vec![StandardOp(f)])
-        } else {
-            None
         }
+        _ => None,
     });
 }
 
 /// Remove push-pop type patterns, such as: `[DUP1, POP]`.
 // Could be extended to other non-side-effecting operations, e.g. [DUP1, ADD, POP] -> [POP].
 fn remove_ignored_values(code: &mut Vec<Item>) {
-    replace_windows(code, |[a, b]| {
-        if let StandardOp(pop) = b && &pop == "POP" {
-            match a {
-                Push(_) => Some(vec![]),
-                StandardOp(dup) if dup.starts_with("DUP") => Some(vec![]),
-                _ => None,
-            }
-        } else {
-            None
-        }
+    replace_windows(code, |[a, b]| match b {
+        StandardOp(pop) if &pop == "POP" => match a {
+            Push(_) => Some(vec![]),
+            StandardOp(dup) if dup.starts_with("DUP") => Some(vec![]),
+            _ => None,
+        },
+        _ => None,
     });
 }
 
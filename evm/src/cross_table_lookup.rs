@@ -21,7 +21,6 @@ use crate::permutation::{
     get_grand_product_challenge_set, GrandProductChallenge, GrandProductChallengeSet,
 };
 use crate::proof::{StarkProof, StarkProofTarget};
-use crate::stark::Stark;
 use crate::vars::{StarkEvaluationTargets, StarkEvaluationVars};
 
 /// Represent a linear combination of columns.
@@ -407,16 +406,14 @@ impl<'a, F: RichField + Extendable<D>, const D: usize>
     }
 }
 
-pub(crate) fn eval_cross_table_lookup_checks<F, FE, P, C, S, const D: usize, const D2: usize>(
-    vars: StarkEvaluationVars<FE, P, { S::COLUMNS }>,
+pub(crate) fn eval_cross_table_lookup_checks<F, FE, P, const D: usize, const D2: usize>(
+    vars: StarkEvaluationVars<FE, P>,
     ctl_vars: &[CtlCheckVars<F, FE, P, D2>],
     consumer: &mut ConstraintConsumer<P>,
 ) where
     F: RichField + Extendable<D>,
     FE: FieldExtension<D2, BaseField = F>,
     P: PackedField<Scalar = FE>,
-    C: GenericConfig<D, F = F>,
-    S: Stark<F, D>,
 {
     for lookup_vars in ctl_vars {
         let CtlCheckVars {
@@ -516,12 +513,11 @@ impl<'a, F: Field, const D: usize> CtlCheckVarsTarget<'a, F, D> {
 }
 
 pub(crate) fn eval_cross_table_lookup_checks_circuit<
-    S: Stark<F, D>,
     F: RichField + Extendable<D>,
     const D: usize,
 >(
     builder: &mut CircuitBuilder<F, D>,
-    vars: StarkEvaluationTargets<D, { S::COLUMNS }>,
+    vars: StarkEvaluationTargets<D>,
     ctl_vars: &[CtlCheckVarsTarget<F, D>],
     consumer: &mut RecursiveConstraintConsumer<F, D>,
 ) {
@@ -348,17 +348,20 @@ impl<F: RichField + Extendable<D>, const D: usize> KeccakSpongeStark<F, D> {
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for KeccakSpongeStark<F, D> {
-    const COLUMNS: usize = NUM_KECCAK_SPONGE_COLUMNS;
+    fn num_columns(&self) -> usize {
+        NUM_KECCAK_SPONGE_COLUMNS
+    }
 
     fn eval_packed_generic<FE, P, const D2: usize>(
         &self,
-        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }>,
+        vars: StarkEvaluationVars<FE, P>,
         _yield_constr: &mut ConstraintConsumer<P>,
     ) where
         FE: FieldExtension<D2, BaseField = F>,
         P: PackedField<Scalar = FE>,
     {
-        let _local_values: &KeccakSpongeColumnsView<P> = vars.local_values.borrow();
+        let local_values: &[P; NUM_KECCAK_SPONGE_COLUMNS] = vars.local_values.try_into().unwrap();
+        let _local_values: &KeccakSpongeColumnsView<P> = local_values.borrow();
 
         // TODO: Each flag (full-input block, final block or implied dummy flag) must be boolean.
         // TODO: before_rate_bits, block_bits and is_final_input_len must contain booleans.
@@ -380,11 +383,12 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for KeccakSpongeS
     fn eval_ext_circuit(
         &self,
         _builder: &mut plonky2::plonk::circuit_builder::CircuitBuilder<F, D>,
-        vars: StarkEvaluationTargets<D, { Self::COLUMNS }>,
+        vars: StarkEvaluationTargets<D>,
         _yield_constr: &mut RecursiveConstraintConsumer<F, D>,
     ) {
-        let _local_values: &KeccakSpongeColumnsView<ExtensionTarget<D>> =
-            vars.local_values.borrow();
+        let local_values: &[ExtensionTarget<D>; NUM_KECCAK_SPONGE_COLUMNS] =
+            vars.local_values.try_into().unwrap();
+        let _local_values: &KeccakSpongeColumnsView<ExtensionTarget<D>> = local_values.borrow();
 
         // TODO
     }
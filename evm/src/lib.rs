@@ -1,10 +1,7 @@
-#![allow(incomplete_features)]
 #![allow(clippy::needless_range_loop)]
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::type_complexity)]
 #![allow(clippy::field_reassign_with_default)]
-#![feature(let_chains)]
-#![feature(generic_const_exprs)]
 
 pub mod all_stark;
 pub mod arithmetic;
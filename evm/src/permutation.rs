@@ -140,9 +140,7 @@ fn compute_permutation_z_poly<F: Field>(
     let denominator = poly_product_elementwise(reduced_rhs_polys.into_iter());
 
     // Compute the quotients.
-    let denominator_inverses = F::batch_multiplicative_inverse(&denominator.values);
-    let mut quotients = numerator.values;
-    batch_multiply_inplace(&mut quotients, &denominator_inverses);
+    let quotients = numerator.div_pointwise(&denominator).values;
 
     // Compute Z, which contains partial products of the quotients.
     let mut partial_products = Vec::with_capacity(degree);
@@ -300,7 +298,7 @@ where
 pub(crate) fn eval_permutation_checks<F, FE, P, C, S, const D: usize, const D2: usize>(
     stark: &S,
     config: &StarkConfig,
-    vars: StarkEvaluationVars<FE, P, { S::COLUMNS }>,
+    vars: StarkEvaluationVars<FE, P>,
     permutation_vars: PermutationCheckVars<F, FE, P, D2>,
     consumer: &mut ConstraintConsumer<P>,
 ) where
@@ -367,13 +365,12 @@ pub(crate) fn eval_permutation_checks_circuit<F, S, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
     stark: &S,
     config: &StarkConfig,
-    vars: StarkEvaluationTargets<D, { S::COLUMNS }>,
+    vars: StarkEvaluationTargets<D>,
     permutation_data: PermutationCheckDataTarget<D>,
     consumer: &mut RecursiveConstraintConsumer<F, D>,
 ) where
     F: RichField + Extendable<D>,
     S: Stark<F, D>,
-    [(); S::COLUMNS]:,
 {
     let PermutationCheckDataTarget {
         local_zs,
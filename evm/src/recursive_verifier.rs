@@ -21,15 +21,10 @@ use plonky2::with_context;
 use crate::all_stark::{all_cross_table_lookups, AllStark, Table, NUM_TABLES};
 use crate::config::StarkConfig;
 use crate::constraint_consumer::RecursiveConstraintConsumer;
-use crate::cpu::cpu_stark::CpuStark;
 use crate::cross_table_lookup::{
     verify_cross_table_lookups, verify_cross_table_lookups_circuit, CrossTableLookup,
     CtlCheckVarsTarget,
 };
-use crate::keccak::keccak_stark::KeccakStark;
-use crate::keccak_sponge::keccak_sponge_stark::KeccakSpongeStark;
-use crate::logic::LogicStark;
-use crate::memory::memory_stark::MemoryStark;
 use crate::permutation::{
     get_grand_product_challenge_set, get_grand_product_challenge_set_target, GrandProductChallenge,
     GrandProductChallengeSet, PermutationCheckDataTarget,
@@ -112,10 +107,7 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         verifier_data: &[VerifierCircuitData<F, C, D>; NUM_TABLES],
         cross_table_lookups: Vec<CrossTableLookup<F>>,
         inner_config: &StarkConfig,
-    ) -> Result<()>
-    where
-        [(); C::Hasher::HASH_SIZE]:,
-    {
+    ) -> Result<()> {
         let pis: [_; NUM_TABLES] = std::array::from_fn(|i| {
             PublicInputs::from_vec(&self.recursive_proofs[i].public_inputs, inner_config)
         });
@@ -164,7 +156,6 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         verifier_data: &[VerifierCircuitData<F, C, D>; NUM_TABLES],
         inner_config: &StarkConfig,
     ) where
-        [(); C::Hasher::HASH_SIZE]:,
         <C as GenericConfig<D>>::Hasher: AlgebraicHasher<F>,
     {
         let RecursiveAllProofTargetWithData {
@@ -253,8 +244,6 @@ fn verifier_data_recursive_stark_proof<
     circuit_config: &CircuitConfig,
 ) -> VerifierCircuitData<F, C, D>
 where
-    [(); S::COLUMNS]:,
-    [(); C::Hasher::HASH_SIZE]:,
     C::Hasher: AlgebraicHasher<F>,
 {
     let mut builder = CircuitBuilder::<F, D>::new(circuit_config.clone());
@@ -329,12 +318,6 @@ pub fn all_verifier_data_recursive_stark_proof<
     circuit_config: &CircuitConfig,
 ) -> [VerifierCircuitData<F, C, D>; NUM_TABLES]
 where
-    [(); CpuStark::<F, D>::COLUMNS]:,
-    [(); KeccakStark::<F, D>::COLUMNS]:,
-    [(); KeccakSpongeStark::<F, D>::COLUMNS]:,
-    [(); LogicStark::<F, D>::COLUMNS]:,
-    [(); MemoryStark::<F, D>::COLUMNS]:,
-    [(); C::Hasher::HASH_SIZE]:,
     C::Hasher: AlgebraicHasher<F>,
 {
     [
@@ -396,7 +379,6 @@ fn verify_stark_proof_with_challenges_circuit<
     inner_config: &StarkConfig,
 ) where
     C::Hasher: AlgebraicHasher<F>,
-    [(); S::COLUMNS]:,
 {
     let zero = builder.zero();
     let one = builder.one_extension();
@@ -410,8 +392,8 @@ fn verify_stark_proof_with_challenges_circuit<
         quotient_polys,
     } = &proof.openings;
     let vars = StarkEvaluationTargets {
-        local_values: &local_values.to_vec().try_into().unwrap(),
-        next_values: &next_values.to_vec().try_into().unwrap(),
+        local_values,
+        next_values,
     };
 
     let degree_bits = proof.recover_degree_bits(inner_config);
@@ -647,7 +629,7 @@ pub fn add_virtual_stark_proof<F: RichField + Extendable<D>, S: Stark<F, D>, con
     let cap_height = fri_params.config.cap_height;
 
     let num_leaves_per_oracle = vec![
-        S::COLUMNS,
+        stark.num_columns(),
         stark.num_permutation_batches(config) + num_ctl_zs,
         stark.quotient_degree_factor() * config.num_challenges,
     ];
@@ -671,8 +653,8 @@ fn add_virtual_stark_opening_set<F: RichField + Extendable<D>, S: Stark<F, D>, c
 ) -> StarkOpeningSetTarget<D> {
     let num_challenges = config.num_challenges;
     StarkOpeningSetTarget {
-        local_values: builder.add_virtual_extension_targets(S::COLUMNS),
-        next_values: builder.add_virtual_extension_targets(S::COLUMNS),
+        local_values: builder.add_virtual_extension_targets(stark.num_columns()),
+        next_values: builder.add_virtual_extension_targets(stark.num_columns()),
         permutation_ctl_zs: builder
             .add_virtual_extension_targets(stark.num_permutation_batches(config) + num_ctl_zs),
         permutation_ctl_zs_next: builder
@@ -844,17 +826,12 @@ pub(crate) mod tests {
     use plonky2::iop::witness::{PartialWitness, WitnessWrite};
     use plonky2::plonk::circuit_builder::CircuitBuilder;
     use plonky2::plonk::circuit_data::{CircuitConfig, VerifierCircuitData};
-    use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, Hasher};
+    use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
     use plonky2::plonk::proof::ProofWithPublicInputs;
 
     use crate::all_stark::{AllStark, Table};
     use crate::config::StarkConfig;
-    use crate::cpu::cpu_stark::CpuStark;
     use crate::cross_table_lookup::{CrossTableLookup, CtlCheckVarsTarget};
-    use crate::keccak::keccak_stark::KeccakStark;
-    use crate::keccak_sponge::keccak_sponge_stark::KeccakSpongeStark;
-    use crate::logic::LogicStark;
-    use crate::memory::memory_stark::MemoryStark;
     use crate::permutation::{GrandProductChallenge, GrandProductChallengeSet};
     use crate::proof::{AllChallengerState, AllProof, StarkProof};
     use crate::recursive_verifier::{
@@ -882,8 +859,6 @@ pub(crate) mod tests {
         circuit_config: &CircuitConfig,
     ) -> Result<(ProofWithPublicInputs<F, C, D>, VerifierCircuitData<F, C, D>)>
     where
-        [(); S::COLUMNS]:,
-        [(); C::Hasher::HASH_SIZE]:,
         C::Hasher: AlgebraicHasher<F>,
     {
         let mut builder = CircuitBuilder::<F, D>::new(circuit_config.clone());
@@ -977,12 +952,6 @@ pub(crate) mod tests {
         circuit_config: &CircuitConfig,
     ) -> Result<RecursiveAllProof<F, C, D>>
     where
-        [(); CpuStark::<F, D>::COLUMNS]:,
-        [(); KeccakStark::<F, D>::COLUMNS]:,
-        [(); KeccakSpongeStark::<F, D>::COLUMNS]:,
-        [(); LogicStark::<F, D>::COLUMNS]:,
-        [(); MemoryStark::<F, D>::COLUMNS]:,
-        [(); C::Hasher::HASH_SIZE]:,
         C::Hasher: AlgebraicHasher<F>,
     {
         let AllChallengerState {
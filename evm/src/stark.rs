@@ -22,7 +22,7 @@ const QUOTIENT_ORACLE_INDEX: usize = 2;
 /// Represents a STARK system.
 pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
     /// The total number of columns in the trace.
-    const COLUMNS: usize;
+    fn num_columns(&self) -> usize;
 
     /// Evaluate constraints at a vector of points.
     ///
@@ -32,7 +32,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
     /// constraints over `F`.
     fn eval_packed_generic<FE, P, const D2: usize>(
         &self,
-        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }>,
+        vars: StarkEvaluationVars<FE, P>,
         yield_constr: &mut ConstraintConsumer<P>,
     ) where
         FE: FieldExtension<D2, BaseField = F>,
@@ -41,7 +41,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
     /// Evaluate constraints at a vector of points from the base field `F`.
     fn eval_packed_base<P: PackedField<Scalar = F>>(
         &self,
-        vars: StarkEvaluationVars<F, P, { Self::COLUMNS }>,
+        vars: StarkEvaluationVars<F, P>,
         yield_constr: &mut ConstraintConsumer<P>,
     ) {
         self.eval_packed_generic(vars, yield_constr)
@@ -50,7 +50,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
     /// Evaluate constraints at a single point from the degree `D` extension field.
     fn eval_ext(
         &self,
-        vars: StarkEvaluationVars<F::Extension, F::Extension, { Self::COLUMNS }>,
+        vars: StarkEvaluationVars<F::Extension, F::Extension>,
         yield_constr: &mut ConstraintConsumer<F::Extension>,
     ) {
         self.eval_packed_generic(vars, yield_constr)
@@ -63,7 +63,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
     fn eval_ext_circuit(
         &self,
         builder: &mut CircuitBuilder<F, D>,
-        vars: StarkEvaluationTargets<D, { Self::COLUMNS }>,
+        vars: StarkEvaluationTargets<D>,
         yield_constr: &mut RecursiveConstraintConsumer<F, D>,
     );
 
@@ -89,10 +89,10 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         config: &StarkConfig,
     ) -> FriInstanceInfo<F, D> {
         let trace_oracle = FriOracleInfo {
-            num_polys: Self::COLUMNS,
+            num_polys: self.num_columns(),
             blinding: false,
         };
-        let trace_info = FriPolynomialInfo::from_range(TRACE_ORACLE_INDEX, 0..Self::COLUMNS);
+        let trace_info = FriPolynomialInfo::from_range(TRACE_ORACLE_INDEX, 0..self.num_columns());
 
         let num_permutation_batches = self.num_permutation_batches(config);
         let num_perutation_ctl_polys = num_permutation_batches + num_ctl_zs;
@@ -152,10 +152,10 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         inner_config: &StarkConfig,
     ) -> FriInstanceInfoTarget<D> {
         let trace_oracle = FriOracleInfo {
-            num_polys: Self::COLUMNS,
+            num_polys: self.num_columns(),
             blinding: false,
         };
-        let trace_info = FriPolynomialInfo::from_range(TRACE_ORACLE_INDEX, 0..Self::COLUMNS);
+        let trace_info = FriPolynomialInfo::from_range(TRACE_ORACLE_INDEX, 0..self.num_columns());
 
         let num_permutation_batches = self.num_permutation_batches(inner_config);
         let num_perutation_ctl_polys = num_permutation_batches + num_ctl_zs;
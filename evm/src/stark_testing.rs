@@ -6,7 +6,7 @@ use plonky2::hash::hash_types::RichField;
 use plonky2::iop::witness::{PartialWitness, WitnessWrite};
 use plonky2::plonk::circuit_builder::CircuitBuilder;
 use plonky2::plonk::circuit_data::CircuitConfig;
-use plonky2::plonk::config::{GenericConfig, Hasher};
+use plonky2::plonk::config::GenericConfig;
 use plonky2::util::transpose;
 use plonky2_util::{log2_ceil, log2_strict};
 
@@ -20,13 +20,10 @@ const WITNESS_SIZE: usize = 1 << 5;
 /// low-degree witness polynomials.
 pub fn test_stark_low_degree<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
     stark: S,
-) -> Result<()>
-where
-    [(); S::COLUMNS]:,
-{
+) -> Result<()> {
     let rate_bits = log2_ceil(stark.constraint_degree() + 1);
 
-    let trace_ldes = random_low_degree_matrix::<F>(S::COLUMNS, rate_bits);
+    let trace_ldes = random_low_degree_matrix::<F>(stark.num_columns(), rate_bits);
     let size = trace_ldes.len();
 
     let lagrange_first = PolynomialValues::selector(WITNESS_SIZE, 0).lde(rate_bits);
@@ -39,11 +36,8 @@ where
     let constraint_evals = (0..size)
         .map(|i| {
             let vars = StarkEvaluationVars {
-                local_values: &trace_ldes[i].clone().try_into().unwrap(),
-                next_values: &trace_ldes[(i + (1 << rate_bits)) % size]
-                    .clone()
-                    .try_into()
-                    .unwrap(),
+                local_values: &trace_ldes[i],
+                next_values: &trace_ldes[(i + (1 << rate_bits)) % size],
             };
 
             let mut consumer = ConstraintConsumer::<F>::new(
@@ -83,15 +77,13 @@ pub fn test_stark_circuit_constraints<
     const D: usize,
 >(
     stark: S,
-) -> Result<()>
-where
-    [(); S::COLUMNS]:,
-    [(); C::Hasher::HASH_SIZE]:,
-{
+) -> Result<()> {
     // Compute native constraint evaluation on random values.
+    let local_values = F::Extension::rand_vec(stark.num_columns());
+    let next_values = F::Extension::rand_vec(stark.num_columns());
     let vars = StarkEvaluationVars {
-        local_values: &F::Extension::rand_array::<{ S::COLUMNS }>(),
-        next_values: &F::Extension::rand_array::<{ S::COLUMNS }>(),
+        local_values: &local_values,
+        next_values: &next_values,
     };
     let alphas = F::rand_vec(1);
     let z_last = F::Extension::rand();
@@ -115,9 +107,9 @@ where
     let mut builder = CircuitBuilder::<F, D>::new(circuit_config);
     let mut pw = PartialWitness::<F>::new();
 
-    let locals_t = builder.add_virtual_extension_targets(S::COLUMNS);
+    let locals_t = builder.add_virtual_extension_targets(stark.num_columns());
     pw.set_extension_targets(&locals_t, vars.local_values);
-    let nexts_t = builder.add_virtual_extension_targets(S::COLUMNS);
+    let nexts_t = builder.add_virtual_extension_targets(stark.num_columns());
     pw.set_extension_targets(&nexts_t, vars.next_values);
     let alphas_t = builder.add_virtual_targets(1);
     pw.set_target(alphas_t[0], alphas[0]);
@@ -128,9 +120,9 @@ where
     let lagrange_last_t = builder.add_virtual_extension_target();
     pw.set_extension_target(lagrange_last_t, lagrange_last);
 
-    let vars = StarkEvaluationTargets::<D, { S::COLUMNS }> {
-        local_values: &locals_t.try_into().unwrap(),
-        next_values: &nexts_t.try_into().unwrap(),
+    let vars = StarkEvaluationTargets::<D> {
+        local_values: &locals_t,
+        next_values: &nexts_t,
     };
     let mut consumer = RecursiveConstraintConsumer::<F, D>::new(
         builder.zero_extension(),
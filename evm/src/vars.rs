@@ -2,18 +2,20 @@ use plonky2::field::packed::PackedField;
 use plonky2::field::types::Field;
 use plonky2::iop::ext_target::ExtensionTarget;
 
+/// The values of a STARK's trace columns on the current and next rows. Both slices hold
+/// `num_columns()` values.
 #[derive(Debug, Copy, Clone)]
-pub struct StarkEvaluationVars<'a, F, P, const COLUMNS: usize>
+pub struct StarkEvaluationVars<'a, F, P>
 where
     F: Field,
     P: PackedField<Scalar = F>,
 {
-    pub local_values: &'a [P; COLUMNS],
-    pub next_values: &'a [P; COLUMNS],
+    pub local_values: &'a [P],
+    pub next_values: &'a [P],
 }
 
 #[derive(Debug, Copy, Clone)]
-pub struct StarkEvaluationTargets<'a, const D: usize, const COLUMNS: usize> {
-    pub local_values: &'a [ExtensionTarget<D>; COLUMNS],
-    pub next_values: &'a [ExtensionTarget<D>; COLUMNS],
+pub struct StarkEvaluationTargets<'a, const D: usize> {
+    pub local_values: &'a [ExtensionTarget<D>],
+    pub next_values: &'a [ExtensionTarget<D>],
 }
@@ -22,6 +22,16 @@ use crate::types::{Field, Field64};
 pub struct Avx2GoldilocksField(pub [GoldilocksField; 4]);
 
 impl Avx2GoldilocksField {
+    #[inline]
+    pub fn from_arr(arr: [GoldilocksField; 4]) -> Self {
+        Self(arr)
+    }
+
+    #[inline]
+    pub fn as_arr(&self) -> [GoldilocksField; 4] {
+        self.0
+    }
+
     #[inline]
     fn new(x: __m256i) -> Self {
         unsafe { transmute(x) }
@@ -157,16 +167,6 @@ unsafe impl PackedField for Avx2GoldilocksField {
     const ZEROS: Self = Self([GoldilocksField::ZERO; 4]);
     const ONES: Self = Self([GoldilocksField::ONE; 4]);
 
-    #[inline]
-    fn from_arr(arr: [Self::Scalar; Self::WIDTH]) -> Self {
-        Self(arr)
-    }
-
-    #[inline]
-    fn as_arr(&self) -> [Self::Scalar; Self::WIDTH] {
-        self.0
-    }
-
     #[inline]
     fn from_slice(slice: &[Self::Scalar]) -> &Self {
         assert_eq!(slice.len(), Self::WIDTH);
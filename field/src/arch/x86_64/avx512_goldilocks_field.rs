@@ -22,6 +22,16 @@ use crate::types::{Field, Field64};
 pub struct Avx512GoldilocksField(pub [GoldilocksField; 8]);
 
 impl Avx512GoldilocksField {
+    #[inline]
+    pub fn from_arr(arr: [GoldilocksField; 8]) -> Self {
+        Self(arr)
+    }
+
+    #[inline]
+    pub fn as_arr(&self) -> [GoldilocksField; 8] {
+        self.0
+    }
+
     #[inline]
     fn new(x: __m512i) -> Self {
         unsafe { transmute(x) }
@@ -40,16 +50,6 @@ unsafe impl PackedField for Avx512GoldilocksField {
     const ZEROS: Self = Self([GoldilocksField::ZERO; 8]);
     const ONES: Self = Self([GoldilocksField::ONE; 8]);
 
-    #[inline]
-    fn from_arr(arr: [Self::Scalar; Self::WIDTH]) -> Self {
-        Self(arr)
-    }
-
-    #[inline]
-    fn as_arr(&self) -> [Self::Scalar; Self::WIDTH] {
-        self.0
-    }
-
     #[inline]
     fn from_slice(slice: &[Self::Scalar]) -> &Self {
         assert_eq!(slice.len(), Self::WIDTH);
@@ -3,6 +3,7 @@ use alloc::vec::Vec;
 use crate::types::Field;
 
 pub mod algebra;
+pub mod packed;
 pub mod quadratic;
 pub mod quartic;
 pub mod quintic;
@@ -81,6 +82,28 @@ pub trait Extendable<const D: usize>: Field + Sized {
     /// we get `Self::BaseField::POWER_OF_TWO_GENERATOR`. This makes `primitive_root_of_unity` coherent
     /// with the base field which implies that the FFT commutes with field inclusion.
     const EXT_POWER_OF_TWO_GENERATOR: [Self; D];
+
+    /// Multiplies two elements of `Self::Extension`, given by their coordinates, i.e. computes
+    /// their product modulo `X^D - W`. Fields may override this with a faster implementation.
+    #[inline]
+    fn ext_mul(a: [Self; D], b: [Self; D]) -> [Self; D] {
+        // The terms of degree `>= D` are accumulated separately and multiplied by `W` once.
+        let mut lo = [Self::ZERO; D];
+        let mut hi = [Self::ZERO; D];
+        for i in 0..D {
+            for j in 0..D {
+                if i + j < D {
+                    lo[i + j] += a[i] * b[j];
+                } else {
+                    hi[i + j - D] += a[i] * b[j];
+                }
+            }
+        }
+        for i in 0..D {
+            lo[i] += Self::W * hi[i];
+        }
+        lo
+    }
 }
 
 impl<F: Field + Frobenius<1> + FieldExtension<1, BaseField = F>> Extendable<1> for F {
@@ -0,0 +1,362 @@
+use alloc::vec::Vec;
+use core::iter::{Product, Sum};
+use core::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::extension::{Extendable, FieldExtension};
+use crate::ops::Square;
+use crate::packed::PackedField;
+use crate::types::Field;
+
+/// `P::WIDTH` elements of the degree `D` extension of `P::Scalar`, stored in structure-of-arrays
+/// layout: `self.0[i]` holds the `i`th basefield coordinate of every lane. This lets extension
+/// arithmetic run on `D` packed basefield vectors instead of one extension element at a time.
+#[derive(Copy, Clone, Debug)]
+pub struct PackedExtension<P: PackedField, const D: usize>(pub [P; D])
+where
+    P::Scalar: Extendable<D>;
+
+impl<P: PackedField, const D: usize> PackedExtension<P, D>
+where
+    P::Scalar: Extendable<D>,
+{
+    pub const ZEROS: Self = Self([P::ZEROS; D]);
+
+    pub fn ones() -> Self {
+        Self::from_basefield(P::ONES)
+    }
+
+    /// Embeds packed basefield elements into the extension, lane by lane.
+    pub fn from_basefield(x: P) -> Self {
+        let mut arr = [P::ZEROS; D];
+        arr[0] = x;
+        Self(arr)
+    }
+
+    /// Fills every lane with the same extension element.
+    pub fn broadcast(x: <P::Scalar as Extendable<D>>::Extension) -> Self {
+        Self(x.to_basefield_array().map(P::from))
+    }
+
+    /// Builds a packed value whose `i`th lane is `f(i)`.
+    pub fn from_fn<G>(mut f: G) -> Self
+    where
+        G: FnMut(usize) -> <P::Scalar as Extendable<D>>::Extension,
+    {
+        let mut arr = [P::ZEROS; D];
+        for lane in 0..P::WIDTH {
+            let x = f(lane).to_basefield_array();
+            for i in 0..D {
+                arr[i].as_slice_mut()[lane] = x[i];
+            }
+        }
+        Self(arr)
+    }
+
+    /// Packs exactly `P::WIDTH` extension elements.
+    pub fn pack(xs: &[<P::Scalar as Extendable<D>>::Extension]) -> Self {
+        assert_eq!(xs.len(), P::WIDTH, "Expected {} elements", P::WIDTH);
+        Self::from_fn(|lane| xs[lane])
+    }
+
+    /// Returns the `i`th lane.
+    pub fn extract(&self, lane: usize) -> <P::Scalar as Extendable<D>>::Extension {
+        let arr = core::array::from_fn(|i| self.0[i].as_slice()[lane]);
+        <P::Scalar as Extendable<D>>::Extension::from_basefield_array(arr)
+    }
+
+    /// Writes the `P::WIDTH` lanes to `out`.
+    pub fn unpack_into(&self, out: &mut [<P::Scalar as Extendable<D>>::Extension]) {
+        assert_eq!(out.len(), P::WIDTH, "Expected {} elements", P::WIDTH);
+        for (lane, x) in out.iter_mut().enumerate() {
+            *x = self.extract(lane);
+        }
+    }
+
+    pub fn unpack(&self) -> Vec<<P::Scalar as Extendable<D>>::Extension> {
+        (0..P::WIDTH).map(|lane| self.extract(lane)).collect()
+    }
+
+    /// Multiplies every coordinate by the packed basefield element `scalar`.
+    pub fn scalar_mul(&self, scalar: P) -> Self {
+        Self(self.0.map(|x| x * scalar))
+    }
+
+    /// Lane-wise `x -> x^(p^count)`, where `p` is the order of the basefield.
+    /// See `Frobenius::repeated_frobenius`.
+    pub fn repeated_frobenius(&self, count: usize) -> Self {
+        // `x -> x^(p^D)` is the identity.
+        let count = count % D;
+        if count == 0 {
+            return *self;
+        }
+        let z0 = <P::Scalar as Extendable<D>>::DTH_ROOT.exp_u64(count as u64);
+        let mut res = self.0;
+        for (x, z) in res.iter_mut().zip(z0.powers()) {
+            *x *= z;
+        }
+        Self(res)
+    }
+
+    pub fn frobenius(&self) -> Self {
+        self.repeated_frobenius(1)
+    }
+
+    /// Lane-wise inverse, using `x^-1 = x^(r-1) / N(x)` where `r = (p^D - 1) / (p - 1)` and the
+    /// norm `N(x) = x^r` lies in the basefield. The `P::WIDTH` norms are inverted together with
+    /// Montgomery's trick. Panics if any lane is zero.
+    pub fn inverse(&self) -> Self {
+        let mut a_pow_r_minus_1 = Self::ones();
+        for k in 1..D {
+            a_pow_r_minus_1 *= self.repeated_frobenius(k);
+        }
+        let norm = (a_pow_r_minus_1 * *self).0[0];
+        let norm_inv = P::Scalar::batch_multiplicative_inverse(norm.as_slice());
+        a_pow_r_minus_1.scalar_mul(*P::from_slice(&norm_inv))
+    }
+}
+
+impl<P: PackedField, const D: usize> Default for PackedExtension<P, D>
+where
+    P::Scalar: Extendable<D>,
+{
+    fn default() -> Self {
+        Self::ZEROS
+    }
+}
+
+impl<P: PackedField, const D: usize> From<P> for PackedExtension<P, D>
+where
+    P::Scalar: Extendable<D>,
+{
+    fn from(x: P) -> Self {
+        Self::from_basefield(x)
+    }
+}
+
+impl<P: PackedField, const D: usize> Neg for PackedExtension<P, D>
+where
+    P::Scalar: Extendable<D>,
+{
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self(self.0.map(|x| -x))
+    }
+}
+
+impl<P: PackedField, const D: usize> Add for PackedExtension<P, D>
+where
+    P::Scalar: Extendable<D>,
+{
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        let mut arr = self.0;
+        arr.iter_mut().zip(&rhs.0).for_each(|(x, &y)| *x += y);
+        Self(arr)
+    }
+}
+
+impl<P: PackedField, const D: usize> AddAssign for PackedExtension<P, D>
+where
+    P::Scalar: Extendable<D>,
+{
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<P: PackedField, const D: usize> Sum for PackedExtension<P, D>
+where
+    P::Scalar: Extendable<D>,
+{
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZEROS, |acc, x| acc + x)
+    }
+}
+
+impl<P: PackedField, const D: usize> Sub for PackedExtension<P, D>
+where
+    P::Scalar: Extendable<D>,
+{
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        let mut arr = self.0;
+        arr.iter_mut().zip(&rhs.0).for_each(|(x, &y)| *x -= y);
+        Self(arr)
+    }
+}
+
+impl<P: PackedField, const D: usize> SubAssign for PackedExtension<P, D>
+where
+    P::Scalar: Extendable<D>,
+{
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<P: PackedField, const D: usize> Mul for PackedExtension<P, D>
+where
+    P::Scalar: Extendable<D>,
+{
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        if P::WIDTH == 1 {
+            // Without SIMD the packing is the scalar field itself, so use the extension field's
+            // own multiplication, which may be specialized (e.g. for Goldilocks).
+            return Self::from_fn(|_| self.extract(0) * rhs.extract(0));
+        }
+
+        // Schoolbook multiplication modulo `X^D - W`: the terms of degree `>= D` are accumulated
+        // separately and multiplied by `W` once per coordinate.
+        let mut lo = [P::ZEROS; D];
+        let mut hi = [P::ZEROS; D];
+        for i in 0..D {
+            for j in 0..D {
+                if i + j < D {
+                    lo[i + j] += self.0[i] * rhs.0[j];
+                } else {
+                    hi[i + j - D] += self.0[i] * rhs.0[j];
+                }
+            }
+        }
+        let w = <P::Scalar as Extendable<D>>::W;
+        for i in 0..D {
+            lo[i] += hi[i] * w;
+        }
+        Self(lo)
+    }
+}
+
+impl<P: PackedField, const D: usize> MulAssign for PackedExtension<P, D>
+where
+    P::Scalar: Extendable<D>,
+{
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<P: PackedField, const D: usize> Mul<P> for PackedExtension<P, D>
+where
+    P::Scalar: Extendable<D>,
+{
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: P) -> Self {
+        self.scalar_mul(rhs)
+    }
+}
+
+impl<P: PackedField, const D: usize> Square for PackedExtension<P, D>
+where
+    P::Scalar: Extendable<D>,
+{
+    #[inline]
+    fn square(&self) -> Self {
+        // Like `mul`, but each cross term `a_i * a_j` with `i < j` is computed once and doubled.
+        let mut lo = [P::ZEROS; D];
+        let mut hi = [P::ZEROS; D];
+        for i in 0..D {
+            let sq = self.0[i].square();
+            if 2 * i < D {
+                lo[2 * i] += sq;
+            } else {
+                hi[2 * i - D] += sq;
+            }
+            for j in i + 1..D {
+                let cross = (self.0[i] * self.0[j]).doubles();
+                if i + j < D {
+                    lo[i + j] += cross;
+                } else {
+                    hi[i + j - D] += cross;
+                }
+            }
+        }
+        let w = <P::Scalar as Extendable<D>>::W;
+        for i in 0..D {
+            lo[i] += hi[i] * w;
+        }
+        Self(lo)
+    }
+}
+
+impl<P: PackedField, const D: usize> Product for PackedExtension<P, D>
+where
+    P::Scalar: Extendable<D>,
+{
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ones(), |acc, x| acc * x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::extension::packed::PackedExtension;
+    use crate::extension::{Extendable, Frobenius};
+    use crate::goldilocks_field::GoldilocksField;
+    use crate::ops::Square;
+    use crate::packable::Packable;
+    use crate::packed::PackedField;
+    use crate::types::{Field, Sample};
+
+    type F = GoldilocksField;
+    type P = <F as Packable>::Packing;
+
+    fn test_packed_extension_arithmetic<const D: usize>()
+    where
+        F: Extendable<D>,
+    {
+        type FE<const D: usize> = <F as Extendable<D>>::Extension;
+
+        let xs = FE::<D>::rand_vec(P::WIDTH);
+        let ys = FE::<D>::rand_vec(P::WIDTH);
+        let x = PackedExtension::<P, D>::pack(&xs);
+        let y = PackedExtension::<P, D>::pack(&ys);
+
+        let check = |packed: PackedExtension<P, D>, expected: Vec<FE<D>>| {
+            assert_eq!(packed.unpack(), expected);
+        };
+
+        check(x, xs.clone());
+        check(x + y, xs.iter().zip(&ys).map(|(&a, &b)| a + b).collect());
+        check(x - y, xs.iter().zip(&ys).map(|(&a, &b)| a - b).collect());
+        check(-x, xs.iter().map(|&a| -a).collect());
+        check(x * y, xs.iter().zip(&ys).map(|(&a, &b)| a * b).collect());
+        check(x.square(), xs.iter().map(|a| a.square()).collect());
+        check(x.frobenius(), xs.iter().map(|a| a.frobenius()).collect());
+        check(x.inverse(), xs.iter().map(|a| a.inverse()).collect());
+        check(
+            PackedExtension::broadcast(ys[0]) * x,
+            xs.iter().map(|&a| a * ys[0]).collect(),
+        );
+    }
+
+    #[test]
+    fn test_packed_quadratic() {
+        test_packed_extension_arithmetic::<2>();
+    }
+
+    #[test]
+    fn test_packed_quartic() {
+        test_packed_extension_arithmetic::<4>();
+    }
+
+    #[test]
+    fn test_packed_quintic() {
+        test_packed_extension_arithmetic::<5>();
+    }
+}
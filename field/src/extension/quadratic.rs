@@ -59,6 +59,8 @@ impl<F: Extendable<2>> Sample for QuadraticExtension<F> {
 }
 
 impl<F: Extendable<2>> Field for QuadraticExtension<F> {
+    type Packing = Self;
+
     const ZERO: Self = Self([F::ZERO; 2]);
     const ONE: Self = Self([F::ONE, F::ZERO]);
     const TWO: Self = Self([F::TWO, F::ZERO]);
@@ -179,14 +181,8 @@ impl<F: Extendable<2>> Mul for QuadraticExtension<F> {
     type Output = Self;
 
     #[inline]
-    default fn mul(self, rhs: Self) -> Self {
-        let Self([a0, a1]) = self;
-        let Self([b0, b1]) = rhs;
-
-        let c0 = a0 * b0 + <Self as OEF<2>>::W * a1 * b1;
-        let c1 = a0 * b1 + a1 * b0;
-
-        Self([c0, c1])
+    fn mul(self, rhs: Self) -> Self {
+        Self(F::ext_mul(self.0, rhs.0))
     }
 }
 
@@ -65,6 +65,8 @@ impl<F: Extendable<4>> Sample for QuarticExtension<F> {
 }
 
 impl<F: Extendable<4>> Field for QuarticExtension<F> {
+    type Packing = Self;
+
     const ZERO: Self = Self([F::ZERO; 4]);
     const ONE: Self = Self([F::ONE, F::ZERO, F::ZERO, F::ZERO]);
     const TWO: Self = Self([F::TWO, F::ZERO, F::ZERO, F::ZERO]);
@@ -203,16 +205,8 @@ impl<F: Extendable<4>> Mul for QuarticExtension<F> {
     type Output = Self;
 
     #[inline]
-    default fn mul(self, rhs: Self) -> Self {
-        let Self([a0, a1, a2, a3]) = self;
-        let Self([b0, b1, b2, b3]) = rhs;
-
-        let c0 = a0 * b0 + <Self as OEF<4>>::W * (a1 * b3 + a2 * b2 + a3 * b1);
-        let c1 = a0 * b1 + a1 * b0 + <Self as OEF<4>>::W * (a2 * b3 + a3 * b2);
-        let c2 = a0 * b2 + a1 * b1 + a2 * b0 + <Self as OEF<4>>::W * a3 * b3;
-        let c3 = a0 * b3 + a1 * b2 + a2 * b1 + a3 * b0;
-
-        Self([c0, c1, c2, c3])
+    fn mul(self, rhs: Self) -> Self {
+        Self(F::ext_mul(self.0, rhs.0))
     }
 }
 
@@ -1,5 +1,3 @@
-use core::ops::Mul;
-
 use static_assertions::const_assert;
 
 use crate::extension::quadratic::QuadraticExtension;
@@ -30,15 +28,10 @@ impl Extendable<2> for GoldilocksField {
         [Self(18081566051660590251), Self(16121475356294670766)];
 
     const EXT_POWER_OF_TWO_GENERATOR: [Self; 2] = [Self(0), Self(15659105665374529263)];
-}
 
-impl Mul for QuadraticExtension<GoldilocksField> {
     #[inline]
-    fn mul(self, rhs: Self) -> Self {
-        let Self([a0, a1]) = self;
-        let Self([b0, b1]) = rhs;
-        let c = ext2_mul([a0.0, a1.0], [b0.0, b1.0]);
-        Self(c)
+    fn ext_mul(a: [Self; 2], b: [Self; 2]) -> [Self; 2] {
+        ext2_mul([a[0].0, a[1].0], [b[0].0, b[1].0])
     }
 }
 
@@ -64,15 +57,13 @@ impl Extendable<4> for GoldilocksField {
 
     const EXT_POWER_OF_TWO_GENERATOR: [Self; 4] =
         [Self(0), Self(0), Self(0), Self(12587610116473453104)];
-}
 
-impl Mul for QuarticExtension<GoldilocksField> {
     #[inline]
-    fn mul(self, rhs: Self) -> Self {
-        let Self([a0, a1, a2, a3]) = self;
-        let Self([b0, b1, b2, b3]) = rhs;
-        let c = ext4_mul([a0.0, a1.0, a2.0, a3.0], [b0.0, b1.0, b2.0, b3.0]);
-        Self(c)
+    fn ext_mul(a: [Self; 4], b: [Self; 4]) -> [Self; 4] {
+        ext4_mul(
+            [a[0].0, a[1].0, a[2].0, a[3].0],
+            [b[0].0, b[1].0, b[2].0, b[3].0],
+        )
     }
 }
 
@@ -104,18 +95,13 @@ impl Extendable<5> for GoldilocksField {
         Self(0),
         Self(0),
     ];
-}
 
-impl Mul for QuinticExtension<GoldilocksField> {
     #[inline]
-    fn mul(self, rhs: Self) -> Self {
-        let Self([a0, a1, a2, a3, a4]) = self;
-        let Self([b0, b1, b2, b3, b4]) = rhs;
-        let c = ext5_mul(
-            [a0.0, a1.0, a2.0, a3.0, a4.0],
-            [b0.0, b1.0, b2.0, b3.0, b4.0],
-        );
-        Self(c)
+    fn ext_mul(a: [Self; 5], b: [Self; 5]) -> [Self; 5] {
+        ext5_mul(
+            [a[0].0, a[1].0, a[2].0, a[3].0, a[4].0],
+            [b[0].0, b[1].0, b[2].0, b[3].0, b[4].0],
+        )
     }
 }
 
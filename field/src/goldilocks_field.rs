@@ -8,6 +8,7 @@ use plonky2_util::{assume, branch_hint};
 use serde::{Deserialize, Serialize};
 
 use crate::inversion::try_inverse_u64;
+use crate::ops::Square;
 use crate::types::{Field, Field64, PrimeField, PrimeField64, Sample};
 
 const EPSILON: u64 = (1 << 32) - 1;
@@ -68,6 +69,8 @@ impl Sample for GoldilocksField {
 }
 
 impl Field for GoldilocksField {
+    type Packing = crate::packable::GoldilocksPacking;
+
     const ZERO: Self = Self(0);
     const ONE: Self = Self(1);
     const TWO: Self = Self(2);
@@ -285,6 +288,13 @@ impl MulAssign for GoldilocksField {
     }
 }
 
+impl Square for GoldilocksField {
+    #[inline]
+    fn square(&self) -> Self {
+        *self * *self
+    }
+}
+
 impl Product for GoldilocksField {
     fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
         iter.fold(Self::ONE, |acc, x| acc * x)
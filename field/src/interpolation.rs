@@ -3,6 +3,7 @@ use alloc::vec::Vec;
 use plonky2_util::log2_ceil;
 
 use crate::fft::ifft;
+use crate::inversion::batch_multiplicative_inverse;
 use crate::polynomial::{PolynomialCoeffs, PolynomialValues};
 use crate::types::Field;
 
@@ -36,14 +37,18 @@ pub fn interpolate<F: Field>(points: &[(F, F)], x: F, barycentric_weights: &[F])
         }
     }
 
-    let l_x: F = points.iter().map(|&(x_i, _y_i)| x - x_i).product();
+    let diffs = points
+        .iter()
+        .map(|&(x_i, _y_i)| x - x_i)
+        .collect::<Vec<_>>();
+    let l_x: F = diffs.iter().copied().product();
+    let diff_invs = batch_multiplicative_inverse(&diffs);
 
     let sum = (0..points.len())
         .map(|i| {
-            let x_i = points[i].0;
             let y_i = points[i].1;
             let w_i = barycentric_weights[i];
-            w_i / (x - x_i) * y_i
+            w_i * diff_invs[i] * y_i
         })
         .sum();
 
@@ -52,7 +57,7 @@ pub fn interpolate<F: Field>(points: &[(F, F)], x: F, barycentric_weights: &[F])
 
 pub fn barycentric_weights<F: Field>(points: &[(F, F)]) -> Vec<F> {
     let n = points.len();
-    F::batch_multiplicative_inverse(
+    batch_multiplicative_inverse(
         &(0..n)
             .map(|i| {
                 (0..n)
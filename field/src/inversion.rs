@@ -1,4 +1,68 @@
-use crate::types::PrimeField64;
+use alloc::vec::Vec;
+
+use maybe_rayon::*;
+
+use crate::types::{Field, PrimeField64};
+
+/// Number of elements handled by each Montgomery's trick invocation in the parallel batch
+/// inversion functions below. Every chunk costs a single field inversion, which is negligible
+/// compared to the roughly `3 * BATCH_INVERSION_CHUNK_SIZE` multiplications of the trick itself.
+const BATCH_INVERSION_CHUNK_SIZE: usize = 1 << 10;
+
+/// Inverts every element of `x`. The input is split into chunks which are inverted in parallel,
+/// each with Montgomery's trick (see `Field::batch_multiplicative_inverse`). Panics if any element
+/// is zero.
+pub fn batch_multiplicative_inverse<F: Field>(x: &[F]) -> Vec<F> {
+    let mut res = x.to_vec();
+    batch_multiplicative_inverse_inplace(&mut res);
+    res
+}
+
+/// Like `batch_multiplicative_inverse`, but overwrites `x` with its inverses.
+pub fn batch_multiplicative_inverse_inplace<F: Field>(x: &mut [F]) {
+    let invert_chunk = |chunk: &mut [F]| {
+        let inverses = F::batch_multiplicative_inverse(chunk);
+        chunk.copy_from_slice(&inverses);
+    };
+    // Inputs fitting in a single chunk, such as the few denominators of a FRI query, are not worth
+    // dispatching to the thread pool.
+    if x.len() <= BATCH_INVERSION_CHUNK_SIZE {
+        invert_chunk(x);
+    } else {
+        x.par_chunks_mut(BATCH_INVERSION_CHUNK_SIZE)
+            .for_each(invert_chunk);
+    }
+}
+
+/// Like `batch_multiplicative_inverse`, but maps zero to zero instead of panicking.
+pub fn batch_multiplicative_inverse_or_zero<F: Field>(x: &[F]) -> Vec<F> {
+    let mut res = x.to_vec();
+    batch_multiplicative_inverse_or_zero_inplace(&mut res);
+    res
+}
+
+/// Like `batch_multiplicative_inverse_inplace`, but leaves zeros untouched instead of panicking.
+pub fn batch_multiplicative_inverse_or_zero_inplace<F: Field>(x: &mut [F]) {
+    let invert_chunk = |chunk: &mut [F]| {
+        // Zeros are replaced by ones so they don't poison the cumulative products.
+        let nonzero = chunk
+            .iter()
+            .map(|&v| if v.is_zero() { F::ONE } else { v })
+            .collect::<Vec<_>>();
+        let inverses = F::batch_multiplicative_inverse(&nonzero);
+        chunk
+            .iter_mut()
+            .zip(inverses)
+            .filter(|(v, _)| v.is_nonzero())
+            .for_each(|(v, inv)| *v = inv);
+    };
+    if x.len() <= BATCH_INVERSION_CHUNK_SIZE {
+        invert_chunk(x);
+    } else {
+        x.par_chunks_mut(BATCH_INVERSION_CHUNK_SIZE)
+            .for_each(invert_chunk);
+    }
+}
 
 /// This is a 'safe' iteration for the modular inversion algorithm. It
 /// is safe in the sense that it will produce the right answer even
@@ -134,3 +198,74 @@ pub(crate) fn try_inverse_u64<F: PrimeField64>(x: &F) -> Option<F> {
     debug_assert!(*x * res == F::ONE, "bug in try_inverse_u64");
     Some(res)
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::extension::quadratic::QuadraticExtension;
+    use crate::goldilocks_field::GoldilocksField;
+
+    fn test_batch_inversion<F: Field>() {
+        // Sizes below, at and above the chunk size.
+        for n in [
+            0,
+            1,
+            5,
+            BATCH_INVERSION_CHUNK_SIZE,
+            3 * BATCH_INVERSION_CHUNK_SIZE + 7,
+        ] {
+            let xs = (0..n)
+                .map(|_| {
+                    let mut x = F::rand();
+                    while x.is_zero() {
+                        x = F::rand();
+                    }
+                    x
+                })
+                .collect::<Vec<_>>();
+            let invs = batch_multiplicative_inverse(&xs);
+            assert_eq!(invs.len(), n);
+            for (&x, &inv) in xs.iter().zip(&invs) {
+                assert_eq!(x * inv, F::ONE);
+            }
+            assert_eq!(batch_multiplicative_inverse_or_zero(&xs), invs);
+        }
+    }
+
+    fn test_batch_inversion_or_zero<F: Field>() {
+        // Sizes handled serially and in parallel.
+        for n in [7, 2 * BATCH_INVERSION_CHUNK_SIZE + 3] {
+            let xs = (0..n)
+                .map(|i| if i % 3 == 0 { F::ZERO } else { F::rand() })
+                .collect::<Vec<_>>();
+            let invs = batch_multiplicative_inverse_or_zero(&xs);
+            for (&x, &inv) in xs.iter().zip(&invs) {
+                if x.is_zero() {
+                    assert!(inv.is_zero());
+                } else {
+                    assert_eq!(x * inv, F::ONE);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_batch_inversion_base() {
+        test_batch_inversion::<GoldilocksField>();
+        test_batch_inversion_or_zero::<GoldilocksField>();
+    }
+
+    #[test]
+    fn test_batch_inversion_extension() {
+        test_batch_inversion::<QuadraticExtension<GoldilocksField>>();
+        test_batch_inversion_or_zero::<QuadraticExtension<GoldilocksField>>();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_batch_inversion_zero_panics() {
+        batch_multiplicative_inverse(&[GoldilocksField::ONE, GoldilocksField::ZERO]);
+    }
+}
@@ -1,19 +1,13 @@
-#![allow(incomplete_features)]
 #![allow(clippy::new_without_default)]
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::type_complexity)]
 #![allow(clippy::len_without_is_empty)]
 #![allow(clippy::needless_range_loop)]
 #![allow(clippy::return_self_not_must_use)]
-#![feature(generic_const_exprs)]
-#![feature(stdsimd)]
-#![feature(specialization)]
 // #![cfg_attr(not(test), no_std)]
 
 extern crate alloc;
 
-mod inversion;
-
 pub(crate) mod arch;
 
 pub mod batch_util;
@@ -23,6 +17,7 @@ pub mod fft;
 pub mod goldilocks_extensions;
 pub mod goldilocks_field;
 pub mod interpolation;
+pub mod inversion;
 pub mod ops;
 pub mod packable;
 pub mod packed;
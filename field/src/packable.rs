@@ -3,13 +3,14 @@ use crate::types::Field;
 
 /// Points us to the default packing for a particular field. There may me multiple choices of
 /// PackedField for a particular Field (e.g. every Field is also a PackedField), but this is the
-/// recommended one. The recommended packing varies by target_arch and target_feature.
+/// recommended one. The recommended packing varies by target_arch and target_feature, and is
+/// declared by each field as `Field::Packing`.
 pub trait Packable: Field {
     type Packing: PackedField<Scalar = Self>;
 }
 
 impl<F: Field> Packable for F {
-    default type Packing = Self;
+    type Packing = <F as Field>::Packing;
 }
 
 #[cfg(all(
@@ -23,9 +24,7 @@ impl<F: Field> Packable for F {
         target_feature = "avx512vl"
     ))
 ))]
-impl Packable for crate::goldilocks_field::GoldilocksField {
-    type Packing = crate::arch::x86_64::avx2_goldilocks_field::Avx2GoldilocksField;
-}
+pub(crate) type GoldilocksPacking = crate::arch::x86_64::avx2_goldilocks_field::Avx2GoldilocksField;
 
 #[cfg(all(
     target_arch = "x86_64",
@@ -35,6 +34,8 @@ impl Packable for crate::goldilocks_field::GoldilocksField {
     target_feature = "avx512f",
     target_feature = "avx512vl"
 ))]
-impl Packable for crate::goldilocks_field::GoldilocksField {
-    type Packing = crate::arch::x86_64::avx512_goldilocks_field::Avx512GoldilocksField;
-}
+pub(crate) type GoldilocksPacking =
+    crate::arch::x86_64::avx512_goldilocks_field::Avx512GoldilocksField;
+
+#[cfg(not(all(target_arch = "x86_64", target_feature = "avx2")))]
+pub(crate) type GoldilocksPacking = crate::goldilocks_field::GoldilocksField;
@@ -1,4 +1,5 @@
 pub(crate) mod division;
+pub mod multilinear;
 
 use alloc::vec;
 use alloc::vec::Vec;
@@ -11,8 +12,10 @@ use itertools::Itertools;
 use plonky2_util::log2_strict;
 use serde::{Deserialize, Serialize};
 
+use crate::batch_util::batch_multiply_inplace;
 use crate::extension::{Extendable, FieldExtension};
 use crate::fft::{fft, fft_with_options, ifft, FftRootTable};
+use crate::inversion::{batch_multiplicative_inverse, batch_multiplicative_inverse_inplace};
 use crate::types::Field;
 
 /// A polynomial in point-value form.
@@ -97,6 +100,20 @@ impl<F: Field> PolynomialValues<F> {
         self.clone().ifft().degree_plus_one()
     }
 
+    /// Returns the pointwise inverse of `self`. Panics if any value is zero.
+    pub fn batch_multiplicative_inverse(&self) -> Self {
+        Self::new(batch_multiplicative_inverse(&self.values))
+    }
+
+    /// Returns the pointwise quotient `self / rhs`. Assumes `self.len() == rhs.len()`, and panics
+    /// if any value of `rhs` is zero.
+    pub fn div_pointwise(&self, rhs: &Self) -> Self {
+        let mut values = rhs.values.clone();
+        batch_multiplicative_inverse_inplace(&mut values);
+        batch_multiply_inplace(&mut values, &self.values);
+        Self::new(values)
+    }
+
     /// Adds `rhs * rhs_weight` to `self`. Assumes `self.len() == rhs.len()`.
     pub fn add_assign_scaled(&mut self, rhs: &Self, rhs_weight: F) {
         self.values
@@ -475,6 +492,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_div_pointwise() {
+        type F = GoldilocksField;
+
+        let n = 1 << 6;
+        let a = PolynomialValues::new(F::rand_vec(n));
+        let b = PolynomialValues::new(F::rand_vec(n));
+        let quotient = a.div_pointwise(&b);
+        for ((&a_i, &b_i), &q_i) in a.values.iter().zip(&b.values).zip(&quotient.values) {
+            assert_eq!(q_i * b_i, a_i);
+        }
+        assert_eq!(b.div_pointwise(&b), PolynomialValues::constant(F::ONE, n));
+    }
+
     #[test]
     fn test_coset_fft() {
         type F = GoldilocksField;
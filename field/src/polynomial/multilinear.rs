@@ -0,0 +1,180 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use plonky2_util::log2_strict;
+
+use crate::extension::{Extendable, FieldExtension};
+use crate::types::Field;
+
+/// A multilinear polynomial in `num_vars` variables, represented by its evaluations on the boolean
+/// hypercube `{0, 1}^num_vars`.
+///
+/// The evaluation at `(x_0, ..., x_{n-1})` is stored at index `sum_i x_i 2^i`, i.e. `x_0` is the
+/// least significant bit of the index.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MultilinearPolynomial<F: Field> {
+    pub evals: Vec<F>,
+}
+
+impl<F: Field> MultilinearPolynomial<F> {
+    pub fn new(evals: Vec<F>) -> Self {
+        assert!(
+            evals.len().is_power_of_two(),
+            "Number of evaluations must be a power of two"
+        );
+        Self { evals }
+    }
+
+    pub fn constant(value: F) -> Self {
+        Self::new(vec![value])
+    }
+
+    pub fn zero(num_vars: usize) -> Self {
+        Self::new(vec![F::ZERO; 1 << num_vars])
+    }
+
+    pub fn num_vars(&self) -> usize {
+        log2_strict(self.evals.len())
+    }
+
+    /// The number of evaluations, `2^num_vars`.
+    pub fn len(&self) -> usize {
+        self.evals.len()
+    }
+
+    /// Returns the multilinear polynomial `eq(point, .)`, i.e. the polynomial whose evaluation at
+    /// a boolean point `b` is one if `b == point` and zero otherwise, extended multilinearly.
+    pub fn eq_polynomial(point: &[F]) -> Self {
+        Self::new(eq_evals(point))
+    }
+
+    /// Evaluates the multilinear extension at `point`.
+    pub fn evaluate(&self, point: &[F]) -> F {
+        assert_eq!(point.len(), self.num_vars(), "Wrong number of variables");
+        self.partial_evaluate(point).evals[0]
+    }
+
+    /// Fixes the first variable `x_0` to `r`, returning a polynomial in the remaining
+    /// `num_vars - 1` variables.
+    pub fn fix_first_variable(&self, r: F) -> Self {
+        assert!(self.num_vars() > 0, "No variable left to fix");
+        let evals = self
+            .evals
+            .chunks_exact(2)
+            .map(|pair| pair[0] + r * (pair[1] - pair[0]))
+            .collect();
+        Self { evals }
+    }
+
+    /// Fixes the first `point.len()` variables to the coordinates of `point`.
+    pub fn partial_evaluate(&self, point: &[F]) -> Self {
+        assert!(point.len() <= self.num_vars(), "Too many variables");
+        point
+            .iter()
+            .fold(self.clone(), |acc, &r| acc.fix_first_variable(r))
+    }
+
+    pub fn to_extension<const D: usize>(&self) -> MultilinearPolynomial<F::Extension>
+    where
+        F: Extendable<D>,
+    {
+        MultilinearPolynomial::new(
+            self.evals
+                .iter()
+                .map(|&x| F::Extension::from_basefield(x))
+                .collect(),
+        )
+    }
+}
+
+impl<F: Field> From<Vec<F>> for MultilinearPolynomial<F> {
+    fn from(evals: Vec<F>) -> Self {
+        Self::new(evals)
+    }
+}
+
+/// Returns the evaluations of `eq(point, .)` on the boolean hypercube, using the same index
+/// ordering as `MultilinearPolynomial`.
+pub fn eq_evals<F: Field>(point: &[F]) -> Vec<F> {
+    let mut evals = Vec::with_capacity(1 << point.len());
+    evals.push(F::ONE);
+    for &r in point {
+        // Indices with the new bit set are appended after the existing ones.
+        let len = evals.len();
+        for i in 0..len {
+            let v = evals[i] * r;
+            evals[i] -= v;
+            evals.push(v);
+        }
+    }
+    evals
+}
+
+/// Evaluates `eq(x, y) = prod_i (x_i y_i + (1 - x_i)(1 - y_i))`.
+pub fn eval_eq<F: Field>(x: &[F], y: &[F]) -> F {
+    assert_eq!(x.len(), y.len(), "Points have different dimensions");
+    x.iter()
+        .zip(y)
+        .map(|(&x_i, &y_i)| x_i * y_i + (F::ONE - x_i) * (F::ONE - y_i))
+        .product()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::goldilocks_field::GoldilocksField;
+    use crate::types::Sample;
+
+    type F = GoldilocksField;
+
+    fn boolean_point(index: usize, num_vars: usize) -> Vec<F> {
+        (0..num_vars)
+            .map(|i| F::from_bool((index >> i) & 1 == 1))
+            .collect()
+    }
+
+    #[test]
+    fn test_evaluate_on_hypercube() {
+        let num_vars = 4;
+        let poly = MultilinearPolynomial::new(F::rand_vec(1 << num_vars));
+        for i in 0..poly.len() {
+            assert_eq!(poly.evaluate(&boolean_point(i, num_vars)), poly.evals[i]);
+        }
+    }
+
+    #[test]
+    fn test_evaluate_matches_eq_sum() {
+        let num_vars = 5;
+        let poly = MultilinearPolynomial::new(F::rand_vec(1 << num_vars));
+        let point = F::rand_vec(num_vars);
+        let expected = poly
+            .evals
+            .iter()
+            .zip(eq_evals(&point))
+            .map(|(&v, e)| v * e)
+            .sum::<F>();
+        assert_eq!(poly.evaluate(&point), expected);
+    }
+
+    #[test]
+    fn test_partial_evaluate() {
+        let num_vars = 5;
+        let poly = MultilinearPolynomial::new(F::rand_vec(1 << num_vars));
+        let point = F::rand_vec(num_vars);
+        let partial = poly.partial_evaluate(&point[..2]);
+        assert_eq!(partial.num_vars(), num_vars - 2);
+        assert_eq!(partial.evaluate(&point[2..]), poly.evaluate(&point));
+    }
+
+    #[test]
+    fn test_eq() {
+        let num_vars = 3;
+        let x = F::rand_vec(num_vars);
+        let eq = MultilinearPolynomial::eq_polynomial(&x);
+        for i in 0..eq.len() {
+            assert_eq!(eq.evals[i], eval_eq(&x, &boolean_point(i, num_vars)));
+        }
+        let y = F::rand_vec(num_vars);
+        assert_eq!(eq.evaluate(&y), eval_eq(&x, &y));
+    }
+}
@@ -9,6 +9,7 @@ use num::bigint::BigUint;
 use num::{Integer, One};
 use serde::{Deserialize, Serialize};
 
+use crate::ops::Square;
 use crate::types::{Field, PrimeField, Sample};
 
 /// The base field of the secp256k1 elliptic curve.
@@ -79,6 +80,8 @@ impl Sample for Secp256K1Scalar {
 }
 
 impl Field for Secp256K1Scalar {
+    type Packing = Self;
+
     const ZERO: Self = Self([0; 4]);
     const ONE: Self = Self([1, 0, 0, 0]);
     const TWO: Self = Self([2, 0, 0, 0]);
@@ -243,6 +246,13 @@ impl MulAssign for Secp256K1Scalar {
     }
 }
 
+impl Square for Secp256K1Scalar {
+    #[inline]
+    fn square(&self) -> Self {
+        *self * *self
+    }
+}
+
 impl Product for Secp256K1Scalar {
     #[inline]
     fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
@@ -14,6 +14,7 @@ use serde::Serialize;
 
 use crate::extension::Frobenius;
 use crate::ops::Square;
+use crate::packed::PackedField;
 
 /// Sampling
 pub trait Sample: Sized {
@@ -71,6 +72,9 @@ pub trait Field:
     + Serialize
     + DeserializeOwned
 {
+    /// The recommended packing of this field. See [`Packable`](crate::packable::Packable).
+    type Packing: PackedField<Scalar = Self>;
+
     const ZERO: Self;
     const ONE: Self;
     const TWO: Self;
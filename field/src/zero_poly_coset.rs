@@ -1,5 +1,6 @@
 use alloc::vec::Vec;
 
+use crate::inversion::batch_multiplicative_inverse;
 use crate::packed::PackedField;
 use crate::types::Field;
 
@@ -23,7 +24,7 @@ impl<F: Field> ZeroPolyOnCoset<F> {
             .into_iter()
             .map(|x| g_pow_n * x - F::ONE)
             .collect::<Vec<_>>();
-        let inverses = F::batch_multiplicative_inverse(&evals);
+        let inverses = batch_multiplicative_inverse(&evals);
         Self {
             n: F::from_canonical_usize(1 << n_log),
             rate: 1 << rate_bits,
@@ -55,7 +56,20 @@ impl<F: Field> ZeroPolyOnCoset<F> {
 
     /// Returns `L_0(x) = Z_H(x)/(n * (x - 1))` with `x = w^i`.
     pub fn eval_l_0(&self, i: usize, x: F) -> F {
-        // Could also precompute the inverses using Montgomery.
         self.eval(i) * (self.n * (x - F::ONE)).inverse()
     }
+
+    /// Like `eval_l_0`, but for a batch of points, sharing a single inversion between them.
+    pub fn eval_l_0_batch(&self, indices: &[usize], xs: &[F]) -> Vec<F> {
+        debug_assert_eq!(indices.len(), xs.len());
+        let denominators = xs
+            .iter()
+            .map(|&x| self.n * (x - F::ONE))
+            .collect::<Vec<_>>();
+        indices
+            .iter()
+            .zip(batch_multiplicative_inverse(&denominators))
+            .map(|(&i, denominator_inv)| self.eval(i) * denominator_inv)
+            .collect()
+    }
 }
@@ -1,11 +1,16 @@
 mod allocator;
 
 use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use plonky2::field::extension::packed::PackedExtension;
 use plonky2::field::extension::quadratic::QuadraticExtension;
 use plonky2::field::extension::quartic::QuarticExtension;
 use plonky2::field::extension::quintic::QuinticExtension;
+use plonky2::field::extension::Extendable;
 use plonky2::field::goldilocks_field::GoldilocksField;
-use plonky2::field::types::Field;
+use plonky2::field::ops::Square;
+use plonky2::field::packable::Packable;
+use plonky2::field::packed::PackedField;
+use plonky2::field::types::{Field, Sample};
 use tynm::type_name;
 
 pub(crate) fn bench_field<F: Field>(c: &mut Criterion) {
@@ -176,11 +181,68 @@ pub(crate) fn bench_field<F: Field>(c: &mut Criterion) {
     );
 }
 
+pub(crate) fn bench_packed_extension<F: Extendable<D>, const D: usize>(c: &mut Criterion) {
+    type P<F> = <F as Packable>::Packing;
+
+    let name = format!(
+        "PackedExtension<{}, {}>",
+        type_name::<P<F>>(),
+        type_name::<F::Extension>()
+    );
+    let rand_packed = || PackedExtension::<P<F>, D>::pack(&F::Extension::rand_vec(P::<F>::WIDTH));
+
+    c.bench_function(&format!("mul-throughput<{}>", name), |b| {
+        b.iter_batched(
+            || (rand_packed(), rand_packed(), rand_packed(), rand_packed()),
+            |(mut x, mut y, mut z, mut w)| {
+                for _ in 0..25 {
+                    (x, y, z, w) = (x * y, y * z, z * w, w * x);
+                }
+                (x, y, z, w)
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function(&format!("mul-latency<{}>", name), |b| {
+        b.iter_batched(
+            rand_packed,
+            |mut x| {
+                for _ in 0..100 {
+                    x = x * x;
+                }
+                x
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function(&format!("sqr-throughput<{}>", name), |b| {
+        b.iter_batched(
+            || (rand_packed(), rand_packed(), rand_packed(), rand_packed()),
+            |(mut x, mut y, mut z, mut w)| {
+                for _ in 0..25 {
+                    (x, y, z, w) = (x.square(), y.square(), z.square(), w.square());
+                }
+                (x, y, z, w)
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function(&format!("inverse<{}>", name), |b| {
+        b.iter_batched(rand_packed, |x| x.inverse(), BatchSize::SmallInput)
+    });
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     bench_field::<GoldilocksField>(c);
     bench_field::<QuadraticExtension<GoldilocksField>>(c);
     bench_field::<QuarticExtension<GoldilocksField>>(c);
     bench_field::<QuinticExtension<GoldilocksField>>(c);
+    bench_packed_extension::<GoldilocksField, 2>(c);
+    bench_packed_extension::<GoldilocksField, 4>(c);
+    bench_packed_extension::<GoldilocksField, 5>(c);
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,51 @@
+mod allocator;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use plonky2::field::extension::Extendable;
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::field::types::Sample;
+use plonky2::gates::arithmetic_base::ArithmeticGate;
+use plonky2::gates::arithmetic_extension::ArithmeticExtensionGate;
+use plonky2::gates::gate::Gate;
+use plonky2::gates::multiplication_extension::MulExtensionGate;
+use plonky2::hash::hash_types::{HashOut, RichField};
+use plonky2::plonk::circuit_data::CircuitConfig;
+use plonky2::plonk::vars::EvaluationVarsBaseBatch;
+use tynm::type_name;
+
+/// Number of points evaluated together when the prover computes the quotient polynomials.
+const BATCH_SIZE: usize = 32;
+
+/// Benchmarks the batched base-field constraint evaluation used by the quotient computation.
+fn bench_gate<F: RichField + Extendable<D>, G: Gate<F, D>, const D: usize>(
+    c: &mut Criterion,
+    gate: G,
+) {
+    let local_constants = F::rand_vec(BATCH_SIZE * gate.num_constants());
+    let local_wires = F::rand_vec(BATCH_SIZE * gate.num_wires());
+    let public_inputs_hash = HashOut::rand();
+    let vars_batch = EvaluationVarsBaseBatch::new(
+        BATCH_SIZE,
+        &local_constants,
+        &local_wires,
+        &public_inputs_hash,
+    );
+
+    c.bench_function(
+        &format!("eval_unfiltered_base_batch<{}>", type_name::<G>()),
+        |b| b.iter(|| gate.eval_unfiltered_base_batch(vars_batch)),
+    );
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    type F = GoldilocksField;
+    const D: usize = 2;
+
+    let config = CircuitConfig::standard_recursion_config();
+    bench_gate::<F, _, D>(c, ArithmeticGate::new_from_config(&config));
+    bench_gate::<F, _, D>(c, ArithmeticExtensionGate::<D>::new_from_config(&config));
+    bench_gate::<F, _, D>(c, MulExtensionGate::<D>::new_from_config(&config));
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,85 @@
+mod allocator;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use plonky2::field::types::Sample;
+use plonky2::gates::noop::NoopGate;
+use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::CircuitConfig;
+use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+use plonky2::plonk::proof::CompressedProofWithPublicInputs;
+
+const D: usize = 2;
+type C = PoseidonGoldilocksConfig;
+type F = <C as GenericConfig<D>>::F;
+
+/// Compares the size of the `to_bytes`, compressed and compact proof encodings. Criterion measures
+/// the encoding time; the encoded sizes are printed and reported as throughput.
+fn bench_proof_size(c: &mut Criterion) {
+    let mut group = c.benchmark_group("proof-size");
+    group.sample_size(10);
+
+    for log_num_gates in [12, 14] {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = builder.mul(x, y);
+        builder.register_public_input(z);
+        while builder.num_gates() < 1 << log_num_gates {
+            builder.add_gate(NoopGate, vec![]);
+        }
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::rand());
+        pw.set_target(y, F::rand());
+        let data = builder.build::<C>();
+        let degree_bits = data.common.degree_bits();
+        let proof = data.prove(pw).unwrap();
+        let compressed_proof = data.compress(proof.clone()).unwrap();
+
+        let proof_bytes = proof.to_bytes();
+        let compressed_bytes = compressed_proof.to_bytes();
+        let compact_bytes = compressed_proof.to_compact_bytes(&data.common);
+        assert_eq!(
+            CompressedProofWithPublicInputs::from_compact_bytes(
+                compact_bytes.clone(),
+                &data.verifier_only.circuit_digest,
+                &data.common,
+            )
+            .unwrap(),
+            compressed_proof
+        );
+        println!(
+            "degree 2^{degree_bits}: to_bytes {} bytes, compressed {} bytes, compact {} bytes",
+            proof_bytes.len(),
+            compressed_bytes.len(),
+            compact_bytes.len(),
+        );
+
+        group.throughput(Throughput::Bytes(proof_bytes.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("to_bytes", degree_bits),
+            &degree_bits,
+            |b, _| b.iter(|| proof.to_bytes()),
+        );
+        group.throughput(Throughput::Bytes(compressed_bytes.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("compressed", degree_bits),
+            &degree_bits,
+            |b, _| b.iter(|| compressed_proof.to_bytes()),
+        );
+        group.throughput(Throughput::Bytes(compact_bytes.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("compact", degree_bits),
+            &degree_bits,
+            |b, _| b.iter(|| compressed_proof.to_compact_bytes(&data.common)),
+        );
+    }
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    bench_proof_size(c);
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
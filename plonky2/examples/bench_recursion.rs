@@ -169,6 +169,15 @@ fn test_serialization<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>,
         CompressedProofWithPublicInputs::from_bytes(compressed_proof_bytes, cd)?;
     assert_eq!(compressed_proof, compressed_proof_from_bytes);
 
+    let compact_proof_bytes = compressed_proof.to_compact_bytes(cd);
+    info!("Compact proof length: {} bytes", compact_proof_bytes.len());
+    let compact_proof_from_bytes = CompressedProofWithPublicInputs::from_compact_bytes(
+        compact_proof_bytes,
+        &vd.circuit_digest,
+        cd,
+    )?;
+    assert_eq!(compressed_proof, compact_proof_from_bytes);
+
     Ok(())
 }
 
@@ -1,12 +1,15 @@
+use alloc::collections::BTreeMap;
 use alloc::vec;
 use alloc::vec::Vec;
 
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
 use itertools::izip;
 use serde::{Deserialize, Serialize};
 
 use crate::field::extension::{flatten, unflatten, Extendable};
 use crate::field::polynomial::PolynomialCoeffs;
+use crate::fri::structure::{FriInstanceInfo, FriOpenings};
+use crate::fri::verifier::{compute_evaluation, fri_combine_initial, PrecomputedReducedOpenings};
 use crate::fri::FriParams;
 use crate::gadgets::polynomial::PolynomialCoeffsExtTarget;
 use crate::hash::hash_types::{MerkleCapTarget, RichField};
@@ -17,7 +20,8 @@ use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::target::Target;
 use crate::plonk::config::{GenericConfig, Hasher};
 use crate::plonk::plonk_common::salt_size;
-use crate::plonk::proof::{FriInferredElements, ProofChallenges};
+use crate::plonk::proof::FriInferredElements;
+use crate::util::reverse_bits;
 
 /// Evaluations and Merkle proof produced by the prover in a FRI query step.
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
@@ -240,10 +244,69 @@ impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> FriProof<F, H,
 }
 
 impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> CompressedFriProof<F, H, D> {
+    /// Computes all coset elements that can be inferred in the FRI reduction steps, for a proof of
+    /// the given openings of `instance`.
+    pub fn get_inferred_elements<C: GenericConfig<D, F = F, Hasher = H>>(
+        &self,
+        instance: &FriInstanceInfo<F, D>,
+        openings: &FriOpenings<F, D>,
+        challenges: &FriChallenges<F, D>,
+        params: &FriParams,
+    ) -> FriInferredElements<F, D> {
+        let FriChallenges {
+            fri_alpha,
+            fri_betas,
+            fri_query_indices,
+            ..
+        } = challenges;
+        let mut fri_inferred_elements = Vec::new();
+        // Holds the indices that have already been seen at each reduction depth.
+        let mut seen_indices_by_depth = vec![HashSet::new(); params.reduction_arity_bits.len()];
+        let precomputed_reduced_evals =
+            PrecomputedReducedOpenings::from_os_and_alpha(openings, *fri_alpha);
+        let log_n = params.degree_bits + params.config.rate_bits;
+        // Simulate the proof verification and collect the inferred elements.
+        // The content of the loop is basically the same as the `fri_verifier_query_round` function.
+        for &(mut x_index) in fri_query_indices {
+            let mut subgroup_x = F::MULTIPLICATIVE_GROUP_GENERATOR
+                * F::primitive_root_of_unity(log_n).exp_u64(reverse_bits(x_index, log_n) as u64);
+            let mut old_eval = fri_combine_initial::<F, C, D>(
+                instance,
+                &self.query_round_proofs.initial_trees_proofs[&x_index],
+                *fri_alpha,
+                subgroup_x,
+                &precomputed_reduced_evals,
+                params,
+            );
+            for (i, &arity_bits) in params.reduction_arity_bits.iter().enumerate() {
+                let coset_index = x_index >> arity_bits;
+                if !seen_indices_by_depth[i].insert(coset_index) {
+                    // If this index has already been seen, we can skip the rest of the reductions.
+                    break;
+                }
+                fri_inferred_elements.push(old_eval);
+                let arity = 1 << arity_bits;
+                let mut evals = self.query_round_proofs.steps[i][&coset_index].evals.clone();
+                let x_index_within_coset = x_index & (arity - 1);
+                evals.insert(x_index_within_coset, old_eval);
+                old_eval = compute_evaluation(
+                    subgroup_x,
+                    x_index_within_coset,
+                    arity_bits,
+                    &evals,
+                    fri_betas[i],
+                );
+                subgroup_x = subgroup_x.exp_power_of_2(arity_bits);
+                x_index = coset_index;
+            }
+        }
+        FriInferredElements(fri_inferred_elements)
+    }
+
     /// Decompress all the Merkle paths in the FRI proof and reinsert duplicate indices.
-    pub(crate) fn decompress<C: GenericConfig<D, F = F, Hasher = H>>(
+    pub fn decompress<C: GenericConfig<D, F = F, Hasher = H>>(
         self,
-        challenges: &ProofChallenges<F, D>,
+        challenges: &FriChallenges<F, D>,
         fri_inferred_elements: FriInferredElements<F, D>,
         params: &FriParams,
     ) -> FriProof<F, H, D> {
@@ -257,7 +320,7 @@ impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> CompressedFriPr
         let FriChallenges {
             fri_query_indices: indices,
             ..
-        } = &challenges.fri_challenges;
+        } = challenges;
         let mut fri_inferred_elements = fri_inferred_elements.0.into_iter();
         let cap_height = params.config.cap_height;
         let reduction_arity_bits = &params.reduction_arity_bits;
@@ -364,6 +427,33 @@ impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> CompressedFriPr
     }
 }
 
+/// For each FRI reduction step, maps the index of each coset reached by a query to the distinct
+/// positions within that coset reached by the queries, in order of first appearance in `indices`.
+///
+/// Every reached position holds an evaluation the verifier infers from the previous step, so the
+/// first one is omitted from compressed proofs and all of them from compact proofs.
+pub(crate) fn fri_coset_positions(
+    indices: &[usize],
+    reduction_arity_bits: &[usize],
+) -> Vec<BTreeMap<usize, Vec<usize>>> {
+    let mut indices = indices.to_vec();
+    reduction_arity_bits
+        .iter()
+        .map(|&arity_bits| {
+            let mut cosets = BTreeMap::<usize, Vec<usize>>::new();
+            for index in &mut indices {
+                let positions = cosets.entry(*index >> arity_bits).or_default();
+                let position = *index & ((1 << arity_bits) - 1);
+                if !positions.contains(&position) {
+                    positions.push(position);
+                }
+                *index >>= arity_bits;
+            }
+            cosets
+        })
+        .collect()
+}
+
 pub struct FriChallenges<F: RichField + Extendable<D>, const D: usize> {
     // Scaling factor to combine polynomials.
     pub fri_alpha: F::Extension,
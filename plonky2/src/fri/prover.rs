@@ -1,9 +1,14 @@
+use alloc::vec;
 use alloc::vec::Vec;
 
 use maybe_rayon::*;
 
+use crate::field::extension::packed::PackedExtension;
 use crate::field::extension::{flatten, unflatten, Extendable};
+use crate::field::packable::Packable;
+use crate::field::packed::PackedField;
 use crate::field::polynomial::{PolynomialCoeffs, PolynomialValues};
+use crate::field::types::Field;
 use crate::fri::proof::{FriInitialTreeProof, FriProof, FriQueryRound, FriQueryStep};
 use crate::fri::{FriConfig, FriParams};
 use crate::hash::hash_types::RichField;
@@ -91,13 +96,7 @@ fn fri_committed_trees<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>,
 
         let beta = challenger.get_extension_challenge::<D>();
         // P(x) = sum_{i<r} x^i * P_i(x^r) becomes sum_{i<r} beta^i * P_i(x).
-        coeffs = PolynomialCoeffs::new(
-            coeffs
-                .coeffs
-                .par_chunks_exact(arity)
-                .map(|chunk| reduce_with_powers(chunk, beta))
-                .collect::<Vec<_>>(),
-        );
+        coeffs = PolynomialCoeffs::new(fold_coeffs::<F, D>(&coeffs.coeffs, arity, beta));
         shift = shift.exp_u64(arity as u64);
         values = coeffs.coset_fft(shift.into())
     }
@@ -111,6 +110,43 @@ fn fri_committed_trees<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>,
     (trees, coeffs)
 }
 
+/// Folds `coeffs` by `arity` with the challenge `beta`, i.e. maps every chunk `c` of `arity`
+/// coefficients to `sum_i beta^i * c_i`. `P::WIDTH` consecutive chunks are folded together using
+/// `PackedExtension` arithmetic, and any leftover chunks are folded one at a time.
+fn fold_coeffs<F: RichField + Extendable<D>, const D: usize>(
+    coeffs: &[F::Extension],
+    arity: usize,
+    beta: F::Extension,
+) -> Vec<F::Extension> {
+    type P<F> = <F as Packable>::Packing;
+
+    debug_assert_eq!(coeffs.len() % arity, 0);
+    let mut folded = vec![F::Extension::ZERO; coeffs.len() / arity];
+    let packed_beta = PackedExtension::<P<F>, D>::broadcast(beta);
+
+    let packed_chunk_len = arity * P::<F>::WIDTH;
+    let (folded_packed, folded_leftovers) =
+        folded.split_at_mut(coeffs.len() / packed_chunk_len * P::<F>::WIDTH);
+    let (coeffs_packed, coeffs_leftovers) = coeffs.split_at(folded_packed.len() * arity);
+
+    folded_packed
+        .par_chunks_exact_mut(P::<F>::WIDTH)
+        .zip(coeffs_packed.par_chunks_exact(packed_chunk_len))
+        .for_each(|(out, chunk)| {
+            // Lane `j` folds the `j`th group of `arity` coefficients with Horner's method.
+            let res = (0..arity).rev().fold(PackedExtension::ZEROS, |acc, i| {
+                acc * packed_beta + PackedExtension::from_fn(|j| chunk[j * arity + i])
+            });
+            res.unpack_into(out);
+        });
+    folded_leftovers
+        .iter_mut()
+        .zip(coeffs_leftovers.chunks_exact(arity))
+        .for_each(|(out, chunk)| *out = reduce_with_powers(chunk, beta));
+
+    folded
+}
+
 /// Performs the proof-of-work (a.k.a. grinding) step of the FRI protocol. Returns the PoW witness.
 fn fri_proof_of_work<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
     challenger: &mut Challenger<F, C::Hasher>,
@@ -217,3 +253,28 @@ fn fri_prover_query_round<
         steps: query_steps,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::types::Sample;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn test_fold_coeffs() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type FF = <C as GenericConfig<D>>::FE;
+
+        for (n, arity) in [(64, 2), (64, 8), (24, 8), (8, 8)] {
+            let coeffs = FF::rand_vec(n);
+            let beta = FF::rand();
+            let expected = coeffs
+                .chunks_exact(arity)
+                .map(|chunk| reduce_with_powers(chunk, beta))
+                .collect::<Vec<_>>();
+            assert_eq!(fold_coeffs::<F, D>(&coeffs, arity, beta), expected);
+        }
+    }
+}
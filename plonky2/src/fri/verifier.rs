@@ -4,6 +4,7 @@ use anyhow::{ensure, Result};
 
 use crate::field::extension::{flatten, Extendable, FieldExtension};
 use crate::field::interpolation::{barycentric_weights, interpolate};
+use crate::field::inversion::batch_multiplicative_inverse;
 use crate::field::types::Field;
 use crate::fri::proof::{FriChallenges, FriInitialTreeProof, FriProof, FriQueryRound};
 use crate::fri::structure::{FriBatchInfo, FriInstanceInfo, FriOpenings};
@@ -136,12 +137,21 @@ pub(crate) fn fri_combine_initial<
     let mut alpha = ReducingFactor::new(alpha);
     let mut sum = F::Extension::ZERO;
 
-    for (batch, reduced_openings) in instance
+    // The denominators `subgroup_x - z_i` of all batches are inverted together.
+    let denominators = instance
+        .batches
+        .iter()
+        .map(|batch| subgroup_x - batch.point)
+        .collect::<Vec<_>>();
+    let denominator_invs = batch_multiplicative_inverse(&denominators);
+
+    for ((batch, reduced_openings), denominator_inv) in instance
         .batches
         .iter()
         .zip(&precomputed_reduced_evals.reduced_openings_at_point)
+        .zip(denominator_invs)
     {
-        let FriBatchInfo { point, polynomials } = batch;
+        let FriBatchInfo { polynomials, .. } = batch;
         let evals = polynomials
             .iter()
             .map(|p| {
@@ -152,9 +162,8 @@ pub(crate) fn fri_combine_initial<
             .map(F::Extension::from_basefield);
         let reduced_evals = alpha.reduce(evals);
         let numerator = reduced_evals - *reduced_openings;
-        let denominator = subgroup_x - *point;
         sum = alpha.shift(sum);
-        sum += numerator / denominator;
+        sum += numerator * denominator_inv;
     }
 
     // Multiply the final polynomial by `X`, so that `final_poly` has the maximum degree for
@@ -7,3 +7,4 @@ pub mod range_check;
 pub mod select;
 pub mod split_base;
 pub(crate) mod split_join;
+pub mod var_len_array;
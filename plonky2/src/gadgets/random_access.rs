@@ -38,6 +38,35 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         claimed_element
     }
 
+    /// Like `random_access`, but for vectors of any length. The vector is padded with zeros to a
+    /// power of two, and split across several `RandomAccessGate`s if it is too long for one.
+    /// Note: `access_index` is only range-checked when the vector is split.
+    pub fn random_access_padded(&mut self, access_index: Target, mut v: Vec<Target>) -> Target {
+        let zero = self.zero();
+        v.resize(v.len().next_power_of_two(), zero);
+        let bits = log2_strict(v.len());
+        let max_bits = self.max_random_access_bits();
+        if bits <= max_bits {
+            return self.random_access(access_index, v);
+        }
+
+        let (low, high) = self.split_low_high(access_index, max_bits, bits);
+        let chunk_elements = v
+            .chunks(1 << max_bits)
+            .map(|chunk| self.random_access(low, chunk.to_vec()))
+            .collect();
+        self.random_access_padded(high, chunk_elements)
+    }
+
+    /// The log2 of the size of the largest vector a single `RandomAccessGate` can access.
+    fn max_random_access_bits(&self) -> usize {
+        let mut bits = 1;
+        while RandomAccessGate::<F, D>::new_from_config(&self.config, bits + 1).num_copies > 0 {
+            bits += 1;
+        }
+        bits
+    }
+
     /// Checks that an `ExtensionTarget` matches a vector at a non-deterministic index.
     /// Note: `access_index` is not range-checked.
     pub fn random_access_extension(
@@ -96,4 +125,30 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_random_access_padded() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        let config = CircuitConfig::standard_recursion_config();
+        let pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        // Too long for a single gate, and not a power of two.
+        let len = 150;
+        let vec = F::rand_vec(len);
+        let v: Vec<_> = vec.iter().map(|&x| builder.constant(x)).collect();
+        for i in [0, 63, 64, 100, len - 1] {
+            let it = builder.constant(F::from_canonical_usize(i));
+            let elem = builder.constant(vec[i]);
+            let res = builder.random_access_padded(it, v.clone());
+            builder.connect(elem, res);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
 }
@@ -0,0 +1,365 @@
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::{HashOutTarget, RichField};
+use crate::hash::hashing::{SPONGE_RATE, SPONGE_WIDTH};
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::config::AlgebraicHasher;
+use crate::util::{ceil_div_usize, log2_ceil};
+
+/// An array of at most `data.len()` targets, whose length is itself a target.
+#[derive(Clone, Debug)]
+pub struct VarLenArrayTarget {
+    /// The number of elements in the array, at most `data.len()`.
+    pub len: Target,
+    /// The elements of the array. Only the first `len` are meaningful; the others are
+    /// unconstrained.
+    pub data: Vec<Target>,
+}
+
+impl VarLenArrayTarget {
+    pub fn max_len(&self) -> usize {
+        self.data.len()
+    }
+}
+
+/// The number of bits used to range-check lengths and indices of arrays of at most `max_len`
+/// elements.
+fn len_bits(max_len: usize) -> usize {
+    log2_ceil(max_len + 1).max(1)
+}
+
+/// The length of a message of length `len` after `pad10*1` padding, as in `Hasher::hash_pad`.
+fn padded_len(len: usize) -> usize {
+    ((len + 1) / SPONGE_WIDTH + 1) * SPONGE_WIDTH
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    pub fn add_virtual_var_len_array(&mut self, max_len: usize) -> VarLenArrayTarget {
+        let len = self.add_virtual_target();
+        let data = self.add_virtual_targets(max_len);
+        self.var_len_array(data, len)
+    }
+
+    /// Wraps `data` in an array of length `len`, checking that `len <= data.len()`.
+    pub fn var_len_array(&mut self, data: Vec<Target>, len: Target) -> VarLenArrayTarget {
+        let max_len = self.constant(F::from_canonical_usize(data.len()));
+        self.assert_le_bits(len, max_len, len_bits(data.len()));
+        VarLenArrayTarget { len, data }
+    }
+
+    /// Returns the element at `index`, checking that `index < arr.len`.
+    pub fn var_len_array_get(&mut self, arr: &VarLenArrayTarget, index: Target) -> Target {
+        self.assert_lt_bits(index, arr.len, len_bits(arr.max_len()));
+        self.random_access_padded(index, arr.data.clone())
+    }
+
+    /// Returns `arr` with `value` appended, checking that `arr` is not full.
+    pub fn var_len_array_push(
+        &mut self,
+        arr: &VarLenArrayTarget,
+        value: Target,
+    ) -> VarLenArrayTarget {
+        let max_len = self.constant(F::from_canonical_usize(arr.max_len()));
+        self.assert_lt_bits(arr.len, max_len, len_bits(arr.max_len()));
+
+        let is_len = self.one_hot(arr.len, arr.max_len());
+        let data = arr
+            .data
+            .iter()
+            .zip(is_len)
+            .map(|(&x, is_len)| self.select(is_len, value, x))
+            .collect();
+        let len = self.add_const(arr.len, F::ONE);
+        VarLenArrayTarget { len, data }
+    }
+
+    /// Returns `arr` without its last element, and that element, checking that `arr` is not
+    /// empty.
+    pub fn var_len_array_pop(&mut self, arr: &VarLenArrayTarget) -> (VarLenArrayTarget, Target) {
+        let len = self.add_const(arr.len, F::NEG_ONE);
+        let value = self.var_len_array_get(arr, len);
+        let popped = VarLenArrayTarget {
+            len,
+            data: arr.data.clone(),
+        };
+        (popped, value)
+    }
+
+    /// Returns `arr[offset..offset + len]` as an array of at most `max_len` elements, checking
+    /// that `len <= max_len` and `offset + len <= arr.len`.
+    pub fn var_len_array_slice(
+        &mut self,
+        arr: &VarLenArrayTarget,
+        offset: Target,
+        len: Target,
+        max_len: usize,
+    ) -> VarLenArrayTarget {
+        let bits = len_bits(arr.max_len() + max_len);
+        self.range_check(offset, bits);
+        let max_len_target = self.constant(F::from_canonical_usize(max_len));
+        self.assert_le_bits(len, max_len_target, bits);
+        let end = self.add(offset, len);
+        self.assert_le_bits(end, arr.len, bits);
+
+        // Pad the data so that every index `offset + j` below is in range.
+        let zero = self.zero();
+        let mut padded = arr.data.clone();
+        padded.resize(arr.max_len() + max_len, zero);
+        let data = (0..max_len)
+            .map(|j| {
+                let index = self.add_const(offset, F::from_canonical_usize(j));
+                self.random_access_padded(index, padded.clone())
+            })
+            .collect();
+        VarLenArrayTarget { len, data }
+    }
+
+    /// Returns whether `a` and `b` have the same length and the same first `len` elements.
+    pub fn var_len_arrays_equal(
+        &mut self,
+        a: &VarLenArrayTarget,
+        b: &VarLenArrayTarget,
+    ) -> BoolTarget {
+        let mut result = self.is_equal(a.len, b.len);
+        // If the lengths are equal, they are at most the smaller maximum length.
+        let shorter = if a.max_len() <= b.max_len() { a } else { b };
+        let in_prefix = self.prefix_mask(shorter.len, shorter.max_len());
+        for (j, in_prefix) in in_prefix.into_iter().enumerate() {
+            let equal = self.is_equal(a.data[j], b.data[j]);
+            let not_equal = self.not(equal);
+            let mismatch = self.and(in_prefix, not_equal);
+            let no_mismatch = self.not(mismatch);
+            result = self.and(result, no_mismatch);
+        }
+        result
+    }
+
+    /// Checks that `a` and `b` have the same length and the same first `len` elements.
+    pub fn connect_var_len_arrays(&mut self, a: &VarLenArrayTarget, b: &VarLenArrayTarget) {
+        self.connect(a.len, b.len);
+        let zero = self.zero();
+        let shorter = if a.max_len() <= b.max_len() { a } else { b };
+        let in_prefix = self.prefix_mask(shorter.len, shorter.max_len());
+        for (j, in_prefix) in in_prefix.into_iter().enumerate() {
+            let diff = self.sub(a.data[j], b.data[j]);
+            let masked_diff = self.mul(in_prefix.target, diff);
+            self.connect(masked_diff, zero);
+        }
+    }
+
+    /// Hashes the first `len` elements of `arr` after `pad10*1` padding. This matches
+    /// `Hasher::hash_pad` applied to those elements.
+    pub fn hash_var_len_array<H: AlgebraicHasher<F>>(
+        &mut self,
+        arr: &VarLenArrayTarget,
+    ) -> HashOutTarget {
+        let max_len = arr.max_len();
+        let num_chunks = ceil_div_usize(padded_len(max_len), SPONGE_RATE);
+        let buf_len = num_chunks * SPONGE_RATE;
+
+        let is_len = self.one_hot(arr.len, max_len);
+        let is_data = self.prefix_mask(arr.len, max_len);
+        // `is_last[j]` is one iff `j` is the last index of the padded message, i.e. iff
+        // `j = padded_len(len) - 1`, which holds iff `j + 1` is a multiple of `SPONGE_WIDTH` and
+        // `len` lies in `[j - SPONGE_WIDTH, j - 1]`.
+        let is_last = (0..buf_len)
+            .map(|j| {
+                if (j + 1) % SPONGE_WIDTH != 0 {
+                    return self._false();
+                }
+                let lens = is_len[j.saturating_sub(SPONGE_WIDTH)..j.min(max_len + 1)].iter();
+                BoolTarget::new_unsafe(self.add_many(lens.map(|b| b.target)))
+            })
+            .collect::<Vec<_>>();
+
+        // The padded message, and for each index whether it is part of the padded message.
+        let mut padded = Vec::with_capacity(buf_len);
+        let mut in_range = Vec::with_capacity(buf_len + 1);
+        let one = self.one();
+        let mut num_lasts_before = self.zero();
+        for j in 0..buf_len {
+            in_range.push(BoolTarget::new_unsafe(self.sub(one, num_lasts_before)));
+            num_lasts_before = self.add(num_lasts_before, is_last[j].target);
+
+            let mut value = is_last[j].target;
+            if j <= max_len {
+                value = self.add(value, is_len[j].target);
+            }
+            if j < max_len {
+                value = self.mul_add(is_data[j].target, arr.data[j], value);
+            }
+            padded.push(value);
+        }
+        in_range.push(self._false());
+
+        // Absorb the padded message in overwrite mode as in `hash_n_to_m_no_pad`, keeping the
+        // output after the last chunk that is (possibly partially) part of the padded message.
+        let zero = self.zero();
+        let mut state = [zero; SPONGE_WIDTH];
+        let mut outputs = [zero; 4];
+        for c in 0..num_chunks {
+            for i in 0..SPONGE_RATE {
+                let j = c * SPONGE_RATE + i;
+                state[i] = self.select(in_range[j], padded[j], state[i]);
+            }
+            state = self.permute::<H>(state);
+
+            let next_start = in_range[(c + 1) * SPONGE_RATE].target;
+            let is_final = self.sub(in_range[c * SPONGE_RATE].target, next_start);
+            for i in 0..4 {
+                outputs[i] = self.mul_add(is_final, state[i], outputs[i]);
+            }
+        }
+
+        HashOutTarget { elements: outputs }
+    }
+
+    /// Returns `n + 1` booleans, the `k`-th of which is one iff `x = k`. `x` is assumed to be at
+    /// most `n`.
+    fn one_hot(&mut self, x: Target, n: usize) -> Vec<BoolTarget> {
+        (0..=n)
+            .map(|k| {
+                let k = self.constant(F::from_canonical_usize(k));
+                self.is_equal(x, k)
+            })
+            .collect()
+    }
+
+    /// Returns `n` booleans, the `j`-th of which is one iff `j < x`. `x` is assumed to be at
+    /// most `n`.
+    fn prefix_mask(&mut self, x: Target, n: usize) -> Vec<BoolTarget> {
+        let is_x = self.one_hot(x, n);
+        let mut is_at_most_j = self.zero();
+        let one = self.one();
+        is_x[..n]
+            .iter()
+            .map(|is_j| {
+                is_at_most_j = self.add(is_at_most_j, is_j.target);
+                BoolTarget::new_unsafe(self.sub(one, is_at_most_j))
+            })
+            .collect()
+    }
+
+    /// Checks that `x <= y`, where `y < 2^num_bits` is assumed.
+    fn assert_le_bits(&mut self, x: Target, y: Target, num_bits: usize) {
+        self.range_check(x, num_bits);
+        let diff = self.sub(y, x);
+        self.range_check(diff, num_bits);
+    }
+
+    /// Checks that `x < y`, where `y < 2^num_bits` is assumed.
+    fn assert_lt_bits(&mut self, x: Target, y: Target, num_bits: usize) {
+        let x_plus_one = self.add_const(x, F::ONE);
+        self.range_check(x, num_bits);
+        self.assert_le_bits(x_plus_one, y, num_bits);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::{Field, Sample};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, Hasher, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_push_pop_get() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let max_len = 70;
+        let values = F::rand_vec(66);
+        let arr = builder.add_virtual_var_len_array(max_len);
+        pw.set_var_len_array_target(&arr, &values[..65]);
+
+        let pushed_value = builder.constant(values[65]);
+        let pushed = builder.var_len_array_push(&arr, pushed_value);
+        let expected_len = builder.constant(F::from_canonical_usize(66));
+        builder.connect(pushed.len, expected_len);
+        for i in [0, 64, 65] {
+            let index = builder.constant(F::from_canonical_usize(i));
+            let value = builder.var_len_array_get(&pushed, index);
+            let expected = builder.constant(values[i]);
+            builder.connect(value, expected);
+        }
+
+        let (popped, value) = builder.var_len_array_pop(&pushed);
+        builder.connect(value, pushed_value);
+        builder.connect_var_len_arrays(&popped, &arr);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_slice_and_equality() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let values = F::rand_vec(20);
+        let arr = builder.add_virtual_var_len_array(24);
+        pw.set_var_len_array_target(&arr, &values);
+        let expected_slice = builder.add_virtual_var_len_array(10);
+        pw.set_var_len_array_target(&expected_slice, &values[7..15]);
+
+        let offset = builder.constant(F::from_canonical_usize(7));
+        let len = builder.constant(F::from_canonical_usize(8));
+        let slice = builder.var_len_array_slice(&arr, offset, len, 12);
+        let equal = builder.var_len_arrays_equal(&slice, &expected_slice);
+        builder.assert_one(equal.target);
+        builder.connect_var_len_arrays(&expected_slice, &slice);
+
+        // Arrays agreeing on a shorter prefix, or with different lengths, are different.
+        let shifted_offset = builder.constant(F::from_canonical_usize(8));
+        let shifted_slice = builder.var_len_array_slice(&arr, shifted_offset, len, 12);
+        let equal = builder.var_len_arrays_equal(&shifted_slice, &expected_slice);
+        builder.assert_zero(equal.target);
+        let shorter_len = builder.constant(F::from_canonical_usize(7));
+        let shorter_slice = builder.var_len_array_slice(&arr, offset, shorter_len, 12);
+        let equal = builder.var_len_arrays_equal(&shorter_slice, &expected_slice);
+        builder.assert_zero(equal.target);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_hash_var_len_array() -> Result<()> {
+        type H = <C as GenericConfig<D>>::InnerHasher;
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let max_len = 30;
+        let values = F::rand_vec(max_len);
+        // Lengths around the boundaries of sponge chunks and padding blocks.
+        for len in [0, 3, 10, 11, 12, 22, max_len] {
+            let arr = builder.add_virtual_var_len_array(max_len);
+            pw.set_var_len_array_target(&arr, &values[..len]);
+            let hash = builder.hash_var_len_array::<H>(&arr);
+            let expected = builder.constant_hash(H::hash_pad(&values[..len]));
+            builder.connect_hashes(hash, expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
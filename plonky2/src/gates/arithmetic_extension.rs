@@ -5,7 +5,9 @@ use alloc::vec::Vec;
 use core::ops::Range;
 
 use crate::field::extension::{Extendable, FieldExtension};
+use crate::field::packed::PackedField;
 use crate::gates::gate::Gate;
+use crate::gates::packed_util::PackedEvaluableBase;
 use crate::gates::util::StridedConstraintConsumer;
 use crate::hash::hash_types::RichField;
 use crate::iop::ext_target::ExtensionTarget;
@@ -14,7 +16,10 @@ use crate::iop::target::Target;
 use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::CircuitConfig;
-use crate::plonk::vars::{EvaluationTargets, EvaluationVars, EvaluationVarsBase};
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+    EvaluationVarsBasePacked,
+};
 
 /// A gate which can perform a weighted multiply-add, i.e. `result = c0 x y + c1 z`. If the config
 /// supports enough routed wires, it can support several such operations in one gate.
@@ -128,22 +133,14 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ArithmeticExte
 
     fn eval_unfiltered_base_one(
         &self,
-        vars: EvaluationVarsBase<F>,
-        mut yield_constr: StridedConstraintConsumer<F>,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
     ) {
-        let const_0 = vars.local_constants[0];
-        let const_1 = vars.local_constants[1];
-
-        for i in 0..self.num_ops {
-            let multiplicand_0 = vars.get_local_ext(Self::wires_ith_multiplicand_0(i));
-            let multiplicand_1 = vars.get_local_ext(Self::wires_ith_multiplicand_1(i));
-            let addend = vars.get_local_ext(Self::wires_ith_addend(i));
-            let output = vars.get_local_ext(Self::wires_ith_output(i));
-            let computed_output =
-                (multiplicand_0 * multiplicand_1).scalar_mul(const_0) + addend.scalar_mul(const_1);
+        panic!("use eval_unfiltered_base_packed instead");
+    }
 
-            yield_constr.many((output - computed_output).to_basefield_array());
-        }
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
     }
 
     fn eval_unfiltered_circuit(
@@ -207,6 +204,30 @@ impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for ArithmeticExte
     }
 }
 
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D>
+    for ArithmeticExtensionGate<D>
+{
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        vars: EvaluationVarsBasePacked<P>,
+        mut yield_constr: StridedConstraintConsumer<P>,
+    ) {
+        let const_0 = vars.local_constants[0];
+        let const_1 = vars.local_constants[1];
+
+        for i in 0..self.num_ops {
+            let multiplicand_0 = vars.get_local_ext(Self::wires_ith_multiplicand_0(i));
+            let multiplicand_1 = vars.get_local_ext(Self::wires_ith_multiplicand_1(i));
+            let addend = vars.get_local_ext(Self::wires_ith_addend(i));
+            let output = vars.get_local_ext(Self::wires_ith_output(i));
+            let computed_output =
+                (multiplicand_0 * multiplicand_1).scalar_mul(const_0) + addend.scalar_mul(const_1);
+
+            yield_constr.many((output - computed_output).0);
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 struct ArithmeticExtensionGenerator<F: RichField + Extendable<D>, const D: usize> {
     row: usize,
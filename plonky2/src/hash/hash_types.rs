@@ -98,6 +98,15 @@ impl<F: RichField> GenericHashOut<F> for HashOut<F> {
         }
     }
 
+    fn try_from_bytes(bytes: &[u8]) -> Option<Self> {
+        // One little-endian limb per element, each of which must be canonical.
+        let is_canonical = bytes.len() == 32
+            && bytes
+                .chunks(8)
+                .all(|limb| u64::from_le_bytes(limb.try_into().unwrap()) < F::ORDER);
+        is_canonical.then(|| Self::from_bytes(bytes))
+    }
+
     fn to_vec(&self) -> Vec<F> {
         self.elements.to_vec()
     }
@@ -176,6 +185,10 @@ impl<F: RichField, const N: usize> GenericHashOut<F> for BytesHash<N> {
         Self(bytes.try_into().unwrap())
     }
 
+    fn try_from_bytes(bytes: &[u8]) -> Option<Self> {
+        bytes.try_into().ok().map(Self)
+    }
+
     fn to_vec(&self) -> Vec<F> {
         self.0
             // Chunks of 7 bytes since 8 bytes would allow collisions.
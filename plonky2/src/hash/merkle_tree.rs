@@ -1,3 +1,5 @@
+use alloc::collections::BTreeSet;
+use alloc::vec;
 use alloc::vec::Vec;
 use core::mem::MaybeUninit;
 use core::slice;
@@ -34,6 +36,13 @@ impl<F: RichField, H: Hasher<F>> MerkleCap<F, H> {
     }
 }
 
+/// A binary Merkle tree.
+///
+/// Wider nodes do not pay off with the width-12, rate-8 Poseidon sponge: a 4-ary node absorbs
+/// four digests in two permutations, the same cost as the two binary layers it replaces, while
+/// its authentication path carries three siblings instead of two. An 8-ary node needs four
+/// permutations, more than the three binary layers it replaces. Higher arities would only help
+/// with a wider permutation.
 #[derive(Clone, Debug)]
 pub struct MerkleTree<F: RichField, H: Hasher<F>> {
     /// The data in the leaves of the Merkle tree.
@@ -205,6 +214,205 @@ impl<F: RichField, H: Hasher<F>> MerkleTree<F, H> {
 
         MerkleProof { siblings }
     }
+
+    /// The number of layers of each sub-tree below the cap.
+    fn num_layers(&self) -> usize {
+        log2_strict(self.leaves.len()) - self.cap.height()
+    }
+
+    /// Returns the position in `digests` of the node at layer `layer` (with leaves at layer 0) and
+    /// index `node_index` within that layer, or `None` if that node is an element of the cap.
+    /// `num_layers` must be `self.num_layers()`, which callers compute once per update.
+    fn digest_position(&self, num_layers: usize, layer: usize, node_index: usize) -> Option<usize> {
+        if layer == num_layers {
+            return None;
+        }
+        let tree_index = node_index >> (num_layers - layer);
+        let tree_len = self.digests.len() >> self.cap.height();
+        let index_within_tree = node_index & ((1 << (num_layers - layer)) - 1);
+        // See `prove` for the layout of a sub-tree.
+        let pair_index = index_within_tree >> 1;
+        let siblings_index = (pair_index << (layer + 1)) + (1 << layer) - 1;
+        Some(tree_len * tree_index + 2 * siblings_index + (index_within_tree & 1))
+    }
+
+    fn node_digest(&self, num_layers: usize, layer: usize, node_index: usize) -> H::Hash {
+        match self.digest_position(num_layers, layer, node_index) {
+            Some(i) => self.digests[i],
+            None => self.cap.0[node_index],
+        }
+    }
+
+    fn set_node_digest(
+        &mut self,
+        num_layers: usize,
+        layer: usize,
+        node_index: usize,
+        digest: H::Hash,
+    ) {
+        match self.digest_position(num_layers, layer, node_index) {
+            Some(i) => self.digests[i] = digest,
+            None => self.cap.0[node_index] = digest,
+        }
+    }
+
+    /// Replaces the leaf at `leaf_index`, rehashing only the path from that leaf to the cap.
+    pub fn update_leaf(&mut self, leaf_index: usize, leaf: Vec<F>) {
+        let num_layers = self.num_layers();
+        let mut digest = H::hash_or_noop(&leaf);
+        self.leaves[leaf_index] = leaf;
+        let mut node_index = leaf_index;
+        self.set_node_digest(num_layers, 0, node_index, digest);
+        for layer in 0..num_layers {
+            let sibling = self.node_digest(num_layers, layer, node_index ^ 1);
+            digest = if node_index & 1 == 1 {
+                H::two_to_one(sibling, digest)
+            } else {
+                H::two_to_one(digest, sibling)
+            };
+            node_index >>= 1;
+            self.set_node_digest(num_layers, layer + 1, node_index, digest);
+        }
+    }
+
+    /// Replaces several leaves at once. Each node above an updated leaf is rehashed once, and the
+    /// nodes of each layer are hashed in parallel. If an index appears several times, the last
+    /// update wins.
+    pub fn update_leaves<I>(&mut self, updates: I)
+    where
+        I: IntoIterator<Item = (usize, Vec<F>)>,
+    {
+        let mut dirty = BTreeSet::new();
+        for (leaf_index, leaf) in updates {
+            self.leaves[leaf_index] = leaf;
+            dirty.insert(leaf_index);
+        }
+
+        let mut node_indices = dirty.into_iter().collect::<Vec<_>>();
+        let digests = node_indices
+            .par_iter()
+            .map(|&i| H::hash_or_noop(&self.leaves[i]))
+            .collect::<Vec<_>>();
+        let num_layers = self.num_layers();
+        for (&i, digest) in node_indices.iter().zip(digests) {
+            self.set_node_digest(num_layers, 0, i, digest);
+        }
+
+        for layer in 0..num_layers {
+            node_indices = node_indices.into_iter().map(|i| i >> 1).collect();
+            node_indices.dedup();
+            let digests = node_indices
+                .par_iter()
+                .map(|&i| {
+                    H::two_to_one(
+                        self.node_digest(num_layers, layer, 2 * i),
+                        self.node_digest(num_layers, layer, 2 * i + 1),
+                    )
+                })
+                .collect::<Vec<_>>();
+            for (&i, digest) in node_indices.iter().zip(digests) {
+                self.set_node_digest(num_layers, layer + 1, i, digest);
+            }
+        }
+    }
+}
+
+/// A Merkle tree that leaves can be appended to. It wraps a `MerkleTree` whose unused leaves are
+/// empty, and whose capacity doubles whenever it is full, so proofs from `prove` can be checked
+/// with `verify_merkle_proof_to_cap` against the current `cap`.
+#[derive(Clone, Debug)]
+pub struct AppendableMerkleTree<F: RichField, H: Hasher<F>> {
+    pub tree: MerkleTree<F, H>,
+    /// The number of leaves appended so far.
+    len: usize,
+}
+
+impl<F: RichField, H: Hasher<F>> AppendableMerkleTree<F, H> {
+    pub fn new(cap_height: usize) -> Self {
+        Self::with_capacity(1 << cap_height, cap_height)
+    }
+
+    /// Creates an empty tree with room for `capacity` leaves before it needs to grow.
+    pub fn with_capacity(capacity: usize, cap_height: usize) -> Self {
+        let capacity = capacity.next_power_of_two().max(1 << cap_height);
+        Self {
+            tree: MerkleTree::new(vec![Vec::new(); capacity], cap_height),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.tree.leaves.len()
+    }
+
+    pub fn cap(&self) -> &MerkleCap<F, H> {
+        &self.tree.cap
+    }
+
+    pub fn get(&self, i: usize) -> &[F] {
+        assert!(i < self.len, "Leaf index out of range");
+        self.tree.get(i)
+    }
+
+    /// Rebuilds the tree with room for at least `min_capacity` leaves.
+    fn grow(&mut self, min_capacity: usize) {
+        let capacity = min_capacity.next_power_of_two();
+        let mut leaves = core::mem::take(&mut self.tree.leaves);
+        leaves.resize(capacity, Vec::new());
+        self.tree = MerkleTree::new(leaves, self.tree.cap.height());
+    }
+
+    /// Appends a leaf, returning its index.
+    pub fn append(&mut self, leaf: Vec<F>) -> usize {
+        let index = self.len;
+        if index == self.capacity() {
+            self.grow(index + 1);
+        }
+        self.tree.update_leaf(index, leaf);
+        self.len += 1;
+        index
+    }
+
+    /// Appends several leaves, rehashing each affected node once.
+    pub fn extend(&mut self, leaves: Vec<Vec<F>>) {
+        let start = self.len;
+        let end = start + leaves.len();
+        if end > self.capacity() {
+            self.grow(end);
+        }
+        self.tree.update_leaves((start..end).zip(leaves));
+        self.len = end;
+    }
+
+    pub fn update_leaf(&mut self, leaf_index: usize, leaf: Vec<F>) {
+        assert!(leaf_index < self.len, "Leaf index out of range");
+        self.tree.update_leaf(leaf_index, leaf);
+    }
+
+    pub fn update_leaves<I>(&mut self, updates: I)
+    where
+        I: IntoIterator<Item = (usize, Vec<F>)>,
+    {
+        let updates = updates.into_iter().collect::<Vec<_>>();
+        assert!(
+            updates.iter().all(|&(i, _)| i < self.len),
+            "Leaf index out of range"
+        );
+        self.tree.update_leaves(updates);
+    }
+
+    pub fn prove(&self, leaf_index: usize) -> MerkleProof<F, H> {
+        assert!(leaf_index < self.len, "Leaf index out of range");
+        self.tree.prove(leaf_index)
+    }
 }
 
 #[cfg(test)]
@@ -213,6 +421,7 @@ mod tests {
 
     use super::*;
     use crate::field::extension::Extendable;
+    use crate::field::types::Sample;
     use crate::hash::merkle_proofs::verify_merkle_proof_to_cap;
     use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
 
@@ -236,6 +445,61 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_update_leaves() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type H = <C as GenericConfig<D>>::Hasher;
+
+        let log_n = 8;
+        let n = 1 << log_n;
+        for cap_height in [0, 2, log_n] {
+            let mut leaves = random_data::<F>(n, 7);
+            let mut tree = MerkleTree::<F, H>::new(leaves.clone(), cap_height);
+
+            leaves[5] = F::rand_vec(7);
+            tree.update_leaf(5, leaves[5].clone());
+            let updates = (0..20)
+                .map(|i| ((i * 37) % n, F::rand_vec(7)))
+                .collect::<Vec<_>>();
+            for (i, leaf) in &updates {
+                leaves[*i] = leaf.clone();
+            }
+            tree.update_leaves(updates);
+
+            let expected = MerkleTree::<F, H>::new(leaves, cap_height);
+            assert_eq!(tree.digests, expected.digests);
+            assert_eq!(tree.cap, expected.cap);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_appendable_merkle_tree() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type H = <C as GenericConfig<D>>::Hasher;
+
+        let cap_height = 1;
+        let leaves = random_data::<F>(37, 5);
+        let mut tree = AppendableMerkleTree::<F, H>::new(cap_height);
+        for leaf in &leaves[..10] {
+            tree.append(leaf.clone());
+        }
+        tree.extend(leaves[10..].to_vec());
+        assert_eq!(tree.len(), leaves.len());
+        assert_eq!(tree.capacity(), 64);
+
+        for (i, leaf) in leaves.into_iter().enumerate() {
+            verify_merkle_proof_to_cap(leaf, i, tree.cap(), &tree.prove(i))?;
+        }
+
+        Ok(())
+    }
+
     #[test]
     #[should_panic]
     fn test_cap_height_too_big() {
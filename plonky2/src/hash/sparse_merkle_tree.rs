@@ -0,0 +1,671 @@
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::iter;
+
+use anyhow::{ensure, Result};
+use maybe_rayon::*;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::{HashOut, HashOutTarget, RichField};
+use crate::hash::hashing::SPONGE_WIDTH;
+use crate::hash::merkle_proofs::{MerkleProof, MerkleProofTarget};
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::config::{AlgebraicHasher, GenericHashOut, Hasher};
+
+/// The maximum depth of a sparse Merkle tree, so that keys fit in a `u64`.
+pub const MAX_SPARSE_MERKLE_TREE_DEPTH: usize = 64;
+
+/// A persistent Merkle tree with `2^depth` leaves indexed by `u64` keys, almost all of which are
+/// empty.
+///
+/// Only the leaves that have been set and the nodes above them are stored. The digest of an empty
+/// leaf is the all-zero hash, and the digest of an empty subtree of height `h` is precomputed in
+/// `default_digests[h]`. A non-empty leaf holding `value` is hashed as `H::hash_no_pad(1 || value)`,
+/// so that no value, not even an empty one, has the same digest as the empty leaf.
+///
+/// Every update creates a new version of the tree, numbered from 0 for the empty tree. Versions
+/// share all the nodes that the update did not touch, so each update only allocates the nodes on
+/// the paths of the updated leaves, and every past version can still be read and proven against.
+///
+/// Proofs are `MerkleProof`s whose siblings go from the bottommost layer up to the root, i.e. the
+/// same layout as for `MerkleTree` with a cap of height zero.
+#[derive(Clone, Debug)]
+pub struct SparseMerkleTree<F: RichField, H: Hasher<F>> {
+    depth: usize,
+    /// `default_digests[h]` is the digest of an empty subtree of height `h`.
+    default_digests: Vec<H::Hash>,
+    /// The root of each version of the tree, oldest first.
+    roots: Vec<Arc<Node<F, H>>>,
+}
+
+/// A node of a sparse Merkle tree. Nodes are never modified, so that versions can share them.
+#[derive(Debug)]
+enum Node<F: RichField, H: Hasher<F>> {
+    /// An empty subtree, whose digest only depends on its height.
+    Empty,
+    Leaf {
+        digest: H::Hash,
+        value: Vec<F>,
+    },
+    Branch {
+        digest: H::Hash,
+        num_leaves: usize,
+        left: Arc<Node<F, H>>,
+        right: Arc<Node<F, H>>,
+    },
+}
+
+/// The digest of an empty leaf.
+fn empty_leaf_digest<F: RichField, H: Hasher<F>>() -> H::Hash {
+    H::Hash::from_bytes(&vec![0; H::HASH_SIZE])
+}
+
+/// The digest of a leaf holding `value`, or of an empty leaf if `value` is `None`.
+pub fn sparse_merkle_leaf_digest<F: RichField, H: Hasher<F>>(value: Option<&[F]>) -> H::Hash {
+    match value {
+        // Hashing no input gives the all-zero digest of empty leaves, so we prefix a one.
+        Some(value) => H::hash_no_pad(
+            &iter::once(F::ONE)
+                .chain(value.iter().copied())
+                .collect::<Vec<_>>(),
+        ),
+        None => empty_leaf_digest::<F, H>(),
+    }
+}
+
+/// Index of the ancestor at height `height` of the leaf with key `key`.
+fn ancestor_index(key: u64, height: usize) -> u64 {
+    key.checked_shr(height as u32).unwrap_or(0)
+}
+
+/// Whether the leaf with key `key` is in the right subtree of its ancestor at height `height + 1`.
+fn is_right_child(key: u64, height: usize) -> bool {
+    (key >> height) & 1 == 1
+}
+
+impl<F: RichField, H: Hasher<F>> SparseMerkleTree<F, H> {
+    /// Creates an empty tree with `2^depth` leaves.
+    pub fn new(depth: usize) -> Self {
+        assert!(
+            depth <= MAX_SPARSE_MERKLE_TREE_DEPTH,
+            "depth={} should be at most {}",
+            depth,
+            MAX_SPARSE_MERKLE_TREE_DEPTH
+        );
+        let mut default_digests = Vec::with_capacity(depth + 1);
+        default_digests.push(empty_leaf_digest::<F, H>());
+        for h in 0..depth {
+            default_digests.push(H::two_to_one(default_digests[h], default_digests[h]));
+        }
+        Self {
+            depth,
+            default_digests,
+            roots: vec![Arc::new(Node::Empty)],
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The current version of the tree, i.e. the number of updates applied so far.
+    pub fn version(&self) -> usize {
+        self.roots.len() - 1
+    }
+
+    pub fn root(&self) -> H::Hash {
+        self.root_at(self.version())
+    }
+
+    pub fn root_at(&self, version: usize) -> H::Hash {
+        self.digest(self.root_node(version), self.depth)
+    }
+
+    /// The number of non-empty leaves.
+    pub fn len(&self) -> usize {
+        match self.root_node(self.version()).as_ref() {
+            Node::Empty => 0,
+            Node::Leaf { .. } => 1,
+            Node::Branch { num_leaves, .. } => *num_leaves,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, key: u64) -> Option<&[F]> {
+        self.get_at(self.version(), key)
+    }
+
+    /// The value of the leaf at `key` in the given version of the tree, if it is non-empty.
+    pub fn get_at(&self, version: usize, key: u64) -> Option<&[F]> {
+        self.check_key(key);
+        let mut node = self.root_node(version);
+        for height in (0..self.depth).rev() {
+            match node.as_ref() {
+                Node::Branch { left, right, .. } => {
+                    node = if is_right_child(key, height) {
+                        right
+                    } else {
+                        left
+                    };
+                }
+                _ => return None,
+            }
+        }
+        match node.as_ref() {
+            Node::Leaf { value, .. } => Some(value.as_slice()),
+            _ => None,
+        }
+    }
+
+    fn root_node(&self, version: usize) -> &Arc<Node<F, H>> {
+        assert!(
+            version <= self.version(),
+            "Version {} does not exist yet, the current version is {}",
+            version,
+            self.version()
+        );
+        &self.roots[version]
+    }
+
+    fn check_key(&self, key: u64) {
+        assert_eq!(
+            ancestor_index(key, self.depth),
+            0,
+            "Key {} out of range for a tree of depth {}",
+            key,
+            self.depth
+        );
+    }
+
+    fn digest(&self, node: &Node<F, H>, height: usize) -> H::Hash {
+        match node {
+            Node::Empty => self.default_digests[height],
+            Node::Leaf { digest, .. } | Node::Branch { digest, .. } => *digest,
+        }
+    }
+
+    fn children(node: &Arc<Node<F, H>>) -> [Arc<Node<F, H>>; 2] {
+        match node.as_ref() {
+            Node::Branch { left, right, .. } => [left.clone(), right.clone()],
+            _ => [node.clone(), node.clone()],
+        }
+    }
+
+    fn leaf(value: Option<Vec<F>>) -> Arc<Node<F, H>> {
+        Arc::new(match value {
+            Some(value) => Node::Leaf {
+                digest: sparse_merkle_leaf_digest::<F, H>(Some(&value)),
+                value,
+            },
+            None => Node::Empty,
+        })
+    }
+
+    /// Creates the node at height `height` with the given children.
+    fn branch(
+        &self,
+        height: usize,
+        left: Arc<Node<F, H>>,
+        right: Arc<Node<F, H>>,
+    ) -> Arc<Node<F, H>> {
+        let num_leaves = |node: &Node<F, H>| match node {
+            Node::Empty => 0,
+            Node::Leaf { .. } => 1,
+            Node::Branch { num_leaves, .. } => *num_leaves,
+        };
+        if let (Node::Empty, Node::Empty) = (left.as_ref(), right.as_ref()) {
+            return left;
+        }
+        Arc::new(Node::Branch {
+            digest: H::two_to_one(
+                self.digest(&left, height - 1),
+                self.digest(&right, height - 1),
+            ),
+            num_leaves: num_leaves(&left) + num_leaves(&right),
+            left,
+            right,
+        })
+    }
+
+    /// Sets the leaf at `key` to `value`, returning its previous value if it was non-empty.
+    pub fn insert(&mut self, key: u64, value: Vec<F>) -> Option<Vec<F>> {
+        self.set(key, Some(value))
+    }
+
+    /// Empties the leaf at `key`, returning its previous value if it was non-empty.
+    pub fn remove(&mut self, key: u64) -> Option<Vec<F>> {
+        self.set(key, None)
+    }
+
+    fn set(&mut self, key: u64, value: Option<Vec<F>>) -> Option<Vec<F>> {
+        let old_value = self.get(key).map(|v| v.to_vec());
+        let root = self.root_node(self.version()).clone();
+        let new_root = self.update_node(&root, self.depth, &[(key, value)]);
+        self.roots.push(new_root);
+        old_value
+    }
+
+    /// Applies several updates at once as a single new version, where `None` empties a leaf. Each
+    /// affected node is rehashed once, and disjoint subtrees are updated in parallel. If a key
+    /// appears several times, the last update wins.
+    pub fn batch_update<I>(&mut self, updates: I)
+    where
+        I: IntoIterator<Item = (u64, Option<Vec<F>>)>,
+    {
+        let updates = updates.into_iter().collect::<BTreeMap<_, _>>();
+        updates.keys().for_each(|&key| self.check_key(key));
+        let updates = updates.into_iter().collect::<Vec<_>>();
+        let root = self.root_node(self.version()).clone();
+        let new_root = self.update_node(&root, self.depth, &updates);
+        self.roots.push(new_root);
+    }
+
+    /// Applies `updates`, sorted by key and all within the subtree of `node`, to that subtree.
+    fn update_node(
+        &self,
+        node: &Arc<Node<F, H>>,
+        height: usize,
+        updates: &[(u64, Option<Vec<F>>)],
+    ) -> Arc<Node<F, H>> {
+        if updates.is_empty() {
+            return node.clone();
+        }
+        if height == 0 {
+            return Self::leaf(updates[0].1.clone());
+        }
+        let [left, right] = Self::children(node);
+        let split = updates.partition_point(|&(key, _)| !is_right_child(key, height - 1));
+        let (left_updates, right_updates) = updates.split_at(split);
+        let (left, right) = if left_updates.is_empty() || right_updates.is_empty() {
+            (
+                self.update_node(&left, height - 1, left_updates),
+                self.update_node(&right, height - 1, right_updates),
+            )
+        } else {
+            join(
+                || self.update_node(&left, height - 1, left_updates),
+                || self.update_node(&right, height - 1, right_updates),
+            )
+        };
+        self.branch(height, left, right)
+    }
+
+    /// Creates a proof for the leaf at `key`. It proves membership of the leaf's value if it is
+    /// non-empty, and non-membership of `key` otherwise.
+    pub fn prove(&self, key: u64) -> MerkleProof<F, H> {
+        self.prove_at(self.version(), key)
+    }
+
+    /// Creates a proof for the leaf at `key` in the given version of the tree.
+    pub fn prove_at(&self, version: usize, key: u64) -> MerkleProof<F, H> {
+        self.check_key(key);
+        let mut siblings = Vec::with_capacity(self.depth);
+        let mut node = self.root_node(version);
+        for height in (0..self.depth).rev() {
+            match node.as_ref() {
+                Node::Branch { left, right, .. } => {
+                    let (child, sibling) = if is_right_child(key, height) {
+                        (right, left)
+                    } else {
+                        (left, right)
+                    };
+                    siblings.push(self.digest(sibling, height));
+                    node = child;
+                }
+                _ => siblings.push(self.default_digests[height]),
+            }
+        }
+        siblings.reverse();
+        MerkleProof { siblings }
+    }
+}
+
+/// Computes the root of a sparse Merkle tree from a leaf digest and its authentication path.
+fn sparse_merkle_root<F: RichField, H: Hasher<F>>(
+    key: u64,
+    leaf_digest: H::Hash,
+    proof: &MerkleProof<F, H>,
+) -> Result<H::Hash> {
+    ensure!(
+        ancestor_index(key, proof.len()) == 0,
+        "Key out of range for the proof length."
+    );
+    let mut index = key;
+    let mut current_digest = leaf_digest;
+    for &sibling_digest in proof.siblings.iter() {
+        let bit = index & 1;
+        index >>= 1;
+        current_digest = if bit == 1 {
+            H::two_to_one(sibling_digest, current_digest)
+        } else {
+            H::two_to_one(current_digest, sibling_digest)
+        }
+    }
+    Ok(current_digest)
+}
+
+/// Verifies that the leaf at `key` holds `value` in the sparse Merkle tree with the given root.
+pub fn verify_sparse_merkle_membership<F: RichField, H: Hasher<F>>(
+    key: u64,
+    value: &[F],
+    root: H::Hash,
+    proof: &MerkleProof<F, H>,
+) -> Result<()> {
+    let leaf_digest = sparse_merkle_leaf_digest::<F, H>(Some(value));
+    ensure!(
+        sparse_merkle_root(key, leaf_digest, proof)? == root,
+        "Invalid sparse Merkle membership proof."
+    );
+    Ok(())
+}
+
+/// Verifies that the leaf at `key` is empty in the sparse Merkle tree with the given root.
+pub fn verify_sparse_merkle_non_membership<F: RichField, H: Hasher<F>>(
+    key: u64,
+    root: H::Hash,
+    proof: &MerkleProof<F, H>,
+) -> Result<()> {
+    let leaf_digest = sparse_merkle_leaf_digest::<F, H>(None);
+    ensure!(
+        sparse_merkle_root(key, leaf_digest, proof)? == root,
+        "Invalid sparse Merkle non-membership proof."
+    );
+    Ok(())
+}
+
+/// Verifies that changing the leaf at `key` from `old_value` to `new_value` takes the tree with
+/// root `old_root` to the tree with root `new_root`, where `None` denotes an empty leaf. `proof`
+/// is the proof for `key` in either tree, since they only differ along that leaf's path.
+pub fn verify_sparse_merkle_transition<F: RichField, H: Hasher<F>>(
+    key: u64,
+    old_value: Option<&[F]>,
+    new_value: Option<&[F]>,
+    old_root: H::Hash,
+    new_root: H::Hash,
+    proof: &MerkleProof<F, H>,
+) -> Result<()> {
+    let old_leaf_digest = sparse_merkle_leaf_digest::<F, H>(old_value);
+    let new_leaf_digest = sparse_merkle_leaf_digest::<F, H>(new_value);
+    ensure!(
+        sparse_merkle_root(key, old_leaf_digest, proof)? == old_root,
+        "Invalid sparse Merkle transition proof: old root mismatch."
+    );
+    ensure!(
+        sparse_merkle_root(key, new_leaf_digest, proof)? == new_root,
+        "Invalid sparse Merkle transition proof: new root mismatch."
+    );
+    Ok(())
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Computes the digest of a sparse Merkle tree leaf holding `value`, or of an empty leaf if
+    /// `is_empty` is true.
+    pub fn sparse_merkle_leaf_digest<H: AlgebraicHasher<F>>(
+        &mut self,
+        value: Vec<Target>,
+        is_empty: BoolTarget,
+    ) -> HashOutTarget {
+        let digest = self.sparse_merkle_nonempty_leaf_digest::<H>(value);
+        let empty = self.constant_hash(HashOut::ZERO);
+        self.select_hash(is_empty, empty, digest)
+    }
+
+    /// Computes the digest of a sparse Merkle tree leaf holding `value`, with the same one prefix
+    /// as `sparse_merkle_leaf_digest` outside circuits.
+    fn sparse_merkle_nonempty_leaf_digest<H: AlgebraicHasher<F>>(
+        &mut self,
+        value: Vec<Target>,
+    ) -> HashOutTarget {
+        let one = self.one();
+        self.hash_n_to_hash_no_pad::<H>(iter::once(one).chain(value).collect())
+    }
+
+    /// Computes the root of a sparse Merkle tree from a leaf digest and its authentication path.
+    /// The key is given by its little-endian bits, one per layer of the tree.
+    pub fn sparse_merkle_root<H: AlgebraicHasher<F>>(
+        &mut self,
+        leaf_digest: HashOutTarget,
+        key_bits: &[BoolTarget],
+        proof: &MerkleProofTarget,
+    ) -> HashOutTarget {
+        assert_eq!(key_bits.len(), proof.siblings.len());
+        let zero = self.zero();
+        let mut state = leaf_digest;
+        for (&bit, &sibling) in key_bits.iter().zip(&proof.siblings) {
+            let mut perm_inputs = [zero; SPONGE_WIDTH];
+            perm_inputs[..4].copy_from_slice(&state.elements);
+            perm_inputs[4..8].copy_from_slice(&sibling.elements);
+            let perm_outs = self.permute_swapped::<H>(perm_inputs, bit);
+            state = HashOutTarget {
+                elements: perm_outs[0..4].try_into().unwrap(),
+            };
+        }
+        state
+    }
+
+    /// Verifies that the leaf at the given key holds `value` in the sparse Merkle tree with the
+    /// given root.
+    pub fn verify_sparse_merkle_membership<H: AlgebraicHasher<F>>(
+        &mut self,
+        value: Vec<Target>,
+        key_bits: &[BoolTarget],
+        root: HashOutTarget,
+        proof: &MerkleProofTarget,
+    ) {
+        let leaf_digest = self.sparse_merkle_nonempty_leaf_digest::<H>(value);
+        let computed_root = self.sparse_merkle_root::<H>(leaf_digest, key_bits, proof);
+        self.connect_hashes(computed_root, root);
+    }
+
+    /// Verifies that the leaf at the given key is empty in the sparse Merkle tree with the given
+    /// root.
+    pub fn verify_sparse_merkle_non_membership<H: AlgebraicHasher<F>>(
+        &mut self,
+        key_bits: &[BoolTarget],
+        root: HashOutTarget,
+        proof: &MerkleProofTarget,
+    ) {
+        let leaf_digest = self.constant_hash(HashOut::ZERO);
+        let computed_root = self.sparse_merkle_root::<H>(leaf_digest, key_bits, proof);
+        self.connect_hashes(computed_root, root);
+    }
+
+    /// Verifies that replacing the leaf with digest `old_leaf_digest` at the given key by one with
+    /// digest `new_leaf_digest` takes the tree with root `old_root` to the tree with root
+    /// `new_root`. Leaf digests can be computed with `sparse_merkle_leaf_digest`, which covers
+    /// insertions and deletions.
+    pub fn verify_sparse_merkle_transition<H: AlgebraicHasher<F>>(
+        &mut self,
+        key_bits: &[BoolTarget],
+        old_leaf_digest: HashOutTarget,
+        new_leaf_digest: HashOutTarget,
+        old_root: HashOutTarget,
+        new_root: HashOutTarget,
+        proof: &MerkleProofTarget,
+    ) {
+        let computed_old_root = self.sparse_merkle_root::<H>(old_leaf_digest, key_bits, proof);
+        self.connect_hashes(computed_old_root, old_root);
+        let computed_new_root = self.sparse_merkle_root::<H>(new_leaf_digest, key_bits, proof);
+        self.connect_hashes(computed_new_root, new_root);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::field::types::Sample;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type H = <C as GenericConfig<D>>::Hasher;
+
+    #[test]
+    fn test_sparse_merkle_tree() -> Result<()> {
+        let mut tree = SparseMerkleTree::<F, H>::new(64);
+        let empty_root = tree.root();
+        let keys = (0..8).map(|_| OsRng.gen::<u64>()).collect::<Vec<_>>();
+        for &key in &keys {
+            tree.insert(key, F::rand_vec(3));
+        }
+
+        for &key in &keys {
+            let value = tree.get(key).unwrap().to_vec();
+            verify_sparse_merkle_membership(key, &value, tree.root(), &tree.prove(key))?;
+            assert!(
+                verify_sparse_merkle_non_membership(key, tree.root(), &tree.prove(key)).is_err()
+            );
+        }
+        let absent = OsRng.gen::<u64>();
+        verify_sparse_merkle_non_membership(absent, tree.root(), &tree.prove(absent))?;
+
+        // Updating a leaf.
+        let old_root = tree.root();
+        let proof = tree.prove(keys[0]);
+        let new_value = F::rand_vec(3);
+        let old_value = tree.insert(keys[0], new_value.clone()).unwrap();
+        verify_sparse_merkle_transition(
+            keys[0],
+            Some(&old_value),
+            Some(&new_value),
+            old_root,
+            tree.root(),
+            &proof,
+        )?;
+
+        // Past versions can still be read and proven against.
+        let version = tree.version();
+        verify_sparse_merkle_membership(
+            keys[0],
+            &old_value,
+            tree.root_at(version - 1),
+            &tree.prove_at(version - 1, keys[0]),
+        )?;
+        assert_eq!(
+            tree.get_at(version - 1, keys[0]),
+            Some(old_value.as_slice())
+        );
+        assert_eq!(tree.root_at(0), empty_root);
+
+        // Removing every leaf gives back the empty tree.
+        for &key in &keys {
+            assert!(tree.remove(key).is_some());
+        }
+        assert!(tree.is_empty());
+        assert_eq!(tree.root(), empty_root);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_value() -> Result<()> {
+        let mut tree = SparseMerkleTree::<F, H>::new(16);
+        let empty_root = tree.root();
+        let key = 5;
+        tree.insert(key, vec![]);
+        assert_ne!(tree.root(), empty_root);
+        assert_eq!(tree.len(), 1);
+        assert!(verify_sparse_merkle_non_membership(key, tree.root(), &tree.prove(key)).is_err());
+        verify_sparse_merkle_membership(key, &[], tree.root(), &tree.prove(key))
+    }
+
+    #[test]
+    fn test_batch_update() {
+        let depth = 20;
+        let updates = (0..32)
+            .map(|i| {
+                let key = OsRng.gen_range(0..1 << depth);
+                let value = (i % 5 != 0).then(|| F::rand_vec(2));
+                (key, value)
+            })
+            .collect::<Vec<_>>();
+
+        let mut expected = SparseMerkleTree::<F, H>::new(depth);
+        for (key, value) in updates.clone() {
+            match value {
+                Some(value) => expected.insert(key, value),
+                None => expected.remove(key),
+            };
+        }
+
+        let mut tree = SparseMerkleTree::<F, H>::new(depth);
+        tree.batch_update(updates);
+        assert_eq!(tree.version(), 1);
+        assert_eq!(tree.root(), expected.root());
+        assert_eq!(tree.len(), expected.len());
+        for key in 0..1 << 6 {
+            assert_eq!(tree.prove(key).siblings, expected.prove(key).siblings);
+        }
+    }
+
+    #[test]
+    fn test_recursive_sparse_merkle_proofs() -> Result<()> {
+        let depth = 64;
+        let mut tree = SparseMerkleTree::<F, H>::new(depth);
+        for _ in 0..4 {
+            tree.insert(OsRng.gen(), F::rand_vec(3));
+        }
+        let key = OsRng.gen::<u64>();
+        let value = F::rand_vec(3);
+        let old_root = tree.root();
+        let proof = tree.prove(key);
+        tree.insert(key, value.clone());
+        let new_root = tree.root();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let mut pw = PartialWitness::new();
+
+        let key_bits = (0..depth)
+            .map(|_| builder.add_virtual_bool_target_safe())
+            .collect::<Vec<_>>();
+        for (i, &bit) in key_bits.iter().enumerate() {
+            pw.set_bool_target(bit, (key >> i) & 1 == 1);
+        }
+        let proof_t = MerkleProofTarget {
+            siblings: builder.add_virtual_hashes(depth),
+        };
+        for (&t, &h) in proof_t.siblings.iter().zip(&proof.siblings) {
+            pw.set_hash_target(t, h);
+        }
+        let value_t = builder.add_virtual_targets(value.len());
+        for (&t, &x) in value_t.iter().zip(&value) {
+            pw.set_target(t, x);
+        }
+        let old_root_t = builder.constant_hash(old_root);
+        let new_root_t = builder.constant_hash(new_root);
+
+        // The key is absent before the insertion and present after it.
+        builder.verify_sparse_merkle_non_membership::<H>(&key_bits, old_root_t, &proof_t);
+        builder.verify_sparse_merkle_membership::<H>(
+            value_t.clone(),
+            &key_bits,
+            new_root_t,
+            &proof_t,
+        );
+        let _true = builder._true();
+        let _false = builder._false();
+        let old_leaf = builder.sparse_merkle_leaf_digest::<H>(value_t.clone(), _true);
+        let new_leaf = builder.sparse_merkle_leaf_digest::<H>(value_t, _false);
+        builder.verify_sparse_merkle_transition::<H>(
+            &key_bits, old_leaf, new_leaf, old_root_t, new_root_t, &proof_t,
+        );
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+}
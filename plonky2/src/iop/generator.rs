@@ -1,8 +1,11 @@
+use alloc::collections::BTreeMap;
 use alloc::vec;
 use alloc::vec::Vec;
 use core::fmt::Debug;
 use core::marker::PhantomData;
 
+use maybe_rayon::*;
+
 use crate::field::extension::Extendable;
 use crate::field::types::Field;
 use crate::hash::hash_types::RichField;
@@ -39,6 +42,28 @@ pub(crate) fn generate_partial_witness<
         witness.set_target(t, v);
     }
 
+    let remaining_generators = if prover_data.parallel_witness_generation {
+        run_generators_parallel(&mut witness, generators, generator_indices_by_watches)
+    } else {
+        run_generators_serial(&mut witness, generators, generator_indices_by_watches)
+    };
+
+    assert_eq!(
+        remaining_generators, 0,
+        "{} generators weren't run",
+        remaining_generators,
+    );
+
+    witness
+}
+
+/// Runs generators one at a time until no more progress can be made, returning the number of
+/// generators that never finished.
+fn run_generators_serial<F: Field>(
+    witness: &mut PartitionWitness<F>,
+    generators: &[Box<dyn WitnessGenerator<F>>],
+    generator_indices_by_watches: &BTreeMap<usize, Vec<usize>>,
+) -> usize {
     // Build a list of "pending" generators which are queued to be run. Initially, all generators
     // are queued.
     let mut pending_generator_indices: Vec<_> = (0..generators.len()).collect();
@@ -58,7 +83,7 @@ pub(crate) fn generate_partial_witness<
                 continue;
             }
 
-            let finished = generators[generator_idx].run(&witness, &mut buffer);
+            let finished = generators[generator_idx].run(witness, &mut buffer);
             if finished {
                 generator_is_expired[generator_idx] = true;
                 remaining_generators -= 1;
@@ -87,13 +112,62 @@ pub(crate) fn generate_partial_witness<
         pending_generator_indices = next_pending_generator_indices;
     }
 
-    assert_eq!(
-        remaining_generators, 0,
-        "{} generators weren't run",
-        remaining_generators,
-    );
+    remaining_generators
+}
 
-    witness
+/// Runs generators in rounds until no more progress can be made, returning the number of
+/// generators that never finished.
+///
+/// Within a round, every pending generator runs concurrently against the witness as it stood at
+/// the start of the round, writing to its own buffer. The buffers are then merged in generator
+/// index order, so the resulting witness does not depend on how the work was scheduled.
+fn run_generators_parallel<F: Field>(
+    witness: &mut PartitionWitness<F>,
+    generators: &[Box<dyn WitnessGenerator<F>>],
+    generator_indices_by_watches: &BTreeMap<usize, Vec<usize>>,
+) -> usize {
+    let mut pending_generator_indices: Vec<_> = (0..generators.len()).collect();
+    let mut generator_is_expired = vec![false; generators.len()];
+    let mut remaining_generators = generators.len();
+
+    while !pending_generator_indices.is_empty() {
+        let witness_snapshot = &*witness;
+        let outputs = pending_generator_indices
+            .par_iter()
+            .map(|&generator_idx| {
+                let mut buffer = GeneratedValues::empty();
+                let finished = generators[generator_idx].run(witness_snapshot, &mut buffer);
+                (finished, buffer)
+            })
+            .collect::<Vec<_>>();
+
+        let mut next_pending_generator_indices: Vec<usize> = Vec::new();
+        for (&generator_idx, (finished, buffer)) in pending_generator_indices.iter().zip(outputs) {
+            if finished {
+                generator_is_expired[generator_idx] = true;
+                remaining_generators -= 1;
+            }
+
+            let new_target_reps = buffer
+                .target_values
+                .into_iter()
+                .flat_map(|(t, v)| witness.set_target_returning_rep(t, v));
+            for watch in new_target_reps {
+                if let Some(watchers) = generator_indices_by_watches.get(&watch) {
+                    next_pending_generator_indices.extend(watchers);
+                }
+            }
+        }
+
+        // A generator may have been enqueued by several targets, or have expired after being
+        // enqueued earlier in this round.
+        next_pending_generator_indices.sort_unstable();
+        next_pending_generator_indices.dedup();
+        next_pending_generator_indices.retain(|&i| !generator_is_expired[i]);
+        pending_generator_indices = next_pending_generator_indices;
+    }
+
+    remaining_generators
 }
 
 /// A generator participates in the generation of the witness.
@@ -278,3 +352,54 @@ impl<F: Field> SimpleGenerator<F> for ConstantGenerator<F> {
         out_buffer.set_target(Target::wire(self.row, self.wire_index), self.constant);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::field::types::Sample;
+    use crate::hash::poseidon::PoseidonHash;
+    use crate::iop::generator::generate_partial_witness;
+    use crate::iop::witness::{PartialWitness, Witness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn test_parallel_generation_matches_serial() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        // Several independent hash chains, with some arithmetic mixed in.
+        let inputs = builder.add_virtual_targets(8);
+        let mut generated = Vec::new();
+        for chunk in inputs.chunks(2) {
+            let mut state = chunk.to_vec();
+            for _ in 0..4 {
+                let hash = builder.hash_n_to_hash_no_pad::<PoseidonHash>(state.clone());
+                let sum = builder.add_many(hash.elements);
+                let inv = builder.inverse(sum);
+                state = hash.elements.to_vec();
+                state.push(inv);
+                generated.extend(&state);
+            }
+            builder.register_public_inputs(&state);
+        }
+        let mut circuit = builder.build_prover::<C>();
+
+        let mut pw = PartialWitness::new();
+        for (&t, x) in inputs.iter().zip(F::rand_vec(inputs.len())) {
+            pw.set_target(t, x);
+        }
+
+        // Blinding wires are filled with random values, so only compare the targets we generated.
+        let parallel = generate_partial_witness(pw.clone(), &circuit.prover_only, &circuit.common)
+            .get_targets(&generated);
+        circuit.prover_only.parallel_witness_generation = false;
+        let serial = generate_partial_witness(pw, &circuit.prover_only, &circuit.common)
+            .get_targets(&generated);
+        assert_eq!(parallel, serial);
+    }
+}
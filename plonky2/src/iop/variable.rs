@@ -0,0 +1,283 @@
+//! Typed groups of targets with matching native values.
+
+use alloc::vec;
+#[doc(hidden)]
+pub use alloc::vec::Vec;
+use core::array;
+use core::fmt::Debug;
+
+pub use plonky2_derive::CircuitVariable;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::{HashOut, HashOutTarget, MerkleCapTarget, RichField};
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// A group of targets representing a value of type `Self::Value<F>` in a circuit.
+///
+/// This can be derived for structs whose fields are all `CircuitVariable`s, see
+/// `plonky2_derive::CircuitVariable`.
+pub trait CircuitVariable: Clone + Debug {
+    /// The native value assigned to this variable in a witness.
+    type Value<F: RichField>: Clone + Debug + PartialEq;
+
+    /// The shape information needed to allocate this variable, such as the height of a Merkle
+    /// cap. This is `()` for variables with a fixed number of targets.
+    type Layout: Clone + Debug + Default;
+
+    /// Adds a new variable made of virtual targets, constrained to hold a valid value of its type
+    /// (e.g. `BoolTarget`s are checked to be boolean, and integer targets to be in range).
+    /// Variables assigned by other gadgets can skip these checks by using the builder's unchecked
+    /// allocation methods instead, such as `CircuitBuilder::add_virtual_bool_target_unsafe`.
+    fn add_virtual<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        layout: &Self::Layout,
+    ) -> Self;
+
+    /// The targets making up this variable, in a canonical order.
+    fn targets(&self) -> Vec<Target>;
+
+    fn set<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: &Self::Value<F>);
+
+    fn get<F: RichField, W: Witness<F>>(&self, witness: &W) -> Self::Value<F>;
+
+    fn connect<F: RichField + Extendable<D>, const D: usize>(
+        &self,
+        other: &Self,
+        builder: &mut CircuitBuilder<F, D>,
+    ) {
+        let (targets, other_targets) = (self.targets(), other.targets());
+        assert_eq!(
+            targets.len(),
+            other_targets.len(),
+            "Cannot connect variables with different layouts"
+        );
+        for (x, y) in targets.into_iter().zip(other_targets) {
+            builder.connect(x, y);
+        }
+    }
+
+    /// Registers the targets of this variable as public inputs, in the order given by `targets`.
+    fn register_public_inputs<F: RichField + Extendable<D>, const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+    ) {
+        builder.register_public_inputs(&self.targets());
+    }
+}
+
+impl CircuitVariable for Target {
+    type Value<F: RichField> = F;
+    type Layout = ();
+
+    fn add_virtual<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        _layout: &(),
+    ) -> Self {
+        builder.add_virtual_target()
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        vec![*self]
+    }
+
+    fn set<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: &F) {
+        witness.set_target(*self, *value);
+    }
+
+    fn get<F: RichField, W: Witness<F>>(&self, witness: &W) -> F {
+        witness.get_target(*self)
+    }
+}
+
+impl CircuitVariable for BoolTarget {
+    type Value<F: RichField> = bool;
+    type Layout = ();
+
+    fn add_virtual<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        _layout: &(),
+    ) -> Self {
+        builder.add_virtual_bool_target_safe()
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        vec![self.target]
+    }
+
+    fn set<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: &bool) {
+        witness.set_bool_target(*self, *value);
+    }
+
+    fn get<F: RichField, W: Witness<F>>(&self, witness: &W) -> bool {
+        witness.get_bool_target(*self)
+    }
+}
+
+impl<const D: usize> CircuitVariable for ExtensionTarget<D> {
+    /// The coordinates of the extension field element in the base field.
+    type Value<F: RichField> = [F; D];
+    type Layout = ();
+
+    fn add_virtual<F: RichField + Extendable<E>, const E: usize>(
+        builder: &mut CircuitBuilder<F, E>,
+        _layout: &(),
+    ) -> Self {
+        ExtensionTarget(array::from_fn(|_| builder.add_virtual_target()))
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        self.to_target_array().to_vec()
+    }
+
+    fn set<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: &[F; D]) {
+        witness.set_target_arr(self.0, *value);
+    }
+
+    fn get<F: RichField, W: Witness<F>>(&self, witness: &W) -> [F; D] {
+        self.0.map(|t| witness.get_target(t))
+    }
+}
+
+impl CircuitVariable for HashOutTarget {
+    type Value<F: RichField> = HashOut<F>;
+    type Layout = ();
+
+    fn add_virtual<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        _layout: &(),
+    ) -> Self {
+        builder.add_virtual_hash()
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        self.elements.to_vec()
+    }
+
+    fn set<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: &HashOut<F>) {
+        witness.set_hash_target(*self, *value);
+    }
+
+    fn get<F: RichField, W: Witness<F>>(&self, witness: &W) -> HashOut<F> {
+        witness.get_hash_target(*self)
+    }
+}
+
+impl CircuitVariable for MerkleCapTarget {
+    /// The hashes making up the cap.
+    type Value<F: RichField> = Vec<HashOut<F>>;
+    /// The cap height.
+    type Layout = usize;
+
+    fn add_virtual<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        cap_height: &usize,
+    ) -> Self {
+        builder.add_virtual_cap(*cap_height)
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        self.0.iter().flat_map(|h| h.elements).collect()
+    }
+
+    fn set<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: &Vec<HashOut<F>>) {
+        assert_eq!(self.0.len(), value.len(), "Merkle cap length mismatch");
+        for (&ht, &h) in self.0.iter().zip(value) {
+            witness.set_hash_target(ht, h);
+        }
+    }
+
+    fn get<F: RichField, W: Witness<F>>(&self, witness: &W) -> Vec<HashOut<F>> {
+        self.0
+            .iter()
+            .map(|&ht| witness.get_hash_target(ht))
+            .collect()
+    }
+}
+
+impl<V: CircuitVariable, const N: usize> CircuitVariable for [V; N] {
+    type Value<F: RichField> = [V::Value<F>; N];
+    /// The layout shared by every element.
+    type Layout = V::Layout;
+
+    fn add_virtual<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        layout: &V::Layout,
+    ) -> Self {
+        array::from_fn(|_| V::add_virtual(builder, layout))
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        self.iter().flat_map(V::targets).collect()
+    }
+
+    fn set<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: &Self::Value<F>) {
+        for (v, x) in self.iter().zip(value) {
+            v.set(witness, x);
+        }
+    }
+
+    fn get<F: RichField, W: Witness<F>>(&self, witness: &W) -> Self::Value<F> {
+        array::from_fn(|i| self[i].get(witness))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::{Field, Sample};
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[derive(Clone, Debug, CircuitVariable)]
+    struct Header {
+        root: HashOutTarget,
+        cap: MerkleCapTarget,
+        flags: [BoolTarget; 2],
+        counter: Target,
+        challenge: ExtensionTarget<2>,
+    }
+
+    #[test]
+    fn test_derived_circuit_variable() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let layout = HeaderLayout {
+            cap: 2,
+            ..Default::default()
+        };
+        let header = Header::add_virtual(&mut builder, &layout);
+        let copy = Header::add_virtual(&mut builder, &layout);
+        header.connect(&copy, &mut builder);
+        copy.register_public_inputs(&mut builder);
+        assert_eq!(header.targets().len(), 4 + 4 * 4 + 2 + 1 + 2);
+
+        let value = HeaderValue {
+            root: HashOut::rand(),
+            cap: (0..4).map(|_| HashOut::rand()).collect(),
+            flags: [true, false],
+            counter: F::from_canonical_u64(42),
+            challenge: F::rand_array(),
+        };
+        let mut pw = PartialWitness::new();
+        header.set(&mut pw, &value);
+        assert_eq!(header.get(&pw), value);
+
+        let expected_public_inputs = pw.get_targets(&header.targets());
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        assert_eq!(proof.public_inputs, expected_public_inputs);
+        data.verify(proof)
+    }
+}
@@ -5,9 +5,10 @@ use hashbrown::HashMap;
 use itertools::Itertools;
 
 use crate::field::extension::{Extendable, FieldExtension};
-use crate::field::types::Field;
+use crate::field::types::{Field, PrimeField64};
 use crate::fri::structure::{FriOpenings, FriOpeningsTarget};
 use crate::fri::witness_util::set_fri_proof_target;
+use crate::gadgets::var_len_array::VarLenArrayTarget;
 use crate::hash::hash_types::{HashOut, HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_tree::MerkleCap;
 use crate::iop::ext_target::ExtensionTarget;
@@ -16,6 +17,9 @@ use crate::iop::wire::Wire;
 use crate::plonk::circuit_data::{VerifierCircuitTarget, VerifierOnlyCircuitData};
 use crate::plonk::config::{AlgebraicHasher, GenericConfig};
 use crate::plonk::proof::{Proof, ProofTarget, ProofWithPublicInputs, ProofWithPublicInputsTarget};
+use crate::util::serialization::Write;
+#[cfg(feature = "std")]
+use crate::util::serialization::{Buffer, Read};
 
 pub trait WitnessWrite<F: Field> {
     fn set_target(&mut self, target: Target, value: F);
@@ -69,6 +73,18 @@ pub trait WitnessWrite<F: Field> {
         self.set_target(target.target, F::from_bool(value))
     }
 
+    /// Set a `VarLenArrayTarget` to hold `values`, padding its unused elements with zeros.
+    fn set_var_len_array_target(&mut self, target: &VarLenArrayTarget, values: &[F]) {
+        assert!(
+            values.len() <= target.max_len(),
+            "Too many values for the array"
+        );
+        self.set_target(target.len, F::from_canonical_usize(values.len()));
+        for (i, &t) in target.data.iter().enumerate() {
+            self.set_target(t, values.get(i).copied().unwrap_or(F::ZERO));
+        }
+    }
+
     /// Set the targets in a `ProofWithPublicInputsTarget` to their corresponding values in a
     /// `ProofWithPublicInputs`.
     fn set_proof_with_pis_target<C: GenericConfig<D, F = F>, const D: usize>(
@@ -252,6 +268,23 @@ impl<F: Field> MatrixWitness<F> {
     }
 }
 
+impl<F: PrimeField64> MatrixWitness<F> {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_matrix_witness(self)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    #[cfg(feature = "std")]
+    pub fn from_bytes(bytes: Vec<u8>) -> anyhow::Result<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let witness = buffer.read_matrix_witness().map_err(anyhow::Error::msg)?;
+        Ok(witness)
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct PartialWitness<F: Field> {
     pub(crate) target_values: HashMap<Target, F>,
@@ -265,6 +298,23 @@ impl<F: Field> PartialWitness<F> {
     }
 }
 
+impl<F: PrimeField64> PartialWitness<F> {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_partial_witness(self)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    #[cfg(feature = "std")]
+    pub fn from_bytes(bytes: Vec<u8>) -> anyhow::Result<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let witness = buffer.read_partial_witness().map_err(anyhow::Error::msg)?;
+        Ok(witness)
+    }
+}
+
 impl<F: Field> WitnessWrite<F> for PartialWitness<F> {
     fn set_target(&mut self, target: Target, value: F) {
         let opt_old_value = self.target_values.insert(target, value);
@@ -353,3 +403,51 @@ impl<'a, F: Field> Witness<F> for PartitionWitness<'a, F> {
         self.values[rep_index]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::Sample;
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    #[test]
+    fn test_prove_from_serialized_witness() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = builder.mul(x, y);
+        let w = builder.add(z, x);
+        builder.register_public_input(x);
+        builder.register_public_input(w);
+        let data = builder.build::<C>();
+
+        let (x_value, y_value) = (F::rand(), F::rand());
+        let mut inputs = PartialWitness::new();
+        inputs.set_target(x, x_value);
+        inputs.set_target(y, y_value);
+
+        // Ship the inputs to the witness generator, and the full witness to the prover.
+        let inputs_bytes = inputs.to_bytes();
+        assert_eq!(inputs_bytes, inputs.clone().to_bytes());
+        let inputs = PartialWitness::<F>::from_bytes(inputs_bytes)?;
+        assert_eq!(inputs.get_target(y), y_value);
+
+        let witness = data.generate_full_witness(inputs);
+        let witness = MatrixWitness::<F>::from_bytes(witness.to_bytes())?;
+        let proof = data.prove_from_full_witness(witness)?;
+        assert_eq!(
+            proof.public_inputs,
+            vec![x_value, x_value * y_value + x_value]
+        );
+        data.verify(proof)
+    }
+}
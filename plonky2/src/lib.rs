@@ -1,9 +1,10 @@
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::needless_range_loop)]
 #![cfg_attr(not(feature = "std"), no_std)]
-#![feature(int_roundings)]
 
 extern crate alloc;
+// Lets `plonky2_derive` macros, which refer to `::plonky2`, be used within this crate.
+extern crate self as plonky2;
 
 #[doc(inline)]
 pub use plonky2_field as field;
@@ -13,6 +14,8 @@ pub mod gadgets;
 pub mod gates;
 pub mod hash;
 pub mod iop;
+pub mod pcs;
 pub mod plonk;
 pub mod recursion;
+pub mod sumcheck;
 pub mod util;
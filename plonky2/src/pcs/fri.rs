@@ -0,0 +1,505 @@
+use alloc::vec::Vec;
+
+use anyhow::{ensure, Result};
+use maybe_rayon::*;
+
+use crate::field::extension::Extendable;
+use crate::field::polynomial::PolynomialCoeffs;
+use crate::fri::oracle::PolynomialBatch;
+use crate::fri::proof::{FriProof, FriProofTarget};
+use crate::fri::structure::{
+    FriBatchInfo, FriBatchInfoTarget, FriInstanceInfo, FriInstanceInfoTarget, FriOpeningBatch,
+    FriOpeningBatchTarget, FriOpenings, FriOpeningsTarget, FriOracleInfo, FriPolynomialInfo,
+};
+use crate::fri::verifier::verify_fri_proof;
+use crate::fri::witness_util::set_fri_proof_target;
+use crate::fri::FriParams;
+use crate::hash::hash_types::{MerkleCapTarget, RichField};
+use crate::hash::merkle_tree::MerkleCap;
+use crate::iop::challenger::{Challenger, RecursiveChallenger};
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::witness::WitnessWrite;
+use crate::pcs::{PolynomialCommitmentScheme, RecursivePolynomialCommitmentScheme};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::config::{AlgebraicHasher, GenericConfig, Hasher};
+use crate::plonk::plonk_common::salt_size;
+use crate::util::timing::TimingTree;
+
+/// FRI used as a polynomial commitment scheme for polynomials of degree `< 2^params.degree_bits`.
+/// If `params.hiding` is set, commitments are blinded with salted Merkle leaves.
+#[derive(Clone, Debug)]
+pub struct FriPcs {
+    pub params: FriParams,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FriCommitment<F: RichField, H: Hasher<F>> {
+    pub cap: MerkleCap<F, H>,
+    pub num_polys: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct FriCommitmentTarget {
+    pub cap: MerkleCapTarget,
+    pub num_polys: usize,
+}
+
+impl FriPcs {
+    pub fn new(params: FriParams) -> Self {
+        Self { params }
+    }
+
+    fn oracles(&self, num_polys: &[usize]) -> Vec<FriOracleInfo> {
+        num_polys
+            .iter()
+            .map(|&num_polys| FriOracleInfo {
+                num_polys,
+                blinding: self.params.hiding,
+            })
+            .collect()
+    }
+
+    /// All polynomials of all oracles, in order.
+    fn all_polys(num_polys: &[usize]) -> Vec<FriPolynomialInfo> {
+        num_polys
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &n)| FriPolynomialInfo::from_range(i, 0..n))
+            .collect()
+    }
+
+    fn instance<F: RichField + Extendable<D>, const D: usize>(
+        &self,
+        num_polys: &[usize],
+        points: &[F::Extension],
+    ) -> FriInstanceInfo<F, D> {
+        let polynomials = Self::all_polys(num_polys);
+        FriInstanceInfo {
+            oracles: self.oracles(num_polys),
+            batches: points
+                .iter()
+                .map(|&point| FriBatchInfo {
+                    point,
+                    polynomials: polynomials.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    fn instance_target<const D: usize>(
+        &self,
+        num_polys: &[usize],
+        points: &[ExtensionTarget<D>],
+    ) -> FriInstanceInfoTarget<D> {
+        let polynomials = Self::all_polys(num_polys);
+        FriInstanceInfoTarget {
+            oracles: self.oracles(num_polys),
+            batches: points
+                .iter()
+                .map(|&point| FriBatchInfoTarget {
+                    point,
+                    polynomials: polynomials.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    PolynomialCommitmentScheme<F, C, D> for FriPcs
+{
+    type Commitment = FriCommitment<F, C::Hasher>;
+    type ProverData = PolynomialBatch<F, C, D>;
+    type Proof = FriProof<F, C::Hasher, D>;
+
+    fn commit(
+        &self,
+        polynomials: Vec<PolynomialCoeffs<F>>,
+        timing: &mut TimingTree,
+    ) -> (Self::Commitment, Self::ProverData) {
+        let degree = 1 << self.params.degree_bits;
+        let polynomials = polynomials
+            .into_iter()
+            .enumerate()
+            .map(|(i, p)| {
+                assert!(
+                    p.len() <= degree,
+                    "Polynomial {} has {} coefficients, but FRI commits to polynomials of degree < 2^{}",
+                    i,
+                    p.len(),
+                    self.params.degree_bits
+                );
+                p.padded(degree)
+            })
+            .collect::<Vec<_>>();
+        let num_polys = polynomials.len();
+        let batch = PolynomialBatch::from_coeffs(
+            polynomials,
+            self.params.config.rate_bits,
+            self.params.hiding,
+            self.params.config.cap_height,
+            timing,
+            None,
+        );
+        let commitment = FriCommitment {
+            cap: batch.merkle_tree.cap.clone(),
+            num_polys,
+        };
+        (commitment, batch)
+    }
+
+    fn open(
+        &self,
+        prover_data: &[&Self::ProverData],
+        points: &[F::Extension],
+        challenger: &mut Challenger<F, C::Hasher>,
+        timing: &mut TimingTree,
+    ) -> (Vec<Vec<F::Extension>>, Self::Proof) {
+        let values = points
+            .iter()
+            .map(|&z| {
+                prover_data
+                    .iter()
+                    .flat_map(|batch| &batch.polynomials)
+                    .collect::<Vec<_>>()
+                    .par_iter()
+                    .map(|p| p.to_extension().eval(z))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        for v in &values {
+            challenger.observe_extension_elements::<D>(v);
+        }
+
+        let num_polys = prover_data
+            .iter()
+            .map(|batch| batch.polynomials.len())
+            .collect::<Vec<_>>();
+        let instance = self.instance::<F, D>(&num_polys, points);
+        let proof = PolynomialBatch::prove_openings(
+            &instance,
+            prover_data,
+            challenger,
+            &self.params,
+            timing,
+        );
+        (values, proof)
+    }
+
+    fn verify(
+        &self,
+        commitments: &[Self::Commitment],
+        points: &[F::Extension],
+        values: &[Vec<F::Extension>],
+        proof: &Self::Proof,
+        challenger: &mut Challenger<F, C::Hasher>,
+    ) -> Result<()> {
+        let num_polys = commitments.iter().map(|c| c.num_polys).collect::<Vec<_>>();
+        let total_polys = num_polys.iter().sum::<usize>();
+        ensure!(
+            values.len() == points.len(),
+            "Expected one batch of values per point"
+        );
+        ensure!(
+            values.iter().all(|v| v.len() == total_polys),
+            "Wrong number of opened values"
+        );
+
+        for v in values {
+            challenger.observe_extension_elements::<D>(v);
+        }
+        let challenges = challenger.fri_challenges::<C, D>(
+            &proof.commit_phase_merkle_caps,
+            &proof.final_poly,
+            proof.pow_witness,
+            self.params.degree_bits,
+            &self.params.config,
+        );
+
+        let instance = self.instance::<F, D>(&num_polys, points);
+        let openings = FriOpenings {
+            batches: values
+                .iter()
+                .map(|v| FriOpeningBatch { values: v.clone() })
+                .collect(),
+        };
+        let caps = commitments
+            .iter()
+            .map(|c| c.cap.clone())
+            .collect::<Vec<_>>();
+        verify_fri_proof::<F, C, D>(
+            &instance,
+            &openings,
+            &challenges,
+            &caps,
+            proof,
+            &self.params,
+        )
+    }
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    RecursivePolynomialCommitmentScheme<F, C, D> for FriPcs
+where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    type CommitmentTarget = FriCommitmentTarget;
+    type ProofTarget = FriProofTarget<D>;
+
+    fn add_virtual_commitment(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        num_polys: usize,
+    ) -> Self::CommitmentTarget {
+        FriCommitmentTarget {
+            cap: builder.add_virtual_cap(self.params.config.cap_height),
+            num_polys,
+        }
+    }
+
+    fn add_virtual_proof(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        num_polys: &[usize],
+        _num_points: usize,
+    ) -> Self::ProofTarget {
+        let salt = salt_size(self.params.hiding);
+        let num_leaves_per_oracle = num_polys.iter().map(|&n| n + salt).collect::<Vec<_>>();
+        builder.add_virtual_fri_proof(&num_leaves_per_oracle, &self.params)
+    }
+
+    fn verify_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        commitments: &[Self::CommitmentTarget],
+        points: &[ExtensionTarget<D>],
+        values: &[Vec<ExtensionTarget<D>>],
+        proof: &Self::ProofTarget,
+        challenger: &mut RecursiveChallenger<F, C::Hasher, D>,
+    ) {
+        let num_polys = commitments.iter().map(|c| c.num_polys).collect::<Vec<_>>();
+        let total_polys = num_polys.iter().sum::<usize>();
+        assert_eq!(values.len(), points.len());
+        assert!(values.iter().all(|v| v.len() == total_polys));
+
+        for v in values {
+            challenger.observe_extension_elements(v);
+        }
+        let challenges = challenger.fri_challenges::<C>(
+            builder,
+            &proof.commit_phase_merkle_caps,
+            &proof.final_poly,
+            proof.pow_witness,
+            &self.params.config,
+        );
+
+        let instance = self.instance_target(&num_polys, points);
+        let openings = FriOpeningsTarget {
+            batches: values
+                .iter()
+                .map(|v| FriOpeningBatchTarget { values: v.clone() })
+                .collect(),
+        };
+        let caps = commitments
+            .iter()
+            .map(|c| c.cap.clone())
+            .collect::<Vec<_>>();
+        builder.verify_fri_proof::<C>(
+            &instance,
+            &openings,
+            &challenges,
+            &caps,
+            proof,
+            &self.params,
+        );
+    }
+
+    fn set_commitment_target<W: WitnessWrite<F> + ?Sized>(
+        witness: &mut W,
+        target: &Self::CommitmentTarget,
+        commitment: &Self::Commitment,
+    ) {
+        witness.set_cap_target(&target.cap, &commitment.cap);
+    }
+
+    fn set_proof_target<W: WitnessWrite<F> + ?Sized>(
+        witness: &mut W,
+        target: &Self::ProofTarget,
+        proof: &Self::Proof,
+    ) {
+        set_fri_proof_target(witness, target, proof);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+    use crate::field::types::{Field, Sample};
+    use crate::iop::witness::PartialWitness;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type FE = <F as Extendable<D>>::Extension;
+    type H = <C as GenericConfig<D>>::Hasher;
+
+    const DEGREE_BITS: usize = 6;
+
+    fn pcs(hiding: bool) -> FriPcs {
+        let config = CircuitConfig::standard_recursion_config().fri_config;
+        FriPcs::new(config.fri_params(DEGREE_BITS, hiding))
+    }
+
+    fn random_polys(num_polys: usize) -> Vec<PolynomialCoeffs<F>> {
+        (0..num_polys)
+            .map(|_| PolynomialCoeffs::new(F::rand_vec(1 << DEGREE_BITS)))
+            .collect()
+    }
+
+    /// Points, opened values and opening proof.
+    type OpenedValues = (Vec<FE>, Vec<Vec<FE>>, FriProof<F, H, D>);
+
+    /// Commits to two batches, opens them at two random points and returns everything the
+    /// verifier needs.
+    fn commit_and_open(pcs: &FriPcs) -> (Vec<FriCommitment<F, H>>, OpenedValues) {
+        let mut timing = TimingTree::default();
+        let (c0, batch0) =
+            PolynomialCommitmentScheme::<F, C, D>::commit(pcs, random_polys(3), &mut timing);
+        let (c1, batch1) =
+            PolynomialCommitmentScheme::<F, C, D>::commit(pcs, random_polys(2), &mut timing);
+
+        let mut challenger = Challenger::<F, H>::new();
+        challenger.observe_cap(&c0.cap);
+        challenger.observe_cap(&c1.cap);
+        let points = vec![
+            challenger.get_extension_challenge::<D>(),
+            challenger.get_extension_challenge::<D>(),
+        ];
+        let (values, proof) = PolynomialCommitmentScheme::<F, C, D>::open(
+            pcs,
+            &[&batch0, &batch1],
+            &points,
+            &mut challenger,
+            &mut timing,
+        );
+        (vec![c0, c1], (points, values, proof))
+    }
+
+    fn verifier_challenger(commitments: &[FriCommitment<F, H>]) -> Challenger<F, H> {
+        let mut challenger = Challenger::<F, H>::new();
+        for c in commitments {
+            challenger.observe_cap(&c.cap);
+        }
+        challenger.get_extension_challenge::<D>();
+        challenger.get_extension_challenge::<D>();
+        challenger
+    }
+
+    #[test]
+    fn test_fri_pcs() -> Result<()> {
+        for hiding in [false, true] {
+            let pcs = pcs(hiding);
+            let (commitments, (points, mut values, proof)) = commit_and_open(&pcs);
+
+            PolynomialCommitmentScheme::<F, C, D>::verify(
+                &pcs,
+                &commitments,
+                &points,
+                &values,
+                &proof,
+                &mut verifier_challenger(&commitments),
+            )?;
+
+            values[1][4] += FE::ONE;
+            assert!(PolynomialCommitmentScheme::<F, C, D>::verify(
+                &pcs,
+                &commitments,
+                &points,
+                &values,
+                &proof,
+                &mut verifier_challenger(&commitments),
+            )
+            .is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "Polynomial 0 has 65 coefficients")]
+    fn test_fri_pcs_degree_too_large() {
+        let poly = PolynomialCoeffs::new(F::rand_vec((1 << DEGREE_BITS) + 1));
+        PolynomialCommitmentScheme::<F, C, D>::commit(
+            &pcs(false),
+            vec![poly],
+            &mut TimingTree::default(),
+        );
+    }
+
+    #[test]
+    fn test_fri_pcs_recursive() -> Result<()> {
+        let pcs = pcs(false);
+        let (commitments, (points, values, proof)) = commit_and_open(&pcs);
+        let num_polys = [3, 2];
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let commitments_t = num_polys
+            .iter()
+            .map(|&n| {
+                RecursivePolynomialCommitmentScheme::<F, C, D>::add_virtual_commitment(
+                    &pcs,
+                    &mut builder,
+                    n,
+                )
+            })
+            .collect::<Vec<_>>();
+        let values_t = values
+            .iter()
+            .map(|v| builder.add_virtual_extension_targets(v.len()))
+            .collect::<Vec<_>>();
+        let proof_t = RecursivePolynomialCommitmentScheme::<F, C, D>::add_virtual_proof(
+            &pcs,
+            &mut builder,
+            &num_polys,
+            points.len(),
+        );
+
+        let mut challenger = RecursiveChallenger::<F, H, D>::new(&mut builder);
+        for c in &commitments_t {
+            challenger.observe_cap(&c.cap);
+        }
+        let points_t = (0..points.len())
+            .map(|_| challenger.get_extension_challenge(&mut builder))
+            .collect::<Vec<_>>();
+        RecursivePolynomialCommitmentScheme::<F, C, D>::verify_circuit(
+            &pcs,
+            &mut builder,
+            &commitments_t,
+            &points_t,
+            &values_t,
+            &proof_t,
+            &mut challenger,
+        );
+
+        let mut pw = PartialWitness::new();
+        for (t, c) in commitments_t.iter().zip(&commitments) {
+            <FriPcs as RecursivePolynomialCommitmentScheme<F, C, D>>::set_commitment_target(
+                &mut pw, t, c,
+            );
+        }
+        for (t, v) in values_t.iter().zip(&values) {
+            pw.set_extension_targets(t, v);
+        }
+        <FriPcs as RecursivePolynomialCommitmentScheme<F, C, D>>::set_proof_target(
+            &mut pw, &proof_t, &proof,
+        );
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+}
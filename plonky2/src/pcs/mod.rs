@@ -0,0 +1,117 @@
+//! A generic interface for polynomial commitment schemes, so that protocols built on top of
+//! plonky2 can commit to and open arbitrary polynomials without dealing with the PLONK-specific
+//! oracle layout.
+//!
+//! Opening values are laid out as `values[i][j]`, the evaluation at `points[i]` of the `j`th
+//! committed polynomial, counting the polynomials of each commitment in order.
+
+use alloc::vec::Vec;
+
+use anyhow::Result;
+
+use crate::field::extension::Extendable;
+use crate::field::polynomial::PolynomialCoeffs;
+use crate::hash::hash_types::RichField;
+use crate::iop::challenger::{Challenger, RecursiveChallenger};
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::witness::WitnessWrite;
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::config::{AlgebraicHasher, GenericConfig};
+use crate::util::timing::TimingTree;
+
+pub mod fri;
+
+pub trait PolynomialCommitmentScheme<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>
+{
+    /// The data sent to the verifier.
+    type Commitment;
+    /// The data the prover keeps in order to open the commitment later.
+    type ProverData;
+    type Proof;
+
+    /// Commits to a batch of polynomials. Each polynomial must have at most as many coefficients
+    /// as the degree bound of the scheme, e.g. `2^degree_bits` for FRI; implementations panic
+    /// otherwise.
+    fn commit(
+        &self,
+        polynomials: Vec<PolynomialCoeffs<F>>,
+        timing: &mut TimingTree,
+    ) -> (Self::Commitment, Self::ProverData);
+
+    /// Opens every polynomial of every commitment in `prover_data` at every point in `points`.
+    /// The commitments should already have been observed by `challenger` before the points were
+    /// sampled.
+    fn open(
+        &self,
+        prover_data: &[&Self::ProverData],
+        points: &[F::Extension],
+        challenger: &mut Challenger<F, C::Hasher>,
+        timing: &mut TimingTree,
+    ) -> (Vec<Vec<F::Extension>>, Self::Proof);
+
+    /// Checks that `values` are the evaluations at `points` of the polynomials in `commitments`.
+    /// `challenger` must be in the same state as the prover's was when calling `open`.
+    fn verify(
+        &self,
+        commitments: &[Self::Commitment],
+        points: &[F::Extension],
+        values: &[Vec<F::Extension>],
+        proof: &Self::Proof,
+        challenger: &mut Challenger<F, C::Hasher>,
+    ) -> Result<()>;
+}
+
+/// A polynomial commitment scheme whose openings can also be verified in a circuit.
+pub trait RecursivePolynomialCommitmentScheme<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>: PolynomialCommitmentScheme<F, C, D> where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    type CommitmentTarget;
+    type ProofTarget;
+
+    /// Adds targets for a commitment to `num_polys` polynomials.
+    fn add_virtual_commitment(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        num_polys: usize,
+    ) -> Self::CommitmentTarget;
+
+    /// Adds targets for a proof opening commitments to `num_polys[k]` polynomials each, at
+    /// `num_points` points.
+    fn add_virtual_proof(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        num_polys: &[usize],
+        num_points: usize,
+    ) -> Self::ProofTarget;
+
+    /// In-circuit version of `verify`.
+    fn verify_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        commitments: &[Self::CommitmentTarget],
+        points: &[ExtensionTarget<D>],
+        values: &[Vec<ExtensionTarget<D>>],
+        proof: &Self::ProofTarget,
+        challenger: &mut RecursiveChallenger<F, C::Hasher, D>,
+    );
+
+    fn set_commitment_target<W: WitnessWrite<F> + ?Sized>(
+        witness: &mut W,
+        target: &Self::CommitmentTarget,
+        commitment: &Self::Commitment,
+    );
+
+    fn set_proof_target<W: WitnessWrite<F> + ?Sized>(
+        witness: &mut W,
+        target: &Self::ProofTarget,
+        proof: &Self::Proof,
+    );
+}
@@ -919,6 +919,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             representative_map: forest.parents,
             fft_root_table: Some(fft_root_table),
             circuit_digest,
+            parallel_witness_generation: true,
         };
 
         let verifier_only = VerifierOnlyCircuitData {
@@ -23,14 +23,17 @@ use crate::hash::merkle_tree::MerkleCap;
 use crate::iop::ext_target::ExtensionTarget;
 use crate::iop::generator::WitnessGenerator;
 use crate::iop::target::Target;
-use crate::iop::witness::PartialWitness;
+use crate::iop::witness::{MatrixWitness, PartialWitness};
 use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::config::{GenericConfig, Hasher};
 use crate::plonk::plonk_common::PlonkOracle;
 use crate::plonk::proof::{CompressedProofWithPublicInputs, ProofWithPublicInputs};
-use crate::plonk::prover::prove;
+use crate::plonk::prover::{generate_full_witness, prove, prove_from_full_witness};
 use crate::plonk::verifier::verify;
 use crate::timed;
+use crate::util::serialization::Write;
+#[cfg(feature = "std")]
+use crate::util::serialization::{Buffer, Read, Remaining};
 use crate::util::timing::TimingTree;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -132,6 +135,29 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         )
     }
 
+    /// Runs witness generation only; see `prove_from_full_witness`.
+    pub fn generate_full_witness(&self, inputs: PartialWitness<F>) -> MatrixWitness<F> {
+        generate_full_witness(
+            &self.prover_only,
+            &self.common,
+            inputs,
+            &mut TimingTree::default(),
+        )
+    }
+
+    /// Proves a witness returned by `generate_full_witness`, skipping witness generation.
+    pub fn prove_from_full_witness(
+        &self,
+        witness: MatrixWitness<F>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        prove_from_full_witness(
+            &self.prover_only,
+            &self.common,
+            witness,
+            &mut TimingTree::default(),
+        )
+    }
+
     pub fn verify(&self, proof_with_pis: ProofWithPublicInputs<F, C, D>) -> Result<()> {
         verify(proof_with_pis, &self.verifier_only, &self.common)
     }
@@ -209,6 +235,29 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
             &mut TimingTree::default(),
         )
     }
+
+    /// Runs witness generation only; see `prove_from_full_witness`.
+    pub fn generate_full_witness(&self, inputs: PartialWitness<F>) -> MatrixWitness<F> {
+        generate_full_witness(
+            &self.prover_only,
+            &self.common,
+            inputs,
+            &mut TimingTree::default(),
+        )
+    }
+
+    /// Proves a witness returned by `generate_full_witness`, skipping witness generation.
+    pub fn prove_from_full_witness(
+        &self,
+        witness: MatrixWitness<F>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        prove_from_full_witness(
+            &self.prover_only,
+            &self.common,
+            witness,
+            &mut TimingTree::default(),
+        )
+    }
 }
 
 /// Circuit data required by the prover.
@@ -263,6 +312,9 @@ pub struct ProverOnlyCircuitData<
     /// A digest of the "circuit" (i.e. the instance, minus public inputs), which can be used to
     /// seed Fiat-Shamir.
     pub circuit_digest: <<C as GenericConfig<D>>::Hasher as Hasher<F>>::Hash,
+    /// Whether the prover runs ready witness generators concurrently. If false, generators are
+    /// run one at a time, as they become ready. This does not affect the generated witness.
+    pub parallel_witness_generation: bool,
 }
 
 /// Circuit data required by the verifier, but not the prover.
@@ -275,6 +327,26 @@ pub struct VerifierOnlyCircuitData<C: GenericConfig<D>, const D: usize> {
     pub circuit_digest: <<C as GenericConfig<D>>::Hasher as Hasher<C::F>>::Hash,
 }
 
+impl<C: GenericConfig<D>, const D: usize> VerifierOnlyCircuitData<C, D> {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_verifier_only_circuit_data(self)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    #[cfg(feature = "std")]
+    pub fn from_bytes(bytes: Vec<u8>, common_data: &CommonCircuitData<C::F, D>) -> Result<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let verifier_data = buffer
+            .read_verifier_only_circuit_data(common_data)
+            .map_err(anyhow::Error::msg)?;
+        anyhow::ensure!(buffer.is_empty(), "Trailing bytes after verifier data.");
+        Ok(verifier_data)
+    }
+}
+
 /// Circuit data required by both the prover and the verifier.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct CommonCircuitData<F: RichField + Extendable<D>, const D: usize> {
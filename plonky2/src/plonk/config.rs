@@ -21,6 +21,14 @@ pub trait GenericHashOut<F: RichField>:
     fn to_bytes(&self) -> Vec<u8>;
     fn from_bytes(bytes: &[u8]) -> Self;
 
+    /// Like `from_bytes`, but returns `None` unless `bytes` is the output of `to_bytes` for some
+    /// hash. The default implementation checks that decoding and re-encoding gives back `bytes`;
+    /// implementations whose `from_bytes` can't accept every input should override it.
+    fn try_from_bytes(bytes: &[u8]) -> Option<Self> {
+        let hash = Self::from_bytes(bytes);
+        (hash.to_bytes() == bytes).then_some(hash)
+    }
+
     fn to_vec(&self) -> Vec<F>;
 }
 
@@ -107,6 +115,11 @@ pub trait GenericConfig<const D: usize>:
     type InnerHasher: AlgebraicHasher<Self::F>;
 }
 
+/// A stable name for a configuration, recorded in proof containers.
+pub trait ConfigId {
+    const ID: &'static str;
+}
+
 /// Configuration using Poseidon over the Goldilocks field.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct PoseidonGoldilocksConfig;
@@ -117,6 +130,10 @@ impl GenericConfig<2> for PoseidonGoldilocksConfig {
     type InnerHasher = PoseidonHash;
 }
 
+impl ConfigId for PoseidonGoldilocksConfig {
+    const ID: &'static str = "poseidon-goldilocks";
+}
+
 /// Configuration using truncated Keccak over the Goldilocks field.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct KeccakGoldilocksConfig;
@@ -126,3 +143,7 @@ impl GenericConfig<2> for KeccakGoldilocksConfig {
     type Hasher = KeccakHash<25>;
     type InnerHasher = PoseidonHash;
 }
+
+impl ConfigId for KeccakGoldilocksConfig {
+    const ID: &'static str = "keccak-goldilocks";
+}
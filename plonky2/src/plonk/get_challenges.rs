@@ -1,11 +1,12 @@
-use alloc::vec;
 use alloc::vec::Vec;
 
-use hashbrown::HashSet;
+use hashbrown::HashMap;
 
 use crate::field::extension::Extendable;
 use crate::field::polynomial::PolynomialCoeffs;
-use crate::fri::proof::{CompressedFriProof, FriChallenges, FriProof, FriProofTarget};
+use crate::fri::proof::{
+    fri_coset_positions, CompressedFriProof, FriChallenges, FriProof, FriProofTarget,
+};
 use crate::fri::verifier::{compute_evaluation, fri_combine_initial, PrecomputedReducedOpenings};
 use crate::gadgets::polynomial::PolynomialCoeffsExtTarget;
 use crate::hash::hash_types::{HashOutTarget, MerkleCapTarget, RichField};
@@ -164,6 +165,22 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         challenges: &ProofChallenges<F, D>,
         common_data: &CommonCircuitData<F, D>,
     ) -> FriInferredElements<F, D> {
+        self.proof.opening_proof.get_inferred_elements::<C>(
+            &common_data.get_fri_instance(challenges.plonk_zeta),
+            &self.proof.openings.to_fri_openings(),
+            &challenges.fri_challenges,
+            &common_data.fri_params,
+        )
+    }
+
+    /// Fills in the FRI query step evaluations omitted by the compact encoding, i.e. the ones at
+    /// positions reached by a query other than the first query reaching that coset. Those
+    /// evaluations are inferred from the previous reduction step, as in `get_inferred_elements`.
+    pub(crate) fn fill_compact_fri_evals(
+        &mut self,
+        challenges: &ProofChallenges<F, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) {
         let ProofChallenges {
             plonk_zeta,
             fri_challenges:
@@ -175,62 +192,55 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
                 },
             ..
         } = challenges;
-        let mut fri_inferred_elements = Vec::new();
-        // Holds the indices that have already been seen at each reduction depth.
-        let mut seen_indices_by_depth =
-            vec![HashSet::new(); common_data.fri_params.reduction_arity_bits.len()];
+        let reduction_arity_bits = &common_data.fri_params.reduction_arity_bits;
         let precomputed_reduced_evals = PrecomputedReducedOpenings::from_os_and_alpha(
             &self.proof.openings.to_fri_openings(),
             *fri_alpha,
         );
+        let fri_instance = common_data.get_fri_instance(*plonk_zeta);
+        let query_round_proofs = &mut self.proof.opening_proof.query_round_proofs;
         let log_n = common_data.degree_bits() + common_data.config.fri_config.rate_bits;
-        // Simulate the proof verification and collect the inferred elements.
-        // The content of the loop is basically the same as the `fri_verifier_query_round` function.
-        for &(mut x_index) in fri_query_indices {
-            let mut subgroup_x = F::MULTIPLICATIVE_GROUP_GENERATOR
+
+        // Maps each index reached at the current depth to its point and inferred evaluation.
+        let mut reached = HashMap::new();
+        for &x_index in fri_query_indices {
+            let subgroup_x = F::MULTIPLICATIVE_GROUP_GENERATOR
                 * F::primitive_root_of_unity(log_n).exp_u64(reverse_bits(x_index, log_n) as u64);
-            let mut old_eval = fri_combine_initial::<F, C, D>(
-                &common_data.get_fri_instance(*plonk_zeta),
-                &self
-                    .proof
-                    .opening_proof
-                    .query_round_proofs
-                    .initial_trees_proofs[&x_index],
+            let eval = fri_combine_initial::<F, C, D>(
+                &fri_instance,
+                &query_round_proofs.initial_trees_proofs[&x_index],
                 *fri_alpha,
                 subgroup_x,
                 &precomputed_reduced_evals,
                 &common_data.fri_params,
             );
-            for (i, &arity_bits) in common_data
-                .fri_params
-                .reduction_arity_bits
-                .iter()
-                .enumerate()
-            {
-                let coset_index = x_index >> arity_bits;
-                if !seen_indices_by_depth[i].insert(coset_index) {
-                    // If this index has already been seen, we can skip the rest of the reductions.
-                    break;
+            reached.insert(x_index, (subgroup_x, eval));
+        }
+
+        let coset_positions = fri_coset_positions(fri_query_indices, reduction_arity_bits);
+        for (i, (&arity_bits, cosets)) in
+            reduction_arity_bits.iter().zip(coset_positions).enumerate()
+        {
+            let mut next_reached = HashMap::new();
+            for (coset_index, positions) in cosets {
+                let first = positions[0];
+                let evals = &mut query_round_proofs.steps[i]
+                    .get_mut(&coset_index)
+                    .unwrap()
+                    .evals;
+                for &position in &positions[1..] {
+                    let (_, eval) = reached[&((coset_index << arity_bits) | position)];
+                    evals[position - usize::from(position > first)] = eval;
                 }
-                fri_inferred_elements.push(old_eval);
-                let arity = 1 << arity_bits;
-                let mut evals = self.proof.opening_proof.query_round_proofs.steps[i][&coset_index]
-                    .evals
-                    .clone();
-                let x_index_within_coset = x_index & (arity - 1);
-                evals.insert(x_index_within_coset, old_eval);
-                old_eval = compute_evaluation(
-                    subgroup_x,
-                    x_index_within_coset,
-                    arity_bits,
-                    &evals,
-                    fri_betas[i],
-                );
-                subgroup_x = subgroup_x.exp_power_of_2(arity_bits);
-                x_index = coset_index;
+                let (subgroup_x, first_eval) = reached[&((coset_index << arity_bits) | first)];
+                let mut coset_evals = evals.clone();
+                coset_evals.insert(first, first_eval);
+                let eval =
+                    compute_evaluation(subgroup_x, first, arity_bits, &coset_evals, fri_betas[i]);
+                next_reached.insert(coset_index, (subgroup_x.exp_power_of_2(arity_bits), eval));
             }
+            reached = next_reached;
         }
-        FriInferredElements(fri_inferred_elements)
     }
 }
 
@@ -6,6 +6,7 @@ mod get_challenges;
 pub(crate) mod permutation_argument;
 pub mod plonk_common;
 pub mod proof;
+pub mod proof_container;
 pub mod prover;
 mod validate_shape;
 pub(crate) mod vanishing_poly;
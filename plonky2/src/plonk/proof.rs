@@ -23,7 +23,7 @@ use crate::plonk::config::{GenericConfig, Hasher};
 use crate::plonk::verifier::verify_with_challenges;
 use crate::util::serialization::Write;
 #[cfg(feature = "std")]
-use crate::util::serialization::{Buffer, Read};
+use crate::util::serialization::{Buffer, Read, Remaining};
 
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
 #[serde(bound = "")]
@@ -163,7 +163,11 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
             plonk_zs_partial_products_cap,
             quotient_polys_cap,
             openings,
-            opening_proof: opening_proof.decompress::<C>(challenges, fri_inferred_elements, params),
+            opening_proof: opening_proof.decompress::<C>(
+                &challenges.fri_challenges,
+                fri_inferred_elements,
+                params,
+            ),
         }
     }
 }
@@ -252,6 +256,31 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
             .map_err(anyhow::Error::msg)?;
         Ok(proof)
     }
+
+    /// Serializes the proof more compactly than `to_bytes`, by omitting everything the verifier
+    /// can recompute: the FRI query indices, the last quotient chunk opening for each challenge,
+    /// and the FRI query step evaluations implied by other query rounds.
+    pub fn to_compact_bytes(&self, common_data: &CommonCircuitData<F, D>) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_compact_proof_with_public_inputs(self, common_data)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    #[cfg(feature = "std")]
+    pub fn from_compact_bytes(
+        bytes: Vec<u8>,
+        circuit_digest: &<<C as GenericConfig<D>>::Hasher as Hasher<C::F>>::Hash,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> anyhow::Result<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let proof = buffer
+            .read_compact_proof_with_public_inputs(circuit_digest, common_data)
+            .map_err(anyhow::Error::msg)?;
+        ensure!(buffer.is_empty(), "Trailing bytes after compact proof.");
+        Ok(proof)
+    }
 }
 
 pub(crate) struct ProofChallenges<F: RichField + Extendable<D>, const D: usize> {
@@ -279,9 +308,7 @@ pub(crate) struct ProofChallengesTarget<const D: usize> {
 }
 
 /// Coset elements that can be inferred in the FRI reduction steps.
-pub(crate) struct FriInferredElements<F: RichField + Extendable<D>, const D: usize>(
-    pub Vec<F::Extension>,
-);
+pub struct FriInferredElements<F: RichField + Extendable<D>, const D: usize>(pub Vec<F::Extension>);
 
 #[derive(Clone, Debug)]
 pub struct ProofWithPublicInputsTarget<const D: usize> {
@@ -392,10 +419,11 @@ impl<const D: usize> OpeningSetTarget<D> {
 mod tests {
     use anyhow::Result;
 
+    use super::*;
     use crate::field::types::Sample;
     use crate::fri::reduction_strategies::FriReductionStrategy;
     use crate::gates::noop::NoopGate;
-    use crate::iop::witness::PartialWitness;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
     use crate::plonk::circuit_builder::CircuitBuilder;
     use crate::plonk::circuit_data::CircuitConfig;
     use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
@@ -438,4 +466,45 @@ mod tests {
         verify(proof, &data.verifier_only, &data.common)?;
         data.verify_compressed(compressed_proof)
     }
+
+    #[test]
+    fn test_compact_proof_encoding() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut config = CircuitConfig::standard_recursion_config();
+        // Use few, high-arity reductions and many queries so that query rounds share cosets.
+        config.fri_config.reduction_strategy = FriReductionStrategy::Fixed(vec![3, 3]);
+        config.fri_config.num_query_rounds = 80;
+
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.add_virtual_target();
+        let z = builder.mul(x, y);
+        builder.register_public_input(z);
+        for _ in 0..100 {
+            builder.add_gate(NoopGate, vec![]);
+        }
+        pw.set_target(x, F::rand());
+        pw.set_target(y, F::rand());
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        let compressed_proof = data.compress(proof.clone())?;
+        let compact_bytes = compressed_proof.to_compact_bytes(&data.common);
+        let compressed_bytes = compressed_proof.to_bytes();
+        let proof_bytes = proof.to_bytes();
+        assert!(compact_bytes.len() < compressed_bytes.len());
+        assert!(compressed_bytes.len() < proof_bytes.len());
+
+        let compact_proof = CompressedProofWithPublicInputs::from_compact_bytes(
+            compact_bytes,
+            &data.verifier_only.circuit_digest,
+            &data.common,
+        )?;
+        assert_eq!(compressed_proof, compact_proof);
+        data.verify_compressed(compact_proof)
+    }
 }
@@ -0,0 +1,365 @@
+//! A versioned, self-describing envelope around serialized proofs.
+//!
+//! The container records which configuration and circuit a proof was generated for, so that a
+//! proof given to the wrong verifier fails with a descriptive error rather than an opaque
+//! deserialization failure. The layout is:
+//!
+//! | field          | encoding                    |
+//! |----------------|-----------------------------|
+//! | magic          | `PLK2`                      |
+//! | version        | `u16`, little-endian        |
+//! | config ID      | `u8` length, UTF-8 bytes    |
+//! | circuit digest | `u8` length, bytes          |
+//! | degree bits    | `u8`                        |
+//! | encoding       | `u8`, see `ProofEncoding`   |
+//! | payload        | `u32` length, little-endian, followed by the encoded proof |
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use anyhow::{anyhow, bail, ensure, Result};
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::plonk::config::{ConfigId, GenericConfig, GenericHashOut, Hasher};
+#[cfg(feature = "std")]
+use crate::plonk::proof::CompressedProofWithPublicInputs;
+use crate::plonk::proof::ProofWithPublicInputs;
+
+/// The bytes every proof container starts with.
+pub const PROOF_CONTAINER_MAGIC: [u8; 4] = *b"PLK2";
+
+/// The current container format version. Readers reject containers with a different version.
+pub const PROOF_CONTAINER_VERSION: u16 = 1;
+
+/// How the proof inside a container is serialized.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u8)]
+pub enum ProofEncoding {
+    /// `ProofWithPublicInputs::to_bytes`.
+    Plain = 0,
+    /// `CompressedProofWithPublicInputs::to_bytes`.
+    Compressed = 1,
+    /// `CompressedProofWithPublicInputs::to_compact_bytes`.
+    Compact = 2,
+}
+
+impl ProofEncoding {
+    fn from_u8(tag: u8) -> Result<Self> {
+        match tag {
+            0 => Ok(Self::Plain),
+            1 => Ok(Self::Compressed),
+            2 => Ok(Self::Compact),
+            _ => bail!("Unknown proof encoding {} in proof container", tag),
+        }
+    }
+}
+
+/// The metadata stored in front of the proof in a container.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProofContainerHeader {
+    pub version: u16,
+    /// The `ConfigId::ID` of the configuration used to generate the proof.
+    pub config_id: String,
+    /// The bytes of the circuit digest of the circuit being proven.
+    pub circuit_digest: Vec<u8>,
+    pub degree_bits: usize,
+    pub encoding: ProofEncoding,
+}
+
+impl ProofContainerHeader {
+    fn new<F: RichField + Extendable<D>, C: GenericConfig<D, F = F> + ConfigId, const D: usize>(
+        encoding: ProofEncoding,
+        circuit_digest: &<<C as GenericConfig<D>>::Hasher as Hasher<F>>::Hash,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Self {
+        Self {
+            version: PROOF_CONTAINER_VERSION,
+            config_id: C::ID.into(),
+            circuit_digest: circuit_digest.to_bytes(),
+            degree_bits: common_data.degree_bits(),
+            encoding,
+        }
+    }
+
+    /// Parses the header of a container, returning it along with the encoded proof. This does
+    /// not require any circuit data, so it can be used to inspect a container before verifying.
+    pub fn parse(bytes: &[u8]) -> Result<(Self, &[u8])> {
+        let mut reader = HeaderReader(bytes);
+        ensure!(
+            reader.take(PROOF_CONTAINER_MAGIC.len(), "magic")? == PROOF_CONTAINER_MAGIC,
+            "Not a proof container: bad magic bytes"
+        );
+        let version = u16::from_le_bytes(reader.take(2, "version")?.try_into().unwrap());
+        ensure!(
+            version == PROOF_CONTAINER_VERSION,
+            "Unsupported proof container version {}, expected {}",
+            version,
+            PROOF_CONTAINER_VERSION
+        );
+        let config_id_len = reader.take_u8("config ID length")? as usize;
+        let config_id = String::from_utf8(reader.take(config_id_len, "config ID")?.to_vec())
+            .map_err(|_| anyhow!("Proof container config ID is not valid UTF-8"))?;
+        let digest_len = reader.take_u8("circuit digest length")? as usize;
+        let circuit_digest = reader.take(digest_len, "circuit digest")?.to_vec();
+        let degree_bits = reader.take_u8("degree bits")? as usize;
+        let encoding = ProofEncoding::from_u8(reader.take_u8("encoding")?)?;
+        let payload_len =
+            u32::from_le_bytes(reader.take(4, "payload length")?.try_into().unwrap()) as usize;
+        let payload = reader.take(payload_len, "payload")?;
+        ensure!(
+            reader.0.is_empty(),
+            "Proof container has {} trailing bytes after the payload",
+            reader.0.len()
+        );
+
+        let header = Self {
+            version,
+            config_id,
+            circuit_digest,
+            degree_bits,
+            encoding,
+        };
+        Ok((header, payload))
+    }
+
+    /// Checks that this header describes a proof for the given configuration and circuit.
+    pub fn check<F, C, const D: usize>(
+        &self,
+        circuit_digest: &<<C as GenericConfig<D>>::Hasher as Hasher<F>>::Hash,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Result<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F> + ConfigId,
+    {
+        ensure!(
+            self.config_id == C::ID,
+            "Proof was generated with config `{}`, but the verifier uses `{}`",
+            self.config_id,
+            C::ID
+        );
+        ensure!(
+            self.degree_bits == common_data.degree_bits(),
+            "Proof is for a circuit of degree 2^{}, but the verifier's circuit has degree 2^{}",
+            self.degree_bits,
+            common_data.degree_bits()
+        );
+        ensure!(
+            self.circuit_digest == circuit_digest.to_bytes(),
+            "Proof circuit digest doesn't match the verifier's circuit digest"
+        );
+        Ok(())
+    }
+
+    fn write(&self, payload: &[u8]) -> Result<Vec<u8>> {
+        let config_id_len = u8::try_from(self.config_id.len())
+            .map_err(|_| anyhow!("Config ID `{}` is too long", self.config_id))?;
+        let digest_len = u8::try_from(self.circuit_digest.len())
+            .map_err(|_| anyhow!("Circuit digest is too long"))?;
+        let degree_bits =
+            u8::try_from(self.degree_bits).map_err(|_| anyhow!("Degree bits are too large"))?;
+        let payload_len =
+            u32::try_from(payload.len()).map_err(|_| anyhow!("Proof is too large"))?;
+
+        let mut bytes = Vec::with_capacity(payload.len() + 64);
+        bytes.extend_from_slice(&PROOF_CONTAINER_MAGIC);
+        bytes.extend_from_slice(&self.version.to_le_bytes());
+        bytes.push(config_id_len);
+        bytes.extend_from_slice(self.config_id.as_bytes());
+        bytes.push(digest_len);
+        bytes.extend_from_slice(&self.circuit_digest);
+        bytes.push(degree_bits);
+        bytes.push(self.encoding as u8);
+        bytes.extend_from_slice(&payload_len.to_le_bytes());
+        bytes.extend_from_slice(payload);
+        Ok(bytes)
+    }
+}
+
+struct HeaderReader<'a>(&'a [u8]);
+
+impl<'a> HeaderReader<'a> {
+    fn take(&mut self, n: usize, field: &str) -> Result<&'a [u8]> {
+        ensure!(
+            self.0.len() >= n,
+            "Proof container is truncated: missing {} ({} bytes needed, {} left)",
+            field,
+            n,
+            self.0.len()
+        );
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    fn take_u8(&mut self, field: &str) -> Result<u8> {
+        Ok(self.take(1, field)?[0])
+    }
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    ProofWithPublicInputs<F, C, D>
+{
+    /// Serializes the proof with the given encoding, wrapped in a versioned container that
+    /// records the configuration and circuit it belongs to.
+    pub fn to_container_bytes(
+        &self,
+        encoding: ProofEncoding,
+        circuit_digest: &<<C as GenericConfig<D>>::Hasher as Hasher<F>>::Hash,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Result<Vec<u8>>
+    where
+        C: ConfigId,
+    {
+        let payload = match encoding {
+            ProofEncoding::Plain => self.to_bytes(),
+            ProofEncoding::Compressed => self
+                .clone()
+                .compress(circuit_digest, common_data)?
+                .to_bytes(),
+            ProofEncoding::Compact => self
+                .clone()
+                .compress(circuit_digest, common_data)?
+                .to_compact_bytes(common_data),
+        };
+        ProofContainerHeader::new::<F, C, D>(encoding, circuit_digest, common_data).write(&payload)
+    }
+
+    /// Reads a proof from a container written by `to_container_bytes`, in any encoding. Fails
+    /// with a descriptive error if the container was written for a different configuration or
+    /// circuit.
+    #[cfg(feature = "std")]
+    pub fn from_container_bytes(
+        bytes: &[u8],
+        circuit_digest: &<<C as GenericConfig<D>>::Hasher as Hasher<F>>::Hash,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> Result<Self>
+    where
+        C: ConfigId,
+    {
+        let (header, payload) = ProofContainerHeader::parse(bytes)?;
+        header.check::<F, C, D>(circuit_digest, common_data)?;
+        let payload = payload.to_vec();
+        let proof = match header.encoding {
+            ProofEncoding::Plain => Self::from_bytes(payload, common_data),
+            ProofEncoding::Compressed => {
+                CompressedProofWithPublicInputs::from_bytes(payload, common_data)
+                    .and_then(|p| p.decompress(circuit_digest, common_data))
+            }
+            ProofEncoding::Compact => CompressedProofWithPublicInputs::from_compact_bytes(
+                payload,
+                circuit_digest,
+                common_data,
+            )
+            .and_then(|p| p.decompress(circuit_digest, common_data)),
+        }
+        .map_err(|e| anyhow!("Malformed {:?} proof in container: {}", header.encoding, e))?;
+        ensure!(
+            proof.public_inputs.len() == common_data.num_public_inputs,
+            "Proof has {} public inputs, but the circuit expects {}",
+            proof.public_inputs.len(),
+            common_data.num_public_inputs
+        );
+        Ok(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::types::Sample;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::{CircuitConfig, CircuitData};
+    use crate::plonk::config::PoseidonGoldilocksConfig;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn circuit(num_muls: usize) -> (CircuitData<F, C, D>, PartialWitness<F>) {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let mut y = x;
+        for _ in 0..num_muls {
+            y = builder.mul(y, x);
+        }
+        builder.register_public_input(y);
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::rand());
+        (builder.build::<C>(), pw)
+    }
+
+    #[test]
+    fn test_proof_container_round_trip() -> Result<()> {
+        let (data, pw) = circuit(1);
+        let proof = data.prove(pw)?;
+        let digest = &data.verifier_only.circuit_digest;
+
+        for encoding in [
+            ProofEncoding::Plain,
+            ProofEncoding::Compressed,
+            ProofEncoding::Compact,
+        ] {
+            let bytes = proof.to_container_bytes(encoding, digest, &data.common)?;
+            let (header, _) = ProofContainerHeader::parse(&bytes)?;
+            assert_eq!(header.encoding, encoding);
+            assert_eq!(header.config_id, C::ID);
+            assert_eq!(header.degree_bits, data.common.degree_bits());
+
+            let read = ProofWithPublicInputs::from_container_bytes(&bytes, digest, &data.common)?;
+            assert_eq!(read, proof);
+        }
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_proof_container_mismatch() -> Result<()> {
+        let (data, pw) = circuit(1);
+        let (other_data, _) = circuit(1000);
+        let proof = data.prove(pw)?;
+        let bytes = proof.to_container_bytes(
+            ProofEncoding::Compressed,
+            &data.verifier_only.circuit_digest,
+            &data.common,
+        )?;
+
+        let err = ProofWithPublicInputs::<F, C, D>::from_container_bytes(
+            &bytes,
+            &other_data.verifier_only.circuit_digest,
+            &other_data.common,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("degree"), "{}", err);
+
+        let err = ProofWithPublicInputs::<F, C, D>::from_container_bytes(
+            &bytes,
+            &other_data.verifier_only.circuit_digest,
+            &data.common,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("digest"), "{}", err);
+
+        let mut corrupted = bytes.clone();
+        corrupted[0] ^= 1;
+        let err = ProofWithPublicInputs::<F, C, D>::from_container_bytes(
+            &corrupted,
+            &data.verifier_only.circuit_digest,
+            &data.common,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("magic"), "{}", err);
+
+        let err = ProofWithPublicInputs::<F, C, D>::from_container_bytes(
+            &bytes[..bytes.len() - 1],
+            &data.verifier_only.circuit_digest,
+            &data.common,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("truncated"), "{}", err);
+        Ok(())
+    }
+}
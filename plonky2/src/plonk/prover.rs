@@ -2,7 +2,8 @@ use alloc::vec::Vec;
 use alloc::{format, vec};
 use core::mem::swap;
 
-use anyhow::{ensure, Result};
+use anyhow::{anyhow, ensure, Result};
+use hashbrown::HashMap;
 use maybe_rayon::*;
 
 use crate::field::extension::Extendable;
@@ -31,11 +32,6 @@ pub fn prove<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D:
     inputs: PartialWitness<F>,
     timing: &mut TimingTree,
 ) -> Result<ProofWithPublicInputs<F, C, D>> {
-    let config = &common_data.config;
-    let num_challenges = config.num_challenges;
-    let quotient_degree = common_data.quotient_degree();
-    let degree = common_data.degree();
-
     let partition_witness = timed!(
         timing,
         &format!("run {} generators", prover_data.generators.len()),
@@ -43,7 +39,6 @@ pub fn prove<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D:
     );
 
     let public_inputs = partition_witness.get_targets(&prover_data.public_inputs);
-    let public_inputs_hash = C::InnerHasher::hash_public_inputs(&public_inputs);
 
     let witness = timed!(
         timing,
@@ -51,6 +46,109 @@ pub fn prove<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D:
         partition_witness.full_witness()
     );
 
+    prove_with_witness(prover_data, common_data, public_inputs, witness, timing)
+}
+
+/// Runs the witness generators on `inputs` and returns the resulting wire values, which can later
+/// be proven with `prove_from_full_witness`, possibly on another machine.
+pub fn generate_full_witness<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    inputs: PartialWitness<F>,
+    timing: &mut TimingTree,
+) -> MatrixWitness<F> {
+    let partition_witness = timed!(
+        timing,
+        &format!("run {} generators", prover_data.generators.len()),
+        generate_partial_witness(inputs, prover_data, common_data)
+    );
+
+    timed!(
+        timing,
+        "compute full witness",
+        partition_witness.full_witness()
+    )
+}
+
+/// Proves a full witness, as returned by `generate_full_witness`, without running any witness
+/// generators. The public inputs are read from the wires they are copied to.
+pub fn prove_from_full_witness<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    witness: MatrixWitness<F>,
+    timing: &mut TimingTree,
+) -> Result<ProofWithPublicInputs<F, C, D>> {
+    let num_wires = common_data.config.num_wires;
+    let degree = common_data.degree();
+    ensure!(
+        witness.wire_values.len() == num_wires
+            && witness
+                .wire_values
+                .iter()
+                .all(|column| column.len() == degree),
+        "Witness shape does not match the circuit."
+    );
+
+    let public_inputs = public_inputs_from_full_witness(&witness, prover_data, common_data)?;
+
+    prove_with_witness(prover_data, common_data, public_inputs, witness, timing)
+}
+
+/// Finds the value of each public input in the wires of `witness`. Every public input is copied
+/// to at least one wire, since they are all hashed in the circuit.
+fn public_inputs_from_full_witness<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    witness: &MatrixWitness<F>,
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+) -> Result<Vec<F>> {
+    let num_wires = common_data.config.num_wires;
+    let degree = common_data.degree();
+
+    // Map each partition representative to the first wire in its partition.
+    let mut rep_wires = HashMap::new();
+    for i in (0..num_wires * degree).rev() {
+        rep_wires.insert(prover_data.representative_map[i], i);
+    }
+
+    prover_data
+        .public_inputs
+        .iter()
+        .map(|t| {
+            let rep = prover_data.representative_map[t.index(num_wires, degree)];
+            let &i = rep_wires
+                .get(&rep)
+                .ok_or_else(|| anyhow!("Public input {:?} is not copied to any wire.", t))?;
+            Ok(witness.get_wire(i / num_wires, i % num_wires))
+        })
+        .collect()
+}
+
+fn prove_with_witness<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    prover_data: &ProverOnlyCircuitData<F, C, D>,
+    common_data: &CommonCircuitData<F, D>,
+    public_inputs: Vec<F>,
+    witness: MatrixWitness<F>,
+    timing: &mut TimingTree,
+) -> Result<ProofWithPublicInputs<F, C, D>> {
+    let config = &common_data.config;
+    let num_challenges = config.num_challenges;
+    let quotient_degree = common_data.quotient_degree();
+    let degree = common_data.degree();
+
+    let public_inputs_hash = C::InnerHasher::hash_public_inputs(&public_inputs);
+
     let wires_values: Vec<PolynomialValues<F>> = timed!(
         timing,
         "compute wire polynomials",
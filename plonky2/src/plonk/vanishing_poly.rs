@@ -143,9 +143,10 @@ pub(crate) fn eval_vanishing_poly_base_batch<
     // The terms checking the partial products.
     let mut vanishing_partial_products_terms = Vec::new();
 
+    let l_0_xs = z_h_on_coset.eval_l_0_batch(indices_batch, xs_batch);
+
     let mut res_batch: Vec<Vec<F>> = Vec::with_capacity(n);
     for k in 0..n {
-        let index = indices_batch[k];
         let x = xs_batch[k];
         let vars = vars_batch.view(k);
         let local_zs = local_zs_batch[k];
@@ -155,7 +156,7 @@ pub(crate) fn eval_vanishing_poly_base_batch<
 
         let constraint_terms = PackedStridedView::new(&constraint_terms_batch, n, k);
 
-        let l_0_x = z_h_on_coset.eval_l_0(index, x);
+        let l_0_x = l_0_xs[k];
         for i in 0..num_challenges {
             let z_x = local_zs[i];
             let z_gx = next_zs[i];
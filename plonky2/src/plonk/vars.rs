@@ -1,6 +1,7 @@
 use core::ops::Range;
 
 use crate::field::extension::algebra::ExtensionAlgebra;
+use crate::field::extension::packed::PackedExtension;
 use crate::field::extension::{Extendable, FieldExtension};
 use crate::field::packed::PackedField;
 use crate::field::types::Field;
@@ -35,8 +36,6 @@ pub struct EvaluationVarsBase<'a, F: Field> {
 }
 
 /// Like `EvaluationVarsBase`, but packed.
-// It's a separate struct because `EvaluationVarsBase` implements `get_local_ext` and we do not yet
-// have packed extension fields.
 #[derive(Debug, Copy, Clone)]
 pub struct EvaluationVarsBasePacked<'a, P: PackedField> {
     pub local_constants: PackedStridedView<'a, P>,
@@ -129,6 +128,17 @@ impl<'a, F: Field> EvaluationVarsBase<'a, F> {
     }
 }
 
+impl<'a, P: PackedField> EvaluationVarsBasePacked<'a, P> {
+    pub fn get_local_ext<const D: usize>(&self, wire_range: Range<usize>) -> PackedExtension<P, D>
+    where
+        P::Scalar: RichField + Extendable<D>,
+    {
+        debug_assert_eq!(wire_range.len(), D);
+        let arr = self.local_wires.view(wire_range).try_into().unwrap();
+        PackedExtension(arr)
+    }
+}
+
 /// Iterator of views (`EvaluationVarsBase`) into a `EvaluationVarsBaseBatch`.
 pub struct EvaluationVarsBaseBatchIter<'a, F: Field> {
     i: usize,
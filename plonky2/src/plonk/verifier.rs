@@ -7,7 +7,7 @@ use crate::hash::hash_types::RichField;
 use crate::plonk::circuit_data::{CommonCircuitData, VerifierOnlyCircuitData};
 use crate::plonk::config::{GenericConfig, Hasher};
 use crate::plonk::plonk_common::reduce_with_powers;
-use crate::plonk::proof::{Proof, ProofChallenges, ProofWithPublicInputs};
+use crate::plonk::proof::{OpeningSet, Proof, ProofChallenges, ProofWithPublicInputs};
 use crate::plonk::validate_shape::validate_proof_with_pis_shape;
 use crate::plonk::vanishing_poly::eval_vanishing_poly;
 use crate::plonk::vars::EvaluationVars;
@@ -109,3 +109,56 @@ pub(crate) fn verify_with_challenges<
 
     Ok(())
 }
+
+/// Sets the opening of the last quotient chunk for each challenge, which is determined by the other
+/// openings through the identity `vanishing(zeta) = Z_H(zeta) quotient(zeta)` checked in
+/// `verify_with_challenges`. The current values of these openings are ignored.
+pub(crate) fn infer_last_quotient_openings<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    openings: &mut OpeningSet<F, D>,
+    public_inputs_hash: <<C as GenericConfig<D>>::InnerHasher as Hasher<F>>::Hash,
+    challenges: &ProofChallenges<F, D>,
+    common_data: &CommonCircuitData<F, D>,
+) -> Result<()> {
+    let vars = EvaluationVars {
+        local_constants: &openings.constants,
+        local_wires: &openings.wires,
+        public_inputs_hash: &public_inputs_hash,
+    };
+    let vanishing_polys_zeta = eval_vanishing_poly::<F, C, D>(
+        common_data,
+        challenges.plonk_zeta,
+        vars,
+        &openings.plonk_zs,
+        &openings.plonk_zs_next,
+        &openings.partial_products,
+        &openings.plonk_sigmas,
+        &challenges.plonk_betas,
+        &challenges.plonk_gammas,
+        &challenges.plonk_alphas,
+    );
+
+    let zeta_pow_deg = challenges
+        .plonk_zeta
+        .exp_power_of_2(common_data.degree_bits());
+    let z_h_zeta = zeta_pow_deg - F::Extension::ONE;
+    ensure!(
+        zeta_pow_deg != F::Extension::ZERO && z_h_zeta != F::Extension::ZERO,
+        "Cannot infer quotient openings at this point."
+    );
+    let last_power = zeta_pow_deg.exp_u64(common_data.quotient_degree_factor as u64 - 1);
+    for (vanishing_zeta, chunk) in vanishing_polys_zeta.into_iter().zip(
+        openings
+            .quotient_polys
+            .chunks_mut(common_data.quotient_degree_factor),
+    ) {
+        let (last, rest) = chunk.split_last_mut().unwrap();
+        let rest_zeta = reduce_with_powers(rest.iter(), zeta_pow_deg);
+        *last = (vanishing_zeta / z_h_zeta - rest_zeta) / last_power;
+    }
+
+    Ok(())
+}
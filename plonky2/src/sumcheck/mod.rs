@@ -0,0 +1,64 @@
+//! A generic sumcheck protocol over the extension field.
+//!
+//! The prover convinces the verifier that
+//! `sum_{b in {0,1}^n} g(f_0(b), ..., f_{k-1}(b)) = claimed_sum`, where the `f_i` are multilinear
+//! polynomials and `g` is a combination function of total degree at most `degree`. The protocol
+//! reduces this claim to a claim about `g(f_0(r), ..., f_{k-1}(r))` at a random point `r`, which
+//! the caller is responsible for checking, e.g. with a polynomial commitment opening.
+
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::field::interpolation::{barycentric_weights, interpolate};
+use crate::field::types::Field;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+pub mod proof;
+pub mod prover;
+pub mod recursive_verifier;
+pub mod verifier;
+pub mod witness_util;
+
+/// Evaluates at `x` the univariate polynomial of degree `< evals.len()` whose evaluations at
+/// `0, 1, ..., evals.len() - 1` are `evals`.
+pub(crate) fn eval_round_poly<F: Field>(evals: &[F], x: F) -> F {
+    let points = evals
+        .iter()
+        .enumerate()
+        .map(|(i, &y)| (F::from_canonical_usize(i), y))
+        .collect::<Vec<_>>();
+    interpolate(&points, x, &barycentric_weights(&points))
+}
+
+/// In-circuit version of `eval_round_poly`, using the Lagrange form
+/// `p(x) = sum_i y_i w_i prod_{j != i} (x - j)` with constant weights `w_i = 1 / prod_{j != i} (i - j)`.
+pub(crate) fn eval_round_poly_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    evals: &[ExtensionTarget<D>],
+    x: ExtensionTarget<D>,
+) -> ExtensionTarget<D> {
+    let n = evals.len();
+    let points = (0..n)
+        .map(|i| (F::from_canonical_usize(i), F::ZERO))
+        .collect::<Vec<_>>();
+    let weights = barycentric_weights(&points);
+    let x_minus_js = (0..n)
+        .map(|j| builder.add_const_extension(x, -F::from_canonical_usize(j)))
+        .collect::<Vec<_>>();
+
+    let mut sum = builder.zero_extension();
+    for (i, (&y, &w)) in evals.iter().zip(&weights).enumerate() {
+        let others = x_minus_js
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .map(|(_, &t)| t)
+            .collect::<Vec<_>>();
+        let basis = builder.mul_many_extension(others);
+        let term = builder.mul_extension(y, basis);
+        sum = builder.mul_const_add_extension(w, term, sum);
+    }
+    sum
+}
@@ -0,0 +1,33 @@
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::ext_target::ExtensionTarget;
+
+/// A sumcheck proof: for each round, the evaluations of that round's univariate polynomial at
+/// `0, 1, ..., degree`.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(bound = "")]
+pub struct SumcheckProof<F: RichField + Extendable<D>, const D: usize> {
+    pub round_polys: Vec<Vec<F::Extension>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SumcheckProofTarget<const D: usize> {
+    pub round_polys: Vec<Vec<ExtensionTarget<D>>>,
+}
+
+/// The claim a sumcheck reduces to: `g(f_0(point), ..., f_{k-1}(point)) = expected_evaluation`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SumcheckSubclaim<F: RichField + Extendable<D>, const D: usize> {
+    pub point: Vec<F::Extension>,
+    pub expected_evaluation: F::Extension,
+}
+
+#[derive(Clone, Debug)]
+pub struct SumcheckSubclaimTarget<const D: usize> {
+    pub point: Vec<ExtensionTarget<D>>,
+    pub expected_evaluation: ExtensionTarget<D>,
+}
@@ -0,0 +1,111 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use maybe_rayon::*;
+
+use crate::field::extension::Extendable;
+use crate::field::polynomial::multilinear::MultilinearPolynomial;
+use crate::field::types::Field;
+use crate::hash::hash_types::RichField;
+use crate::iop::challenger::Challenger;
+use crate::plonk::config::Hasher;
+use crate::sumcheck::proof::SumcheckProof;
+
+/// Number of hypercube points each parallel task handles when computing a round polynomial.
+const SUMCHECK_CHUNK_SIZE: usize = 1 << 8;
+
+/// The output of the sumcheck prover.
+#[derive(Clone, Debug)]
+pub struct SumcheckProverOutput<F: RichField + Extendable<D>, const D: usize> {
+    pub proof: SumcheckProof<F, D>,
+    /// The random point the claim was reduced to.
+    pub point: Vec<F::Extension>,
+    /// The evaluations `f_i(point)` of each multilinear polynomial.
+    pub final_evaluations: Vec<F::Extension>,
+}
+
+/// Proves that `sum_{b in {0,1}^n} combine(f_0(b), ..., f_{k-1}(b))` equals the claimed sum, which
+/// the caller is expected to have already observed in `challenger`. `combine` must have total
+/// degree at most `degree`.
+pub fn prove_sumcheck<F, H, C, const D: usize>(
+    mut mles: Vec<MultilinearPolynomial<F::Extension>>,
+    degree: usize,
+    combine: C,
+    challenger: &mut Challenger<F, H>,
+) -> SumcheckProverOutput<F, D>
+where
+    F: RichField + Extendable<D>,
+    H: Hasher<F>,
+    C: Fn(&[F::Extension]) -> F::Extension + Sync,
+{
+    assert!(
+        !mles.is_empty(),
+        "Expected at least one multilinear polynomial"
+    );
+    let num_vars = mles[0].num_vars();
+    assert!(
+        mles.iter().all(|f| f.num_vars() == num_vars),
+        "All polynomials must have the same number of variables"
+    );
+
+    let mut round_polys = Vec::with_capacity(num_vars);
+    let mut point = Vec::with_capacity(num_vars);
+    for _ in 0..num_vars {
+        let round_poly = round_poly_evals(&mles, degree, &combine);
+        challenger.observe_extension_elements::<D>(&round_poly);
+        round_polys.push(round_poly);
+
+        let r = challenger.get_extension_challenge::<D>();
+        mles = mles.iter().map(|f| f.fix_first_variable(r)).collect();
+        point.push(r);
+    }
+
+    SumcheckProverOutput {
+        proof: SumcheckProof { round_polys },
+        point,
+        final_evaluations: mles.iter().map(|f| f.evals[0]).collect(),
+    }
+}
+
+/// Evaluates the current round polynomial `p(t) = sum_b combine(f_0(t, b), ...)` at
+/// `t = 0, ..., degree`.
+fn round_poly_evals<F: Field, C>(
+    mles: &[MultilinearPolynomial<F>],
+    degree: usize,
+    combine: &C,
+) -> Vec<F>
+where
+    C: Fn(&[F]) -> F + Sync,
+{
+    let half = mles[0].len() / 2;
+    let ts = (0..=degree)
+        .map(F::from_canonical_usize)
+        .collect::<Vec<_>>();
+    let starts = (0..half).step_by(SUMCHECK_CHUNK_SIZE).collect::<Vec<_>>();
+
+    let partial_sums = starts
+        .into_par_iter()
+        .map(|start| {
+            let mut sums = vec![F::ZERO; degree + 1];
+            let mut values = vec![F::ZERO; mles.len()];
+            for b in start..(start + SUMCHECK_CHUNK_SIZE).min(half) {
+                for (sum, &t) in sums.iter_mut().zip(&ts) {
+                    for (v, f) in values.iter_mut().zip(mles) {
+                        let (lo, hi) = (f.evals[2 * b], f.evals[2 * b + 1]);
+                        *v = lo + t * (hi - lo);
+                    }
+                    *sum += combine(&values);
+                }
+            }
+            sums
+        })
+        .collect::<Vec<_>>();
+
+    let mut sums = vec![F::ZERO; degree + 1];
+    for partial in partial_sums {
+        for (sum, p) in sums.iter_mut().zip(partial) {
+            *sum += p;
+        }
+    }
+    sums
+}
@@ -0,0 +1,205 @@
+use alloc::vec::Vec;
+
+use itertools::Itertools;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::challenger::RecursiveChallenger;
+use crate::iop::ext_target::ExtensionTarget;
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::config::AlgebraicHasher;
+use crate::sumcheck::eval_round_poly_circuit;
+use crate::sumcheck::proof::{SumcheckProofTarget, SumcheckSubclaimTarget};
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    pub fn add_virtual_sumcheck_proof(
+        &mut self,
+        num_vars: usize,
+        degree: usize,
+    ) -> SumcheckProofTarget<D> {
+        assert!(
+            degree >= 1,
+            "Sumcheck round polynomials must have degree at least 1"
+        );
+        let round_polys = (0..num_vars)
+            .map(|_| self.add_virtual_extension_targets(degree + 1))
+            .collect();
+        SumcheckProofTarget { round_polys }
+    }
+
+    /// In-circuit version of `verify_sumcheck`. The caller must check the returned subclaim.
+    pub fn verify_sumcheck<H: AlgebraicHasher<F>>(
+        &mut self,
+        proof: &SumcheckProofTarget<D>,
+        claimed_sum: ExtensionTarget<D>,
+        challenger: &mut RecursiveChallenger<F, H, D>,
+    ) -> SumcheckSubclaimTarget<D> {
+        let mut claim = claimed_sum;
+        let mut point = Vec::with_capacity(proof.round_polys.len());
+        for round_poly in &proof.round_polys {
+            assert!(
+                round_poly.len() >= 2,
+                "Sumcheck round polynomials must have degree at least 1"
+            );
+            let sum = self.add_extension(round_poly[0], round_poly[1]);
+            self.connect_extension(sum, claim);
+            challenger.observe_extension_elements(round_poly);
+            let r = challenger.get_extension_challenge(self);
+            claim = eval_round_poly_circuit(self, round_poly, r);
+            point.push(r);
+        }
+
+        SumcheckSubclaimTarget {
+            point,
+            expected_evaluation: claim,
+        }
+    }
+
+    /// Computes `eq(x, y) = prod_i (x_i y_i + (1 - x_i)(1 - y_i))`.
+    pub fn eval_eq_extension(
+        &mut self,
+        x: &[ExtensionTarget<D>],
+        y: &[ExtensionTarget<D>],
+    ) -> ExtensionTarget<D> {
+        let terms = x
+            .iter()
+            .zip_eq(y)
+            .map(|(&x_i, &y_i)| {
+                // x_i y_i + (1 - x_i)(1 - y_i) = 2 x_i y_i - x_i - y_i + 1.
+                let xy = self.mul_extension(x_i, y_i);
+                let two_xy = self.add_extension(xy, xy);
+                let x_plus_y = self.add_extension(x_i, y_i);
+                let diff = self.sub_extension(two_xy, x_plus_y);
+                self.add_const_extension(diff, F::ONE)
+            })
+            .collect::<Vec<_>>();
+        self.mul_many_extension(terms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::extension::Extendable;
+    use crate::field::polynomial::multilinear::{eval_eq, MultilinearPolynomial};
+    use crate::field::types::{Field, Sample};
+    use crate::iop::challenger::{Challenger, RecursiveChallenger};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::sumcheck::proof::SumcheckProof;
+    use crate::sumcheck::prover::prove_sumcheck;
+    use crate::sumcheck::verifier::verify_sumcheck;
+    use crate::sumcheck::witness_util::set_sumcheck_proof_target;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type FE = <F as Extendable<D>>::Extension;
+    type H = <C as GenericConfig<D>>::Hasher;
+
+    /// Proves `sum_b eq(z, b) f(b) g(b) = claimed_sum` and returns everything the verifiers need.
+    fn product_instance(num_vars: usize) -> (Vec<MultilinearPolynomial<FE>>, Vec<FE>, FE) {
+        let f = MultilinearPolynomial::new(FE::rand_vec(1 << num_vars));
+        let g = MultilinearPolynomial::new(FE::rand_vec(1 << num_vars));
+        let z = FE::rand_vec(num_vars);
+        let eq = MultilinearPolynomial::eq_polynomial(&z);
+        let claimed_sum = (0..f.len())
+            .map(|i| eq.evals[i] * f.evals[i] * g.evals[i])
+            .sum();
+        (vec![eq, f, g], z, claimed_sum)
+    }
+
+    fn combine(v: &[FE]) -> FE {
+        v[0] * v[1] * v[2]
+    }
+
+    #[test]
+    fn test_sumcheck() -> Result<()> {
+        let num_vars = 6;
+        let (mles, z, claimed_sum) = product_instance(num_vars);
+        let (f, g) = (mles[1].clone(), mles[2].clone());
+
+        let mut challenger = Challenger::<F, H>::new();
+        challenger.observe_extension_element::<D>(&claimed_sum);
+        let output = prove_sumcheck::<F, H, _, D>(mles, 3, combine, &mut challenger);
+
+        let mut challenger = Challenger::<F, H>::new();
+        challenger.observe_extension_element::<D>(&claimed_sum);
+        let subclaim = verify_sumcheck(&output.proof, claimed_sum, num_vars, 3, &mut challenger)?;
+        assert_eq!(subclaim.point, output.point);
+        assert_eq!(
+            subclaim.expected_evaluation,
+            eval_eq(&z, &subclaim.point)
+                * f.evaluate(&subclaim.point)
+                * g.evaluate(&subclaim.point)
+        );
+        assert_eq!(
+            subclaim.expected_evaluation,
+            combine(&output.final_evaluations)
+        );
+
+        // A wrong claimed sum must be rejected.
+        let mut challenger = Challenger::<F, H>::new();
+        challenger.observe_extension_element::<D>(&claimed_sum);
+        assert!(verify_sumcheck(
+            &output.proof,
+            claimed_sum + FE::ONE,
+            num_vars,
+            3,
+            &mut challenger
+        )
+        .is_err());
+
+        // Round polynomials of degree 0 must be rejected rather than indexed out of bounds.
+        let constant_proof = SumcheckProof::<F, D> {
+            round_polys: vec![vec![claimed_sum]; num_vars],
+        };
+        let mut challenger = Challenger::<F, H>::new();
+        assert!(
+            verify_sumcheck(&constant_proof, claimed_sum, num_vars, 0, &mut challenger).is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recursive_sumcheck() -> Result<()> {
+        let num_vars = 4;
+        let degree = 3;
+        let (mles, z, claimed_sum) = product_instance(num_vars);
+
+        let mut challenger = Challenger::<F, H>::new();
+        challenger.observe_extension_element::<D>(&claimed_sum);
+        let output = prove_sumcheck::<F, H, _, D>(mles, degree, combine, &mut challenger);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let proof_t = builder.add_virtual_sumcheck_proof(num_vars, degree);
+        let claimed_sum_t = builder.add_virtual_extension_target();
+        let z_t = builder.add_virtual_extension_targets(num_vars);
+        let final_evals_t = builder.add_virtual_extension_targets(3);
+
+        let mut challenger_t = RecursiveChallenger::<F, H, D>::new(&mut builder);
+        challenger_t.observe_extension_element(claimed_sum_t);
+        let subclaim = builder.verify_sumcheck(&proof_t, claimed_sum_t, &mut challenger_t);
+
+        // Check the subclaim against the eq polynomial and the claimed final evaluations.
+        let eq_t = builder.eval_eq_extension(&z_t, &subclaim.point);
+        builder.connect_extension(eq_t, final_evals_t[0]);
+        let combined = builder.mul_many_extension(final_evals_t.clone());
+        builder.connect_extension(combined, subclaim.expected_evaluation);
+
+        let mut pw = PartialWitness::new();
+        set_sumcheck_proof_target(&mut pw, &proof_t, &output.proof);
+        pw.set_extension_target(claimed_sum_t, claimed_sum);
+        pw.set_extension_targets(&z_t, &z);
+        pw.set_extension_targets(&final_evals_t, &output.final_evaluations);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+}
@@ -0,0 +1,55 @@
+use alloc::vec::Vec;
+
+use anyhow::{ensure, Result};
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::challenger::Challenger;
+use crate::plonk::config::Hasher;
+use crate::sumcheck::eval_round_poly;
+use crate::sumcheck::proof::{SumcheckProof, SumcheckSubclaim};
+
+/// Checks a sumcheck proof for `claimed_sum`, reducing it to a claim about the combined
+/// evaluation at a random point. The caller must check that subclaim.
+pub fn verify_sumcheck<F, H, const D: usize>(
+    proof: &SumcheckProof<F, D>,
+    claimed_sum: F::Extension,
+    num_vars: usize,
+    degree: usize,
+    challenger: &mut Challenger<F, H>,
+) -> Result<SumcheckSubclaim<F, D>>
+where
+    F: RichField + Extendable<D>,
+    H: Hasher<F>,
+{
+    ensure!(
+        degree >= 1,
+        "Sumcheck round polynomials must have degree at least 1"
+    );
+    ensure!(
+        proof.round_polys.len() == num_vars,
+        "Wrong number of sumcheck rounds"
+    );
+
+    let mut claim = claimed_sum;
+    let mut point = Vec::with_capacity(num_vars);
+    for round_poly in &proof.round_polys {
+        ensure!(
+            round_poly.len() == degree + 1,
+            "Wrong sumcheck round polynomial degree"
+        );
+        ensure!(
+            round_poly[0] + round_poly[1] == claim,
+            "Sumcheck round polynomial inconsistent with claim"
+        );
+        challenger.observe_extension_elements::<D>(round_poly);
+        let r = challenger.get_extension_challenge::<D>();
+        claim = eval_round_poly(round_poly, r);
+        point.push(r);
+    }
+
+    Ok(SumcheckSubclaim {
+        point,
+        expected_evaluation: claim,
+    })
+}
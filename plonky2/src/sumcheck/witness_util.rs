@@ -0,0 +1,24 @@
+use itertools::Itertools;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::witness::WitnessWrite;
+use crate::sumcheck::proof::{SumcheckProof, SumcheckProofTarget};
+
+/// Set the targets in a `SumcheckProofTarget` to their corresponding values in a `SumcheckProof`.
+pub fn set_sumcheck_proof_target<F, W, const D: usize>(
+    witness: &mut W,
+    sumcheck_proof_target: &SumcheckProofTarget<D>,
+    sumcheck_proof: &SumcheckProof<F, D>,
+) where
+    F: RichField + Extendable<D>,
+    W: WitnessWrite<F> + ?Sized,
+{
+    for (ts, xs) in sumcheck_proof_target
+        .round_polys
+        .iter()
+        .zip_eq(&sumcheck_proof.round_polys)
+    {
+        witness.set_extension_targets(ts, xs);
+    }
+}
@@ -0,0 +1,875 @@
+//! Stable JSON encodings of proofs and circuit data, for consumers outside of Rust.
+//!
+//! Unlike the serde implementations derived on the underlying types, these encodings are
+//! independent of internal representations:
+//! - a field element is a string holding its canonical value in decimal, e.g. `"42"`;
+//! - an extension field element is an array of its `D` base field coordinates;
+//! - a hash is a `0x`-prefixed lowercase hex string of `GenericHashOut::to_bytes`;
+//! - Merkle caps and Merkle proofs are arrays of hashes.
+//!
+//! Object keys match the names of the corresponding Rust fields, so the documentation of
+//! `ProofWithPublicInputs`, `VerifierOnlyCircuitData` etc. also describes the JSON schema. The
+//! compressed FRI query rounds, which are maps in Rust, are encoded as arrays of
+//! `{"index": .., "value": ..}` objects sorted by index.
+//!
+//! Converting between JSON and the binary format of `util::serialization` goes through the
+//! in-memory types, e.g. `ProofWithPublicInputs::from_bytes(bytes, &common)?.to_json()`.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use anyhow::{anyhow, ensure, Result};
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::field::extension::{Extendable, FieldExtension};
+use crate::field::polynomial::PolynomialCoeffs;
+use crate::field::types::PrimeField64;
+use crate::fri::proof::{
+    CompressedFriProof, CompressedFriQueryRounds, FriInitialTreeProof, FriProof, FriQueryRound,
+    FriQueryStep,
+};
+use crate::fri::reduction_strategies::FriReductionStrategy;
+use crate::fri::{FriConfig, FriParams};
+use crate::gates::gate::GateRef;
+use crate::gates::selectors::SelectorsInfo;
+use crate::hash::hash_types::RichField;
+use crate::hash::merkle_proofs::MerkleProof;
+use crate::hash::merkle_tree::MerkleCap;
+use crate::plonk::circuit_data::{CircuitConfig, CommonCircuitData, VerifierOnlyCircuitData};
+use crate::plonk::config::{GenericConfig, GenericHashOut, Hasher};
+use crate::plonk::proof::{
+    CompressedProof, CompressedProofWithPublicInputs, OpeningSet, Proof, ProofWithPublicInputs,
+};
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProofWithPublicInputsJson<Q> {
+    public_inputs: Vec<String>,
+    proof: ProofJson<Q>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProofJson<Q> {
+    wires_cap: Vec<String>,
+    plonk_zs_partial_products_cap: Vec<String>,
+    quotient_polys_cap: Vec<String>,
+    openings: OpeningSetJson,
+    opening_proof: FriProofJson<Q>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OpeningSetJson {
+    constants: Vec<Vec<String>>,
+    plonk_sigmas: Vec<Vec<String>>,
+    wires: Vec<Vec<String>>,
+    plonk_zs: Vec<Vec<String>>,
+    plonk_zs_next: Vec<Vec<String>>,
+    partial_products: Vec<Vec<String>>,
+    quotient_polys: Vec<Vec<String>>,
+}
+
+/// A FRI proof, where `Q` is the encoding of the query rounds.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FriProofJson<Q> {
+    commit_phase_merkle_caps: Vec<Vec<String>>,
+    query_round_proofs: Q,
+    final_poly: Vec<Vec<String>>,
+    pow_witness: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FriQueryRoundJson {
+    initial_trees_proof: Vec<FriInitialTreeEvalsJson>,
+    steps: Vec<FriQueryStepJson>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FriInitialTreeEvalsJson {
+    evals: Vec<String>,
+    merkle_proof: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FriQueryStepJson {
+    evals: Vec<Vec<String>>,
+    merkle_proof: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CompressedFriQueryRoundsJson {
+    indices: Vec<usize>,
+    initial_trees_proofs: Vec<IndexedJson<Vec<FriInitialTreeEvalsJson>>>,
+    steps: Vec<Vec<IndexedJson<FriQueryStepJson>>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IndexedJson<T> {
+    index: usize,
+    value: T,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct VerifierOnlyCircuitDataJson {
+    constants_sigmas_cap: Vec<String>,
+    circuit_digest: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CommonCircuitDataJson {
+    config: CircuitConfigJson,
+    fri_params: FriParamsJson,
+    /// The ID of each gate, as given by `Gate::id`.
+    gates: Vec<String>,
+    selectors_info: SelectorsInfoJson,
+    quotient_degree_factor: usize,
+    num_gate_constraints: usize,
+    num_constants: usize,
+    num_public_inputs: usize,
+    k_is: Vec<String>,
+    num_partial_products: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CircuitConfigJson {
+    num_wires: usize,
+    num_routed_wires: usize,
+    num_constants: usize,
+    use_base_arithmetic_gate: bool,
+    security_bits: usize,
+    num_challenges: usize,
+    zero_knowledge: bool,
+    max_quotient_degree_factor: usize,
+    fri_config: FriConfigJson,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FriConfigJson {
+    rate_bits: usize,
+    cap_height: usize,
+    proof_of_work_bits: u32,
+    reduction_strategy: FriReductionStrategyJson,
+    num_query_rounds: usize,
+}
+
+/// Encoded as `{"fixed": [..]}`, `{"constant_arity_bits": [arity_bits, final_poly_bits]}` or
+/// `{"min_size": max_arity_bits}`, where `max_arity_bits` may be `null`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FriReductionStrategyJson {
+    Fixed(Vec<usize>),
+    ConstantArityBits(usize, usize),
+    MinSize(Option<usize>),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FriParamsJson {
+    hiding: bool,
+    degree_bits: usize,
+    reduction_arity_bits: Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SelectorsInfoJson {
+    selector_indices: Vec<usize>,
+    /// Each group as a `[start, end)` pair of gate indices.
+    groups: Vec<[usize; 2]>,
+}
+
+fn field_to_json<F: PrimeField64>(x: F) -> String {
+    x.to_canonical_u64().to_string()
+}
+
+fn field_from_json<F: RichField>(s: &str) -> Result<F> {
+    let x = s
+        .parse::<u64>()
+        .map_err(|_| anyhow!("Invalid field element \"{}\"", s))?;
+    ensure!(
+        x < F::ORDER && x.to_string() == s,
+        "Field element \"{}\" is not in canonical decimal form",
+        s
+    );
+    Ok(F::from_canonical_u64(x))
+}
+
+fn fields_to_json<F: PrimeField64>(v: &[F]) -> Vec<String> {
+    v.iter().map(|&x| field_to_json(x)).collect()
+}
+
+fn fields_from_json<F: RichField>(v: &[String]) -> Result<Vec<F>> {
+    v.iter().map(|s| field_from_json(s)).collect()
+}
+
+fn ext_to_json<F: RichField + Extendable<D>, const D: usize>(x: F::Extension) -> Vec<String> {
+    fields_to_json(&x.to_basefield_array())
+}
+
+fn ext_from_json<F: RichField + Extendable<D>, const D: usize>(
+    v: &[String],
+) -> Result<F::Extension> {
+    ensure!(
+        v.len() == D,
+        "Extension field element has {} coordinates, expected {}",
+        v.len(),
+        D
+    );
+    let coords = fields_from_json::<F>(v)?;
+    Ok(F::Extension::from_basefield_array(
+        coords.try_into().unwrap(),
+    ))
+}
+
+fn exts_to_json<F: RichField + Extendable<D>, const D: usize>(
+    v: &[F::Extension],
+) -> Vec<Vec<String>> {
+    v.iter().map(|&x| ext_to_json::<F, D>(x)).collect()
+}
+
+fn exts_from_json<F: RichField + Extendable<D>, const D: usize>(
+    v: &[Vec<String>],
+) -> Result<Vec<F::Extension>> {
+    v.iter().map(|x| ext_from_json::<F, D>(x)).collect()
+}
+
+fn hash_to_json<F: RichField, H: Hasher<F>>(h: H::Hash) -> String {
+    let hex = h
+        .to_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    format!("0x{}", hex)
+}
+
+fn hash_from_json<F: RichField, H: Hasher<F>>(s: &str) -> Result<H::Hash> {
+    let hex = s
+        .strip_prefix("0x")
+        .ok_or_else(|| anyhow!("Hash \"{}\" is missing the 0x prefix", s))?;
+    ensure!(
+        hex.len() == 2 * H::HASH_SIZE && hex.is_ascii(),
+        "Hash \"{}\" should have {} hex digits",
+        s,
+        2 * H::HASH_SIZE
+    );
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| anyhow!("Invalid hex digit in hash \"{}\"", s))?;
+    H::Hash::try_from_bytes(&bytes).ok_or_else(|| anyhow!("Hash \"{}\" is not canonical", s))
+}
+
+fn hashes_to_json<F: RichField, H: Hasher<F>>(v: &[H::Hash]) -> Vec<String> {
+    v.iter().map(|&h| hash_to_json::<F, H>(h)).collect()
+}
+
+fn hashes_from_json<F: RichField, H: Hasher<F>>(v: &[String]) -> Result<Vec<H::Hash>> {
+    v.iter().map(|s| hash_from_json::<F, H>(s)).collect()
+}
+
+fn cap_from_json<F: RichField, H: Hasher<F>>(v: &[String]) -> Result<MerkleCap<F, H>> {
+    Ok(MerkleCap(hashes_from_json::<F, H>(v)?))
+}
+
+fn merkle_proof_from_json<F: RichField, H: Hasher<F>>(v: &[String]) -> Result<MerkleProof<F, H>> {
+    Ok(MerkleProof {
+        siblings: hashes_from_json::<F, H>(v)?,
+    })
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> OpeningSet<F, D> {
+    fn to_json(&self) -> OpeningSetJson {
+        OpeningSetJson {
+            constants: exts_to_json::<F, D>(&self.constants),
+            plonk_sigmas: exts_to_json::<F, D>(&self.plonk_sigmas),
+            wires: exts_to_json::<F, D>(&self.wires),
+            plonk_zs: exts_to_json::<F, D>(&self.plonk_zs),
+            plonk_zs_next: exts_to_json::<F, D>(&self.plonk_zs_next),
+            partial_products: exts_to_json::<F, D>(&self.partial_products),
+            quotient_polys: exts_to_json::<F, D>(&self.quotient_polys),
+        }
+    }
+
+    fn from_json(json: &OpeningSetJson) -> Result<Self> {
+        Ok(Self {
+            constants: exts_from_json::<F, D>(&json.constants)?,
+            plonk_sigmas: exts_from_json::<F, D>(&json.plonk_sigmas)?,
+            wires: exts_from_json::<F, D>(&json.wires)?,
+            plonk_zs: exts_from_json::<F, D>(&json.plonk_zs)?,
+            plonk_zs_next: exts_from_json::<F, D>(&json.plonk_zs_next)?,
+            partial_products: exts_from_json::<F, D>(&json.partial_products)?,
+            quotient_polys: exts_from_json::<F, D>(&json.quotient_polys)?,
+        })
+    }
+}
+
+impl<F: RichField, H: Hasher<F>> FriInitialTreeProof<F, H> {
+    fn to_json(&self) -> Vec<FriInitialTreeEvalsJson> {
+        self.evals_proofs
+            .iter()
+            .map(|(evals, merkle_proof)| FriInitialTreeEvalsJson {
+                evals: fields_to_json(evals),
+                merkle_proof: hashes_to_json::<F, H>(&merkle_proof.siblings),
+            })
+            .collect()
+    }
+
+    fn from_json(json: &[FriInitialTreeEvalsJson]) -> Result<Self> {
+        let evals_proofs = json
+            .iter()
+            .map(|e| {
+                Ok((
+                    fields_from_json(&e.evals)?,
+                    merkle_proof_from_json(&e.merkle_proof)?,
+                ))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { evals_proofs })
+    }
+}
+
+impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> FriQueryStep<F, H, D> {
+    fn to_json(&self) -> FriQueryStepJson {
+        FriQueryStepJson {
+            evals: exts_to_json::<F, D>(&self.evals),
+            merkle_proof: hashes_to_json::<F, H>(&self.merkle_proof.siblings),
+        }
+    }
+
+    fn from_json(json: &FriQueryStepJson) -> Result<Self> {
+        Ok(Self {
+            evals: exts_from_json::<F, D>(&json.evals)?,
+            merkle_proof: merkle_proof_from_json(&json.merkle_proof)?,
+        })
+    }
+}
+
+impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> FriQueryRound<F, H, D> {
+    fn to_json(&self) -> FriQueryRoundJson {
+        FriQueryRoundJson {
+            initial_trees_proof: self.initial_trees_proof.to_json(),
+            steps: self.steps.iter().map(FriQueryStep::to_json).collect(),
+        }
+    }
+
+    fn from_json(json: &FriQueryRoundJson) -> Result<Self> {
+        Ok(Self {
+            initial_trees_proof: FriInitialTreeProof::from_json(&json.initial_trees_proof)?,
+            steps: json
+                .steps
+                .iter()
+                .map(FriQueryStep::from_json)
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+/// Encodes a map as a list of entries sorted by key, so that equal maps have equal encodings.
+fn indexed_to_json<T, J>(map: &HashMap<usize, T>, f: impl Fn(&T) -> J) -> Vec<IndexedJson<J>> {
+    let mut entries = map
+        .iter()
+        .map(|(&index, value)| IndexedJson {
+            index,
+            value: f(value),
+        })
+        .collect::<Vec<_>>();
+    entries.sort_unstable_by_key(|e| e.index);
+    entries
+}
+
+fn indexed_from_json<T, J>(
+    entries: &[IndexedJson<J>],
+    f: impl Fn(&J) -> Result<T>,
+) -> Result<HashMap<usize, T>> {
+    let mut map = HashMap::with_capacity(entries.len());
+    for e in entries {
+        ensure!(
+            map.insert(e.index, f(&e.value)?).is_none(),
+            "Duplicate index {} in compressed query rounds",
+            e.index
+        );
+    }
+    Ok(map)
+}
+
+impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> CompressedFriQueryRounds<F, H, D> {
+    fn to_json(&self) -> CompressedFriQueryRoundsJson {
+        CompressedFriQueryRoundsJson {
+            indices: self.indices.clone(),
+            initial_trees_proofs: indexed_to_json(
+                &self.initial_trees_proofs,
+                FriInitialTreeProof::to_json,
+            ),
+            steps: self
+                .steps
+                .iter()
+                .map(|step| indexed_to_json(step, FriQueryStep::to_json))
+                .collect(),
+        }
+    }
+
+    fn from_json(json: &CompressedFriQueryRoundsJson) -> Result<Self> {
+        Ok(Self {
+            indices: json.indices.clone(),
+            initial_trees_proofs: indexed_from_json(&json.initial_trees_proofs, |p| {
+                FriInitialTreeProof::from_json(p)
+            })?,
+            steps: json
+                .steps
+                .iter()
+                .map(|step| indexed_from_json(step, FriQueryStep::from_json))
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+fn fri_proof_to_json<F, H, J, const D: usize>(
+    commit_phase_merkle_caps: &[MerkleCap<F, H>],
+    query_round_proofs: J,
+    final_poly: &PolynomialCoeffs<F::Extension>,
+    pow_witness: F,
+) -> FriProofJson<J>
+where
+    F: RichField + Extendable<D>,
+    H: Hasher<F>,
+{
+    FriProofJson {
+        commit_phase_merkle_caps: commit_phase_merkle_caps
+            .iter()
+            .map(|cap| hashes_to_json::<F, H>(&cap.0))
+            .collect(),
+        query_round_proofs,
+        final_poly: exts_to_json::<F, D>(&final_poly.coeffs),
+        pow_witness: field_to_json(pow_witness),
+    }
+}
+
+type FriProofParts<F, H, const D: usize> = (
+    Vec<MerkleCap<F, H>>,
+    PolynomialCoeffs<<F as Extendable<D>>::Extension>,
+    F,
+);
+
+/// Decodes the parts of a FRI proof which don't depend on the query round encoding.
+fn fri_proof_from_json<F, H, J, const D: usize>(
+    json: &FriProofJson<J>,
+) -> Result<FriProofParts<F, H, D>>
+where
+    F: RichField + Extendable<D>,
+    H: Hasher<F>,
+{
+    let commit_phase_merkle_caps = json
+        .commit_phase_merkle_caps
+        .iter()
+        .map(|cap| cap_from_json(cap))
+        .collect::<Result<_>>()?;
+    let final_poly = PolynomialCoeffs::new(exts_from_json::<F, D>(&json.final_poly)?);
+    let pow_witness = field_from_json(&json.pow_witness)?;
+    Ok((commit_phase_merkle_caps, final_poly, pow_witness))
+}
+
+impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> FriProof<F, H, D> {
+    fn to_json(&self) -> FriProofJson<Vec<FriQueryRoundJson>> {
+        fri_proof_to_json::<F, H, _, D>(
+            &self.commit_phase_merkle_caps,
+            self.query_round_proofs
+                .iter()
+                .map(FriQueryRound::to_json)
+                .collect(),
+            &self.final_poly,
+            self.pow_witness,
+        )
+    }
+
+    fn from_json(json: &FriProofJson<Vec<FriQueryRoundJson>>) -> Result<Self> {
+        let (commit_phase_merkle_caps, final_poly, pow_witness) = fri_proof_from_json(json)?;
+        Ok(Self {
+            commit_phase_merkle_caps,
+            query_round_proofs: json
+                .query_round_proofs
+                .iter()
+                .map(FriQueryRound::from_json)
+                .collect::<Result<_>>()?,
+            final_poly,
+            pow_witness,
+        })
+    }
+}
+
+impl<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> CompressedFriProof<F, H, D> {
+    fn to_json(&self) -> FriProofJson<CompressedFriQueryRoundsJson> {
+        fri_proof_to_json::<F, H, _, D>(
+            &self.commit_phase_merkle_caps,
+            self.query_round_proofs.to_json(),
+            &self.final_poly,
+            self.pow_witness,
+        )
+    }
+
+    fn from_json(json: &FriProofJson<CompressedFriQueryRoundsJson>) -> Result<Self> {
+        let (commit_phase_merkle_caps, final_poly, pow_witness) = fri_proof_from_json(json)?;
+        Ok(Self {
+            commit_phase_merkle_caps,
+            query_round_proofs: CompressedFriQueryRounds::from_json(&json.query_round_proofs)?,
+            final_poly,
+            pow_witness,
+        })
+    }
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> Proof<F, C, D> {
+    fn to_json(&self) -> ProofJson<Vec<FriQueryRoundJson>> {
+        ProofJson {
+            wires_cap: hashes_to_json::<F, C::Hasher>(&self.wires_cap.0),
+            plonk_zs_partial_products_cap: hashes_to_json::<F, C::Hasher>(
+                &self.plonk_zs_partial_products_cap.0,
+            ),
+            quotient_polys_cap: hashes_to_json::<F, C::Hasher>(&self.quotient_polys_cap.0),
+            openings: self.openings.to_json(),
+            opening_proof: self.opening_proof.to_json(),
+        }
+    }
+
+    fn from_json(json: &ProofJson<Vec<FriQueryRoundJson>>) -> Result<Self> {
+        Ok(Self {
+            wires_cap: cap_from_json(&json.wires_cap)?,
+            plonk_zs_partial_products_cap: cap_from_json(&json.plonk_zs_partial_products_cap)?,
+            quotient_polys_cap: cap_from_json(&json.quotient_polys_cap)?,
+            openings: OpeningSet::from_json(&json.openings)?,
+            opening_proof: FriProof::from_json(&json.opening_proof)?,
+        })
+    }
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    CompressedProof<F, C, D>
+{
+    fn to_json(&self) -> ProofJson<CompressedFriQueryRoundsJson> {
+        ProofJson {
+            wires_cap: hashes_to_json::<F, C::Hasher>(&self.wires_cap.0),
+            plonk_zs_partial_products_cap: hashes_to_json::<F, C::Hasher>(
+                &self.plonk_zs_partial_products_cap.0,
+            ),
+            quotient_polys_cap: hashes_to_json::<F, C::Hasher>(&self.quotient_polys_cap.0),
+            openings: self.openings.to_json(),
+            opening_proof: self.opening_proof.to_json(),
+        }
+    }
+
+    fn from_json(json: &ProofJson<CompressedFriQueryRoundsJson>) -> Result<Self> {
+        Ok(Self {
+            wires_cap: cap_from_json(&json.wires_cap)?,
+            plonk_zs_partial_products_cap: cap_from_json(&json.plonk_zs_partial_products_cap)?,
+            quotient_polys_cap: cap_from_json(&json.quotient_polys_cap)?,
+            openings: OpeningSet::from_json(&json.openings)?,
+            opening_proof: CompressedFriProof::from_json(&json.opening_proof)?,
+        })
+    }
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    ProofWithPublicInputs<F, C, D>
+{
+    /// Encodes the proof as JSON, as described in the `util::json` module documentation.
+    pub fn to_json(&self) -> String {
+        let json = ProofWithPublicInputsJson {
+            public_inputs: fields_to_json(&self.public_inputs),
+            proof: self.proof.to_json(),
+        };
+        serde_json::to_string(&json).expect("Encoding a proof as JSON cannot fail.")
+    }
+
+    /// Decodes a proof encoded by `to_json`.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let json: ProofWithPublicInputsJson<Vec<FriQueryRoundJson>> =
+            serde_json::from_str(json).map_err(|e| anyhow!("Invalid proof JSON: {}", e))?;
+        Ok(Self {
+            public_inputs: fields_from_json(&json.public_inputs)?,
+            proof: Proof::from_json(&json.proof)?,
+        })
+    }
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    CompressedProofWithPublicInputs<F, C, D>
+{
+    /// Encodes the compressed proof as JSON, as described in the `util::json` module
+    /// documentation.
+    pub fn to_json(&self) -> String {
+        let json = ProofWithPublicInputsJson {
+            public_inputs: fields_to_json(&self.public_inputs),
+            proof: self.proof.to_json(),
+        };
+        serde_json::to_string(&json).expect("Encoding a proof as JSON cannot fail.")
+    }
+
+    /// Decodes a compressed proof encoded by `to_json`.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let json: ProofWithPublicInputsJson<CompressedFriQueryRoundsJson> =
+            serde_json::from_str(json)
+                .map_err(|e| anyhow!("Invalid compressed proof JSON: {}", e))?;
+        Ok(Self {
+            public_inputs: fields_from_json(&json.public_inputs)?,
+            proof: CompressedProof::from_json(&json.proof)?,
+        })
+    }
+}
+
+impl<C: GenericConfig<D>, const D: usize> VerifierOnlyCircuitData<C, D> {
+    /// Encodes the verifier data as JSON, as described in the `util::json` module documentation.
+    pub fn to_json(&self) -> String {
+        let json = VerifierOnlyCircuitDataJson {
+            constants_sigmas_cap: hashes_to_json::<C::F, C::Hasher>(&self.constants_sigmas_cap.0),
+            circuit_digest: hash_to_json::<C::F, C::Hasher>(self.circuit_digest),
+        };
+        serde_json::to_string(&json).expect("Encoding verifier data as JSON cannot fail.")
+    }
+
+    /// Decodes verifier data encoded by `to_json`.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let json: VerifierOnlyCircuitDataJson =
+            serde_json::from_str(json).map_err(|e| anyhow!("Invalid verifier data JSON: {}", e))?;
+        Ok(Self {
+            constants_sigmas_cap: cap_from_json(&json.constants_sigmas_cap)?,
+            circuit_digest: hash_from_json::<C::F, C::Hasher>(&json.circuit_digest)?,
+        })
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CommonCircuitData<F, D> {
+    /// Encodes the circuit parameters as JSON, as described in the `util::json` module
+    /// documentation. Gates are identified by `Gate::id`.
+    pub fn to_json(&self) -> String {
+        let config = &self.config;
+        let fri_config = &config.fri_config;
+        let reduction_strategy = match &fri_config.reduction_strategy {
+            FriReductionStrategy::Fixed(arities) => {
+                FriReductionStrategyJson::Fixed(arities.clone())
+            }
+            &FriReductionStrategy::ConstantArityBits(arity_bits, final_poly_bits) => {
+                FriReductionStrategyJson::ConstantArityBits(arity_bits, final_poly_bits)
+            }
+            &FriReductionStrategy::MinSize(max_arity_bits) => {
+                FriReductionStrategyJson::MinSize(max_arity_bits)
+            }
+        };
+        let json = CommonCircuitDataJson {
+            config: CircuitConfigJson {
+                num_wires: config.num_wires,
+                num_routed_wires: config.num_routed_wires,
+                num_constants: config.num_constants,
+                use_base_arithmetic_gate: config.use_base_arithmetic_gate,
+                security_bits: config.security_bits,
+                num_challenges: config.num_challenges,
+                zero_knowledge: config.zero_knowledge,
+                max_quotient_degree_factor: config.max_quotient_degree_factor,
+                fri_config: FriConfigJson {
+                    rate_bits: fri_config.rate_bits,
+                    cap_height: fri_config.cap_height,
+                    proof_of_work_bits: fri_config.proof_of_work_bits,
+                    reduction_strategy,
+                    num_query_rounds: fri_config.num_query_rounds,
+                },
+            },
+            fri_params: FriParamsJson {
+                hiding: self.fri_params.hiding,
+                degree_bits: self.fri_params.degree_bits,
+                reduction_arity_bits: self.fri_params.reduction_arity_bits.clone(),
+            },
+            gates: self.gates.iter().map(|g| g.0.id()).collect(),
+            selectors_info: SelectorsInfoJson {
+                selector_indices: self.selectors_info.selector_indices.clone(),
+                groups: self
+                    .selectors_info
+                    .groups
+                    .iter()
+                    .map(|r| [r.start, r.end])
+                    .collect(),
+            },
+            quotient_degree_factor: self.quotient_degree_factor,
+            num_gate_constraints: self.num_gate_constraints,
+            num_constants: self.num_constants,
+            num_public_inputs: self.num_public_inputs,
+            k_is: fields_to_json(&self.k_is),
+            num_partial_products: self.num_partial_products,
+        };
+        serde_json::to_string(&json).expect("Encoding circuit data as JSON cannot fail.")
+    }
+
+    /// Decodes circuit parameters encoded by `to_json`. Since gates can't be reconstructed from
+    /// their IDs in general, each gate ID is resolved to the gate of `gates` with that ID.
+    pub fn from_json(json: &str, gates: &[GateRef<F, D>]) -> Result<Self> {
+        let json: CommonCircuitDataJson =
+            serde_json::from_str(json).map_err(|e| anyhow!("Invalid circuit data JSON: {}", e))?;
+        let config = &json.config;
+        let fri_config = &config.fri_config;
+        let reduction_strategy = match &fri_config.reduction_strategy {
+            FriReductionStrategyJson::Fixed(arities) => {
+                FriReductionStrategy::Fixed(arities.clone())
+            }
+            &FriReductionStrategyJson::ConstantArityBits(arity_bits, final_poly_bits) => {
+                FriReductionStrategy::ConstantArityBits(arity_bits, final_poly_bits)
+            }
+            &FriReductionStrategyJson::MinSize(max_arity_bits) => {
+                FriReductionStrategy::MinSize(max_arity_bits)
+            }
+        };
+        let fri_config = FriConfig {
+            rate_bits: fri_config.rate_bits,
+            cap_height: fri_config.cap_height,
+            proof_of_work_bits: fri_config.proof_of_work_bits,
+            reduction_strategy,
+            num_query_rounds: fri_config.num_query_rounds,
+        };
+        let gates = json
+            .gates
+            .iter()
+            .map(|id| {
+                gates
+                    .iter()
+                    .find(|g| &g.0.id() == id)
+                    .cloned()
+                    .ok_or_else(|| anyhow!("Unknown gate \"{}\"", id))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            config: CircuitConfig {
+                num_wires: config.num_wires,
+                num_routed_wires: config.num_routed_wires,
+                num_constants: config.num_constants,
+                use_base_arithmetic_gate: config.use_base_arithmetic_gate,
+                security_bits: config.security_bits,
+                num_challenges: config.num_challenges,
+                zero_knowledge: config.zero_knowledge,
+                max_quotient_degree_factor: config.max_quotient_degree_factor,
+                fri_config: fri_config.clone(),
+            },
+            fri_params: FriParams {
+                config: fri_config,
+                hiding: json.fri_params.hiding,
+                degree_bits: json.fri_params.degree_bits,
+                reduction_arity_bits: json.fri_params.reduction_arity_bits,
+            },
+            gates,
+            selectors_info: SelectorsInfo {
+                selector_indices: json.selectors_info.selector_indices,
+                groups: json
+                    .selectors_info
+                    .groups
+                    .iter()
+                    .map(|&[start, end]| start..end)
+                    .collect(),
+            },
+            quotient_degree_factor: json.quotient_degree_factor,
+            num_gate_constraints: json.num_gate_constraints,
+            num_constants: json.num_constants,
+            num_public_inputs: json.num_public_inputs,
+            k_is: fields_from_json(&json.k_is)?,
+            num_partial_products: json.num_partial_products,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::types::{Field, Field64, Sample};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{KeccakGoldilocksConfig, PoseidonGoldilocksConfig};
+
+    fn test_json_round_trip<C: GenericConfig<D>, const D: usize>() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<C::F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let y = builder.mul(x, x);
+        builder.register_public_input(y);
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, C::F::rand());
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        let json = proof.to_json();
+        assert_eq!(ProofWithPublicInputs::from_json(&json)?, proof);
+        let from_bytes =
+            ProofWithPublicInputs::<C::F, C, D>::from_bytes(proof.to_bytes(), &data.common)?;
+        assert_eq!(from_bytes.to_json(), json);
+
+        let compressed = data.compress(proof)?;
+        let json = compressed.to_json();
+        assert_eq!(
+            CompressedProofWithPublicInputs::from_json(&json)?,
+            compressed
+        );
+
+        let verifier_data =
+            VerifierOnlyCircuitData::<C, D>::from_json(&data.verifier_only.to_json())?;
+        assert_eq!(verifier_data, data.verifier_only);
+        let verifier_data =
+            VerifierOnlyCircuitData::<C, D>::from_bytes(verifier_data.to_bytes(), &data.common)?;
+        assert_eq!(verifier_data, data.verifier_only);
+
+        let common_json = data.common.to_json();
+        let common: serde_json::Value = serde_json::from_str(&common_json)?;
+        assert_eq!(common["num_public_inputs"], 1);
+        assert_eq!(
+            common["fri_params"]["degree_bits"],
+            data.common.degree_bits()
+        );
+        let common = CommonCircuitData::from_json(&common_json, &data.common.gates)?;
+        assert_eq!(common, data.common);
+        assert!(
+            CommonCircuitData::<C::F, D>::from_json(&common_json, &data.common.gates[1..]).is_err()
+        );
+        data.verify_compressed(compressed)
+    }
+
+    #[test]
+    fn test_json_round_trip_poseidon() -> Result<()> {
+        test_json_round_trip::<PoseidonGoldilocksConfig, 2>()
+    }
+
+    #[test]
+    fn test_json_round_trip_keccak() -> Result<()> {
+        test_json_round_trip::<KeccakGoldilocksConfig, 2>()
+    }
+
+    #[test]
+    fn test_json_field_encoding() {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+        let x = F::from_canonical_u64(12345);
+        assert_eq!(field_to_json(x), "12345");
+        assert_eq!(field_from_json::<F>("12345").unwrap(), x);
+        assert_eq!(field_to_json(F::NEG_ONE), (F::ORDER - 1).to_string());
+        assert!(field_from_json::<F>(&F::ORDER.to_string()).is_err());
+        assert!(field_from_json::<F>("012345").is_err());
+        assert!(field_from_json::<F>("+12345").is_err());
+        assert!(field_from_json::<F>("0x10").is_err());
+
+        type H = <PoseidonGoldilocksConfig as GenericConfig<2>>::Hasher;
+        let h = H::hash_no_pad(&[x]);
+        assert_eq!(hash_from_json::<F, H>(&hash_to_json::<F, H>(h)).unwrap(), h);
+        let all_ones = format!("0x{}", "ff".repeat(<H as Hasher<F>>::HASH_SIZE));
+        assert!(hash_from_json::<F, H>(&all_ones).is_err());
+
+        // Byte hashes have no canonicity requirement.
+        type KH = <KeccakGoldilocksConfig as GenericConfig<2>>::Hasher;
+        let all_ones = format!("0x{}", "ff".repeat(<KH as Hasher<F>>::HASH_SIZE));
+        assert!(hash_from_json::<F, KH>(&all_ones).is_ok());
+    }
+}
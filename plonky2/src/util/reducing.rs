@@ -2,7 +2,9 @@ use alloc::vec;
 use alloc::vec::Vec;
 use core::borrow::Borrow;
 
+use crate::field::extension::packed::PackedExtension;
 use crate::field::extension::{Extendable, FieldExtension};
+use crate::field::packable::Packable;
 use crate::field::packed::PackedField;
 use crate::field::polynomial::PolynomialCoeffs;
 use crate::field::types::Field;
@@ -80,18 +82,39 @@ impl<F: Field> ReducingFactor<F> {
         })
     }
 
+    /// Computes `sum_i base^i * poly_i` for basefield polynomials `poly_i`. The coefficients are
+    /// processed `<BF as Packable>::Packing::WIDTH` at a time using `PackedExtension` arithmetic.
     pub fn reduce_polys_base<BF: Extendable<D, Extension = F>, const D: usize>(
         &mut self,
         polys: impl IntoIterator<Item = impl Borrow<PolynomialCoeffs<BF>>>,
     ) -> PolynomialCoeffs<F> {
-        self.base
-            .powers()
-            .zip(polys)
-            .map(|(base_power, poly)| {
-                self.count += 1;
-                poly.borrow().mul_extension(base_power)
-            })
-            .sum()
+        type P<BF> = <BF as Packable>::Packing;
+
+        let polys = polys.into_iter().collect::<Vec<_>>();
+        let powers = self.base.powers().take(polys.len()).collect::<Vec<_>>();
+        self.count += polys.len() as u64;
+
+        let len = polys.iter().map(|p| p.borrow().len()).max().unwrap_or(0);
+        let mut coeffs = vec![F::ZERO; len];
+        for (chunk_i, chunk) in coeffs.chunks_mut(P::<BF>::WIDTH).enumerate() {
+            let start = chunk_i * P::<BF>::WIDTH;
+            let sum = polys
+                .iter()
+                .zip(&powers)
+                .map(|(poly, &power)| {
+                    // Shorter polynomials, and the last chunk, are padded with zeros.
+                    let poly_coeffs = poly.borrow().coeffs.get(start..).unwrap_or_default();
+                    let n = poly_coeffs.len().min(P::<BF>::WIDTH);
+                    let mut c = P::<BF>::ZEROS;
+                    c.as_slice_mut()[..n].copy_from_slice(&poly_coeffs[..n]);
+                    PackedExtension::<P<BF>, D>::broadcast(power).scalar_mul(c)
+                })
+                .sum::<PackedExtension<P<BF>, D>>();
+            for (lane, c) in chunk.iter_mut().enumerate() {
+                *c = sum.extract(lane);
+            }
+        }
+        PolynomialCoeffs::new(coeffs)
     }
 
     pub fn shift(&mut self, x: F) -> F {
@@ -345,6 +368,30 @@ mod tests {
         verify(proof, &data.verifier_only, &data.common)
     }
 
+    #[test]
+    fn test_reduce_polys_base() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type FF = <C as GenericConfig<D>>::FE;
+
+        let alpha = FF::rand();
+        // Polynomials of uneven lengths, so that both the packed and the leftover paths are used.
+        let polys = [37, 64, 13, 50]
+            .into_iter()
+            .map(|n| PolynomialCoeffs::new(F::rand_vec(n)))
+            .collect::<Vec<_>>();
+
+        let expected: PolynomialCoeffs<FF> = alpha
+            .powers()
+            .zip(&polys)
+            .map(|(power, poly)| poly.mul_extension::<D>(power))
+            .sum();
+        let mut alpha = ReducingFactor::new(alpha);
+        assert_eq!(alpha.reduce_polys_base::<F, D>(&polys), expected);
+        assert_eq!(alpha.count, polys.len() as u64);
+    }
+
     #[test]
     fn test_reduce_gadget_even() -> Result<()> {
         test_reduce_gadget(10)
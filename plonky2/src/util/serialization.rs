@@ -8,20 +8,26 @@ use hashbrown::HashMap;
 
 use crate::field::extension::{Extendable, FieldExtension};
 use crate::field::polynomial::PolynomialCoeffs;
-use crate::field::types::{Field64, PrimeField64};
+use crate::field::types::{Field, Field64, PrimeField64};
 use crate::fri::proof::{
-    CompressedFriProof, CompressedFriQueryRounds, FriInitialTreeProof, FriProof, FriQueryRound,
-    FriQueryStep,
+    fri_coset_positions, CompressedFriProof, CompressedFriQueryRounds, FriInitialTreeProof,
+    FriProof, FriQueryRound, FriQueryStep,
 };
+use crate::fri::reduction_strategies::FriReductionStrategy;
+use crate::fri::FriConfig;
 use crate::hash::hash_types::RichField;
 use crate::hash::merkle_proofs::MerkleProof;
 use crate::hash::merkle_tree::MerkleCap;
-use crate::plonk::circuit_data::CommonCircuitData;
+use crate::iop::target::Target;
+use crate::iop::wire::Wire;
+use crate::iop::witness::{MatrixWitness, PartialWitness};
+use crate::plonk::circuit_data::{CommonCircuitData, VerifierOnlyCircuitData};
 use crate::plonk::config::{GenericConfig, GenericHashOut, Hasher};
 use crate::plonk::plonk_common::salt_size;
 use crate::plonk::proof::{
     CompressedProof, CompressedProofWithPublicInputs, OpeningSet, Proof, ProofWithPublicInputs,
 };
+use crate::plonk::verifier::infer_last_quotient_openings;
 
 /// A no_std compatible variant of `std::io::Error`
 #[derive(Debug)]
@@ -465,6 +471,302 @@ pub trait Read {
             public_inputs,
         })
     }
+
+    /// Reads a value of type [`OpeningSet`] written by [`Write::write_compact_opening_set`]. The
+    /// omitted quotient openings are set to zero.
+    #[inline]
+    fn read_compact_opening_set<F, C, const D: usize>(
+        &mut self,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<OpeningSet<F, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let config = &common_data.config;
+        let quotient_degree_factor = common_data.quotient_degree_factor;
+        let lengths = [
+            common_data.num_constants,
+            config.num_routed_wires,
+            config.num_wires,
+            config.num_challenges,
+            config.num_challenges,
+            common_data.num_partial_products * config.num_challenges,
+            (quotient_degree_factor - 1) * config.num_challenges,
+        ];
+        let mut values = self
+            .read_field_ext_vec::<F, D>(lengths.iter().sum())?
+            .into_iter();
+        let [constants, plonk_sigmas, wires, plonk_zs, plonk_zs_next, partial_products, quotients] =
+            lengths.map(|len| values.by_ref().take(len).collect::<Vec<_>>());
+        let mut quotients = quotients.into_iter();
+        let quotient_polys = (0..config.num_challenges)
+            .flat_map(|_| {
+                let chunk = quotients.by_ref().take(quotient_degree_factor - 1);
+                chunk.chain([F::Extension::ZERO]).collect::<Vec<_>>()
+            })
+            .collect();
+        Ok(OpeningSet {
+            constants,
+            plonk_sigmas,
+            wires,
+            plonk_zs,
+            plonk_zs_next,
+            partial_products,
+            quotient_polys,
+        })
+    }
+
+    /// Reads a value of type [`CompressedFriQueryRounds`] written by
+    /// [`Write::write_compact_fri_query_rounds`], given the query `indices`. The omitted
+    /// evaluations are set to zero.
+    #[inline]
+    fn read_compact_fri_query_rounds<F, C, const D: usize>(
+        &mut self,
+        indices: &[usize],
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<CompressedFriQueryRounds<F, C::Hasher, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let mut initial_indices = indices.to_vec();
+        initial_indices.sort_unstable();
+        initial_indices.dedup();
+        let mut initial_trees_proofs = HashMap::with_capacity(initial_indices.len());
+        for i in initial_indices {
+            initial_trees_proofs.insert(i, self.read_fri_initial_proof::<F, C, D>(common_data)?);
+        }
+
+        let reduction_arity_bits = &common_data.fri_params.reduction_arity_bits;
+        let mut steps = Vec::with_capacity(reduction_arity_bits.len());
+        for (&arity_bits, cosets) in reduction_arity_bits
+            .iter()
+            .zip(fri_coset_positions(indices, reduction_arity_bits))
+        {
+            let arity = 1 << arity_bits;
+            let mut query_steps = HashMap::with_capacity(cosets.len());
+            for (coset_index, positions) in cosets {
+                let mut explicit_evals = self
+                    .read_field_ext_vec::<F, D>(arity - positions.len())?
+                    .into_iter();
+                let evals = (0..arity)
+                    .filter(|&position| position != positions[0])
+                    .map(|position| {
+                        if positions.contains(&position) {
+                            F::Extension::ZERO
+                        } else {
+                            explicit_evals.next().unwrap()
+                        }
+                    })
+                    .collect();
+                let merkle_proof = self.read_merkle_proof()?;
+                query_steps.insert(
+                    coset_index,
+                    FriQueryStep {
+                        evals,
+                        merkle_proof,
+                    },
+                );
+            }
+            steps.push(query_steps);
+        }
+
+        Ok(CompressedFriQueryRounds {
+            indices: indices.to_vec(),
+            initial_trees_proofs,
+            steps,
+        })
+    }
+
+    /// Reads a value of type [`CompressedProofWithPublicInputs`] written by
+    /// [`Write::write_compact_proof_with_public_inputs`], recomputing the omitted data from the
+    /// Fiat-Shamir challenges.
+    #[inline]
+    fn read_compact_proof_with_public_inputs<F, C, const D: usize>(
+        &mut self,
+        circuit_digest: &<<C as GenericConfig<D>>::Hasher as Hasher<C::F>>::Hash,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<CompressedProofWithPublicInputs<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let config = &common_data.config;
+        let public_inputs = self.read_field_vec(common_data.num_public_inputs)?;
+        let wires_cap = self.read_merkle_cap(config.fri_config.cap_height)?;
+        let plonk_zs_partial_products_cap = self.read_merkle_cap(config.fri_config.cap_height)?;
+        let quotient_polys_cap = self.read_merkle_cap(config.fri_config.cap_height)?;
+        let openings = self.read_compact_opening_set::<F, C, D>(common_data)?;
+        let commit_phase_merkle_caps = (0..common_data.fri_params.reduction_arity_bits.len())
+            .map(|_| self.read_merkle_cap(config.fri_config.cap_height))
+            .collect::<Result<Vec<_>, _>>()?;
+        let final_poly = PolynomialCoeffs::new(
+            self.read_field_ext_vec::<F, D>(common_data.fri_params.final_poly_len())?,
+        );
+        let pow_witness = self.read_field()?;
+
+        let mut proof_with_pis = CompressedProofWithPublicInputs {
+            proof: CompressedProof {
+                wires_cap,
+                plonk_zs_partial_products_cap,
+                quotient_polys_cap,
+                openings,
+                opening_proof: CompressedFriProof {
+                    commit_phase_merkle_caps,
+                    query_round_proofs: CompressedFriQueryRounds {
+                        indices: Vec::new(),
+                        initial_trees_proofs: HashMap::new(),
+                        steps: Vec::new(),
+                    },
+                    final_poly,
+                    pow_witness,
+                },
+            },
+            public_inputs,
+        };
+
+        // The Plonk challenges don't depend on the openings, so we can use them to infer the
+        // missing quotient openings before computing the FRI challenges.
+        let public_inputs_hash = proof_with_pis.get_public_inputs_hash();
+        let challenges = proof_with_pis
+            .get_challenges(public_inputs_hash, circuit_digest, common_data)
+            .map_err(|_| IoError)?;
+        infer_last_quotient_openings::<F, C, D>(
+            &mut proof_with_pis.proof.openings,
+            public_inputs_hash,
+            &challenges,
+            common_data,
+        )
+        .map_err(|_| IoError)?;
+        let challenges = proof_with_pis
+            .get_challenges(public_inputs_hash, circuit_digest, common_data)
+            .map_err(|_| IoError)?;
+
+        proof_with_pis.proof.opening_proof.query_round_proofs = self
+            .read_compact_fri_query_rounds::<F, C, D>(
+                &challenges.fri_challenges.fri_query_indices,
+                common_data,
+            )?;
+        proof_with_pis.fill_compact_fri_evals(&challenges, common_data);
+        Ok(proof_with_pis)
+    }
+
+    /// Reads a [`Target`] from `self`.
+    #[inline]
+    fn read_target(&mut self) -> IoResult<Target> {
+        match self.read_u8()? {
+            0 => {
+                let row = self.read_u32()? as usize;
+                let column = self.read_u32()? as usize;
+                Ok(Target::wire(row, column))
+            }
+            1 => {
+                let index = self.read_u32()? as usize;
+                Ok(Target::VirtualTarget { index })
+            }
+            _ => Err(IoError),
+        }
+    }
+
+    /// Reads a value of type [`PartialWitness`] from `self`.
+    #[inline]
+    fn read_partial_witness<F>(&mut self) -> IoResult<PartialWitness<F>>
+    where
+        F: Field64,
+    {
+        let len = self.read_u32()? as usize;
+        let mut target_values = HashMap::with_capacity(len);
+        for _ in 0..len {
+            let target = self.read_target()?;
+            let value = self.read_field()?;
+            if target_values.insert(target, value).is_some() {
+                return Err(IoError);
+            }
+        }
+        Ok(PartialWitness { target_values })
+    }
+
+    /// Reads a value of type [`MatrixWitness`] from `self`.
+    #[inline]
+    fn read_matrix_witness<F>(&mut self) -> IoResult<MatrixWitness<F>>
+    where
+        F: Field64,
+    {
+        let num_wires = self.read_u32()? as usize;
+        let degree = self.read_u32()? as usize;
+        let wire_values = (0..num_wires)
+            .map(|_| self.read_field_vec(degree))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(MatrixWitness { wire_values })
+    }
+
+    /// Reads a value of type [`VerifierOnlyCircuitData`] from `self` with the given `common_data`.
+    #[inline]
+    fn read_verifier_only_circuit_data<F, C, const D: usize>(
+        &mut self,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<VerifierOnlyCircuitData<C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let constants_sigmas_cap =
+            self.read_merkle_cap(common_data.config.fri_config.cap_height)?;
+        let circuit_digest = self.read_hash::<F, C::Hasher>()?;
+        Ok(VerifierOnlyCircuitData {
+            constants_sigmas_cap,
+            circuit_digest,
+        })
+    }
+
+    /// Reads a value of type [`FriReductionStrategy`] from `self`.
+    #[inline]
+    fn read_fri_reduction_strategy(&mut self) -> IoResult<FriReductionStrategy> {
+        match self.read_u8()? {
+            0 => {
+                let length = self.read_u8()?;
+                let arities = (0..length)
+                    .map(|_| self.read_u8().map(usize::from))
+                    .collect::<Result<_, _>>()?;
+                Ok(FriReductionStrategy::Fixed(arities))
+            }
+            1 => {
+                let arity_bits = self.read_u32()? as usize;
+                let final_poly_bits = self.read_u32()? as usize;
+                Ok(FriReductionStrategy::ConstantArityBits(
+                    arity_bits,
+                    final_poly_bits,
+                ))
+            }
+            2 => {
+                let max_arity_bits = match self.read_u8()? {
+                    0 => None,
+                    1 => Some(self.read_u32()? as usize),
+                    _ => return Err(IoError),
+                };
+                Ok(FriReductionStrategy::MinSize(max_arity_bits))
+            }
+            _ => Err(IoError),
+        }
+    }
+
+    /// Reads a value of type [`FriConfig`] from `self`.
+    #[inline]
+    fn read_fri_config(&mut self) -> IoResult<FriConfig> {
+        let rate_bits = self.read_u32()? as usize;
+        let cap_height = self.read_u32()? as usize;
+        let proof_of_work_bits = self.read_u32()?;
+        let reduction_strategy = self.read_fri_reduction_strategy()?;
+        let num_query_rounds = self.read_u32()? as usize;
+        Ok(FriConfig {
+            rate_bits,
+            cap_height,
+            proof_of_work_bits,
+            reduction_strategy,
+            num_query_rounds,
+        })
+    }
 }
 
 /// Writing
@@ -769,6 +1071,219 @@ pub trait Write {
         self.write_compressed_proof(proof)?;
         self.write_field_vec(public_inputs)
     }
+
+    /// Writes a value `os` of type [`OpeningSet`] to `self`, omitting the last quotient chunk
+    /// opening for each challenge, which the verifier can infer from the other openings.
+    #[inline]
+    fn write_compact_opening_set<F, const D: usize>(
+        &mut self,
+        os: &OpeningSet<F, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+    {
+        let quotients = os
+            .quotient_polys
+            .chunks(common_data.quotient_degree_factor)
+            .flat_map(|chunk| &chunk[..chunk.len() - 1]);
+        let values = os
+            .constants
+            .iter()
+            .chain(&os.plonk_sigmas)
+            .chain(&os.wires)
+            .chain(&os.plonk_zs)
+            .chain(&os.plonk_zs_next)
+            .chain(&os.partial_products)
+            .chain(quotients)
+            .copied()
+            .collect::<Vec<_>>();
+        self.write_field_ext_vec::<F, D>(&values)
+    }
+
+    /// Writes a value `cfqrs` of type [`CompressedFriQueryRounds`] to `self`, omitting the query
+    /// indices and every query step evaluation that the verifier can infer.
+    #[inline]
+    fn write_compact_fri_query_rounds<F, C, const D: usize>(
+        &mut self,
+        cfqrs: &CompressedFriQueryRounds<F, C::Hasher, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let mut initial_trees_proofs = cfqrs.initial_trees_proofs.iter().collect::<Vec<_>>();
+        initial_trees_proofs.sort_by_key(|&x| x.0);
+        for (_, itp) in initial_trees_proofs {
+            self.write_fri_initial_proof::<F, C, D>(itp)?;
+        }
+
+        let coset_positions =
+            fri_coset_positions(&cfqrs.indices, &common_data.fri_params.reduction_arity_bits);
+        for (query_steps, cosets) in cfqrs.steps.iter().zip(coset_positions) {
+            for (coset_index, positions) in cosets {
+                let query_step = &query_steps[&coset_index];
+                // The compressed evaluations skip the position reached first.
+                let explicit_evals = query_step
+                    .evals
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, _)| !positions.contains(&(j + usize::from(j >= positions[0]))))
+                    .map(|(_, &e)| e)
+                    .collect::<Vec<_>>();
+                self.write_field_ext_vec::<F, D>(&explicit_evals)?;
+                self.write_merkle_proof(&query_step.merkle_proof)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a value `proof_with_pis` of type [`CompressedProofWithPublicInputs`] to `self`,
+    /// omitting everything the verifier can recompute. Values are ordered so that the Fiat-Shamir
+    /// challenges can be computed before reading the query rounds.
+    #[inline]
+    fn write_compact_proof_with_public_inputs<F, C, const D: usize>(
+        &mut self,
+        proof_with_pis: &CompressedProofWithPublicInputs<F, C, D>,
+        common_data: &CommonCircuitData<F, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let CompressedProofWithPublicInputs {
+            proof,
+            public_inputs,
+        } = proof_with_pis;
+        self.write_field_vec(public_inputs)?;
+        self.write_merkle_cap(&proof.wires_cap)?;
+        self.write_merkle_cap(&proof.plonk_zs_partial_products_cap)?;
+        self.write_merkle_cap(&proof.quotient_polys_cap)?;
+        self.write_compact_opening_set(&proof.openings, common_data)?;
+        let fri_proof = &proof.opening_proof;
+        for cap in &fri_proof.commit_phase_merkle_caps {
+            self.write_merkle_cap(cap)?;
+        }
+        self.write_field_ext_vec::<F, D>(&fri_proof.final_poly.coeffs)?;
+        self.write_field(fri_proof.pow_witness)?;
+        self.write_compact_fri_query_rounds::<F, C, D>(&fri_proof.query_round_proofs, common_data)
+    }
+
+    /// Writes a [`Target`] to `self`.
+    #[inline]
+    fn write_target(&mut self, target: Target) -> IoResult<()> {
+        match target {
+            Target::Wire(Wire { row, column }) => {
+                self.write_u8(0)?;
+                self.write_u32(row as u32)?;
+                self.write_u32(column as u32)
+            }
+            Target::VirtualTarget { index } => {
+                self.write_u8(1)?;
+                self.write_u32(index as u32)
+            }
+        }
+    }
+
+    /// Writes a value `pw` of type [`PartialWitness`] to `self`. Targets are written in a fixed
+    /// order, so equal witnesses have equal encodings.
+    #[inline]
+    fn write_partial_witness<F>(&mut self, pw: &PartialWitness<F>) -> IoResult<()>
+    where
+        F: PrimeField64,
+    {
+        let mut entries = pw.target_values.iter().collect::<Vec<_>>();
+        entries.sort_unstable_by_key(|(target, _)| match target {
+            Target::Wire(Wire { row, column }) => (0, *row, *column),
+            Target::VirtualTarget { index } => (1, *index, 0),
+        });
+        self.write_u32(entries.len() as u32)?;
+        for (&target, &value) in entries {
+            self.write_target(target)?;
+            self.write_field(value)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a value `witness` of type [`MatrixWitness`] to `self`.
+    #[inline]
+    fn write_matrix_witness<F>(&mut self, witness: &MatrixWitness<F>) -> IoResult<()>
+    where
+        F: PrimeField64,
+    {
+        let num_wires = witness.wire_values.len();
+        let degree = witness.wire_values.first().map_or(0, |column| column.len());
+        self.write_u32(num_wires as u32)?;
+        self.write_u32(degree as u32)?;
+        for column in &witness.wire_values {
+            self.write_field_vec(column)?;
+        }
+        Ok(())
+    }
+
+    /// Writes a value `verifier_data` of type [`VerifierOnlyCircuitData`] to `self`.
+    #[inline]
+    fn write_verifier_only_circuit_data<F, C, const D: usize>(
+        &mut self,
+        verifier_data: &VerifierOnlyCircuitData<C, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        self.write_merkle_cap(&verifier_data.constants_sigmas_cap)?;
+        self.write_hash::<F, C::Hasher>(verifier_data.circuit_digest)
+    }
+
+    /// Writes a value `strategy` of type [`FriReductionStrategy`] to `self`.
+    #[inline]
+    fn write_fri_reduction_strategy(&mut self, strategy: &FriReductionStrategy) -> IoResult<()> {
+        match strategy {
+            FriReductionStrategy::Fixed(arities) => {
+                self.write_u8(0)?;
+                self.write_u8(
+                    arities
+                        .len()
+                        .try_into()
+                        .expect("Number of FRI reductions must fit in u8."),
+                )?;
+                for &arity_bits in arities {
+                    self.write_u8(
+                        arity_bits
+                            .try_into()
+                            .expect("FRI arity bits must fit in u8."),
+                    )?;
+                }
+                Ok(())
+            }
+            &FriReductionStrategy::ConstantArityBits(arity_bits, final_poly_bits) => {
+                self.write_u8(1)?;
+                self.write_u32(arity_bits as u32)?;
+                self.write_u32(final_poly_bits as u32)
+            }
+            &FriReductionStrategy::MinSize(max_arity_bits) => {
+                self.write_u8(2)?;
+                match max_arity_bits {
+                    None => self.write_u8(0),
+                    Some(max_arity_bits) => {
+                        self.write_u8(1)?;
+                        self.write_u32(max_arity_bits as u32)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Writes a value `config` of type [`FriConfig`] to `self`.
+    #[inline]
+    fn write_fri_config(&mut self, config: &FriConfig) -> IoResult<()> {
+        self.write_u32(config.rate_bits as u32)?;
+        self.write_u32(config.cap_height as u32)?;
+        self.write_u32(config.proof_of_work_bits)?;
+        self.write_fri_reduction_strategy(&config.reduction_strategy)?;
+        self.write_u32(config.num_query_rounds as u32)
+    }
 }
 
 impl Write for Vec<u8> {
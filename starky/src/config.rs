@@ -1,6 +1,18 @@
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use anyhow::{ensure, Result};
 use plonky2::fri::reduction_strategies::FriReductionStrategy;
 use plonky2::fri::{FriConfig, FriParams};
+use plonky2::util::log2_ceil;
+#[cfg(feature = "std")]
+use plonky2::util::serialization::{Buffer, Remaining};
+
+#[cfg(feature = "std")]
+use crate::serialization::StarkRead;
+use crate::serialization::StarkWrite;
 
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct StarkConfig {
     pub security_bits: usize,
 
@@ -9,6 +21,12 @@ pub struct StarkConfig {
     pub num_challenges: usize,
 
     pub fri_config: FriConfig,
+
+    /// Whether to randomize the trace and auxiliary polynomials and salt the leaves of every
+    /// witness-dependent Merkle tree, so that proofs reveal nothing about the trace beyond the
+    /// public inputs. Randomization raises the degree of the committed polynomials above the trace
+    /// length, so constraints of degree `d` need `rate_bits >= log2_ceil(d)`.
+    pub zero_knowledge: bool,
 }
 
 impl StarkConfig {
@@ -25,10 +43,85 @@ impl StarkConfig {
                 reduction_strategy: FriReductionStrategy::ConstantArityBits(4, 5),
                 num_query_rounds: 84,
             },
+            zero_knowledge: false,
         }
     }
 
-    pub(crate) fn fri_params(&self, degree_bits: usize) -> FriParams {
-        self.fri_config.fri_params(degree_bits, false)
+    /// The standard fast configuration, with zero-knowledge enabled. Its rate only supports
+    /// constraints of degree at most 2.
+    pub fn standard_fast_zk_config() -> Self {
+        Self {
+            zero_knowledge: true,
+            ..Self::standard_fast_config()
+        }
+    }
+
+    /// The log of the degree bound of the committed polynomials of a trace of `2^degree_bits` rows,
+    /// for a STARK opened at `num_opening_points` points outside of the trace domain.
+    ///
+    /// In zero-knowledge mode, each witness-dependent polynomial `t` is committed as `t + Z_H r`
+    /// for a random `r`. A proof reveals its evaluations at one point per FRI query and at every
+    /// opening point, which are uniformly random as long as `r` has at least that many
+    /// coefficients. Like plonky2's blinding gates, the degree bound thus grows with the number of
+    /// queries and opening points. It is also at least twice the number of evaluations revealed
+    /// from each quotient chunk, so that the chunk randomizers of `quotient_chunk_degree_bits`
+    /// have enough coefficients.
+    pub(crate) fn committed_degree_bits(
+        &self,
+        degree_bits: usize,
+        num_opening_points: usize,
+    ) -> usize {
+        if self.zero_knowledge {
+            let num_revealed_evaluations = self.fri_config.num_query_rounds + num_opening_points;
+            // Quotient chunks are opened at `zeta` and at one point per FRI query.
+            let num_revealed_quotient_evaluations = self.fri_config.num_query_rounds + 1;
+            log2_ceil(
+                ((1 << degree_bits) + num_revealed_evaluations)
+                    .max(2 * num_revealed_quotient_evaluations),
+            )
+        } else {
+            degree_bits
+        }
+    }
+
+    /// The log of the degree bound of the quotient chunks, before they are committed.
+    ///
+    /// In zero-knowledge mode, this is half the committed degree bound `m`: the other half leaves
+    /// room for the randomizers `b_i` that each chunk is committed with, as
+    /// `t_i + X^{m/2} b_i - b_{i-1}`.
+    pub(crate) fn quotient_chunk_degree_bits(
+        &self,
+        degree_bits: usize,
+        num_opening_points: usize,
+    ) -> usize {
+        let committed_degree_bits = self.committed_degree_bits(degree_bits, num_opening_points);
+        if self.zero_knowledge {
+            committed_degree_bits - 1
+        } else {
+            committed_degree_bits
+        }
+    }
+
+    pub(crate) fn fri_params(&self, degree_bits: usize, num_opening_points: usize) -> FriParams {
+        self.fri_config.fri_params(
+            self.committed_degree_bits(degree_bits, num_opening_points),
+            self.zero_knowledge,
+        )
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_stark_config(self)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    #[cfg(feature = "std")]
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let config = buffer.read_stark_config().map_err(anyhow::Error::msg)?;
+        ensure!(buffer.is_empty(), "Trailing bytes after STARK config.");
+        Ok(config)
     }
 }
@@ -0,0 +1,756 @@
+//! Cross-table lookups, which check that rows of some tables of a multi-STARK appear in another
+//! table of the same multi-STARK.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::iter::{once, repeat};
+
+use anyhow::{bail, ensure, Result};
+use itertools::Itertools;
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::iop::target::Target;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::config::GenericConfig;
+
+use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::permutation::{PermutationChallenge, PermutationChallengeSet};
+use crate::proof::{StarkProofWithPublicInputs, StarkProofWithPublicInputsTarget};
+use crate::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+
+/// Represent a linear combination of columns.
+#[derive(Clone, Debug)]
+pub struct Column<F: Field> {
+    linear_combination: Vec<(usize, F)>,
+    constant: F,
+}
+
+impl<F: Field> Column<F> {
+    pub fn single(c: usize) -> Self {
+        Self {
+            linear_combination: vec![(c, F::ONE)],
+            constant: F::ZERO,
+        }
+    }
+
+    pub fn singles<I: IntoIterator<Item = impl Borrow<usize>>>(
+        cs: I,
+    ) -> impl Iterator<Item = Self> {
+        cs.into_iter().map(|c| Self::single(*c.borrow()))
+    }
+
+    pub fn constant(constant: F) -> Self {
+        Self {
+            linear_combination: vec![],
+            constant,
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::constant(F::ZERO)
+    }
+
+    pub fn one() -> Self {
+        Self::constant(F::ONE)
+    }
+
+    pub fn linear_combination_with_constant<I: IntoIterator<Item = (usize, F)>>(
+        iter: I,
+        constant: F,
+    ) -> Self {
+        let v = iter.into_iter().collect::<Vec<_>>();
+        assert!(!v.is_empty());
+        debug_assert_eq!(
+            v.iter().map(|(c, _)| c).collect::<BTreeSet<_>>().len(),
+            v.len(),
+            "Duplicate columns."
+        );
+        Self {
+            linear_combination: v,
+            constant,
+        }
+    }
+
+    pub fn linear_combination<I: IntoIterator<Item = (usize, F)>>(iter: I) -> Self {
+        Self::linear_combination_with_constant(iter, F::ZERO)
+    }
+
+    pub fn le_bits<I: IntoIterator<Item = impl Borrow<usize>>>(cs: I) -> Self {
+        Self::linear_combination(cs.into_iter().map(|c| *c.borrow()).zip(F::TWO.powers()))
+    }
+
+    pub fn sum<I: IntoIterator<Item = impl Borrow<usize>>>(cs: I) -> Self {
+        Self::linear_combination(cs.into_iter().map(|c| *c.borrow()).zip(repeat(F::ONE)))
+    }
+
+    pub fn eval<FE, P, const D: usize>(&self, v: &[P]) -> P
+    where
+        FE: FieldExtension<D, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        self.linear_combination
+            .iter()
+            .map(|&(c, f)| v[c] * FE::from_basefield(f))
+            .sum::<P>()
+            + FE::from_basefield(self.constant)
+    }
+
+    /// Evaluate on an row of a table given in column-major form.
+    pub fn eval_table(&self, table: &[PolynomialValues<F>], row: usize) -> F {
+        self.linear_combination
+            .iter()
+            .map(|&(c, f)| table[c].values[row] * f)
+            .sum::<F>()
+            + self.constant
+    }
+
+    pub fn eval_circuit<const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        v: &[ExtensionTarget<D>],
+    ) -> ExtensionTarget<D>
+    where
+        F: RichField + Extendable<D>,
+    {
+        let pairs = self
+            .linear_combination
+            .iter()
+            .map(|&(c, f)| {
+                (
+                    v[c],
+                    builder.constant_extension(F::Extension::from_basefield(f)),
+                )
+            })
+            .collect::<Vec<_>>();
+        let constant = builder.constant_extension(F::Extension::from_basefield(self.constant));
+        builder.inner_product_extension(F::ONE, constant, pairs)
+    }
+}
+
+/// A table of a multi-STARK, identified by its index, together with the columns taking part in a
+/// cross-table lookup.
+#[derive(Clone, Debug)]
+pub struct TableWithColumns<F: Field> {
+    table: usize,
+    columns: Vec<Column<F>>,
+    filter_column: Option<Column<F>>,
+}
+
+impl<F: Field> TableWithColumns<F> {
+    pub fn new(table: usize, columns: Vec<Column<F>>, filter_column: Option<Column<F>>) -> Self {
+        Self {
+            table,
+            columns,
+            filter_column,
+        }
+    }
+}
+
+/// A lookup of the rows of `looking_tables` into the rows of `looked_table`. Only rows whose
+/// filter evaluates to one take part in the lookup.
+#[derive(Clone, Debug)]
+pub struct CrossTableLookup<F: Field> {
+    looking_tables: Vec<TableWithColumns<F>>,
+    looked_table: TableWithColumns<F>,
+    /// Default value if filters are not used.
+    default: Option<Vec<F>>,
+}
+
+impl<F: Field> CrossTableLookup<F> {
+    pub fn new(
+        looking_tables: Vec<TableWithColumns<F>>,
+        looked_table: TableWithColumns<F>,
+        default: Option<Vec<F>>,
+    ) -> Self {
+        assert!(looking_tables
+            .iter()
+            .all(|twc| twc.columns.len() == looked_table.columns.len()));
+        assert!(
+            looking_tables
+                .iter()
+                .all(|twc| twc.filter_column.is_none() == default.is_some())
+                && default.is_some() == looked_table.filter_column.is_none(),
+            "Default values should be provided iff there are no filter columns."
+        );
+        if let Some(default) = &default {
+            assert_eq!(default.len(), looked_table.columns.len());
+        }
+        Self {
+            looking_tables,
+            looked_table,
+            default,
+        }
+    }
+
+    fn tables(&self) -> impl Iterator<Item = &TableWithColumns<F>> {
+        self.looking_tables.iter().chain(once(&self.looked_table))
+    }
+
+    /// The number of cross-table lookup `Z` polynomials of the given table.
+    pub fn num_ctl_zs(ctls: &[Self], table: usize, num_challenges: usize) -> usize {
+        let num_ctls = ctls
+            .iter()
+            .flat_map(|ctl| ctl.tables())
+            .filter(|twc| twc.table == table)
+            .count();
+        num_ctls * num_challenges
+    }
+
+    /// The number of tables needed for all tables referenced by the given lookups to exist.
+    pub(crate) fn min_num_tables(ctls: &[Self]) -> usize {
+        ctls.iter()
+            .flat_map(|ctl| ctl.tables())
+            .map(|twc| twc.table + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Checks that the given traces satisfy the given cross-table lookups, reporting the first row
+/// whose values are looked up a different number of times than they appear in the looked table.
+/// Used to debug traces of a multi-STARK.
+pub fn debug_cross_table_lookups<F: RichField>(
+    trace_poly_values: &[Vec<PolynomialValues<F>>],
+    cross_table_lookups: &[CrossTableLookup<F>],
+) -> Result<()> {
+    ensure!(
+        CrossTableLookup::min_num_tables(cross_table_lookups) <= trace_poly_values.len(),
+        "Cross-table lookups reference more tables than there are traces"
+    );
+    let key = |values: &[F]| {
+        values
+            .iter()
+            .map(|v| v.to_canonical_u64())
+            .collect::<Vec<_>>()
+    };
+    for (i, ctl) in cross_table_lookups.iter().enumerate() {
+        // The filtered rows of a table, along with their values.
+        let filtered_rows = |twc: &TableWithColumns<F>| -> Result<Vec<(usize, usize, Vec<F>)>> {
+            let trace = &trace_poly_values[twc.table];
+            let mut rows = vec![];
+            for row in 0..trace[0].len() {
+                let filter = twc
+                    .filter_column
+                    .as_ref()
+                    .map_or(F::ONE, |column| column.eval_table(trace, row));
+                ensure!(
+                    filter.is_zero() || filter.is_one(),
+                    "Cross-table lookup {} has a non-binary filter {} in table {} on row {}",
+                    i,
+                    filter,
+                    twc.table,
+                    row
+                );
+                if filter.is_one() {
+                    let values = twc
+                        .columns
+                        .iter()
+                        .map(|column| column.eval_table(trace, row))
+                        .collect();
+                    rows.push((twc.table, row, values));
+                }
+            }
+            Ok(rows)
+        };
+
+        let mut looking_rows = vec![];
+        for twc in &ctl.looking_tables {
+            looking_rows.extend(filtered_rows(twc)?);
+        }
+        let looked_rows = filtered_rows(&ctl.looked_table)?;
+        let mut counts = BTreeMap::<Vec<u64>, i64>::new();
+        for (_, _, values) in &looking_rows {
+            *counts.entry(key(values)).or_default() += 1;
+        }
+        for (_, _, values) in &looked_rows {
+            *counts.entry(key(values)).or_default() -= 1;
+        }
+        // Without filters, the looked table is implicitly padded with default rows up to the total
+        // length of the looking tables.
+        if let Some(default) = &ctl.default {
+            *counts.entry(key(default)).or_default() +=
+                looked_rows.len() as i64 - looking_rows.len() as i64;
+        }
+
+        if let Some((table, row, values)) = looking_rows
+            .iter()
+            .chain(&looked_rows)
+            .find(|(_, _, values)| counts[&key(values)] != 0)
+        {
+            bail!(
+                "Cross-table lookup {} fails: the values [{}] of table {} on row {} appear {} \
+                 more times in the looking tables than in the looked table {}",
+                i,
+                values.iter().format(", "),
+                table,
+                row,
+                counts[&key(values)],
+                ctl.looked_table.table
+            );
+        }
+        ensure!(
+            counts.values().all(|&count| count == 0),
+            "Cross-table lookup {} fails: the looking tables do not account for the default rows \
+             of the looked table {}",
+            i,
+            ctl.looked_table.table
+        );
+    }
+    Ok(())
+}
+
+/// Cross-table lookup data for one table.
+#[derive(Clone, Default)]
+pub struct CtlData<F: Field> {
+    pub(crate) zs_columns: Vec<CtlZData<F>>,
+}
+
+/// Cross-table lookup data associated with one Z(x) polynomial.
+#[derive(Clone)]
+pub(crate) struct CtlZData<F: Field> {
+    pub(crate) z: PolynomialValues<F>,
+    pub(crate) challenge: PermutationChallenge<F>,
+    pub(crate) columns: Vec<Column<F>>,
+    pub(crate) filter_column: Option<Column<F>>,
+}
+
+impl<F: Field> CtlData<F> {
+    pub fn len(&self) -> usize {
+        self.zs_columns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.zs_columns.is_empty()
+    }
+
+    pub fn z_polys(&self) -> Vec<PolynomialValues<F>> {
+        self.zs_columns
+            .iter()
+            .map(|zs_columns| zs_columns.z.clone())
+            .collect()
+    }
+}
+
+/// Computes the cross-table lookup `Z` polynomials of every table.
+pub(crate) fn cross_table_lookup_data<F: RichField>(
+    trace_poly_values: &[Vec<PolynomialValues<F>>],
+    cross_table_lookups: &[CrossTableLookup<F>],
+    ctl_challenges: &PermutationChallengeSet<F>,
+) -> Vec<CtlData<F>> {
+    let mut ctl_data_per_table = vec![CtlData::default(); trace_poly_values.len()];
+    for CrossTableLookup {
+        looking_tables,
+        looked_table,
+        default,
+    } in cross_table_lookups
+    {
+        log::debug!("Processing CTL for table {}", looked_table.table);
+        for &challenge in &ctl_challenges.challenges {
+            let zs_looking = looking_tables.iter().map(|table| {
+                partial_products(
+                    &trace_poly_values[table.table],
+                    &table.columns,
+                    &table.filter_column,
+                    challenge,
+                )
+            });
+            let z_looked = partial_products(
+                &trace_poly_values[looked_table.table],
+                &looked_table.columns,
+                &looked_table.filter_column,
+                challenge,
+            );
+
+            debug_assert_eq!(
+                zs_looking
+                    .clone()
+                    .map(|z| *z.values.last().unwrap())
+                    .product::<F>(),
+                *z_looked.values.last().unwrap()
+                    * default
+                        .as_ref()
+                        .map(|default| {
+                            challenge.combine(default).exp_u64(
+                                looking_tables
+                                    .iter()
+                                    .map(|table| trace_poly_values[table.table][0].len() as u64)
+                                    .sum::<u64>()
+                                    - trace_poly_values[looked_table.table][0].len() as u64,
+                            )
+                        })
+                        .unwrap_or(F::ONE)
+            );
+
+            for (table, z) in looking_tables.iter().zip(zs_looking) {
+                ctl_data_per_table[table.table].zs_columns.push(CtlZData {
+                    z,
+                    challenge,
+                    columns: table.columns.clone(),
+                    filter_column: table.filter_column.clone(),
+                });
+            }
+            ctl_data_per_table[looked_table.table]
+                .zs_columns
+                .push(CtlZData {
+                    z: z_looked,
+                    challenge,
+                    columns: looked_table.columns.clone(),
+                    filter_column: looked_table.filter_column.clone(),
+                });
+        }
+    }
+    ctl_data_per_table
+}
+
+fn partial_products<F: Field>(
+    trace: &[PolynomialValues<F>],
+    columns: &[Column<F>],
+    filter_column: &Option<Column<F>>,
+    challenge: PermutationChallenge<F>,
+) -> PolynomialValues<F> {
+    let mut partial_prod = F::ONE;
+    let degree = trace[0].len();
+    let mut res = Vec::with_capacity(degree);
+    for i in 0..degree {
+        let filter = if let Some(column) = filter_column {
+            column.eval_table(trace, i)
+        } else {
+            F::ONE
+        };
+        if filter.is_one() {
+            let evals = columns
+                .iter()
+                .map(|c| c.eval_table(trace, i))
+                .collect::<Vec<_>>();
+            partial_prod *= challenge.combine(evals.iter());
+        } else {
+            assert_eq!(filter, F::ZERO, "Non-binary filter?")
+        };
+        res.push(partial_prod);
+    }
+    res.into()
+}
+
+#[derive(Clone)]
+pub struct CtlCheckVars<'a, F, FE, P, const D2: usize>
+where
+    F: Field,
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    pub(crate) local_z: P,
+    pub(crate) next_z: P,
+    pub(crate) challenges: PermutationChallenge<F>,
+    pub(crate) columns: &'a [Column<F>],
+    pub(crate) filter_column: &'a Option<Column<F>>,
+}
+
+impl<'a, F: RichField + Extendable<D>, const D: usize>
+    CtlCheckVars<'a, F, F::Extension, F::Extension, D>
+{
+    /// Splits the cross-table lookup openings of each proof into per-table check variables.
+    pub(crate) fn from_proofs<C: GenericConfig<D, F = F>>(
+        proofs: &[StarkProofWithPublicInputs<F, C, D>],
+        cross_table_lookups: &'a [CrossTableLookup<F>],
+        ctl_challenges: &'a PermutationChallengeSet<F>,
+        num_permutation_lookup_zs: &[usize],
+    ) -> Vec<Vec<Self>> {
+        let mut ctl_zs = proofs
+            .iter()
+            .zip(num_permutation_lookup_zs)
+            .map(|(p, &num_skipped)| {
+                let openings = &p.proof.openings;
+                let ctl_zs = openings
+                    .permutation_ctl_zs
+                    .iter()
+                    .flatten()
+                    .skip(num_skipped);
+                let ctl_zs_next = openings
+                    .permutation_ctl_zs_next
+                    .iter()
+                    .flatten()
+                    .skip(num_skipped);
+                ctl_zs.zip(ctl_zs_next)
+            })
+            .collect::<Vec<_>>();
+
+        let mut ctl_vars_per_table = vec![vec![]; proofs.len()];
+        for CrossTableLookup {
+            looking_tables,
+            looked_table,
+            ..
+        } in cross_table_lookups
+        {
+            for &challenges in &ctl_challenges.challenges {
+                for table in looking_tables.iter().chain(once(looked_table)) {
+                    let (z, z_next) = ctl_zs[table.table].next().unwrap();
+                    ctl_vars_per_table[table.table].push(Self {
+                        local_z: *z,
+                        next_z: *z_next,
+                        challenges,
+                        columns: &table.columns,
+                        filter_column: &table.filter_column,
+                    });
+                }
+            }
+        }
+        ctl_vars_per_table
+    }
+}
+
+pub(crate) fn eval_cross_table_lookup_checks<F, FE, P, const D: usize, const D2: usize>(
+    vars: StarkEvaluationVars<FE, P>,
+    ctl_vars: &[CtlCheckVars<F, FE, P, D2>],
+    consumer: &mut ConstraintConsumer<P>,
+) where
+    F: RichField + Extendable<D>,
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    for lookup_vars in ctl_vars {
+        let CtlCheckVars {
+            local_z,
+            next_z,
+            challenges,
+            columns,
+            filter_column,
+        } = lookup_vars;
+        let combine = |v: &[P]| -> P {
+            let evals = columns.iter().map(|c| c.eval(v)).collect::<Vec<_>>();
+            challenges.combine(evals.iter())
+        };
+        let filter = |v: &[P]| -> P {
+            if let Some(column) = filter_column {
+                column.eval(v)
+            } else {
+                P::ONES
+            }
+        };
+        let local_filter = filter(vars.local_values);
+        let next_filter = filter(vars.next_values);
+        let select = |filter, x| filter * x + P::ONES - filter;
+
+        // Check value of `Z(1)`
+        consumer.constraint_first_row(*local_z - select(local_filter, combine(vars.local_values)));
+        // Check `Z(gw) = combination * Z(w)`
+        consumer.constraint_transition(
+            *next_z - *local_z * select(next_filter, combine(vars.next_values)),
+        );
+    }
+}
+
+#[derive(Clone)]
+pub struct CtlCheckVarsTarget<'a, F: Field, const D: usize> {
+    pub(crate) local_z: ExtensionTarget<D>,
+    pub(crate) next_z: ExtensionTarget<D>,
+    pub(crate) challenges: PermutationChallenge<Target>,
+    pub(crate) columns: &'a [Column<F>],
+    pub(crate) filter_column: &'a Option<Column<F>>,
+}
+
+impl<'a, F: Field, const D: usize> CtlCheckVarsTarget<'a, F, D> {
+    /// Splits the cross-table lookup openings of each proof into per-table check variables.
+    pub(crate) fn from_proofs(
+        proofs: &[StarkProofWithPublicInputsTarget<D>],
+        cross_table_lookups: &'a [CrossTableLookup<F>],
+        ctl_challenges: &'a PermutationChallengeSet<Target>,
+        num_permutation_lookup_zs: &[usize],
+    ) -> Vec<Vec<Self>> {
+        let mut ctl_zs = proofs
+            .iter()
+            .zip(num_permutation_lookup_zs)
+            .map(|(p, &num_skipped)| {
+                let openings = &p.proof.openings;
+                let ctl_zs = openings
+                    .permutation_ctl_zs
+                    .iter()
+                    .flatten()
+                    .skip(num_skipped);
+                let ctl_zs_next = openings
+                    .permutation_ctl_zs_next
+                    .iter()
+                    .flatten()
+                    .skip(num_skipped);
+                ctl_zs.zip(ctl_zs_next)
+            })
+            .collect::<Vec<_>>();
+
+        let mut ctl_vars_per_table = vec![vec![]; proofs.len()];
+        for CrossTableLookup {
+            looking_tables,
+            looked_table,
+            ..
+        } in cross_table_lookups
+        {
+            for &challenges in &ctl_challenges.challenges {
+                for table in looking_tables.iter().chain(once(looked_table)) {
+                    let (z, z_next) = ctl_zs[table.table].next().unwrap();
+                    ctl_vars_per_table[table.table].push(Self {
+                        local_z: *z,
+                        next_z: *z_next,
+                        challenges,
+                        columns: &table.columns,
+                        filter_column: &table.filter_column,
+                    });
+                }
+            }
+        }
+        ctl_vars_per_table
+    }
+}
+
+pub(crate) fn eval_cross_table_lookup_checks_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    vars: StarkEvaluationTargets<D>,
+    ctl_vars: &[CtlCheckVarsTarget<F, D>],
+    consumer: &mut RecursiveConstraintConsumer<F, D>,
+) {
+    fn select<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        filter: ExtensionTarget<D>,
+        x: ExtensionTarget<D>,
+    ) -> ExtensionTarget<D> {
+        let one = builder.one_extension();
+        let tmp = builder.sub_extension(one, filter);
+        builder.mul_add_extension(filter, x, tmp) // filter * x + 1 - filter
+    }
+
+    for lookup_vars in ctl_vars {
+        let CtlCheckVarsTarget {
+            local_z,
+            next_z,
+            challenges,
+            columns,
+            filter_column,
+        } = lookup_vars;
+
+        let one = builder.one_extension();
+        let local_filter = if let Some(column) = filter_column {
+            column.eval_circuit(builder, vars.local_values)
+        } else {
+            one
+        };
+        let next_filter = if let Some(column) = filter_column {
+            column.eval_circuit(builder, vars.next_values)
+        } else {
+            one
+        };
+
+        // Check value of `Z(1)`
+        let local_columns_eval = columns
+            .iter()
+            .map(|c| c.eval_circuit(builder, vars.local_values))
+            .collect::<Vec<_>>();
+        let combined_local = challenges.combine_circuit(builder, &local_columns_eval);
+        let selected_local = select(builder, local_filter, combined_local);
+        let first_row = builder.sub_extension(*local_z, selected_local);
+        consumer.constraint_first_row(builder, first_row);
+        // Check `Z(gw) = combination * Z(w)`
+        let next_columns_eval = columns
+            .iter()
+            .map(|c| c.eval_circuit(builder, vars.next_values))
+            .collect::<Vec<_>>();
+        let combined_next = challenges.combine_circuit(builder, &next_columns_eval);
+        let selected_next = select(builder, next_filter, combined_next);
+        let mut transition = builder.mul_extension(*local_z, selected_next);
+        transition = builder.sub_extension(*next_z, transition);
+        consumer.constraint_transition(builder, transition);
+    }
+}
+
+/// Checks that the final values of the looking `Z` polynomials match those of the looked ones.
+pub(crate) fn verify_cross_table_lookups<F: RichField>(
+    cross_table_lookups: &[CrossTableLookup<F>],
+    ctl_zs_lasts: &[&[F]],
+    degrees_bits: &[usize],
+    challenges: &PermutationChallengeSet<F>,
+) -> Result<()> {
+    let mut ctl_zs_openings = ctl_zs_lasts.iter().map(|v| v.iter()).collect::<Vec<_>>();
+    for CrossTableLookup {
+        looking_tables,
+        looked_table,
+        default,
+    } in cross_table_lookups
+    {
+        let looking_degrees_sum = looking_tables
+            .iter()
+            .map(|table| 1 << degrees_bits[table.table])
+            .sum::<u64>();
+        let looked_degree = 1 << degrees_bits[looked_table.table];
+        for challenge in &challenges.challenges {
+            let looking_zs_prod = looking_tables
+                .iter()
+                .map(|table| *ctl_zs_openings[table.table].next().unwrap())
+                .product::<F>();
+            let looked_z = *ctl_zs_openings[looked_table.table].next().unwrap();
+            let pad = default.as_ref().map_or(F::ONE, |default| {
+                challenge
+                    .combine(default.iter())
+                    .exp_u64(looking_degrees_sum - looked_degree)
+            });
+            ensure!(
+                looking_zs_prod == looked_z * pad,
+                "Cross-table lookup verification failed."
+            );
+        }
+    }
+    ensure!(
+        ctl_zs_openings.iter_mut().all(|iter| iter.next().is_none()),
+        "Unexpected cross-table lookup openings."
+    );
+
+    Ok(())
+}
+
+pub(crate) fn verify_cross_table_lookups_circuit<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    cross_table_lookups: &[CrossTableLookup<F>],
+    ctl_zs_lasts: &[&[Target]],
+    degrees_bits: &[usize],
+    challenges: &PermutationChallengeSet<Target>,
+) {
+    let mut ctl_zs_openings = ctl_zs_lasts.iter().map(|v| v.iter()).collect::<Vec<_>>();
+    for CrossTableLookup {
+        looking_tables,
+        looked_table,
+        default,
+    } in cross_table_lookups
+    {
+        let looking_degrees_sum = looking_tables
+            .iter()
+            .map(|table| 1 << degrees_bits[table.table])
+            .sum::<u64>();
+        let looked_degree = 1 << degrees_bits[looked_table.table];
+        for challenge in &challenges.challenges {
+            let looking_zs_prod = builder.mul_many(
+                looking_tables
+                    .iter()
+                    .map(|table| *ctl_zs_openings[table.table].next().unwrap()),
+            );
+            let looked_z = *ctl_zs_openings[looked_table.table].next().unwrap();
+            if let Some(default) = default.as_ref() {
+                let default = default
+                    .iter()
+                    .map(|&x| builder.constant(x))
+                    .collect::<Vec<_>>();
+                let combined_default = challenge.combine_base_circuit(builder, &default);
+
+                let pad = builder.exp_u64(combined_default, looking_degrees_sum - looked_degree);
+                let padded_looked_z = builder.mul(looked_z, pad);
+                builder.connect(looking_zs_prod, padded_looked_z);
+            } else {
+                builder.connect(looking_zs_prod, looked_z);
+            }
+        }
+    }
+    assert!(ctl_zs_openings.iter_mut().all(|iter| iter.next().is_none()));
+}
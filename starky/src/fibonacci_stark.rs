@@ -58,12 +58,17 @@ impl<F: RichField + Extendable<D>, const D: usize> FibonacciStark<F, D> {
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FibonacciStark<F, D> {
-    const COLUMNS: usize = 4;
-    const PUBLIC_INPUTS: usize = 3;
+    fn num_columns(&self) -> usize {
+        4
+    }
+
+    fn num_public_inputs(&self) -> usize {
+        3
+    }
 
     fn eval_packed_generic<FE, P, const D2: usize>(
         &self,
-        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        vars: StarkEvaluationVars<FE, P>,
         yield_constr: &mut ConstraintConsumer<P>,
     ) where
         FE: FieldExtension<D2, BaseField = F>,
@@ -88,7 +93,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FibonacciStar
     fn eval_ext_circuit(
         &self,
         builder: &mut CircuitBuilder<F, D>,
-        vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        vars: StarkEvaluationTargets<D>,
         yield_constr: &mut RecursiveConstraintConsumer<F, D>,
     ) {
         // Check public inputs.
@@ -121,6 +126,148 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FibonacciStar
     }
 }
 
+/// Toy STARK system computing a Fibonacci sequence in a single column, using a window of three
+/// rows. It reads either the two rows after the current one, or the previous and next rows.
+/// A preprocessed column selects the rows whose window does not wrap around the trace.
+#[derive(Copy, Clone)]
+struct FibonacciWindowStark<F: RichField + Extendable<D>, const D: usize> {
+    backwards: bool,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> FibonacciWindowStark<F, D> {
+    // The public inputs are the same as for `FibonacciStark`.
+    const PI_INDEX_X0: usize = 0;
+    const PI_INDEX_X1: usize = 1;
+    const PI_INDEX_RES: usize = 2;
+
+    fn new(backwards: bool) -> Self {
+        Self {
+            backwards,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Generate the trace `x0, x1, x0 + x1, ...`.
+    fn generate_trace(&self, num_rows: usize, x0: F, x1: F) -> Vec<PolynomialValues<F>> {
+        let values = (0..num_rows)
+            .scan((x0, x1), |acc, _| {
+                let tmp = acc.0;
+                *acc = (acc.1, acc.0 + acc.1);
+                Some(tmp)
+            })
+            .collect();
+        vec![PolynomialValues::new(values)]
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FibonacciWindowStark<F, D> {
+    fn num_columns(&self) -> usize {
+        1
+    }
+
+    fn num_public_inputs(&self) -> usize {
+        3
+    }
+
+    fn num_preprocessed_columns(&self) -> usize {
+        1
+    }
+
+    fn preprocessed_columns(&self, num_rows: usize) -> Vec<PolynomialValues<F>> {
+        let first_row = if self.backwards { 1 } else { 0 };
+        let selector = (0..num_rows)
+            .map(|i| F::from_bool(first_row <= i && i < first_row + num_rows - 2))
+            .collect();
+        vec![PolynomialValues::new(selector)]
+    }
+
+    fn extra_row_offsets(&self) -> Vec<isize> {
+        if self.backwards {
+            vec![-1]
+        } else {
+            vec![2]
+        }
+    }
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        // Check public inputs.
+        yield_constr
+            .constraint_first_row(vars.local_values[0] - vars.public_inputs[Self::PI_INDEX_X0]);
+        yield_constr
+            .constraint_first_row(vars.next_values[0] - vars.public_inputs[Self::PI_INDEX_X1]);
+        yield_constr
+            .constraint_last_row(vars.local_values[0] - vars.public_inputs[Self::PI_INDEX_RES]);
+
+        // x'' <- x + x' over the three rows of the window.
+        let [x, x_next, x_next_next] = if self.backwards {
+            [
+                vars.extra_row_values[0][0],
+                vars.local_values[0],
+                vars.next_values[0],
+            ]
+        } else {
+            [
+                vars.local_values[0],
+                vars.next_values[0],
+                vars.extra_row_values[0][0],
+            ]
+        };
+        let selector = vars.preprocessed_local_values[0];
+        yield_constr.constraint(selector * (x_next_next - x - x_next));
+    }
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: StarkEvaluationTargets<D>,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        // Check public inputs.
+        let pis_constraints = [
+            builder.sub_extension(vars.local_values[0], vars.public_inputs[Self::PI_INDEX_X0]),
+            builder.sub_extension(vars.next_values[0], vars.public_inputs[Self::PI_INDEX_X1]),
+            builder.sub_extension(vars.local_values[0], vars.public_inputs[Self::PI_INDEX_RES]),
+        ];
+        yield_constr.constraint_first_row(builder, pis_constraints[0]);
+        yield_constr.constraint_first_row(builder, pis_constraints[1]);
+        yield_constr.constraint_last_row(builder, pis_constraints[2]);
+
+        // x'' <- x + x' over the three rows of the window.
+        let [x, x_next, x_next_next] = if self.backwards {
+            [
+                vars.extra_row_values[0][0],
+                vars.local_values[0],
+                vars.next_values[0],
+            ]
+        } else {
+            [
+                vars.local_values[0],
+                vars.next_values[0],
+                vars.extra_row_values[0][0],
+            ]
+        };
+        let selector = vars.preprocessed_local_values[0];
+        let constraint = {
+            let tmp = builder.sub_extension(x_next_next, x);
+            let tmp = builder.sub_extension(tmp, x_next);
+            builder.mul_extension(selector, tmp)
+        };
+        yield_constr.constraint(builder, constraint);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        2
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
@@ -130,22 +277,24 @@ mod tests {
     use plonky2::iop::witness::PartialWitness;
     use plonky2::plonk::circuit_builder::CircuitBuilder;
     use plonky2::plonk::circuit_data::CircuitConfig;
-    use plonky2::plonk::config::{
-        AlgebraicHasher, GenericConfig, Hasher, PoseidonGoldilocksConfig,
-    };
+    use plonky2::plonk::config::{AlgebraicHasher, GenericConfig, PoseidonGoldilocksConfig};
     use plonky2::util::timing::TimingTree;
 
     use crate::config::StarkConfig;
-    use crate::fibonacci_stark::FibonacciStark;
-    use crate::proof::StarkProofWithPublicInputs;
+    use crate::fibonacci_stark::{FibonacciStark, FibonacciWindowStark};
+    use crate::preprocessed::StarkVerifyingKey;
+    use crate::proof::{CompressedStarkProofWithPublicInputs, StarkProofWithPublicInputs};
     use crate::prover::prove;
     use crate::recursive_verifier::{
         add_virtual_stark_proof_with_pis, set_stark_proof_with_pis_target,
         verify_stark_proof_circuit,
     };
     use crate::stark::Stark;
-    use crate::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
+    use crate::stark_testing::{
+        debug_constraints, test_stark_circuit_constraints, test_stark_low_degree,
+    };
     use crate::verifier::verify_stark_proof;
+    use crate::wrapper::StarkWrapper;
 
     fn fibonacci<F: Field>(n: usize, x0: F, x1: F) -> F {
         (0..n).fold((x0, x1), |x, _| (x.1, x.0 + x.1)).1
@@ -167,13 +316,188 @@ mod tests {
             stark,
             &config,
             trace,
-            public_inputs,
+            &public_inputs,
             &mut TimingTree::default(),
         )?;
 
         verify_stark_proof(stark, proof, &config)
     }
 
+    #[test]
+    fn test_fibonacci_stark_serialization() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FibonacciStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        assert_eq!(StarkConfig::from_bytes(config.to_bytes())?, config);
+
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace,
+            &public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        let degree_bits = proof.proof.degree_bits;
+
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes[0] as usize, degree_bits);
+        let read_proof = StarkProofWithPublicInputs::from_bytes(bytes.clone(), &stark, &config)?;
+        assert_eq!(read_proof, proof);
+        // A trace too long for the two-adic subgroup is rejected.
+        let mut bad_bytes = bytes;
+        bad_bytes[0] = u8::MAX;
+        assert!(
+            StarkProofWithPublicInputs::<F, C, D>::from_bytes(bad_bytes, &stark, &config).is_err()
+        );
+
+        let verifying_key = StarkVerifyingKey::new(&stark, &config, degree_bits);
+        let compressed_proof = proof.clone().compress(&stark, &verifying_key, &config)?;
+        let compressed_bytes = compressed_proof.to_bytes();
+        assert!(compressed_bytes.len() < proof.to_bytes().len());
+        let read_compressed_proof =
+            CompressedStarkProofWithPublicInputs::from_bytes(compressed_bytes, &stark, &config)?;
+        assert_eq!(read_compressed_proof, compressed_proof);
+        let decompressed_proof =
+            read_compressed_proof.decompress(&stark, &verifying_key, &config)?;
+        assert_eq!(decompressed_proof, proof);
+
+        verify_stark_proof(stark, decompressed_proof, &config)
+    }
+
+    #[test]
+    fn test_fibonacci_stark_zero_knowledge() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FibonacciStark<F, D>;
+
+        let config = StarkConfig::standard_fast_zk_config();
+        assert_eq!(StarkConfig::from_bytes(config.to_bytes())?, config);
+
+        // The trace is shorter than the number of FRI queries, so the randomization needs more
+        // coefficients than the trace has rows.
+        let num_rows = 1 << 4;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        let prove_once = || {
+            prove::<F, C, S, D>(
+                stark,
+                &config,
+                trace.clone(),
+                &public_inputs,
+                &mut TimingTree::default(),
+            )
+        };
+        let proof = prove_once()?;
+        // The trace is randomized, so proving it again commits to different polynomials.
+        let other_proof = prove_once()?;
+        assert_ne!(proof.proof.trace_cap, other_proof.proof.trace_cap);
+        assert_ne!(
+            proof.proof.openings.local_values,
+            other_proof.proof.openings.local_values
+        );
+        // So are the quotient chunks, which are randomized on top of that.
+        assert_ne!(
+            proof.proof.quotient_polys_cap,
+            other_proof.proof.quotient_polys_cap
+        );
+        assert_ne!(
+            proof.proof.openings.quotient_polys,
+            other_proof.proof.openings.quotient_polys
+        );
+        verify_stark_proof(stark, other_proof, &config)?;
+
+        let degree_bits = proof.proof.degree_bits;
+        assert_eq!(degree_bits, 4);
+        let read_proof = StarkProofWithPublicInputs::from_bytes(proof.to_bytes(), &stark, &config)?;
+        assert_eq!(read_proof, proof);
+        let verifying_key = StarkVerifyingKey::new(&stark, &config, degree_bits);
+        let decompressed_proof = proof
+            .clone()
+            .compress(&stark, &verifying_key, &config)?
+            .decompress(&stark, &verifying_key, &config)?;
+        assert_eq!(decompressed_proof, proof);
+
+        verify_stark_proof(stark, proof.clone(), &config)?;
+        recursive_proof::<F, C, S, C, D>(stark, proof, &config, false)
+    }
+
+    #[test]
+    fn test_stark_wrapper() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FibonacciStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let mut wrapper = StarkWrapper::<F, C, S, D>::new(
+            S::new(1 << 5),
+            config.clone(),
+            CircuitConfig::standard_recursion_config(),
+            5..=6,
+        );
+        for num_rows in [1 << 5, 1 << 6] {
+            let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+            let stark = S::new(num_rows);
+            let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+            let proof = prove::<F, C, S, D>(
+                stark,
+                &config,
+                trace,
+                &public_inputs,
+                &mut TimingTree::default(),
+            )?;
+            let degree_bits = proof.proof.degree_bits;
+
+            let wrapper_proof = wrapper.wrap(&proof)?;
+            assert_eq!(wrapper_proof.public_inputs, public_inputs);
+            wrapper
+                .wrapper_circuit(degree_bits)?
+                .data()
+                .verify(wrapper_proof)?;
+
+            let range_proof = wrapper.wrap_in_range(&proof)?;
+            assert_eq!(range_proof.public_inputs, public_inputs);
+            wrapper.range_circuit()?.data.verify(range_proof)?;
+        }
+        assert!(wrapper.wrapper_circuit(7).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_fibonacci_stark_debug_constraints() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FibonacciStark<F, D>;
+
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 1, F::ZERO, F::ONE)];
+        let stark = S::new(num_rows);
+        let trace = stark.generate_trace(public_inputs[0], public_inputs[1]);
+        debug_constraints(&stark, &trace, &public_inputs)?;
+
+        // Row 4 is the first whose transition to row 5 breaks the second column's recurrence.
+        let mut bad_trace = trace.clone();
+        bad_trace[1].values[5] += F::ONE;
+        let err = debug_constraints(&stark, &bad_trace, &public_inputs).unwrap_err();
+        assert!(err.to_string().starts_with("Constraint 4 fails on row 4"));
+
+        let mut bad_trace = trace;
+        bad_trace[2].values[3] = F::from_canonical_u64(1000);
+        let err = debug_constraints(&stark, &bad_trace, &public_inputs).unwrap_err();
+        assert!(err.to_string().starts_with("Permutation pair 0 fails"));
+        Ok(())
+    }
+
     #[test]
     fn test_fibonacci_stark_degree() -> Result<()> {
         const D: usize = 2;
@@ -215,7 +539,7 @@ mod tests {
             stark,
             &config,
             trace,
-            public_inputs,
+            &public_inputs,
             &mut TimingTree::default(),
         )?;
         verify_stark_proof(stark, proof.clone(), &config)?;
@@ -223,6 +547,68 @@ mod tests {
         recursive_proof::<F, C, S, C, D>(stark, proof, &config, true)
     }
 
+    #[test]
+    fn test_fibonacci_window_stark() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FibonacciWindowStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 2, F::ZERO, F::ONE)];
+        for backwards in [false, true] {
+            let stark = S::new(backwards);
+            let trace = stark.generate_trace(num_rows, public_inputs[0], public_inputs[1]);
+            let proof = prove::<F, C, S, D>(
+                stark,
+                &config,
+                trace,
+                &public_inputs,
+                &mut TimingTree::default(),
+            )?;
+            verify_stark_proof(stark, proof, &config)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_fibonacci_window_stark_degree() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FibonacciWindowStark<F, D>;
+
+        for backwards in [false, true] {
+            test_stark_low_degree(S::new(backwards))?;
+            test_stark_circuit_constraints::<F, C, S, D>(S::new(backwards))?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_recursive_fibonacci_window_stark_verifier() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FibonacciWindowStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let num_rows = 1 << 5;
+        let public_inputs = [F::ZERO, F::ONE, fibonacci(num_rows - 2, F::ZERO, F::ONE)];
+        let stark = S::new(false);
+        let trace = stark.generate_trace(num_rows, public_inputs[0], public_inputs[1]);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace,
+            &public_inputs,
+            &mut TimingTree::default(),
+        )?;
+
+        recursive_proof::<F, C, S, C, D>(stark, proof, &config, false)
+    }
+
     fn recursive_proof<
         F: RichField + Extendable<D>,
         C: GenericConfig<D, F = F>,
@@ -237,14 +623,11 @@ mod tests {
     ) -> Result<()>
     where
         InnerC::Hasher: AlgebraicHasher<F>,
-        [(); S::COLUMNS]:,
-        [(); S::PUBLIC_INPUTS]:,
-        [(); C::Hasher::HASH_SIZE]:,
     {
         let circuit_config = CircuitConfig::standard_recursion_config();
         let mut builder = CircuitBuilder::<F, D>::new(circuit_config);
         let mut pw = PartialWitness::new();
-        let degree_bits = inner_proof.proof.recover_degree_bits(inner_config);
+        let degree_bits = inner_proof.proof.degree_bits;
         let pt = add_virtual_stark_proof_with_pis(&mut builder, stark, inner_config, degree_bits);
         set_stark_proof_with_pis_target(&mut pw, &pt, &inner_proof);
 
@@ -1,7 +1,5 @@
-#![allow(incomplete_features)]
 #![allow(clippy::too_many_arguments)]
 #![allow(clippy::type_complexity)]
-#![feature(generic_const_exprs)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
 extern crate alloc;
@@ -10,16 +8,23 @@ mod get_challenges;
 
 pub mod config;
 pub mod constraint_consumer;
+pub mod cross_table_lookup;
+pub mod lookup;
+pub mod multi_stark;
 pub mod permutation;
+pub mod preprocessed;
 pub mod proof;
 pub mod prover;
 pub mod recursive_verifier;
+pub mod serialization;
 pub mod stark;
 pub mod stark_testing;
+pub mod symbolic;
 pub mod util;
 pub mod vanishing_poly;
 pub mod vars;
 pub mod verifier;
+pub mod wrapper;
 
 #[cfg(test)]
 pub mod fibonacci_stark;
@@ -0,0 +1,530 @@
+//! The logUp lookup argument, which checks that every value of some looking columns appears in a
+//! table column of the same trace.
+//!
+//! Given a challenge `alpha`, the values `f_i` of the looking columns are contained in the table
+//! `t`, with multiplicities `m`, if and only if
+//!     `sum_rows sum_i 1 / (alpha - f_i) = sum_rows m / (alpha - t)`.
+//! The looking columns are split into batches, and each batch gets a helper column `h` holding
+//! `sum_i 1 / (alpha - f_i)` over the batch, which is checked with a constraint of degree one more
+//! than the batch size. A final running-sum column `Z` accumulates `sum h - m / (alpha - t)` and
+//! must vanish on the last row.
+//!
+//! Reference: <https://eprint.iacr.org/2022/1530>
+
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use itertools::Itertools;
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::field::types::{Field, PrimeField64};
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::iop::target::Target;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::util::ceil_div_usize;
+
+use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::cross_table_lookup::Column;
+use crate::stark::Stark;
+use crate::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+
+/// A lookup of the values of `columns` into `table_column`, where `frequencies_column` holds, for
+/// each row, the number of times its table value is looked up.
+#[derive(Clone, Debug)]
+pub struct Lookup<F: Field> {
+    /// Columns whose values should be contained in the table.
+    pub columns: Vec<Column<F>>,
+    /// The column holding the table.
+    pub table_column: Column<F>,
+    /// The column holding the multiplicity of each table value among the looking values.
+    pub frequencies_column: Column<F>,
+}
+
+impl<F: Field> Lookup<F> {
+    pub fn new(
+        columns: Vec<Column<F>>,
+        table_column: Column<F>,
+        frequencies_column: Column<F>,
+    ) -> Self {
+        assert!(!columns.is_empty(), "A lookup needs at least one column.");
+        Self {
+            columns,
+            table_column,
+            frequencies_column,
+        }
+    }
+
+    /// The number of helper columns of this lookup, for a single challenge: one per batch of
+    /// looking columns, plus the running sum `Z`.
+    pub fn num_helper_columns(&self, batch_size: usize) -> usize {
+        ceil_div_usize(self.columns.len(), batch_size) + 1
+    }
+}
+
+/// Computes the multiplicities of `table` values among `values`, to be used as the frequencies
+/// column of a lookup. Panics if some value is not in the table.
+pub fn lookup_frequencies<F: PrimeField64>(values: &[F], table: &[F]) -> Vec<F> {
+    // The first row holding each table value, which is where its multiplicity is recorded.
+    let mut rows = BTreeMap::new();
+    for (row, t) in table.iter().enumerate() {
+        rows.entry(t.to_canonical_u64()).or_insert(row);
+    }
+    let mut frequencies = vec![F::ZERO; table.len()];
+    for value in values {
+        let row = *rows
+            .get(&value.to_canonical_u64())
+            .unwrap_or_else(|| panic!("Value {:?} is not in the table", value));
+        frequencies[row] += F::ONE;
+    }
+    frequencies
+}
+
+/// Computes all lookup helper columns of `stark`, for each lookup and then for each challenge.
+pub(crate) fn compute_lookup_helper_polys<F, S, const D: usize>(
+    stark: &S,
+    trace_poly_values: &[PolynomialValues<F>],
+    lookup_challenges: &[F],
+) -> Vec<PolynomialValues<F>>
+where
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+{
+    let batch_size = stark.lookup_batch_size();
+    stark
+        .lookups()
+        .iter()
+        .flat_map(|lookup| {
+            lookup_challenges.iter().flat_map(move |&challenge| {
+                lookup_helper_columns(lookup, trace_poly_values, challenge, batch_size)
+            })
+        })
+        .collect()
+}
+
+/// Computes the helper columns of a lookup for a single challenge: one column per batch of looking
+/// columns, followed by the running sum `Z`.
+pub(crate) fn lookup_helper_columns<F: Field>(
+    lookup: &Lookup<F>,
+    trace_poly_values: &[PolynomialValues<F>],
+    challenge: F,
+    batch_size: usize,
+) -> Vec<PolynomialValues<F>> {
+    let degree = trace_poly_values[0].len();
+    // Computes `1 / (challenge - column)` on every row.
+    let inverses = |column: &Column<F>| {
+        let denominators = (0..degree)
+            .map(|row| challenge - column.eval_table(trace_poly_values, row))
+            .collect_vec();
+        F::batch_multiplicative_inverse(&denominators)
+    };
+
+    let mut helper_columns = lookup
+        .columns
+        .chunks(batch_size)
+        .map(|batch| {
+            let mut sums = vec![F::ZERO; degree];
+            for column in batch {
+                for (sum, inverse) in sums.iter_mut().zip(inverses(column)) {
+                    *sum += inverse;
+                }
+            }
+            PolynomialValues::new(sums)
+        })
+        .collect_vec();
+
+    let table_inverses = inverses(&lookup.table_column);
+    let z = (0..degree)
+        .scan(F::ZERO, |acc, row| {
+            let helpers_sum = helper_columns.iter().map(|h| h.values[row]).sum::<F>();
+            let frequency = lookup.frequencies_column.eval_table(trace_poly_values, row);
+            *acc += helpers_sum - frequency * table_inverses[row];
+            Some(*acc)
+        })
+        .collect();
+    helper_columns.push(PolynomialValues::new(z));
+
+    helper_columns
+}
+
+pub struct LookupCheckVars<F, FE, P, const D2: usize>
+where
+    F: Field,
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    pub(crate) local_values: Vec<P>,
+    pub(crate) next_values: Vec<P>,
+    pub(crate) challenges: Vec<F>,
+}
+
+/// Evaluates the constraints of all lookups of `stark`.
+pub(crate) fn eval_packed_lookups_generic<F, FE, P, S, const D: usize, const D2: usize>(
+    stark: &S,
+    vars: StarkEvaluationVars<FE, P>,
+    lookup_vars: LookupCheckVars<F, FE, P, D2>,
+    yield_constr: &mut ConstraintConsumer<P>,
+) where
+    F: RichField + Extendable<D>,
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+    S: Stark<F, D>,
+{
+    let batch_size = stark.lookup_batch_size();
+    let mut start = 0;
+    for lookup in stark.lookups() {
+        let num_helper_columns = lookup.num_helper_columns(batch_size);
+        for &challenge in &lookup_vars.challenges {
+            let challenge = FE::from_basefield(challenge);
+            let helpers = &lookup_vars.local_values[start..start + num_helper_columns - 1];
+            let next_helpers = &lookup_vars.next_values[start..start + num_helper_columns - 1];
+            let z = lookup_vars.local_values[start + num_helper_columns - 1];
+            let next_z = lookup_vars.next_values[start + num_helper_columns - 1];
+            start += num_helper_columns;
+
+            // Each helper `h` of a batch `f_1, ..., f_k` satisfies
+            //     h * prod_i (alpha - f_i) = sum_i prod_{j != i} (alpha - f_j).
+            for (&h, batch) in helpers.iter().zip(lookup.columns.chunks(batch_size)) {
+                let denominators = batch
+                    .iter()
+                    .map(|column| P::from(challenge) - column.eval(vars.local_values))
+                    .collect_vec();
+                let numerator = (0..denominators.len())
+                    .map(|i| {
+                        denominators
+                            .iter()
+                            .enumerate()
+                            .filter(|&(j, _)| j != i)
+                            .map(|(_, &d)| d)
+                            .product::<P>()
+                    })
+                    .sum::<P>();
+                yield_constr.constraint(h * denominators.into_iter().product::<P>() - numerator);
+            }
+
+            // `Z` accumulates `sum h - m / (alpha - t)`, starting on the first row, and the total
+            // must be zero.
+            let table = lookup.table_column.eval(vars.local_values);
+            let next_table = lookup.table_column.eval(vars.next_values);
+            let frequency = lookup.frequencies_column.eval(vars.local_values);
+            let next_frequency = lookup.frequencies_column.eval(vars.next_values);
+            let helpers_sum = helpers.iter().copied().sum::<P>();
+            let next_helpers_sum = next_helpers.iter().copied().sum::<P>();
+            yield_constr.constraint_first_row((z - helpers_sum) * (-table + challenge) + frequency);
+            yield_constr.constraint_transition(
+                (next_z - z - next_helpers_sum) * (-next_table + challenge) + next_frequency,
+            );
+            yield_constr.constraint_last_row(z);
+        }
+    }
+}
+
+pub struct LookupCheckVarsTarget<const D: usize> {
+    pub(crate) local_values: Vec<ExtensionTarget<D>>,
+    pub(crate) next_values: Vec<ExtensionTarget<D>>,
+    pub(crate) challenges: Vec<Target>,
+}
+
+/// Circuit version of `eval_packed_lookups_generic`.
+pub(crate) fn eval_ext_lookups_circuit<F, S, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    stark: &S,
+    vars: StarkEvaluationTargets<D>,
+    lookup_vars: LookupCheckVarsTarget<D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+) where
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+{
+    let batch_size = stark.lookup_batch_size();
+    let mut start = 0;
+    for lookup in stark.lookups() {
+        let num_helper_columns = lookup.num_helper_columns(batch_size);
+        for &challenge in &lookup_vars.challenges {
+            let challenge = builder.convert_to_ext(challenge);
+            let helpers = &lookup_vars.local_values[start..start + num_helper_columns - 1];
+            let next_helpers = &lookup_vars.next_values[start..start + num_helper_columns - 1];
+            let z = lookup_vars.local_values[start + num_helper_columns - 1];
+            let next_z = lookup_vars.next_values[start + num_helper_columns - 1];
+            start += num_helper_columns;
+
+            for (&h, batch) in helpers.iter().zip(lookup.columns.chunks(batch_size)) {
+                let denominators = batch
+                    .iter()
+                    .map(|column| {
+                        let value = column.eval_circuit(builder, vars.local_values);
+                        builder.sub_extension(challenge, value)
+                    })
+                    .collect_vec();
+                let terms = (0..denominators.len())
+                    .map(|i| {
+                        let others = denominators
+                            .iter()
+                            .enumerate()
+                            .filter(|&(j, _)| j != i)
+                            .map(|(_, &d)| d)
+                            .collect_vec();
+                        builder.mul_many_extension(others)
+                    })
+                    .collect_vec();
+                let numerator = builder.add_many_extension(terms);
+                let product = builder.mul_many_extension(denominators);
+                let constraint = builder.mul_sub_extension(h, product, numerator);
+                yield_constr.constraint(builder, constraint);
+            }
+
+            let table = lookup.table_column.eval_circuit(builder, vars.local_values);
+            let next_table = lookup.table_column.eval_circuit(builder, vars.next_values);
+            let frequency = lookup
+                .frequencies_column
+                .eval_circuit(builder, vars.local_values);
+            let next_frequency = lookup
+                .frequencies_column
+                .eval_circuit(builder, vars.next_values);
+            let helpers_sum = builder.add_many_extension(helpers.iter().copied());
+            let next_helpers_sum = builder.add_many_extension(next_helpers.iter().copied());
+
+            let table_denominator = builder.sub_extension(challenge, table);
+            let z_diff = builder.sub_extension(z, helpers_sum);
+            let first_row_constraint =
+                builder.mul_add_extension(z_diff, table_denominator, frequency);
+            yield_constr.constraint_first_row(builder, first_row_constraint);
+
+            let next_table_denominator = builder.sub_extension(challenge, next_table);
+            let next_z_diff = {
+                let tmp = builder.sub_extension(next_z, z);
+                builder.sub_extension(tmp, next_helpers_sum)
+            };
+            let transition_constraint =
+                builder.mul_add_extension(next_z_diff, next_table_denominator, next_frequency);
+            yield_constr.constraint_transition(builder, transition_constraint);
+
+            yield_constr.constraint_last_row(builder, z);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::marker::PhantomData;
+
+    use anyhow::Result;
+    use plonky2::field::extension::{Extendable, FieldExtension};
+    use plonky2::field::packed::PackedField;
+    use plonky2::field::polynomial::PolynomialValues;
+    use plonky2::field::types::{Field, Sample};
+    use plonky2::hash::hash_types::RichField;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_builder::CircuitBuilder;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+
+    use crate::config::StarkConfig;
+    use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+    use crate::cross_table_lookup::Column;
+    use crate::lookup::{lookup_frequencies, lookup_helper_columns, Lookup};
+    use crate::prover::prove;
+    use crate::recursive_verifier::{
+        add_virtual_stark_proof_with_pis, set_stark_proof_with_pis_target,
+        verify_stark_proof_circuit,
+    };
+    use crate::stark::Stark;
+    use crate::stark_testing::{
+        debug_constraints, test_stark_circuit_constraints, test_stark_low_degree,
+    };
+    use crate::util::trace_rows_to_poly_values;
+    use crate::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+    use crate::verifier::verify_stark_proof;
+
+    /// Range-checks three columns of values against a table column counting from zero, so that
+    /// all values lie in `[0, num_rows)`.
+    #[derive(Copy, Clone)]
+    struct RangeCheckStark<F: RichField + Extendable<D>, const D: usize> {
+        _phantom: PhantomData<F>,
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> RangeCheckStark<F, D> {
+        const NUM_VALUES: usize = 3;
+        const TABLE: usize = Self::NUM_VALUES;
+        const FREQUENCIES: usize = Self::NUM_VALUES + 1;
+
+        fn new() -> Self {
+            Self {
+                _phantom: PhantomData,
+            }
+        }
+
+        /// Generates a trace with one row per row of values.
+        fn generate_trace(&self, values: &[[F; 3]]) -> Vec<PolynomialValues<F>> {
+            let table = (0..values.len())
+                .map(F::from_canonical_usize)
+                .collect::<Vec<_>>();
+            let flat_values = values.iter().flatten().copied().collect::<Vec<_>>();
+            let frequencies = lookup_frequencies(&flat_values, &table);
+            let rows = values
+                .iter()
+                .zip(table.iter().zip(frequencies))
+                .map(|(&[a, b, c], (&t, m))| [a, b, c, t, m])
+                .collect::<Vec<_>>();
+            trace_rows_to_poly_values(rows)
+        }
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for RangeCheckStark<F, D> {
+        fn num_columns(&self) -> usize {
+            Self::NUM_VALUES + 2
+        }
+
+        fn num_public_inputs(&self) -> usize {
+            0
+        }
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            vars: StarkEvaluationVars<FE, P>,
+            yield_constr: &mut ConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            let table = vars.local_values[Self::TABLE];
+            let next_table = vars.next_values[Self::TABLE];
+            yield_constr.constraint_first_row(table);
+            yield_constr.constraint_transition(next_table - table - FE::ONE);
+        }
+
+        fn eval_ext_circuit(
+            &self,
+            builder: &mut CircuitBuilder<F, D>,
+            vars: StarkEvaluationTargets<D>,
+            yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+        ) {
+            let table = vars.local_values[Self::TABLE];
+            let next_table = vars.next_values[Self::TABLE];
+            yield_constr.constraint_first_row(builder, table);
+            let one = builder.one_extension();
+            let diff = builder.sub_extension(next_table, table);
+            let constraint = builder.sub_extension(diff, one);
+            yield_constr.constraint_transition(builder, constraint);
+        }
+
+        fn constraint_degree(&self) -> usize {
+            3
+        }
+
+        fn lookups(&self) -> Vec<Lookup<F>> {
+            vec![Lookup::new(
+                Column::singles(0..Self::NUM_VALUES).collect(),
+                Column::single(Self::TABLE),
+                Column::single(Self::FREQUENCIES),
+            )]
+        }
+    }
+
+    /// Some values in `[0, num_rows)`, with repetitions and unused table entries.
+    fn values<F: Field>(num_rows: usize) -> Vec<[F; 3]> {
+        (0..num_rows)
+            .map(|i| [i, i * i % num_rows, (7 * i + 3) % num_rows].map(F::from_canonical_usize))
+            .collect()
+    }
+
+    #[test]
+    fn test_lookup_stark() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = RangeCheckStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new();
+        let trace = stark.generate_trace(&values(1 << 5));
+        let proof = prove::<F, C, S, D>(stark, &config, trace, &[], &mut TimingTree::default())?;
+
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    fn test_lookup_stark_debug_constraints() -> Result<()> {
+        const D: usize = 2;
+        type F = <PoseidonGoldilocksConfig as GenericConfig<D>>::F;
+        type S = RangeCheckStark<F, D>;
+
+        let stark = S::new();
+        let mut trace = stark.generate_trace(&values(1 << 5));
+        debug_constraints(&stark, &trace, &[])?;
+
+        trace[1].values[7] = F::from_canonical_usize(1 << 5);
+        let err = debug_constraints(&stark, &trace, &[]).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Lookup 0 fails: the value 32 of its column 1 on row 7"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lookup_stark_degree() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = RangeCheckStark<F, D>;
+
+        test_stark_low_degree(S::new())?;
+        test_stark_circuit_constraints::<F, C, S, D>(S::new())
+    }
+
+    #[test]
+    fn test_lookup_helper_columns() {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+        type S = RangeCheckStark<F, 2>;
+
+        let num_rows = 1 << 4;
+        let stark = S::new();
+        let lookup = &stark.lookups()[0];
+        let challenge = F::rand();
+
+        let trace = stark.generate_trace(&values(num_rows));
+        let helpers = lookup_helper_columns(lookup, &trace, challenge, stark.lookup_batch_size());
+        assert_eq!(
+            helpers.len(),
+            lookup.num_helper_columns(stark.lookup_batch_size())
+        );
+        assert_eq!(helpers.last().unwrap().values[num_rows - 1], F::ZERO);
+
+        // A value outside of the table leaves a non-zero total.
+        let mut bad_trace = trace;
+        bad_trace[0].values[0] = F::from_canonical_usize(num_rows);
+        let helpers =
+            lookup_helper_columns(lookup, &bad_trace, challenge, stark.lookup_batch_size());
+        assert_ne!(helpers.last().unwrap().values[num_rows - 1], F::ZERO);
+    }
+
+    #[test]
+    fn test_recursive_lookup_stark_verifier() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = RangeCheckStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new();
+        let trace = stark.generate_trace(&values(1 << 5));
+        let proof = prove::<F, C, S, D>(stark, &config, trace, &[], &mut TimingTree::default())?;
+        verify_stark_proof(stark, proof.clone(), &config)?;
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let mut pw = PartialWitness::new();
+        let degree_bits = proof.proof.degree_bits;
+        let pt = add_virtual_stark_proof_with_pis(&mut builder, stark, &config, degree_bits);
+        set_stark_proof_with_pis_target(&mut pw, &pt, &proof);
+        verify_stark_proof_circuit::<F, C, S, D>(&mut builder, stark, pt, &config);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+}
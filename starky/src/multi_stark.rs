@@ -0,0 +1,500 @@
+//! Support for proving several heterogeneous STARKs together, with their traces linked by
+//! cross-table lookups.
+//!
+//! A `Stark` has generic methods, so different STARKs cannot be stored in one collection of trait
+//! objects. `DynStark` is an object-safe view of a `Stark`, implemented for every `Stark`, through
+//! which `prove_multi_stark`, `verify_multi_stark_proof` and `verify_multi_stark_proof_circuit`
+//! handle each table.
+
+use anyhow::{ensure, Result};
+use plonky2::field::extension::Extendable;
+use plonky2::field::polynomial::PolynomialValues;
+use plonky2::fri::oracle::PolynomialBatch;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::challenger::{Challenger, RecursiveChallenger};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
+use plonky2::util::timing::TimingTree;
+
+use crate::config::StarkConfig;
+use crate::cross_table_lookup::{CtlCheckVars, CtlCheckVarsTarget, CtlData};
+use crate::proof::{StarkProof, StarkProofWithPublicInputs, StarkProofWithPublicInputsTarget};
+use crate::prover::prove_with_commitment;
+use crate::recursive_verifier::{
+    add_virtual_table_proof_with_pis, verify_stark_proof_with_challenges_circuit,
+};
+use crate::stark::Stark;
+use crate::verifier::verify_stark_proof_with_challenges;
+
+/// An object-safe view of a `Stark`, used as one table of a multi-STARK.
+pub trait DynStark<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+    /// The number of permutation `Z` polynomials and lookup helper columns of this STARK, which
+    /// precede its cross-table lookup `Z` polynomials.
+    fn num_permutation_lookup_zs(&self, config: &StarkConfig) -> usize;
+
+    /// The number of points outside of the trace domain at which the trace is opened.
+    fn num_opening_points(&self) -> usize;
+
+    /// Proves this STARK given a trace which has already been committed to and observed by
+    /// `challenger`.
+    fn prove_with_commitment(
+        &self,
+        config: &StarkConfig,
+        trace_poly_values: &[PolynomialValues<F>],
+        trace_commitment: &PolynomialBatch<F, C, D>,
+        public_inputs: &[F],
+        ctl_data: &CtlData<F>,
+        challenger: &mut Challenger<F, C::Hasher>,
+        timing: &mut TimingTree,
+    ) -> Result<StarkProof<F, C, D>>;
+
+    /// Verifies a proof of this STARK, drawing its challenges from `challenger`, which must have
+    /// already observed the proof's trace cap.
+    fn verify_with_challenger(
+        &self,
+        config: &StarkConfig,
+        proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
+        ctl_vars: &[CtlCheckVars<F, F::Extension, F::Extension, D>],
+        challenger: &mut Challenger<F, C::Hasher>,
+    ) -> Result<()>;
+
+    /// Recursively verifies a proof of this STARK, drawing its challenges from `challenger`, which
+    /// must have already observed the proof's trace cap.
+    fn verify_circuit_with_challenger(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        inner_config: &StarkConfig,
+        proof_with_pis: &StarkProofWithPublicInputsTarget<D>,
+        ctl_vars: &[CtlCheckVarsTarget<F, D>],
+        challenger: &mut RecursiveChallenger<F, C::Hasher, D>,
+    ) where
+        C::Hasher: AlgebraicHasher<F>;
+
+    /// Adds the targets of a proof of this STARK with `num_ctl_zs` cross-table lookup `Z`
+    /// polynomials.
+    fn add_virtual_proof_with_pis(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        config: &StarkConfig,
+        degree_bits: usize,
+        num_ctl_zs: usize,
+    ) -> StarkProofWithPublicInputsTarget<D>;
+}
+
+impl<F, C, S, const D: usize> DynStark<F, C, D> for S
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+{
+    fn num_permutation_lookup_zs(&self, config: &StarkConfig) -> usize {
+        Stark::num_permutation_lookup_zs(self, config)
+    }
+
+    fn num_opening_points(&self) -> usize {
+        Stark::num_opening_points(self)
+    }
+
+    fn prove_with_commitment(
+        &self,
+        config: &StarkConfig,
+        trace_poly_values: &[PolynomialValues<F>],
+        trace_commitment: &PolynomialBatch<F, C, D>,
+        public_inputs: &[F],
+        ctl_data: &CtlData<F>,
+        challenger: &mut Challenger<F, C::Hasher>,
+        timing: &mut TimingTree,
+    ) -> Result<StarkProof<F, C, D>> {
+        ensure!(
+            !self.uses_preprocessed_columns(),
+            "STARKs with preprocessed columns are not supported in multi-STARK proofs yet"
+        );
+        prove_with_commitment::<F, C, S, D>(
+            self,
+            config,
+            None,
+            trace_poly_values,
+            trace_commitment,
+            public_inputs,
+            ctl_data,
+            challenger,
+            timing,
+        )
+    }
+
+    fn verify_with_challenger(
+        &self,
+        config: &StarkConfig,
+        proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
+        ctl_vars: &[CtlCheckVars<F, F::Extension, F::Extension, D>],
+        challenger: &mut Challenger<F, C::Hasher>,
+    ) -> Result<()> {
+        ensure!(
+            !self.uses_preprocessed_columns(),
+            "STARKs with preprocessed columns are not supported in multi-STARK proofs yet"
+        );
+        let degree_bits = proof_with_pis.proof.degree_bits;
+        let challenges = proof_with_pis
+            .proof
+            .get_challenges(self, challenger, config, degree_bits);
+        verify_stark_proof_with_challenges(
+            self,
+            proof_with_pis,
+            None,
+            challenges,
+            ctl_vars,
+            degree_bits,
+            config,
+        )
+    }
+
+    fn verify_circuit_with_challenger(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        inner_config: &StarkConfig,
+        proof_with_pis: &StarkProofWithPublicInputsTarget<D>,
+        ctl_vars: &[CtlCheckVarsTarget<F, D>],
+        challenger: &mut RecursiveChallenger<F, C::Hasher, D>,
+    ) where
+        C::Hasher: AlgebraicHasher<F>,
+    {
+        assert!(
+            !self.uses_preprocessed_columns(),
+            "STARKs with preprocessed columns are not supported in multi-STARK proofs yet"
+        );
+        assert_eq!(proof_with_pis.public_inputs.len(), self.num_public_inputs());
+        let degree_bits = proof_with_pis.proof.degree_bits;
+        let challenges =
+            proof_with_pis
+                .proof
+                .get_challenges::<F, C, S>(builder, self, challenger, inner_config);
+        verify_stark_proof_with_challenges_circuit::<F, C, S, D>(
+            builder,
+            self,
+            proof_with_pis,
+            None,
+            challenges,
+            ctl_vars,
+            inner_config,
+            degree_bits,
+        );
+    }
+
+    fn add_virtual_proof_with_pis(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        config: &StarkConfig,
+        degree_bits: usize,
+        num_ctl_zs: usize,
+    ) -> StarkProofWithPublicInputsTarget<D> {
+        add_virtual_table_proof_with_pis::<F, S, D>(builder, self, config, degree_bits, num_ctl_zs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::marker::PhantomData;
+
+    use anyhow::Result;
+    use plonky2::field::extension::{Extendable, FieldExtension};
+    use plonky2::field::packed::PackedField;
+    use plonky2::field::polynomial::PolynomialValues;
+    use plonky2::field::types::Field;
+    use plonky2::hash::hash_types::RichField;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_builder::CircuitBuilder;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+
+    use crate::config::StarkConfig;
+    use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+    use crate::cross_table_lookup::{
+        debug_cross_table_lookups, Column, CrossTableLookup, TableWithColumns,
+    };
+    use crate::multi_stark::DynStark;
+    use crate::prover::prove_multi_stark;
+    use crate::recursive_verifier::{
+        add_virtual_multi_stark_proof, set_multi_stark_proof_target,
+        verify_multi_stark_proof_circuit,
+    };
+    use crate::stark::Stark;
+    use crate::util::trace_rows_to_poly_values;
+    use crate::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+    use crate::verifier::verify_multi_stark_proof;
+
+    /// Computes a Fibonacci sequence with state `[x0, x1]`, starting from the public inputs.
+    #[derive(Copy, Clone)]
+    struct FibonacciStark<F, const D: usize> {
+        _phantom: PhantomData<F>,
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FibonacciStark<F, D> {
+        fn num_columns(&self) -> usize {
+            2
+        }
+
+        fn num_public_inputs(&self) -> usize {
+            2
+        }
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            vars: StarkEvaluationVars<FE, P>,
+            yield_constr: &mut ConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            yield_constr.constraint_first_row(vars.local_values[0] - vars.public_inputs[0]);
+            yield_constr.constraint_first_row(vars.local_values[1] - vars.public_inputs[1]);
+            yield_constr.constraint_transition(vars.next_values[0] - vars.local_values[1]);
+            yield_constr.constraint_transition(
+                vars.next_values[1] - vars.local_values[0] - vars.local_values[1],
+            );
+        }
+
+        fn eval_ext_circuit(
+            &self,
+            builder: &mut CircuitBuilder<F, D>,
+            vars: StarkEvaluationTargets<D>,
+            yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+        ) {
+            let x0 = builder.sub_extension(vars.local_values[0], vars.public_inputs[0]);
+            yield_constr.constraint_first_row(builder, x0);
+            let x1 = builder.sub_extension(vars.local_values[1], vars.public_inputs[1]);
+            yield_constr.constraint_first_row(builder, x1);
+            let next_x0 = builder.sub_extension(vars.next_values[0], vars.local_values[1]);
+            yield_constr.constraint_transition(builder, next_x0);
+            let next_x1 = {
+                let tmp = builder.sub_extension(vars.next_values[1], vars.local_values[0]);
+                builder.sub_extension(tmp, vars.local_values[1])
+            };
+            yield_constr.constraint_transition(builder, next_x1);
+        }
+
+        fn constraint_degree(&self) -> usize {
+            2
+        }
+    }
+
+    /// A table of `[value, square, filter]` rows, where `square = value^2` and `filter` is a
+    /// boolean selecting the rows which can be looked up.
+    #[derive(Copy, Clone)]
+    struct SquaresStark<F, const D: usize> {
+        _phantom: PhantomData<F>,
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for SquaresStark<F, D> {
+        fn num_columns(&self) -> usize {
+            3
+        }
+
+        fn num_public_inputs(&self) -> usize {
+            0
+        }
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            vars: StarkEvaluationVars<FE, P>,
+            yield_constr: &mut ConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            let value = vars.local_values[0];
+            let square = vars.local_values[1];
+            let filter = vars.local_values[2];
+            yield_constr.constraint(square - value * value);
+            yield_constr.constraint(filter * (filter - P::ONES));
+        }
+
+        fn eval_ext_circuit(
+            &self,
+            builder: &mut CircuitBuilder<F, D>,
+            vars: StarkEvaluationTargets<D>,
+            yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+        ) {
+            let value = vars.local_values[0];
+            let square = vars.local_values[1];
+            let filter = vars.local_values[2];
+            let square_constraint =
+                builder.arithmetic_extension(F::NEG_ONE, F::ONE, value, value, square);
+            yield_constr.constraint(builder, square_constraint);
+            let filter_constraint = builder.mul_sub_extension(filter, filter, filter);
+            yield_constr.constraint(builder, filter_constraint);
+        }
+
+        fn constraint_degree(&self) -> usize {
+            3
+        }
+    }
+
+    /// Every `x0` of the Fibonacci table must appear as a selected `value` of the squares table.
+    fn ctls<F: Field>() -> Vec<CrossTableLookup<F>> {
+        vec![CrossTableLookup::new(
+            vec![TableWithColumns::new(
+                0,
+                vec![Column::single(0)],
+                Some(Column::one()),
+            )],
+            TableWithColumns::new(1, vec![Column::single(0)], Some(Column::single(2))),
+            None,
+        )]
+    }
+
+    /// Generates a Fibonacci trace with `num_rows` rows, and a squares trace twice as long whose
+    /// first half holds the Fibonacci `x0` values in reverse order.
+    fn generate_traces<F: Field>(num_rows: usize) -> Vec<Vec<PolynomialValues<F>>> {
+        let fibonacci_rows = (0..num_rows)
+            .scan([F::ZERO, F::ONE], |acc, _| {
+                let row = *acc;
+                *acc = [row[1], row[0] + row[1]];
+                Some(row)
+            })
+            .collect::<Vec<_>>();
+        let squares_rows = fibonacci_rows
+            .iter()
+            .rev()
+            .map(|&[x0, _]| [x0, x0.square(), F::ONE])
+            .chain((num_rows..2 * num_rows).map(|i| {
+                let value = F::from_canonical_usize(i);
+                [value, value.square(), F::ZERO]
+            }))
+            .collect::<Vec<_>>();
+        vec![
+            trace_rows_to_poly_values(fibonacci_rows),
+            trace_rows_to_poly_values(squares_rows),
+        ]
+    }
+
+    #[test]
+    fn test_debug_cross_table_lookups() -> Result<()> {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+
+        let ctls = ctls::<F>();
+        let mut traces = generate_traces::<F>(1 << 5);
+        debug_cross_table_lookups(&traces, &ctls)?;
+
+        traces[0][0].values[3] += F::ONE;
+        let err = debug_cross_table_lookups(&traces, &ctls).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Cross-table lookup 0 fails: the values [3] of table 0 on row 3"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_stark() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = StarkConfig::standard_fast_config();
+        let fibonacci_stark = FibonacciStark::<F, D> {
+            _phantom: PhantomData,
+        };
+        let squares_stark = SquaresStark::<F, D> {
+            _phantom: PhantomData,
+        };
+        let starks: [&dyn DynStark<F, C, D>; 2] = [&fibonacci_stark, &squares_stark];
+        let ctls = ctls();
+
+        let proof = prove_multi_stark::<F, C, D>(
+            &starks,
+            &ctls,
+            &config,
+            generate_traces(1 << 5),
+            vec![vec![F::ZERO, F::ONE], vec![]],
+            &mut TimingTree::default(),
+        )?;
+        verify_multi_stark_proof(&starks, &ctls, proof.clone(), &config)?;
+
+        let mut bad_proof = proof;
+        let ctl_zs_last = bad_proof.stark_proofs[1]
+            .proof
+            .openings
+            .ctl_zs_last
+            .as_mut()
+            .unwrap();
+        ctl_zs_last[0] += F::ONE;
+        assert!(verify_multi_stark_proof(&starks, &ctls, bad_proof, &config).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_stark_zero_knowledge() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut config = StarkConfig::standard_fast_zk_config();
+        let fibonacci_stark = FibonacciStark::<F, D> {
+            _phantom: PhantomData,
+        };
+        let squares_stark = SquaresStark::<F, D> {
+            _phantom: PhantomData,
+        };
+        let starks: [&dyn DynStark<F, C, D>; 2] = [&fibonacci_stark, &squares_stark];
+        let ctls = ctls();
+        let prove = |config: &StarkConfig| {
+            prove_multi_stark::<F, C, D>(
+                &starks,
+                &ctls,
+                config,
+                generate_traces(1 << 5),
+                vec![vec![F::ZERO, F::ONE], vec![]],
+                &mut TimingTree::default(),
+            )
+        };
+
+        // Randomized traces raise the quotients' degree, so the squares STARK's cubic constraints
+        // need a rate of 1/4.
+        assert!(prove(&config).is_err());
+        config.fri_config.rate_bits = 2;
+        let proof = prove(&config)?;
+        assert_eq!(proof.degrees_bits(), vec![5, 6]);
+        verify_multi_stark_proof(&starks, &ctls, proof, &config)
+    }
+
+    #[test]
+    fn test_recursive_multi_stark_verifier() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = StarkConfig::standard_fast_config();
+        let fibonacci_stark = FibonacciStark::<F, D> {
+            _phantom: PhantomData,
+        };
+        let squares_stark = SquaresStark::<F, D> {
+            _phantom: PhantomData,
+        };
+        let starks: [&dyn DynStark<F, C, D>; 2] = [&fibonacci_stark, &squares_stark];
+        let ctls = ctls();
+
+        let proof = prove_multi_stark::<F, C, D>(
+            &starks,
+            &ctls,
+            &config,
+            generate_traces(1 << 5),
+            vec![vec![F::ZERO, F::ONE], vec![]],
+            &mut TimingTree::default(),
+        )?;
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let mut pw = PartialWitness::new();
+        let degrees_bits = proof.degrees_bits();
+        let pt =
+            add_virtual_multi_stark_proof(&mut builder, &starks, &ctls, &config, &degrees_bits);
+        set_multi_stark_proof_target(&mut pw, &pt, &proof);
+        verify_multi_stark_proof_circuit::<F, C, D>(&mut builder, &starks, &ctls, pt, &config);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+}
@@ -0,0 +1,386 @@
+//! Preprocessed columns, which hold values fixed by a STARK rather than by its witness, such as
+//! lookup tables or round constants.
+//!
+//! Their low-degree extensions are committed to once per trace length. The prover keeps the
+//! commitment in a `StarkProvingKey`, while the verifier only needs its Merkle cap, which forms the
+//! `StarkVerifyingKey`, much like PLONK's `constants_sigmas_cap`. The cap is observed before the
+//! trace cap, so every challenge is bound to the preprocessed columns.
+
+use plonky2::field::extension::Extendable;
+use plonky2::fri::oracle::PolynomialBatch;
+use plonky2::hash::hash_types::RichField;
+use plonky2::hash::merkle_tree::MerkleCap;
+use plonky2::plonk::config::GenericConfig;
+use plonky2::timed;
+use plonky2::util::timing::TimingTree;
+
+use crate::config::StarkConfig;
+use crate::prover::commit_polys;
+use crate::stark::Stark;
+
+/// The data needed to prove a STARK with preprocessed columns, for traces of `2^degree_bits` rows.
+pub struct StarkProvingKey<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+{
+    /// The commitment to the preprocessed columns, if the STARK has any.
+    pub preprocessed_commitment: Option<PolynomialBatch<F, C, D>>,
+    pub degree_bits: usize,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    StarkProvingKey<F, C, D>
+{
+    pub fn new<S: Stark<F, D>>(
+        stark: &S,
+        config: &StarkConfig,
+        degree_bits: usize,
+        timing: &mut TimingTree,
+    ) -> Self {
+        let preprocessed_commitment = stark.uses_preprocessed_columns().then(|| {
+            let num_rows = 1 << degree_bits;
+            let columns = stark.preprocessed_columns(num_rows);
+            assert_eq!(
+                columns.len(),
+                stark.num_preprocessed_columns(),
+                "Wrong number of preprocessed columns."
+            );
+            assert!(
+                columns.iter().all(|column| column.len() == num_rows),
+                "Preprocessed columns must have one value per trace row."
+            );
+            timed!(
+                timing,
+                "compute preprocessed commitment",
+                commit_polys(columns, false, stark.num_opening_points(), config, timing)
+            )
+        });
+        Self {
+            preprocessed_commitment,
+            degree_bits,
+        }
+    }
+
+    pub fn verifying_key(&self) -> StarkVerifyingKey<F, C, D> {
+        StarkVerifyingKey {
+            preprocessed_cap: self
+                .preprocessed_commitment
+                .as_ref()
+                .map(|commitment| commitment.merkle_tree.cap.clone()),
+            degree_bits: self.degree_bits,
+        }
+    }
+}
+
+/// The data needed to verify proofs of a STARK with preprocessed columns, for traces of
+/// `2^degree_bits` rows.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StarkVerifyingKey<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+> {
+    /// The Merkle cap of the preprocessed columns, if the STARK has any.
+    pub preprocessed_cap: Option<MerkleCap<F, C::Hasher>>,
+    pub degree_bits: usize,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    StarkVerifyingKey<F, C, D>
+{
+    pub fn new<S: Stark<F, D>>(stark: &S, config: &StarkConfig, degree_bits: usize) -> Self {
+        StarkProvingKey::new(stark, config, degree_bits, &mut TimingTree::default()).verifying_key()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::marker::PhantomData;
+
+    use anyhow::Result;
+    use plonky2::field::extension::{Extendable, FieldExtension};
+    use plonky2::field::packed::PackedField;
+    use plonky2::field::polynomial::PolynomialValues;
+    use plonky2::hash::hash_types::RichField;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_builder::CircuitBuilder;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+
+    use crate::config::StarkConfig;
+    use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+    use crate::preprocessed::{StarkProvingKey, StarkVerifyingKey};
+    use crate::prover::prove_with_key;
+    use crate::recursive_verifier::{
+        add_virtual_stark_proof_with_pis, set_stark_proof_with_pis_target,
+        verify_stark_proof_circuit_with_key,
+    };
+    use crate::stark::Stark;
+    use crate::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
+    use crate::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+    use crate::verifier::{verify_stark_proof, verify_stark_proof_with_key};
+
+    /// Accumulates the running sum of a preprocessed column of constants `i^2 + offset`, and
+    /// exposes the total as its public input.
+    #[derive(Copy, Clone)]
+    struct ConstantSumStark<F: RichField + Extendable<D>, const D: usize> {
+        offset: usize,
+        _phantom: PhantomData<F>,
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> ConstantSumStark<F, D> {
+        fn new(offset: usize) -> Self {
+            Self {
+                offset,
+                _phantom: PhantomData,
+            }
+        }
+
+        fn constants(&self, num_rows: usize) -> Vec<F> {
+            (0..num_rows)
+                .map(|i| F::from_canonical_usize(i * i + self.offset))
+                .collect()
+        }
+
+        /// Generates the trace, along with the public input holding the total sum.
+        fn generate_trace(&self, num_rows: usize) -> (Vec<PolynomialValues<F>>, F) {
+            let sums = self
+                .constants(num_rows)
+                .into_iter()
+                .scan(F::ZERO, |acc, c| {
+                    *acc += c;
+                    Some(*acc)
+                })
+                .collect::<Vec<_>>();
+            let total = sums[num_rows - 1];
+            (vec![PolynomialValues::new(sums)], total)
+        }
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for ConstantSumStark<F, D> {
+        fn num_columns(&self) -> usize {
+            1
+        }
+
+        fn num_public_inputs(&self) -> usize {
+            1
+        }
+
+        fn num_preprocessed_columns(&self) -> usize {
+            1
+        }
+
+        fn preprocessed_columns(&self, num_rows: usize) -> Vec<PolynomialValues<F>> {
+            vec![PolynomialValues::new(self.constants(num_rows))]
+        }
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            vars: StarkEvaluationVars<FE, P>,
+            yield_constr: &mut ConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            let sum = vars.local_values[0];
+            let next_sum = vars.next_values[0];
+            let constant = vars.preprocessed_local_values[0];
+            let next_constant = vars.preprocessed_next_values[0];
+            yield_constr.constraint_first_row(sum - constant);
+            yield_constr.constraint_transition(next_sum - sum - next_constant);
+            yield_constr.constraint_last_row(sum - vars.public_inputs[0]);
+        }
+
+        fn eval_ext_circuit(
+            &self,
+            builder: &mut CircuitBuilder<F, D>,
+            vars: StarkEvaluationTargets<D>,
+            yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+        ) {
+            let sum = vars.local_values[0];
+            let next_sum = vars.next_values[0];
+            let constant = vars.preprocessed_local_values[0];
+            let next_constant = vars.preprocessed_next_values[0];
+            let first_row_constraint = builder.sub_extension(sum, constant);
+            yield_constr.constraint_first_row(builder, first_row_constraint);
+            let diff = builder.sub_extension(next_sum, sum);
+            let transition_constraint = builder.sub_extension(diff, next_constant);
+            yield_constr.constraint_transition(builder, transition_constraint);
+            let last_row_constraint = builder.sub_extension(sum, vars.public_inputs[0]);
+            yield_constr.constraint_last_row(builder, last_row_constraint);
+        }
+
+        fn constraint_degree(&self) -> usize {
+            2
+        }
+    }
+
+    #[test]
+    fn test_preprocessed_stark() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = ConstantSumStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let num_rows = 1 << 5;
+        let stark = S::new(3);
+        let proving_key =
+            StarkProvingKey::<F, C, D>::new(&stark, &config, 5, &mut TimingTree::default());
+        let verifying_key = proving_key.verifying_key();
+        assert_eq!(verifying_key, StarkVerifyingKey::new(&stark, &config, 5));
+
+        let (trace, total) = stark.generate_trace(num_rows);
+        let proof = prove_with_key(
+            stark,
+            &proving_key,
+            &config,
+            trace,
+            &[total],
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof_with_key(stark, proof.clone(), &verifying_key, &config)?;
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    fn test_preprocessed_stark_wrong_key() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = ConstantSumStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(3);
+        let proving_key =
+            StarkProvingKey::<F, C, D>::new(&stark, &config, 5, &mut TimingTree::default());
+        let (trace, total) = stark.generate_trace(1 << 5);
+        let proof = prove_with_key(
+            stark,
+            &proving_key,
+            &config,
+            trace,
+            &[total],
+            &mut TimingTree::default(),
+        )?;
+
+        // A key committing to other constants must not verify the proof.
+        let other_stark = S::new(4);
+        let other_key = StarkVerifyingKey::new(&other_stark, &config, 5);
+        assert!(
+            verify_stark_proof_with_key(other_stark, proof.clone(), &other_key, &config).is_err()
+        );
+        // Neither must a key for another trace length.
+        let short_key = StarkVerifyingKey::new(&stark, &config, 4);
+        assert!(verify_stark_proof_with_key(stark, proof, &short_key, &config).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_preprocessed_stark_compression() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = ConstantSumStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(3);
+        let proving_key =
+            StarkProvingKey::<F, C, D>::new(&stark, &config, 5, &mut TimingTree::default());
+        let verifying_key = proving_key.verifying_key();
+        let (trace, total) = stark.generate_trace(1 << 5);
+        let proof = prove_with_key(
+            stark,
+            &proving_key,
+            &config,
+            trace,
+            &[total],
+            &mut TimingTree::default(),
+        )?;
+
+        // The FRI query indices, and hence the compression, depend on the preprocessed cap.
+        let compressed_proof = proof.clone().compress(&stark, &verifying_key, &config)?;
+        let decompressed_proof = compressed_proof.decompress(&stark, &verifying_key, &config)?;
+        assert_eq!(decompressed_proof, proof);
+        verify_stark_proof_with_key(stark, decompressed_proof, &verifying_key, &config)
+    }
+
+    #[test]
+    fn test_preprocessed_stark_zero_knowledge() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = ConstantSumStark<F, D>;
+
+        // The preprocessed columns are public, so they are committed without randomization, but
+        // to the same degree bound as the randomized trace.
+        let config = StarkConfig::standard_fast_zk_config();
+        let stark = S::new(3);
+        let proving_key =
+            StarkProvingKey::<F, C, D>::new(&stark, &config, 5, &mut TimingTree::default());
+        let verifying_key = proving_key.verifying_key();
+        assert_eq!(verifying_key, StarkVerifyingKey::new(&stark, &config, 5));
+
+        let (trace, total) = stark.generate_trace(1 << 5);
+        let proof = prove_with_key(
+            stark,
+            &proving_key,
+            &config,
+            trace,
+            &[total],
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof_with_key(stark, proof, &verifying_key, &config)
+    }
+
+    #[test]
+    fn test_preprocessed_stark_degree() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = ConstantSumStark<F, D>;
+
+        test_stark_low_degree(S::new(3))?;
+        test_stark_circuit_constraints::<F, C, S, D>(S::new(3))
+    }
+
+    #[test]
+    fn test_recursive_preprocessed_stark_verifier() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = ConstantSumStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(3);
+        let proving_key =
+            StarkProvingKey::<F, C, D>::new(&stark, &config, 5, &mut TimingTree::default());
+        let verifying_key = proving_key.verifying_key();
+        let (trace, total) = stark.generate_trace(1 << 5);
+        let proof = prove_with_key(
+            stark,
+            &proving_key,
+            &config,
+            trace,
+            &[total],
+            &mut TimingTree::default(),
+        )?;
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let mut pw = PartialWitness::new();
+        let pt = add_virtual_stark_proof_with_pis(&mut builder, stark, &config, 5);
+        set_stark_proof_with_pis_target(&mut pw, &pt, &proof);
+        verify_stark_proof_circuit_with_key::<F, C, S, D>(
+            &mut builder,
+            stark,
+            pt,
+            &verifying_key,
+            &config,
+        );
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+}
@@ -1,6 +1,7 @@
 use alloc::vec;
 use alloc::vec::Vec;
 
+use anyhow::{ensure, Result};
 use itertools::Itertools;
 use maybe_rayon::*;
 use plonky2::field::extension::{Extendable, FieldExtension};
@@ -11,21 +12,36 @@ use plonky2::fri::proof::{
 use plonky2::fri::structure::{
     FriOpeningBatch, FriOpeningBatchTarget, FriOpenings, FriOpeningsTarget,
 };
+use plonky2::fri::FriParams;
 use plonky2::hash::hash_types::{MerkleCapTarget, RichField};
 use plonky2::hash::merkle_tree::MerkleCap;
 use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::iop::target::Target;
 use plonky2::plonk::config::GenericConfig;
+use plonky2::plonk::proof::FriInferredElements;
+#[cfg(feature = "std")]
+use plonky2::util::serialization::{Buffer, Remaining};
 
 use crate::config::StarkConfig;
 use crate::permutation::PermutationChallengeSet;
+use crate::preprocessed::StarkVerifyingKey;
+#[cfg(feature = "std")]
+use crate::serialization::StarkRead;
+use crate::serialization::StarkWrite;
+use crate::stark::Stark;
+use crate::util::row_shift;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct StarkProof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+    /// The log of the trace length. The shape of a zero-knowledge proof does not determine it, as
+    /// the committed degree bound of short traces only depends on the number of FRI queries. It is
+    /// not serialized, since readers need it to know the shape of the proof.
+    pub degree_bits: usize,
     /// Merkle cap of LDEs of trace values.
     pub trace_cap: MerkleCap<F, C::Hasher>,
-    /// Merkle cap of LDEs of permutation Z values.
-    pub permutation_zs_cap: Option<MerkleCap<F, C::Hasher>>,
+    /// Merkle cap of LDEs of permutation Z values, lookup helper columns and cross-table lookup Z
+    /// values.
+    pub permutation_ctl_zs_cap: Option<MerkleCap<F, C::Hasher>>,
     /// Merkle cap of LDEs of trace values.
     pub quotient_polys_cap: MerkleCap<F, C::Hasher>,
     /// Purported values of each polynomial at the challenge point.
@@ -35,66 +51,193 @@ pub struct StarkProof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>,
 }
 
 impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> StarkProof<F, C, D> {
-    /// Recover the length of the trace from a STARK proof and a STARK config.
-    pub fn recover_degree_bits(&self, config: &StarkConfig) -> usize {
-        let initial_merkle_proof = &self.opening_proof.query_round_proofs[0]
-            .initial_trees_proof
-            .evals_proofs[0]
-            .1;
-        let lde_bits = config.fri_config.cap_height + initial_merkle_proof.siblings.len();
-        lde_bits - config.fri_config.rate_bits
+    /// Compress the proof.
+    pub fn compress(self, indices: &[usize], params: &FriParams) -> CompressedStarkProof<F, C, D> {
+        let StarkProof {
+            degree_bits,
+            trace_cap,
+            permutation_ctl_zs_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof,
+        } = self;
+
+        CompressedStarkProof {
+            degree_bits,
+            trace_cap,
+            permutation_ctl_zs_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof: opening_proof.compress::<C>(indices, params),
+        }
     }
 }
 
+#[derive(Clone, Debug)]
 pub struct StarkProofTarget<const D: usize> {
+    pub degree_bits: usize,
     pub trace_cap: MerkleCapTarget,
-    pub permutation_zs_cap: Option<MerkleCapTarget>,
+    pub permutation_ctl_zs_cap: Option<MerkleCapTarget>,
     pub quotient_polys_cap: MerkleCapTarget,
     pub openings: StarkOpeningSetTarget<D>,
     pub opening_proof: FriProofTarget<D>,
 }
 
-impl<const D: usize> StarkProofTarget<D> {
-    /// Recover the length of the trace from a STARK proof and a STARK config.
-    pub fn recover_degree_bits(&self, config: &StarkConfig) -> usize {
-        let initial_merkle_proof = &self.opening_proof.query_round_proofs[0]
-            .initial_trees_proof
-            .evals_proofs[0]
-            .1;
-        let lde_bits = config.fri_config.cap_height + initial_merkle_proof.siblings.len();
-        lde_bits - config.fri_config.rate_bits
-    }
-}
-
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct StarkProofWithPublicInputs<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
     const D: usize,
 > {
     pub proof: StarkProof<F, C, D>,
-    // TODO: Maybe make it generic over a `S: Stark` and replace with `[F; S::PUBLIC_INPUTS]`.
     pub public_inputs: Vec<F>,
 }
 
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    StarkProofWithPublicInputs<F, C, D>
+{
+    /// Compresses the Merkle paths of the proof and removes the FRI evaluations the verifier can
+    /// infer. `verifying_key` must be the key the proof verifies against.
+    pub fn compress<S: Stark<F, D>>(
+        self,
+        stark: &S,
+        verifying_key: &StarkVerifyingKey<F, C, D>,
+        config: &StarkConfig,
+    ) -> Result<CompressedStarkProofWithPublicInputs<F, C, D>> {
+        let degree_bits = self.proof.degree_bits;
+        ensure!(
+            verifying_key.degree_bits == degree_bits,
+            "Verifying key is for traces of 2^{} rows, got 2^{}",
+            verifying_key.degree_bits,
+            degree_bits
+        );
+        let indices = self.fri_query_indices(
+            stark,
+            verifying_key.preprocessed_cap.as_ref(),
+            config,
+            degree_bits,
+        );
+        let compressed_proof = self.proof.compress(
+            &indices,
+            &config.fri_params(degree_bits, stark.num_opening_points()),
+        );
+        Ok(CompressedStarkProofWithPublicInputs {
+            public_inputs: self.public_inputs,
+            proof: compressed_proof,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_stark_proof_with_public_inputs(self)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    /// Reads a proof of `stark` from `bytes`.
+    #[cfg(feature = "std")]
+    pub fn from_bytes<S: Stark<F, D>>(
+        bytes: Vec<u8>,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> Result<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let proof = buffer
+            .read_stark_proof_with_public_inputs(stark, config)
+            .map_err(anyhow::Error::msg)?;
+        ensure!(buffer.is_empty(), "Trailing bytes after STARK proof.");
+        Ok(proof)
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct StarkProofWithPublicInputsTarget<const D: usize> {
     pub proof: StarkProofTarget<D>,
     pub public_inputs: Vec<Target>,
 }
 
+/// Proofs of several STARKs whose traces are linked by cross-table lookups. All proofs share a
+/// single Fiat-Shamir transcript.
+#[derive(Debug, Clone)]
+pub struct MultiStarkProof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+{
+    /// One proof per STARK, in the order the STARKs were given to the prover.
+    pub stark_proofs: Vec<StarkProofWithPublicInputs<F, C, D>>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    MultiStarkProof<F, C, D>
+{
+    /// The log of the length of each trace.
+    pub fn degrees_bits(&self) -> Vec<usize> {
+        self.stark_proofs
+            .iter()
+            .map(|p| p.proof.degree_bits)
+            .collect()
+    }
+}
+
+pub struct MultiStarkProofTarget<const D: usize> {
+    pub stark_proofs: Vec<StarkProofWithPublicInputsTarget<D>>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct CompressedStarkProof<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
     const D: usize,
 > {
+    /// The log of the trace length, which is not serialized.
+    pub degree_bits: usize,
     /// Merkle cap of LDEs of trace values.
     pub trace_cap: MerkleCap<F, C::Hasher>,
+    /// Merkle cap of LDEs of permutation Z values, lookup helper columns and cross-table lookup Z
+    /// values.
+    pub permutation_ctl_zs_cap: Option<MerkleCap<F, C::Hasher>>,
+    /// Merkle cap of LDEs of quotient polynomial values.
+    pub quotient_polys_cap: MerkleCap<F, C::Hasher>,
     /// Purported values of each polynomial at the challenge point.
     pub openings: StarkOpeningSet<F, D>,
-    /// A batch FRI argument for all openings.
+    /// A compressed batch FRI argument for all openings.
     pub opening_proof: CompressedFriProof<F, C::Hasher, D>,
 }
 
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    CompressedStarkProof<F, C, D>
+{
+    /// Decompress the proof.
+    pub(crate) fn decompress(
+        self,
+        challenges: &StarkProofChallenges<F, D>,
+        fri_inferred_elements: FriInferredElements<F, D>,
+        params: &FriParams,
+    ) -> StarkProof<F, C, D> {
+        let CompressedStarkProof {
+            degree_bits,
+            trace_cap,
+            permutation_ctl_zs_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof,
+        } = self;
+
+        StarkProof {
+            degree_bits,
+            trace_cap,
+            permutation_ctl_zs_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof: opening_proof.decompress::<C>(
+                &challenges.fri_challenges,
+                fri_inferred_elements,
+                params,
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct CompressedStarkProofWithPublicInputs<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
@@ -104,10 +247,76 @@ pub struct CompressedStarkProofWithPublicInputs<
     pub public_inputs: Vec<F>,
 }
 
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    CompressedStarkProofWithPublicInputs<F, C, D>
+{
+    /// Recovers the uncompressed proof. `verifying_key` must be the key it was compressed with.
+    pub fn decompress<S: Stark<F, D>>(
+        self,
+        stark: &S,
+        verifying_key: &StarkVerifyingKey<F, C, D>,
+        config: &StarkConfig,
+    ) -> Result<StarkProofWithPublicInputs<F, C, D>> {
+        let degree_bits = verifying_key.degree_bits;
+        ensure!(
+            self.proof.degree_bits == degree_bits,
+            "Verifying key is for traces of 2^{} rows, got 2^{}",
+            degree_bits,
+            self.proof.degree_bits
+        );
+        let challenges = self.get_challenges(
+            stark,
+            verifying_key.preprocessed_cap.as_ref(),
+            config,
+            degree_bits,
+        );
+        let fri_inferred_elements =
+            self.get_inferred_elements(stark, &challenges, config, degree_bits);
+        let decompressed_proof = self.proof.decompress(
+            &challenges,
+            fri_inferred_elements,
+            &config.fri_params(degree_bits, stark.num_opening_points()),
+        );
+        Ok(StarkProofWithPublicInputs {
+            public_inputs: self.public_inputs,
+            proof: decompressed_proof,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        buffer
+            .write_compressed_stark_proof_with_public_inputs(self)
+            .expect("Writing to a byte-vector cannot fail.");
+        buffer
+    }
+
+    /// Reads a compressed proof of `stark` from `bytes`.
+    #[cfg(feature = "std")]
+    pub fn from_bytes<S: Stark<F, D>>(
+        bytes: Vec<u8>,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> Result<Self> {
+        let mut buffer = Buffer::new(bytes);
+        let proof = buffer
+            .read_compressed_stark_proof_with_public_inputs(stark, config)
+            .map_err(anyhow::Error::msg)?;
+        ensure!(
+            buffer.is_empty(),
+            "Trailing bytes after compressed STARK proof."
+        );
+        Ok(proof)
+    }
+}
+
 pub(crate) struct StarkProofChallenges<F: RichField + Extendable<D>, const D: usize> {
     /// Randomness used in any permutation arguments.
     pub permutation_challenge_sets: Option<Vec<PermutationChallengeSet<F>>>,
 
+    /// Randomness used in any lookup arguments.
+    pub lookup_challenges: Option<Vec<F>>,
+
     /// Random values used to combine STARK constraints.
     pub stark_alphas: Vec<F>,
 
@@ -119,18 +328,35 @@ pub(crate) struct StarkProofChallenges<F: RichField + Extendable<D>, const D: us
 
 pub(crate) struct StarkProofChallengesTarget<const D: usize> {
     pub permutation_challenge_sets: Option<Vec<PermutationChallengeSet<Target>>>,
+    pub lookup_challenges: Option<Vec<Target>>,
     pub stark_alphas: Vec<Target>,
     pub stark_zeta: ExtensionTarget<D>,
     pub fri_challenges: FriChallengesTarget<D>,
 }
 
 /// Purported values of each polynomial at the challenge point.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct StarkOpeningSet<F: RichField + Extendable<D>, const D: usize> {
+    /// Openings of trace polynomials at `zeta`.
     pub local_values: Vec<F::Extension>,
+    /// Openings of trace polynomials at `g * zeta`.
     pub next_values: Vec<F::Extension>,
-    pub permutation_zs: Option<Vec<F::Extension>>,
-    pub permutation_zs_next: Option<Vec<F::Extension>>,
+    /// Openings of preprocessed polynomials at `zeta`.
+    pub preprocessed_local_values: Option<Vec<F::Extension>>,
+    /// Openings of preprocessed polynomials at `g * zeta`.
+    pub preprocessed_next_values: Option<Vec<F::Extension>>,
+    /// Openings of trace polynomials at `g^offset * zeta`, for each of the STARK's extra row
+    /// offsets.
+    pub extra_row_values: Vec<Vec<F::Extension>>,
+    /// Openings of permutation `Z` polynomials, lookup helper columns and cross-table lookup `Z`
+    /// polynomials at `zeta`.
+    pub permutation_ctl_zs: Option<Vec<F::Extension>>,
+    /// Openings of permutation `Z` polynomials, lookup helper columns and cross-table lookup `Z`
+    /// polynomials at `g * zeta`.
+    pub permutation_ctl_zs_next: Option<Vec<F::Extension>>,
+    /// Openings of cross-table lookup `Z` polynomials at `g^-1`.
+    pub ctl_zs_last: Option<Vec<F>>,
+    /// Openings of quotient polynomials at `zeta`.
     pub quotient_polys: Vec<F::Extension>,
 }
 
@@ -138,9 +364,12 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
     pub fn new<C: GenericConfig<D, F = F>>(
         zeta: F::Extension,
         g: F,
+        preprocessed_commitment: Option<&PolynomialBatch<F, C, D>>,
         trace_commitment: &PolynomialBatch<F, C, D>,
-        permutation_zs_commitment: Option<&PolynomialBatch<F, C, D>>,
+        permutation_ctl_zs_commitment: Option<&PolynomialBatch<F, C, D>>,
         quotient_commitment: &PolynomialBatch<F, C, D>,
+        num_permutation_lookup_zs: usize,
+        extra_row_offsets: &[isize],
     ) -> Self {
         let eval_commitment = |z: F::Extension, c: &PolynomialBatch<F, C, D>| {
             c.polynomials
@@ -148,12 +377,33 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
                 .map(|p| p.to_extension().eval(z))
                 .collect::<Vec<_>>()
         };
+        let eval_commitment_base = |z: F, c: &PolynomialBatch<F, C, D>| {
+            c.polynomials
+                .par_iter()
+                .map(|p| p.eval(z))
+                .collect::<Vec<_>>()
+        };
         let zeta_next = zeta.scalar_mul(g);
         Self {
             local_values: eval_commitment(zeta, trace_commitment),
             next_values: eval_commitment(zeta_next, trace_commitment),
-            permutation_zs: permutation_zs_commitment.map(|c| eval_commitment(zeta, c)),
-            permutation_zs_next: permutation_zs_commitment.map(|c| eval_commitment(zeta_next, c)),
+            preprocessed_local_values: preprocessed_commitment.map(|c| eval_commitment(zeta, c)),
+            preprocessed_next_values: preprocessed_commitment
+                .map(|c| eval_commitment(zeta_next, c)),
+            extra_row_values: extra_row_offsets
+                .iter()
+                .map(|&offset| {
+                    eval_commitment(zeta.scalar_mul(row_shift(g, offset)), trace_commitment)
+                })
+                .collect(),
+            permutation_ctl_zs: permutation_ctl_zs_commitment.map(|c| eval_commitment(zeta, c)),
+            permutation_ctl_zs_next: permutation_ctl_zs_commitment
+                .map(|c| eval_commitment(zeta_next, c)),
+            ctl_zs_last: permutation_ctl_zs_commitment
+                .filter(|c| c.polynomials.len() > num_permutation_lookup_zs)
+                .map(|c| {
+                    eval_commitment_base(g.inverse(), c)[num_permutation_lookup_zs..].to_vec()
+                }),
             quotient_polys: eval_commitment(zeta, quotient_commitment),
         }
     }
@@ -161,56 +411,91 @@ impl<F: RichField + Extendable<D>, const D: usize> StarkOpeningSet<F, D> {
     pub(crate) fn to_fri_openings(&self) -> FriOpenings<F, D> {
         let zeta_batch = FriOpeningBatch {
             values: self
-                .local_values
+                .preprocessed_local_values
                 .iter()
-                .chain(self.permutation_zs.iter().flatten())
+                .flatten()
+                .chain(&self.local_values)
+                .chain(self.permutation_ctl_zs.iter().flatten())
                 .chain(&self.quotient_polys)
                 .copied()
                 .collect_vec(),
         };
         let zeta_next_batch = FriOpeningBatch {
             values: self
-                .next_values
+                .preprocessed_next_values
                 .iter()
-                .chain(self.permutation_zs_next.iter().flatten())
+                .flatten()
+                .chain(&self.next_values)
+                .chain(self.permutation_ctl_zs_next.iter().flatten())
                 .copied()
                 .collect_vec(),
         };
-        FriOpenings {
-            batches: vec![zeta_batch, zeta_next_batch],
+        let mut batches = vec![zeta_batch, zeta_next_batch];
+        batches.extend(self.extra_row_values.iter().map(|values| FriOpeningBatch {
+            values: values.clone(),
+        }));
+        if let Some(ctl_zs_last) = &self.ctl_zs_last {
+            batches.push(FriOpeningBatch {
+                values: ctl_zs_last
+                    .iter()
+                    .copied()
+                    .map(F::Extension::from_basefield)
+                    .collect(),
+            });
         }
+        FriOpenings { batches }
     }
 }
 
+#[derive(Clone, Debug)]
 pub struct StarkOpeningSetTarget<const D: usize> {
     pub local_values: Vec<ExtensionTarget<D>>,
     pub next_values: Vec<ExtensionTarget<D>>,
-    pub permutation_zs: Option<Vec<ExtensionTarget<D>>>,
-    pub permutation_zs_next: Option<Vec<ExtensionTarget<D>>>,
+    pub preprocessed_local_values: Option<Vec<ExtensionTarget<D>>>,
+    pub preprocessed_next_values: Option<Vec<ExtensionTarget<D>>>,
+    pub extra_row_values: Vec<Vec<ExtensionTarget<D>>>,
+    pub permutation_ctl_zs: Option<Vec<ExtensionTarget<D>>>,
+    pub permutation_ctl_zs_next: Option<Vec<ExtensionTarget<D>>>,
+    pub ctl_zs_last: Option<Vec<Target>>,
     pub quotient_polys: Vec<ExtensionTarget<D>>,
 }
 
 impl<const D: usize> StarkOpeningSetTarget<D> {
-    pub(crate) fn to_fri_openings(&self) -> FriOpeningsTarget<D> {
+    pub(crate) fn to_fri_openings(&self, zero: Target) -> FriOpeningsTarget<D> {
         let zeta_batch = FriOpeningBatchTarget {
             values: self
-                .local_values
+                .preprocessed_local_values
                 .iter()
-                .chain(self.permutation_zs.iter().flatten())
+                .flatten()
+                .chain(&self.local_values)
+                .chain(self.permutation_ctl_zs.iter().flatten())
                 .chain(&self.quotient_polys)
                 .copied()
                 .collect_vec(),
         };
         let zeta_next_batch = FriOpeningBatchTarget {
             values: self
-                .next_values
+                .preprocessed_next_values
                 .iter()
-                .chain(self.permutation_zs_next.iter().flatten())
+                .flatten()
+                .chain(&self.next_values)
+                .chain(self.permutation_ctl_zs_next.iter().flatten())
                 .copied()
                 .collect_vec(),
         };
-        FriOpeningsTarget {
-            batches: vec![zeta_batch, zeta_next_batch],
+        let mut batches = vec![zeta_batch, zeta_next_batch];
+        batches.extend(
+            self.extra_row_values
+                .iter()
+                .map(|values| FriOpeningBatchTarget {
+                    values: values.clone(),
+                }),
+        );
+        if let Some(ctl_zs_last) = &self.ctl_zs_last {
+            batches.push(FriOpeningBatchTarget {
+                values: ctl_zs_last.iter().map(|t| t.to_ext_target(zero)).collect(),
+            });
         }
+        FriOpeningsTarget { batches }
     }
 }
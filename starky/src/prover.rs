@@ -13,18 +13,22 @@ use plonky2::field::zero_poly_coset::ZeroPolyOnCoset;
 use plonky2::fri::oracle::PolynomialBatch;
 use plonky2::hash::hash_types::RichField;
 use plonky2::iop::challenger::Challenger;
-use plonky2::plonk::config::{GenericConfig, Hasher};
+use plonky2::plonk::config::GenericConfig;
 use plonky2::timed;
 use plonky2::util::timing::TimingTree;
 use plonky2::util::{log2_ceil, log2_strict, transpose};
 
 use crate::config::StarkConfig;
 use crate::constraint_consumer::ConstraintConsumer;
+use crate::cross_table_lookup::{cross_table_lookup_data, CrossTableLookup, CtlCheckVars, CtlData};
+use crate::lookup::{compute_lookup_helper_polys, LookupCheckVars};
+use crate::multi_stark::DynStark;
 use crate::permutation::{
-    compute_permutation_z_polys, get_n_permutation_challenge_sets, PermutationChallengeSet,
-    PermutationCheckVars,
+    compute_permutation_z_polys, get_n_permutation_challenge_sets, get_permutation_challenge_set,
+    PermutationChallengeSet, PermutationCheckVars,
 };
-use crate::proof::{StarkOpeningSet, StarkProof, StarkProofWithPublicInputs};
+use crate::preprocessed::StarkProvingKey;
+use crate::proof::{MultiStarkProof, StarkOpeningSet, StarkProof, StarkProofWithPublicInputs};
 use crate::stark::Stark;
 use crate::vanishing_poly::eval_vanishing_poly;
 use crate::vars::StarkEvaluationVars;
@@ -33,102 +37,312 @@ pub fn prove<F, C, S, const D: usize>(
     stark: S,
     config: &StarkConfig,
     trace_poly_values: Vec<PolynomialValues<F>>,
-    public_inputs: [F; S::PUBLIC_INPUTS],
+    public_inputs: &[F],
     timing: &mut TimingTree,
 ) -> Result<StarkProofWithPublicInputs<F, C, D>>
 where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
     S: Stark<F, D>,
-    [(); S::COLUMNS]:,
-    [(); S::PUBLIC_INPUTS]:,
-    [(); C::Hasher::HASH_SIZE]:,
 {
-    let degree = trace_poly_values[0].len();
-    let degree_bits = log2_strict(degree);
-    let fri_params = config.fri_params(degree_bits);
-    let rate_bits = config.fri_config.rate_bits;
-    let cap_height = config.fri_config.cap_height;
-    assert!(
-        fri_params.total_arities() <= degree_bits + rate_bits - cap_height,
-        "FRI total reduction arity is too large.",
+    let degree_bits = log2_strict(trace_poly_values[0].len());
+    let proving_key = StarkProvingKey::new(&stark, config, degree_bits, timing);
+    prove_with_key(
+        stark,
+        &proving_key,
+        config,
+        trace_poly_values,
+        public_inputs,
+        timing,
+    )
+}
+
+/// Proves a STARK using a proving key which was computed ahead of time, so that its preprocessed
+/// columns need not be committed to again.
+pub fn prove_with_key<F, C, S, const D: usize>(
+    stark: S,
+    proving_key: &StarkProvingKey<F, C, D>,
+    config: &StarkConfig,
+    trace_poly_values: Vec<PolynomialValues<F>>,
+    public_inputs: &[F],
+    timing: &mut TimingTree,
+) -> Result<StarkProofWithPublicInputs<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+{
+    let degree_bits = log2_strict(trace_poly_values[0].len());
+    ensure!(
+        proving_key.degree_bits == degree_bits,
+        "Proving key is for traces of 2^{} rows, got 2^{}",
+        proving_key.degree_bits,
+        degree_bits
+    );
+    ensure!(
+        proving_key.preprocessed_commitment.is_some() == stark.uses_preprocessed_columns(),
+        "Proving key does not match the STARK's preprocessed columns"
     );
 
     let trace_commitment = timed!(
         timing,
         "compute trace commitment",
-        PolynomialBatch::<F, C, D>::from_values(
-            // TODO: Cloning this isn't great; consider having `from_values` accept a reference,
+        commit_polys::<F, C, D>(
+            // TODO: Cloning this isn't great; consider having `commit_polys` accept a reference,
             // or having `compute_permutation_z_polys` read trace values from the `PolynomialBatch`.
             trace_poly_values.clone(),
-            rate_bits,
-            false,
-            cap_height,
+            true,
+            stark.num_opening_points(),
+            config,
             timing,
-            None,
         )
     );
 
-    let trace_cap = trace_commitment.merkle_tree.cap.clone();
     let mut challenger = Challenger::new();
-    challenger.observe_cap(&trace_cap);
+    if let Some(commitment) = &proving_key.preprocessed_commitment {
+        challenger.observe_cap(&commitment.merkle_tree.cap);
+    }
+    challenger.observe_cap(&trace_commitment.merkle_tree.cap);
+
+    let proof = prove_with_commitment::<F, C, S, D>(
+        &stark,
+        config,
+        proving_key.preprocessed_commitment.as_ref(),
+        &trace_poly_values,
+        &trace_commitment,
+        public_inputs,
+        &CtlData::default(),
+        &mut challenger,
+        timing,
+    )?;
+
+    Ok(StarkProofWithPublicInputs {
+        proof,
+        public_inputs: public_inputs.to_vec(),
+    })
+}
+
+/// Proves several STARKs at once, linking their traces with the given cross-table lookups. The
+/// `i`-th trace and public inputs belong to `starks[i]`, and table indices in `cross_table_lookups`
+/// refer to positions in `starks`.
+pub fn prove_multi_stark<F, C, const D: usize>(
+    starks: &[&dyn DynStark<F, C, D>],
+    cross_table_lookups: &[CrossTableLookup<F>],
+    config: &StarkConfig,
+    trace_poly_values: Vec<Vec<PolynomialValues<F>>>,
+    public_inputs: Vec<Vec<F>>,
+    timing: &mut TimingTree,
+) -> Result<MultiStarkProof<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    ensure!(
+        trace_poly_values.len() == starks.len(),
+        "Expected {} traces, got {}",
+        starks.len(),
+        trace_poly_values.len()
+    );
+    ensure!(
+        public_inputs.len() == starks.len(),
+        "Expected {} public input vectors, got {}",
+        starks.len(),
+        public_inputs.len()
+    );
+    ensure!(
+        CrossTableLookup::min_num_tables(cross_table_lookups) <= starks.len(),
+        "Cross-table lookup refers to a missing table"
+    );
+
+    let trace_commitments = timed!(
+        timing,
+        "compute trace commitments",
+        trace_poly_values
+            .iter()
+            .zip_eq(starks)
+            .map(|(trace, stark)| {
+                commit_polys::<F, C, D>(
+                    // TODO: Cloning this isn't great; consider having `commit_polys` accept a reference.
+                    trace.clone(),
+                    true,
+                    stark.num_opening_points(),
+                    config,
+                    timing,
+                )
+            })
+            .collect::<Vec<_>>()
+    );
+
+    let mut challenger = Challenger::<F, C::Hasher>::new();
+    for commitment in &trace_commitments {
+        challenger.observe_cap(&commitment.merkle_tree.cap);
+    }
+
+    let ctl_challenges = get_permutation_challenge_set(&mut challenger, config.num_challenges);
+    let ctl_data_per_table = timed!(
+        timing,
+        "compute CTL data",
+        cross_table_lookup_data(&trace_poly_values, cross_table_lookups, &ctl_challenges)
+    );
+
+    let stark_proofs = starks
+        .iter()
+        .zip_eq(&trace_poly_values)
+        .zip_eq(&trace_commitments)
+        .zip_eq(public_inputs)
+        .zip_eq(&ctl_data_per_table)
+        .map(
+            |((((stark, trace), commitment), public_inputs), ctl_data)| {
+                let proof = stark.prove_with_commitment(
+                    config,
+                    trace,
+                    commitment,
+                    &public_inputs,
+                    ctl_data,
+                    &mut challenger,
+                    timing,
+                )?;
+                Ok(StarkProofWithPublicInputs {
+                    proof,
+                    public_inputs,
+                })
+            },
+        )
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(MultiStarkProof { stark_proofs })
+}
+
+/// Proves a single STARK whose trace, and preprocessed columns if any, have already been committed
+/// to and observed by `challenger`, together with its share of the cross-table lookup arguments.
+pub(crate) fn prove_with_commitment<F, C, S, const D: usize>(
+    stark: &S,
+    config: &StarkConfig,
+    preprocessed_commitment: Option<&PolynomialBatch<F, C, D>>,
+    trace_poly_values: &[PolynomialValues<F>],
+    trace_commitment: &PolynomialBatch<F, C, D>,
+    public_inputs: &[F],
+    ctl_data: &CtlData<F>,
+    challenger: &mut Challenger<F, C::Hasher>,
+    timing: &mut TimingTree,
+) -> Result<StarkProof<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+{
+    ensure!(
+        public_inputs.len() == stark.num_public_inputs(),
+        "Expected {} public inputs, got {}",
+        stark.num_public_inputs(),
+        public_inputs.len()
+    );
+    let degree = trace_poly_values[0].len();
+    let degree_bits = log2_strict(degree);
+    let fri_params = config.fri_params(degree_bits, stark.num_opening_points());
+    let rate_bits = config.fri_config.rate_bits;
+    let cap_height = config.fri_config.cap_height;
+    // The quotients are evaluated on a coset of `num_quotient_chunks` times the degree bound of
+    // their chunks, which must fit in the LDE.
+    let quotient_lde_extra_bits = config
+        .quotient_chunk_degree_bits(degree_bits, stark.num_opening_points())
+        + log2_ceil(stark.num_quotient_chunks(config))
+        - config.committed_degree_bits(degree_bits, stark.num_opening_points());
+    ensure!(
+        quotient_lde_extra_bits <= rate_bits,
+        "Constraints of degree {} need rate_bits >= {} with this config",
+        stark.constraint_degree(),
+        quotient_lde_extra_bits
+    );
+    assert!(
+        fri_params.total_arities() <= fri_params.lde_bits() - cap_height,
+        "FRI total reduction arity is too large.",
+    );
+    ensure!(
+        stark
+            .extra_row_offsets()
+            .iter()
+            .all(|offset| offset.unsigned_abs() < degree),
+        "Row offsets must be smaller than the trace length"
+    );
 
     // Permutation arguments.
-    let permutation_zs_commitment_challenges = stark.uses_permutation_args().then(|| {
-        let permutation_challenge_sets = get_n_permutation_challenge_sets(
-            &mut challenger,
+    let permutation_challenges = stark.uses_permutation_args().then(|| {
+        get_n_permutation_challenge_sets(
+            challenger,
             config.num_challenges,
             stark.permutation_batch_size(),
-        );
-        let permutation_z_polys = compute_permutation_z_polys::<F, C, S, D>(
-            &stark,
-            config,
-            &trace_poly_values,
-            &permutation_challenge_sets,
-        );
+        )
+    });
+    let mut z_polys = permutation_challenges
+        .as_ref()
+        .map(|challenges| {
+            compute_permutation_z_polys::<F, C, S, D>(stark, config, trace_poly_values, challenges)
+        })
+        .unwrap_or_default();
+    let num_permutation_zs = z_polys.len();
 
-        let permutation_zs_commitment = timed!(
+    // Lookup arguments.
+    let lookup_challenges = stark
+        .uses_lookups()
+        .then(|| challenger.get_n_challenges(config.num_challenges));
+    if let Some(challenges) = &lookup_challenges {
+        z_polys.extend(timed!(
             timing,
-            "compute permutation Z commitments",
-            PolynomialBatch::from_values(
-                permutation_z_polys,
-                rate_bits,
-                false,
-                config.fri_config.cap_height,
-                timing,
-                None,
-            )
-        );
-        (permutation_zs_commitment, permutation_challenge_sets)
+            "compute lookup helper columns",
+            compute_lookup_helper_polys::<F, S, D>(stark, trace_poly_values, challenges)
+        ));
+    }
+    let num_permutation_lookup_zs = z_polys.len();
+    z_polys.extend(ctl_data.z_polys());
+
+    let permutation_ctl_zs_commitment = (!z_polys.is_empty()).then(|| {
+        timed!(
+            timing,
+            "compute permutation, lookup and CTL Z commitments",
+            commit_polys(z_polys, true, stark.num_opening_points(), config, timing)
+        )
     });
-    let permutation_zs_commitment = permutation_zs_commitment_challenges
-        .as_ref()
-        .map(|(comm, _)| comm);
-    let permutation_zs_cap = permutation_zs_commitment
+    let permutation_ctl_zs_cap = permutation_ctl_zs_commitment
         .as_ref()
         .map(|commit| commit.merkle_tree.cap.clone());
-    if let Some(cap) = &permutation_zs_cap {
+    if let Some(cap) = &permutation_ctl_zs_cap {
         challenger.observe_cap(cap);
     }
 
     let alphas = challenger.get_n_challenges(config.num_challenges);
     let quotient_polys = compute_quotient_polys::<F, <F as Packable>::Packing, C, S, D>(
-        &stark,
-        &trace_commitment,
-        &permutation_zs_commitment_challenges,
+        stark,
+        preprocessed_commitment,
+        trace_commitment,
+        permutation_ctl_zs_commitment.as_ref(),
+        permutation_challenges.as_deref(),
+        lookup_challenges.as_deref(),
+        ctl_data,
         public_inputs,
         alphas,
         degree_bits,
+        num_permutation_zs,
+        num_permutation_lookup_zs,
         config,
     );
+    let committed_degree =
+        1 << config.committed_degree_bits(degree_bits, stark.num_opening_points());
+    let chunk_degree =
+        1 << config.quotient_chunk_degree_bits(degree_bits, stark.num_opening_points());
     let all_quotient_chunks = quotient_polys
         .into_par_iter()
         .flat_map(|mut quotient_poly| {
             quotient_poly
-                .trim_to_len(degree * stark.quotient_degree_factor())
+                .trim_to_len(chunk_degree * stark.num_quotient_chunks(config))
                 .expect("Quotient has failed, the vanishing polynomial is not divisible by Z_H");
-            // Split quotient into degree-n chunks.
-            quotient_poly.chunks(degree)
+            // Split quotient into chunks of the chunk degree.
+            let chunks = quotient_poly.chunks(chunk_degree);
+            if config.zero_knowledge {
+                blind_quotient_chunks(chunks, chunk_degree, committed_degree)
+            } else {
+                chunks
+            }
         })
         .collect();
     let quotient_commitment = timed!(
@@ -137,7 +351,7 @@ where
         PolynomialBatch::from_coeffs(
             all_quotient_chunks,
             rate_bits,
-            false,
+            config.zero_knowledge,
             config.fri_config.cap_height,
             timing,
             None,
@@ -158,14 +372,19 @@ where
     let openings = StarkOpeningSet::new(
         zeta,
         g,
-        &trace_commitment,
-        permutation_zs_commitment,
+        preprocessed_commitment,
+        trace_commitment,
+        permutation_ctl_zs_commitment.as_ref(),
         &quotient_commitment,
+        num_permutation_lookup_zs,
+        &stark.extra_row_offsets(),
     );
     challenger.observe_openings(&openings.to_fri_openings());
 
-    let initial_merkle_trees = once(&trace_commitment)
-        .chain(permutation_zs_commitment)
+    let initial_merkle_trees = preprocessed_commitment
+        .into_iter()
+        .chain(once(trace_commitment))
+        .chain(&permutation_ctl_zs_commitment)
         .chain(once(&quotient_commitment))
         .collect_vec();
 
@@ -173,39 +392,126 @@ where
         timing,
         "compute openings proof",
         PolynomialBatch::prove_openings(
-            &stark.fri_instance(zeta, g, config),
+            &stark.fri_instance(zeta, g, ctl_data.len(), config),
             &initial_merkle_trees,
-            &mut challenger,
+            challenger,
             &fri_params,
             timing,
         )
     );
-    let proof = StarkProof {
-        trace_cap,
-        permutation_zs_cap,
+    Ok(StarkProof {
+        degree_bits,
+        trace_cap: trace_commitment.merkle_tree.cap.clone(),
+        permutation_ctl_zs_cap,
         quotient_polys_cap,
         openings,
         opening_proof,
-    };
-
-    Ok(StarkProofWithPublicInputs {
-        proof,
-        public_inputs: public_inputs.to_vec(),
     })
 }
 
+/// Commits to polynomials given by their values on the trace domain `H`, for a STARK opened at
+/// `num_opening_points` points outside of `H`.
+///
+/// In zero-knowledge mode, every committed polynomial has degree `< m`, where `m` is given by
+/// `StarkConfig::committed_degree_bits`. A witness-dependent polynomial `t` is then committed as
+/// `t + Z_H r` for a random `r` of degree `< m - n`, which agrees with `t` on `H`, and the leaves of
+/// its Merkle tree are salted. Since `m - n` is at least the number of FRI queries plus the number
+/// of opening points, this hides `t` from the evaluations revealed by the proof. Public
+/// polynomials, such as preprocessed columns, are committed as is.
+pub(crate) fn commit_polys<F, C, const D: usize>(
+    values: Vec<PolynomialValues<F>>,
+    witness_dependent: bool,
+    num_opening_points: usize,
+    config: &StarkConfig,
+    timing: &mut TimingTree,
+) -> PolynomialBatch<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let rate_bits = config.fri_config.rate_bits;
+    let cap_height = config.fri_config.cap_height;
+    if !config.zero_knowledge {
+        return PolynomialBatch::from_values(values, rate_bits, false, cap_height, timing, None);
+    }
+
+    let coeffs = values
+        .into_par_iter()
+        .map(|values| {
+            let degree = values.len();
+            let committed_degree =
+                1 << config.committed_degree_bits(log2_strict(degree), num_opening_points);
+            let mut coeffs = values.ifft().padded(committed_degree);
+            if witness_dependent {
+                // Since `Z_H = X^n - 1`, we have `Z_H r = X^n r - r`.
+                let r = F::rand_vec(committed_degree - degree);
+                for (i, &r_i) in r.iter().enumerate() {
+                    coeffs.coeffs[i] -= r_i;
+                    coeffs.coeffs[i + degree] += r_i;
+                }
+            }
+            coeffs
+        })
+        .collect();
+    PolynomialBatch::from_coeffs(
+        coeffs,
+        rate_bits,
+        witness_dependent,
+        cap_height,
+        timing,
+        None,
+    )
+}
+
+/// Randomizes the chunks `t_i` of a quotient polynomial, of degree `< c`, as
+/// `t_i + X^c b_i - b_{i-1}` for random `b_i` of degree `< m - c`, where `m` is the committed
+/// degree bound. The randomizers cancel out in `sum_i X^{c i} t_i`, so the verifier recombines the
+/// chunks as before, while each chunk is masked by `b_i` and `b_{i-1}`, which have more
+/// coefficients than the chunk has revealed evaluations.
+fn blind_quotient_chunks<F: Field>(
+    chunks: Vec<PolynomialCoeffs<F>>,
+    chunk_degree: usize,
+    committed_degree: usize,
+) -> Vec<PolynomialCoeffs<F>> {
+    let num_chunks = chunks.len();
+    let mut previous_randomizer: Option<Vec<F>> = None;
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let mut coeffs = chunk.padded(committed_degree).coeffs;
+            if let Some(b) = previous_randomizer.take() {
+                for (c, b_j) in coeffs.iter_mut().zip(b) {
+                    *c -= b_j;
+                }
+            }
+            if i + 1 < num_chunks {
+                let b = F::rand_vec(committed_degree - chunk_degree);
+                for (c, &b_j) in coeffs[chunk_degree..].iter_mut().zip(&b) {
+                    *c += b_j;
+                }
+                previous_randomizer = Some(b);
+            }
+            PolynomialCoeffs::new(coeffs)
+        })
+        .collect()
+}
+
 /// Computes the quotient polynomials `(sum alpha^i C_i(x)) / Z_H(x)` for `alpha` in `alphas`,
 /// where the `C_i`s are the Stark constraints.
 fn compute_quotient_polys<'a, F, P, C, S, const D: usize>(
     stark: &S,
+    preprocessed_commitment: Option<&'a PolynomialBatch<F, C, D>>,
     trace_commitment: &'a PolynomialBatch<F, C, D>,
-    permutation_zs_commitment_challenges: &'a Option<(
-        PolynomialBatch<F, C, D>,
-        Vec<PermutationChallengeSet<F>>,
-    )>,
-    public_inputs: [F; S::PUBLIC_INPUTS],
+    permutation_ctl_zs_commitment: Option<&'a PolynomialBatch<F, C, D>>,
+    permutation_challenges: Option<&'a [PermutationChallengeSet<F>]>,
+    lookup_challenges: Option<&'a [F]>,
+    ctl_data: &CtlData<F>,
+    public_inputs: &[F],
     alphas: Vec<F>,
     degree_bits: usize,
+    num_permutation_zs: usize,
+    num_permutation_lookup_zs: usize,
     config: &StarkConfig,
 ) -> Vec<PolynomialCoeffs<F>>
 where
@@ -213,20 +519,26 @@ where
     P: PackedField<Scalar = F>,
     C: GenericConfig<D, F = F>,
     S: Stark<F, D>,
-    [(); S::COLUMNS]:,
-    [(); S::PUBLIC_INPUTS]:,
 {
     let degree = 1 << degree_bits;
-    let rate_bits = config.fri_config.rate_bits;
+    let committed_degree_bits =
+        config.committed_degree_bits(degree_bits, stark.num_opening_points());
+    // The committed polynomials' LDEs have `2^lde_bits` points.
+    let lde_bits = committed_degree_bits + config.fri_config.rate_bits;
 
-    let quotient_degree_bits = log2_ceil(stark.quotient_degree_factor());
+    // The quotients are evaluated on a coset of `2^quotient_degree_bits` times the trace length,
+    // enough to hold their chunks.
+    let quotient_degree_bits =
+        config.quotient_chunk_degree_bits(degree_bits, stark.num_opening_points()) - degree_bits
+            + log2_ceil(stark.num_quotient_chunks(config));
     assert!(
-        quotient_degree_bits <= rate_bits,
+        degree_bits + quotient_degree_bits <= lde_bits,
         "Having constraints of degree higher than the rate is not supported yet."
     );
-    let step = 1 << (rate_bits - quotient_degree_bits);
+    let step = 1 << (lde_bits - degree_bits - quotient_degree_bits);
     // When opening the `Z`s polys at the "next" point, need to look at the point `next_step` steps away.
     let next_step = 1 << quotient_degree_bits;
+    let extra_row_offsets = stark.extra_row_offsets();
 
     // Evaluation of the first Lagrange polynomial on the LDE domain.
     let lagrange_first = PolynomialValues::selector(degree, 0).lde_onto_coset(quotient_degree_bits);
@@ -236,14 +548,6 @@ where
 
     let z_h_on_coset = ZeroPolyOnCoset::<F>::new(degree_bits, quotient_degree_bits);
 
-    // Retrieve the LDE values at index `i`.
-    let get_trace_values_packed = |i_start| -> [P; S::COLUMNS] {
-        trace_commitment
-            .get_lde_values_packed(i_start, step)
-            .try_into()
-            .unwrap()
-    };
-
     // Last element of the subgroup.
     let last = F::primitive_root_of_unity(degree_bits).inverse();
     let size = degree << quotient_degree_bits;
@@ -273,23 +577,71 @@ where
                 lagrange_basis_first,
                 lagrange_basis_last,
             );
+            // Retrieve the LDE values at indices `i_start` and `i_next_start`.
+            let local_values = trace_commitment.get_lde_values_packed(i_start, step);
+            let next_values = trace_commitment.get_lde_values_packed(i_next_start, step);
+            let (preprocessed_local_values, preprocessed_next_values) = preprocessed_commitment
+                .map(|commitment| {
+                    (
+                        commitment.get_lde_values_packed(i_start, step),
+                        commitment.get_lde_values_packed(i_next_start, step),
+                    )
+                })
+                .unwrap_or_default();
+            let extra_row_values = extra_row_offsets
+                .iter()
+                .map(|&offset| {
+                    let i_shifted = (i_start as isize + offset * next_step as isize)
+                        .rem_euclid(size as isize) as usize;
+                    trace_commitment.get_lde_values_packed(i_shifted, step)
+                })
+                .collect::<Vec<_>>();
             let vars = StarkEvaluationVars {
-                local_values: &get_trace_values_packed(i_start),
-                next_values: &get_trace_values_packed(i_next_start),
-                public_inputs: &public_inputs,
+                local_values: &local_values,
+                next_values: &next_values,
+                preprocessed_local_values: &preprocessed_local_values,
+                preprocessed_next_values: &preprocessed_next_values,
+                extra_row_values: &extra_row_values,
+                public_inputs,
             };
-            let permutation_check_data = permutation_zs_commitment_challenges.as_ref().map(
-                |(permutation_zs_commitment, permutation_challenge_sets)| PermutationCheckVars {
-                    local_zs: permutation_zs_commitment.get_lde_values_packed(i_start, step),
-                    next_zs: permutation_zs_commitment.get_lde_values_packed(i_next_start, step),
+            let (local_zs, next_zs) = permutation_ctl_zs_commitment
+                .map(|commitment| {
+                    (
+                        commitment.get_lde_values_packed(i_start, step),
+                        commitment.get_lde_values_packed(i_next_start, step),
+                    )
+                })
+                .unwrap_or_default();
+            let permutation_check_data =
+                permutation_challenges.map(|permutation_challenge_sets| PermutationCheckVars {
+                    local_zs: local_zs[..num_permutation_zs].to_vec(),
+                    next_zs: next_zs[..num_permutation_zs].to_vec(),
                     permutation_challenge_sets: permutation_challenge_sets.to_vec(),
-                },
-            );
+                });
+            let lookup_vars = lookup_challenges.map(|challenges| LookupCheckVars {
+                local_values: local_zs[num_permutation_zs..num_permutation_lookup_zs].to_vec(),
+                next_values: next_zs[num_permutation_zs..num_permutation_lookup_zs].to_vec(),
+                challenges: challenges.to_vec(),
+            });
+            let ctl_vars = ctl_data
+                .zs_columns
+                .iter()
+                .enumerate()
+                .map(|(i, zs_columns)| CtlCheckVars::<F, F, P, 1> {
+                    local_z: local_zs[num_permutation_lookup_zs + i],
+                    next_z: next_zs[num_permutation_lookup_zs + i],
+                    challenges: zs_columns.challenge,
+                    columns: &zs_columns.columns,
+                    filter_column: &zs_columns.filter_column,
+                })
+                .collect::<Vec<_>>();
             eval_vanishing_poly::<F, F, P, C, S, D, 1>(
                 stark,
                 config,
                 vars,
                 permutation_check_data,
+                lookup_vars,
+                &ctl_vars,
                 &mut consumer,
             );
 
@@ -317,3 +669,38 @@ where
         .map(|values| values.coset_ifft(F::coset_shift()))
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::polynomial::PolynomialCoeffs;
+    use plonky2::field::types::{Field, Sample};
+    use plonky2::plonk::plonk_common::reduce_with_powers;
+
+    use crate::prover::blind_quotient_chunks;
+
+    #[test]
+    fn test_blind_quotient_chunks() {
+        type F = GoldilocksField;
+        let (chunk_degree, committed_degree, num_chunks) = (8, 16, 4);
+        let quotient = PolynomialCoeffs::new(F::rand_vec(chunk_degree * num_chunks));
+        let chunks = quotient.chunks(chunk_degree);
+        let blinded_chunks = blind_quotient_chunks(chunks.clone(), chunk_degree, committed_degree);
+
+        assert_eq!(blinded_chunks.len(), num_chunks);
+        assert!(blinded_chunks.iter().all(|c| c.len() == committed_degree));
+        for (chunk, blinded_chunk) in chunks.iter().zip(&blinded_chunks) {
+            assert_ne!(&chunk.padded(committed_degree), blinded_chunk);
+        }
+        // The randomizers cancel out when recombining the chunks.
+        let x = F::rand();
+        let evals =
+            |chunks: &[PolynomialCoeffs<F>]| chunks.iter().map(|c| c.eval(x)).collect::<Vec<_>>();
+        assert_eq!(
+            reduce_with_powers(&evals(&blinded_chunks), x.exp_u64(chunk_degree as u64)),
+            quotient.eval(x)
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use alloc::format;
 use alloc::vec::Vec;
 use core::iter::once;
 
@@ -6,7 +7,8 @@ use itertools::Itertools;
 use plonky2::field::extension::Extendable;
 use plonky2::field::types::Field;
 use plonky2::fri::witness_util::set_fri_proof_target;
-use plonky2::hash::hash_types::RichField;
+use plonky2::hash::hash_types::{MerkleCapTarget, RichField};
+use plonky2::iop::challenger::RecursiveChallenger;
 use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::iop::witness::Witness;
 use plonky2::plonk::circuit_builder::CircuitBuilder;
@@ -16,12 +18,20 @@ use plonky2::with_context;
 
 use crate::config::StarkConfig;
 use crate::constraint_consumer::RecursiveConstraintConsumer;
-use crate::permutation::PermutationCheckDataTarget;
+use crate::cross_table_lookup::{
+    verify_cross_table_lookups_circuit, CrossTableLookup, CtlCheckVarsTarget,
+};
+use crate::lookup::LookupCheckVarsTarget;
+use crate::multi_stark::DynStark;
+use crate::permutation::{get_permutation_challenge_set_target, PermutationCheckDataTarget};
+use crate::preprocessed::StarkVerifyingKey;
 use crate::proof::{
-    StarkOpeningSetTarget, StarkProof, StarkProofChallengesTarget, StarkProofTarget,
-    StarkProofWithPublicInputs, StarkProofWithPublicInputsTarget,
+    MultiStarkProof, MultiStarkProofTarget, StarkOpeningSet, StarkOpeningSetTarget, StarkProof,
+    StarkProofChallengesTarget, StarkProofTarget, StarkProofWithPublicInputs,
+    StarkProofWithPublicInputsTarget,
 };
 use crate::stark::Stark;
+use crate::util::num_leaves_per_oracle;
 use crate::vanishing_poly::eval_vanishing_poly_circuit;
 use crate::vars::StarkEvaluationTargets;
 
@@ -37,46 +47,166 @@ pub fn verify_stark_proof_circuit<
     inner_config: &StarkConfig,
 ) where
     C::Hasher: AlgebraicHasher<F>,
-    [(); S::COLUMNS]:,
-    [(); S::PUBLIC_INPUTS]:,
 {
-    assert_eq!(proof_with_pis.public_inputs.len(), S::PUBLIC_INPUTS);
-    let degree_bits = proof_with_pis.proof.recover_degree_bits(inner_config);
+    let degree_bits = proof_with_pis.proof.degree_bits;
+    let verifying_key = StarkVerifyingKey::<F, C, D>::new(&stark, inner_config, degree_bits);
+    verify_stark_proof_circuit_with_key::<F, C, S, D>(
+        builder,
+        stark,
+        proof_with_pis,
+        &verifying_key,
+        inner_config,
+    );
+}
+
+/// Recursively verifies an inner proof against a verifying key which was computed ahead of time.
+/// The preprocessed cap is hardcoded in the circuit as a constant.
+pub fn verify_stark_proof_circuit_with_key<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    stark: S,
+    proof_with_pis: StarkProofWithPublicInputsTarget<D>,
+    verifying_key: &StarkVerifyingKey<F, C, D>,
+    inner_config: &StarkConfig,
+) where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    assert_eq!(
+        proof_with_pis.public_inputs.len(),
+        stark.num_public_inputs()
+    );
+    let degree_bits = proof_with_pis.proof.degree_bits;
+    assert_eq!(verifying_key.degree_bits, degree_bits);
+    assert_eq!(
+        verifying_key.preprocessed_cap.is_some(),
+        stark.uses_preprocessed_columns()
+    );
+    let preprocessed_cap = verifying_key
+        .preprocessed_cap
+        .as_ref()
+        .map(|cap| builder.constant_merkle_cap(cap));
     let challenges = with_context!(
         builder,
         "compute challenges",
-        proof_with_pis.get_challenges::<F, C, S>(builder, &stark, inner_config)
+        proof_with_pis.get_challenges::<F, C, S>(
+            builder,
+            &stark,
+            preprocessed_cap.as_ref(),
+            inner_config
+        )
     );
 
     verify_stark_proof_with_challenges_circuit::<F, C, S, D>(
         builder,
-        stark,
-        proof_with_pis,
+        &stark,
+        &proof_with_pis,
+        preprocessed_cap.as_ref(),
         challenges,
+        &[],
         inner_config,
         degree_bits,
     );
 }
 
+/// Recursively verifies a multi-STARK proof, including its cross-table lookups.
+pub fn verify_multi_stark_proof_circuit<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    starks: &[&dyn DynStark<F, C, D>],
+    cross_table_lookups: &[CrossTableLookup<F>],
+    proof: MultiStarkProofTarget<D>,
+    inner_config: &StarkConfig,
+) where
+    C::Hasher: AlgebraicHasher<F>,
+{
+    assert_eq!(proof.stark_proofs.len(), starks.len());
+    let degrees_bits = proof
+        .stark_proofs
+        .iter()
+        .map(|p| p.proof.degree_bits)
+        .collect_vec();
+
+    let mut challenger = RecursiveChallenger::<F, C::Hasher, D>::new(builder);
+    for proof_with_pis in &proof.stark_proofs {
+        challenger.observe_cap(&proof_with_pis.proof.trace_cap);
+    }
+    let ctl_challenges =
+        get_permutation_challenge_set_target(builder, &mut challenger, inner_config.num_challenges);
+
+    let num_permutation_lookup_zs = starks
+        .iter()
+        .map(|stark| stark.num_permutation_lookup_zs(inner_config))
+        .collect_vec();
+    let ctl_vars_per_table = CtlCheckVarsTarget::from_proofs(
+        &proof.stark_proofs,
+        cross_table_lookups,
+        &ctl_challenges,
+        &num_permutation_lookup_zs,
+    );
+    for (i, ((stark, proof_with_pis), ctl_vars)) in starks
+        .iter()
+        .zip(&proof.stark_proofs)
+        .zip(&ctl_vars_per_table)
+        .enumerate()
+    {
+        with_context!(
+            builder,
+            &format!("verify STARK {}", i),
+            stark.verify_circuit_with_challenger(
+                builder,
+                inner_config,
+                proof_with_pis,
+                ctl_vars,
+                &mut challenger,
+            )
+        );
+    }
+
+    let ctl_zs_lasts = proof
+        .stark_proofs
+        .iter()
+        .map(|p| p.proof.openings.ctl_zs_last.as_deref().unwrap_or_default())
+        .collect_vec();
+    with_context!(
+        builder,
+        "verify cross-table lookups",
+        verify_cross_table_lookups_circuit(
+            builder,
+            cross_table_lookups,
+            &ctl_zs_lasts,
+            &degrees_bits,
+            &ctl_challenges,
+        )
+    );
+}
+
 /// Recursively verifies an inner proof.
-fn verify_stark_proof_with_challenges_circuit<
+pub(crate) fn verify_stark_proof_with_challenges_circuit<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
     S: Stark<F, D>,
     const D: usize,
 >(
     builder: &mut CircuitBuilder<F, D>,
-    stark: S,
-    proof_with_pis: StarkProofWithPublicInputsTarget<D>,
+    stark: &S,
+    proof_with_pis: &StarkProofWithPublicInputsTarget<D>,
+    preprocessed_cap: Option<&MerkleCapTarget>,
     challenges: StarkProofChallengesTarget<D>,
+    ctl_vars: &[CtlCheckVarsTarget<F, D>],
     inner_config: &StarkConfig,
     degree_bits: usize,
 ) where
     C::Hasher: AlgebraicHasher<F>,
-    [(); S::COLUMNS]:,
-    [(); S::PUBLIC_INPUTS]:,
 {
-    check_permutation_options(&stark, &proof_with_pis, &challenges).unwrap();
+    check_permutation_options(stark, &challenges).unwrap();
+    let zero = builder.zero();
     let one = builder.one_extension();
 
     let StarkProofWithPublicInputsTarget {
@@ -86,19 +216,25 @@ fn verify_stark_proof_with_challenges_circuit<
     let StarkOpeningSetTarget {
         local_values,
         next_values,
-        permutation_zs,
-        permutation_zs_next,
+        preprocessed_local_values,
+        preprocessed_next_values,
+        extra_row_values,
+        permutation_ctl_zs,
+        permutation_ctl_zs_next,
+        ctl_zs_last: _,
         quotient_polys,
     } = &proof.openings;
+    let public_inputs = public_inputs
+        .iter()
+        .map(|&t| builder.convert_to_ext(t))
+        .collect::<Vec<_>>();
     let vars = StarkEvaluationTargets {
-        local_values: &local_values.to_vec().try_into().unwrap(),
-        next_values: &next_values.to_vec().try_into().unwrap(),
-        public_inputs: &public_inputs
-            .into_iter()
-            .map(|t| builder.convert_to_ext(t))
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap(),
+        local_values,
+        next_values,
+        preprocessed_local_values: preprocessed_local_values.as_deref().unwrap_or_default(),
+        preprocessed_next_values: preprocessed_next_values.as_deref().unwrap_or_default(),
+        extra_row_values,
+        public_inputs: &public_inputs,
     };
 
     let zeta_pow_deg = builder.exp_power_of_2_extension(challenges.stark_zeta, degree_bits);
@@ -117,32 +253,50 @@ fn verify_stark_proof_with_challenges_circuit<
         l_last,
     );
 
+    let num_permutation_zs = stark.num_permutation_batches(inner_config);
     let permutation_data = stark
         .uses_permutation_args()
         .then(|| PermutationCheckDataTarget {
-            local_zs: permutation_zs.as_ref().unwrap().clone(),
-            next_zs: permutation_zs_next.as_ref().unwrap().clone(),
+            local_zs: permutation_ctl_zs.as_ref().unwrap()[..num_permutation_zs].to_vec(),
+            next_zs: permutation_ctl_zs_next.as_ref().unwrap()[..num_permutation_zs].to_vec(),
             permutation_challenge_sets: challenges.permutation_challenge_sets.unwrap(),
         });
+    let num_permutation_lookup_zs = stark.num_permutation_lookup_zs(inner_config);
+    let lookup_vars = stark.uses_lookups().then(|| LookupCheckVarsTarget {
+        local_values: permutation_ctl_zs.as_ref().unwrap()
+            [num_permutation_zs..num_permutation_lookup_zs]
+            .to_vec(),
+        next_values: permutation_ctl_zs_next.as_ref().unwrap()
+            [num_permutation_zs..num_permutation_lookup_zs]
+            .to_vec(),
+        challenges: challenges.lookup_challenges.unwrap(),
+    });
 
     with_context!(
         builder,
         "evaluate vanishing polynomial",
         eval_vanishing_poly_circuit::<F, C, S, D>(
             builder,
-            &stark,
+            stark,
             inner_config,
             vars,
             permutation_data,
+            lookup_vars,
+            ctl_vars,
             &mut consumer,
         )
     );
     let vanishing_polys_zeta = consumer.accumulators();
 
     // Check each polynomial identity, of the form `vanishing(x) = Z_H(x) quotient(x)`, at zeta.
-    let mut scale = ReducingFactorTarget::new(zeta_pow_deg);
+    // The degree bound of the quotient chunks exceeds the trace length in zero-knowledge mode.
+    let quotient_chunk_degree_bits =
+        inner_config.quotient_chunk_degree_bits(degree_bits, stark.num_opening_points());
+    let zeta_pow_chunk_deg =
+        builder.exp_power_of_2_extension(zeta_pow_deg, quotient_chunk_degree_bits - degree_bits);
+    let mut scale = ReducingFactorTarget::new(zeta_pow_chunk_deg);
     for (i, chunk) in quotient_polys
-        .chunks(stark.quotient_degree_factor())
+        .chunks(stark.num_quotient_chunks(inner_config))
         .enumerate()
     {
         let recombined_quotient = scale.reduce(chunk, builder);
@@ -150,24 +304,28 @@ fn verify_stark_proof_with_challenges_circuit<
         builder.connect_extension(vanishing_polys_zeta[i], computed_vanishing_poly);
     }
 
-    let merkle_caps = once(proof.trace_cap)
-        .chain(proof.permutation_zs_cap)
-        .chain(once(proof.quotient_polys_cap))
+    let merkle_caps = preprocessed_cap
+        .cloned()
+        .into_iter()
+        .chain(once(proof.trace_cap.clone()))
+        .chain(proof.permutation_ctl_zs_cap.clone())
+        .chain(once(proof.quotient_polys_cap.clone()))
         .collect_vec();
 
     let fri_instance = stark.fri_instance_target(
         builder,
         challenges.stark_zeta,
         F::primitive_root_of_unity(degree_bits),
+        ctl_vars.len(),
         inner_config,
     );
     builder.verify_fri_proof::<C>(
         &fri_instance,
-        &proof.openings.to_fri_openings(),
+        &proof.openings.to_fri_openings(zero),
         &challenges.fri_challenges,
         &merkle_caps,
         &proof.opening_proof,
-        &inner_config.fri_params(degree_bits),
+        &inner_config.fri_params(degree_bits, stark.num_opening_points()),
     );
 }
 
@@ -200,62 +358,136 @@ pub fn add_virtual_stark_proof_with_pis<
     config: &StarkConfig,
     degree_bits: usize,
 ) -> StarkProofWithPublicInputsTarget<D> {
-    let proof = add_virtual_stark_proof::<F, S, D>(builder, stark, config, degree_bits);
-    let public_inputs = builder.add_virtual_targets(S::PUBLIC_INPUTS);
+    add_virtual_table_proof_with_pis::<F, S, D>(builder, &stark, config, degree_bits, 0)
+}
+
+pub fn add_virtual_stark_proof<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    stark: S,
+    config: &StarkConfig,
+    degree_bits: usize,
+) -> StarkProofTarget<D> {
+    add_virtual_table_proof::<F, S, D>(builder, &stark, config, degree_bits, 0)
+}
+
+/// Adds the targets of a multi-STARK proof, where `degrees_bits[i]` is the log of the trace length
+/// of `starks[i]`.
+pub fn add_virtual_multi_stark_proof<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    starks: &[&dyn DynStark<F, C, D>],
+    cross_table_lookups: &[CrossTableLookup<F>],
+    config: &StarkConfig,
+    degrees_bits: &[usize],
+) -> MultiStarkProofTarget<D> {
+    assert_eq!(starks.len(), degrees_bits.len());
+    let stark_proofs = starks
+        .iter()
+        .zip(degrees_bits)
+        .enumerate()
+        .map(|(i, (stark, &degree_bits))| {
+            let num_ctl_zs =
+                CrossTableLookup::num_ctl_zs(cross_table_lookups, i, config.num_challenges);
+            stark.add_virtual_proof_with_pis(builder, config, degree_bits, num_ctl_zs)
+        })
+        .collect();
+    MultiStarkProofTarget { stark_proofs }
+}
+
+/// Adds the targets of a proof of one table of a multi-STARK, with `num_ctl_zs` cross-table
+/// lookup `Z` polynomials.
+pub(crate) fn add_virtual_table_proof_with_pis<
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    stark: &S,
+    config: &StarkConfig,
+    degree_bits: usize,
+    num_ctl_zs: usize,
+) -> StarkProofWithPublicInputsTarget<D> {
+    let proof = add_virtual_table_proof::<F, S, D>(builder, stark, config, degree_bits, num_ctl_zs);
+    let public_inputs = builder.add_virtual_targets(stark.num_public_inputs());
     StarkProofWithPublicInputsTarget {
         proof,
         public_inputs,
     }
 }
 
-pub fn add_virtual_stark_proof<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
+fn add_virtual_table_proof<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    stark: S,
+    stark: &S,
     config: &StarkConfig,
     degree_bits: usize,
+    num_ctl_zs: usize,
 ) -> StarkProofTarget<D> {
-    let fri_params = config.fri_params(degree_bits);
+    let fri_params = config.fri_params(degree_bits, stark.num_opening_points());
     let cap_height = fri_params.config.cap_height;
 
-    let num_leaves_per_oracle = once(S::COLUMNS)
-        .chain(
-            stark
-                .uses_permutation_args()
-                .then(|| stark.num_permutation_batches(config)),
-        )
-        .chain(once(stark.quotient_degree_factor() * config.num_challenges))
-        .collect_vec();
+    let num_zs = stark.num_permutation_lookup_zs(config) + num_ctl_zs;
+    let num_leaves_per_oracle = num_leaves_per_oracle(stark, config, num_ctl_zs);
 
-    let permutation_zs_cap = stark
-        .uses_permutation_args()
-        .then(|| builder.add_virtual_cap(cap_height));
+    let permutation_ctl_zs_cap = (num_zs > 0).then(|| builder.add_virtual_cap(cap_height));
 
     StarkProofTarget {
+        degree_bits,
         trace_cap: builder.add_virtual_cap(cap_height),
-        permutation_zs_cap,
+        permutation_ctl_zs_cap,
         quotient_polys_cap: builder.add_virtual_cap(cap_height),
-        openings: add_stark_opening_set_target::<F, S, D>(builder, stark, config),
+        openings: add_stark_opening_set_target::<F, S, D>(builder, stark, config, num_ctl_zs),
         opening_proof: builder.add_virtual_fri_proof(&num_leaves_per_oracle, &fri_params),
     }
 }
 
 fn add_stark_opening_set_target<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    stark: S,
+    stark: &S,
     config: &StarkConfig,
+    num_ctl_zs: usize,
 ) -> StarkOpeningSetTarget<D> {
-    let num_challenges = config.num_challenges;
+    let num_zs = stark.num_permutation_lookup_zs(config) + num_ctl_zs;
+    let num_preprocessed_columns = stark.num_preprocessed_columns();
     StarkOpeningSetTarget {
-        local_values: builder.add_virtual_extension_targets(S::COLUMNS),
-        next_values: builder.add_virtual_extension_targets(S::COLUMNS),
-        permutation_zs: stark
-            .uses_permutation_args()
-            .then(|| builder.add_virtual_extension_targets(stark.num_permutation_batches(config))),
-        permutation_zs_next: stark
-            .uses_permutation_args()
-            .then(|| builder.add_virtual_extension_targets(stark.num_permutation_batches(config))),
-        quotient_polys: builder
-            .add_virtual_extension_targets(stark.quotient_degree_factor() * num_challenges),
+        local_values: builder.add_virtual_extension_targets(stark.num_columns()),
+        next_values: builder.add_virtual_extension_targets(stark.num_columns()),
+        preprocessed_local_values: stark
+            .uses_preprocessed_columns()
+            .then(|| builder.add_virtual_extension_targets(num_preprocessed_columns)),
+        preprocessed_next_values: stark
+            .uses_preprocessed_columns()
+            .then(|| builder.add_virtual_extension_targets(num_preprocessed_columns)),
+        extra_row_values: stark
+            .extra_row_offsets()
+            .iter()
+            .map(|_| builder.add_virtual_extension_targets(stark.num_columns()))
+            .collect(),
+        permutation_ctl_zs: (num_zs > 0).then(|| builder.add_virtual_extension_targets(num_zs)),
+        permutation_ctl_zs_next: (num_zs > 0)
+            .then(|| builder.add_virtual_extension_targets(num_zs)),
+        ctl_zs_last: (num_ctl_zs > 0).then(|| builder.add_virtual_targets(num_ctl_zs)),
+        quotient_polys: builder.add_virtual_extension_targets(stark.num_quotient_polys(config)),
+    }
+}
+
+pub fn set_multi_stark_proof_target<F, C: GenericConfig<D, F = F>, W, const D: usize>(
+    witness: &mut W,
+    multi_proof_target: &MultiStarkProofTarget<D>,
+    multi_proof: &MultiStarkProof<F, C, D>,
+) where
+    F: RichField + Extendable<D>,
+    C::Hasher: AlgebraicHasher<F>,
+    W: Witness<F>,
+{
+    for (pt, p) in multi_proof_target
+        .stark_proofs
+        .iter()
+        .zip_eq(&multi_proof.stark_proofs)
+    {
+        set_stark_proof_with_pis_target(witness, pt, p);
     }
 }
 
@@ -297,38 +529,82 @@ pub fn set_stark_proof_target<F, C: GenericConfig<D, F = F>, W, const D: usize>(
     witness.set_cap_target(&proof_target.trace_cap, &proof.trace_cap);
     witness.set_cap_target(&proof_target.quotient_polys_cap, &proof.quotient_polys_cap);
 
-    witness.set_fri_openings(
-        &proof_target.openings.to_fri_openings(),
-        &proof.openings.to_fri_openings(),
-    );
+    set_stark_opening_set_target(witness, &proof_target.openings, &proof.openings);
 
-    if let (Some(permutation_zs_cap_target), Some(permutation_zs_cap)) =
-        (&proof_target.permutation_zs_cap, &proof.permutation_zs_cap)
-    {
-        witness.set_cap_target(permutation_zs_cap_target, permutation_zs_cap);
+    if let (Some(permutation_ctl_zs_cap_target), Some(permutation_ctl_zs_cap)) = (
+        &proof_target.permutation_ctl_zs_cap,
+        &proof.permutation_ctl_zs_cap,
+    ) {
+        witness.set_cap_target(permutation_ctl_zs_cap_target, permutation_ctl_zs_cap);
     }
 
     set_fri_proof_target(witness, &proof_target.opening_proof, &proof.opening_proof);
 }
 
-/// Utility function to check that all permutation data wrapped in `Option`s are `Some` iff
-/// the Stark uses a permutation argument.
+fn set_stark_opening_set_target<F, W, const D: usize>(
+    witness: &mut W,
+    openings_target: &StarkOpeningSetTarget<D>,
+    openings: &StarkOpeningSet<F, D>,
+) where
+    F: RichField + Extendable<D>,
+    W: Witness<F>,
+{
+    witness.set_extension_targets(&openings_target.local_values, &openings.local_values);
+    witness.set_extension_targets(&openings_target.next_values, &openings.next_values);
+    if let (Some(values_target), Some(values)) = (
+        &openings_target.preprocessed_local_values,
+        &openings.preprocessed_local_values,
+    ) {
+        witness.set_extension_targets(values_target, values);
+    }
+    if let (Some(values_target), Some(values)) = (
+        &openings_target.preprocessed_next_values,
+        &openings.preprocessed_next_values,
+    ) {
+        witness.set_extension_targets(values_target, values);
+    }
+    for (values_target, values) in openings_target
+        .extra_row_values
+        .iter()
+        .zip_eq(&openings.extra_row_values)
+    {
+        witness.set_extension_targets(values_target, values);
+    }
+    if let (Some(zs_target), Some(zs)) = (
+        &openings_target.permutation_ctl_zs,
+        &openings.permutation_ctl_zs,
+    ) {
+        witness.set_extension_targets(zs_target, zs);
+    }
+    if let (Some(zs_next_target), Some(zs_next)) = (
+        &openings_target.permutation_ctl_zs_next,
+        &openings.permutation_ctl_zs_next,
+    ) {
+        witness.set_extension_targets(zs_next_target, zs_next);
+    }
+    if let (Some(ctl_zs_last_target), Some(ctl_zs_last)) =
+        (&openings_target.ctl_zs_last, &openings.ctl_zs_last)
+    {
+        for (&t, &z) in ctl_zs_last_target.iter().zip_eq(ctl_zs_last) {
+            witness.set_target(t, z);
+        }
+    }
+    witness.set_extension_targets(&openings_target.quotient_polys, &openings.quotient_polys);
+}
+
+/// Utility function to check that the permutation and lookup challenges are `Some` iff the Stark
+/// uses permutation and lookup arguments respectively.
 fn check_permutation_options<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
     stark: &S,
-    proof_with_pis: &StarkProofWithPublicInputsTarget<D>,
     challenges: &StarkProofChallengesTarget<D>,
 ) -> Result<()> {
-    let options_is_some = [
-        proof_with_pis.proof.permutation_zs_cap.is_some(),
-        proof_with_pis.proof.openings.permutation_zs.is_some(),
-        proof_with_pis.proof.openings.permutation_zs_next.is_some(),
-        challenges.permutation_challenge_sets.is_some(),
-    ];
     ensure!(
-        options_is_some
-            .into_iter()
-            .all(|b| b == stark.uses_permutation_args()),
+        challenges.permutation_challenge_sets.is_some() == stark.uses_permutation_args(),
         "Permutation data doesn't match with Stark configuration."
     );
+    ensure!(
+        challenges.lookup_challenges.is_some() == stark.uses_lookups(),
+        "Lookup data doesn't match with Stark configuration."
+    );
     Ok(())
 }
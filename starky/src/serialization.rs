@@ -0,0 +1,517 @@
+//! Binary serialization of STARK proofs and configurations, extending plonky2's [`Read`] and
+//! [`Write`] traits.
+//!
+//! As with plonky2 proofs, only the contents of a proof and the length of its trace are written.
+//! Its shape is recovered when reading it from the STARK and the configuration.
+
+use alloc::vec::Vec;
+
+use plonky2::field::extension::Extendable;
+use plonky2::field::polynomial::PolynomialCoeffs;
+use plonky2::fri::proof::{
+    CompressedFriProof, CompressedFriQueryRounds, FriInitialTreeProof, FriProof, FriQueryRound,
+};
+use plonky2::fri::FriParams;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::config::GenericConfig;
+use plonky2::util::serialization::{IoError, IoResult, Read, Write};
+
+use crate::config::StarkConfig;
+use crate::proof::{
+    CompressedStarkProof, CompressedStarkProofWithPublicInputs, StarkOpeningSet, StarkProof,
+    StarkProofWithPublicInputs,
+};
+use crate::stark::Stark;
+use crate::util::num_leaves_per_oracle;
+
+/// Reading STARK proofs and configurations.
+pub trait StarkRead: Read {
+    /// Reads a value of type [`StarkConfig`] from `self`.
+    #[inline]
+    fn read_stark_config(&mut self) -> IoResult<StarkConfig> {
+        let security_bits = self.read_u32()? as usize;
+        let num_challenges = self.read_u32()? as usize;
+        let fri_config = self.read_fri_config()?;
+        let zero_knowledge = match self.read_u8()? {
+            0 => false,
+            1 => true,
+            _ => return Err(IoError),
+        };
+        Ok(StarkConfig {
+            security_bits,
+            num_challenges,
+            fri_config,
+            zero_knowledge,
+        })
+    }
+
+    /// Reads a value of type [`StarkOpeningSet`] from `self`, for a proof of `stark` with
+    /// `num_ctl_zs` cross-table lookup `Z` polynomials.
+    #[inline]
+    fn read_stark_opening_set<F, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        config: &StarkConfig,
+        num_ctl_zs: usize,
+    ) -> IoResult<StarkOpeningSet<F, D>>
+    where
+        F: RichField + Extendable<D>,
+        S: Stark<F, D>,
+    {
+        let num_columns = stark.num_columns();
+        let num_preprocessed_columns = stark.num_preprocessed_columns();
+        let num_zs = stark.num_permutation_lookup_zs(config) + num_ctl_zs;
+
+        let local_values = self.read_field_ext_vec::<F, D>(num_columns)?;
+        let next_values = self.read_field_ext_vec::<F, D>(num_columns)?;
+        let (preprocessed_local_values, preprocessed_next_values) =
+            if stark.uses_preprocessed_columns() {
+                (
+                    Some(self.read_field_ext_vec::<F, D>(num_preprocessed_columns)?),
+                    Some(self.read_field_ext_vec::<F, D>(num_preprocessed_columns)?),
+                )
+            } else {
+                (None, None)
+            };
+        let extra_row_values = stark
+            .extra_row_offsets()
+            .iter()
+            .map(|_| self.read_field_ext_vec::<F, D>(num_columns))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (permutation_ctl_zs, permutation_ctl_zs_next) = if num_zs > 0 {
+            (
+                Some(self.read_field_ext_vec::<F, D>(num_zs)?),
+                Some(self.read_field_ext_vec::<F, D>(num_zs)?),
+            )
+        } else {
+            (None, None)
+        };
+        let ctl_zs_last = if num_ctl_zs > 0 {
+            Some(self.read_field_vec(num_ctl_zs)?)
+        } else {
+            None
+        };
+        let quotient_polys = self.read_field_ext_vec::<F, D>(stark.num_quotient_polys(config))?;
+        Ok(StarkOpeningSet {
+            local_values,
+            next_values,
+            preprocessed_local_values,
+            preprocessed_next_values,
+            extra_row_values,
+            permutation_ctl_zs,
+            permutation_ctl_zs_next,
+            ctl_zs_last,
+            quotient_polys,
+        })
+    }
+
+    /// Reads a value of type [`FriInitialTreeProof`] from `self`, for oracles of the given sizes.
+    #[inline]
+    fn read_stark_fri_initial_proof<F, C, const D: usize>(
+        &mut self,
+        num_leaves_per_oracle: &[usize],
+    ) -> IoResult<FriInitialTreeProof<F, C::Hasher>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let evals_proofs = num_leaves_per_oracle
+            .iter()
+            .map(|&num_leaves| {
+                let leaves = self.read_field_vec(num_leaves)?;
+                let proof = self.read_merkle_proof()?;
+                Ok((leaves, proof))
+            })
+            .collect::<IoResult<_>>()?;
+        Ok(FriInitialTreeProof { evals_proofs })
+    }
+
+    /// Reads a vector of [`FriQueryRound`]s from `self`, for oracles of the given sizes.
+    #[inline]
+    fn read_stark_fri_query_rounds<F, C, const D: usize>(
+        &mut self,
+        num_leaves_per_oracle: &[usize],
+        params: &FriParams,
+    ) -> IoResult<Vec<FriQueryRound<F, C::Hasher, D>>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        (0..params.config.num_query_rounds)
+            .map(|_| {
+                let initial_trees_proof =
+                    self.read_stark_fri_initial_proof::<F, C, D>(num_leaves_per_oracle)?;
+                let steps = params
+                    .reduction_arity_bits
+                    .iter()
+                    .map(|&arity_bits| self.read_fri_query_step::<F, C, D>(1 << arity_bits, false))
+                    .collect::<Result<_, _>>()?;
+                Ok(FriQueryRound {
+                    initial_trees_proof,
+                    steps,
+                })
+            })
+            .collect()
+    }
+
+    /// Reads a value of type [`FriProof`] from `self`, for oracles of the given sizes.
+    #[inline]
+    fn read_stark_fri_proof<F, C, const D: usize>(
+        &mut self,
+        num_leaves_per_oracle: &[usize],
+        params: &FriParams,
+    ) -> IoResult<FriProof<F, C::Hasher, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let commit_phase_merkle_caps = (0..params.reduction_arity_bits.len())
+            .map(|_| self.read_merkle_cap(params.config.cap_height))
+            .collect::<Result<Vec<_>, _>>()?;
+        let query_round_proofs =
+            self.read_stark_fri_query_rounds::<F, C, D>(num_leaves_per_oracle, params)?;
+        let final_poly =
+            PolynomialCoeffs::new(self.read_field_ext_vec::<F, D>(params.final_poly_len())?);
+        let pow_witness = self.read_field()?;
+        Ok(FriProof {
+            commit_phase_merkle_caps,
+            query_round_proofs,
+            final_poly,
+            pow_witness,
+        })
+    }
+
+    /// Reads the log of the trace length of a proof of `stark`, rejecting traces whose
+    /// low-degree extension would not fit in the two-adic subgroup of `F`.
+    #[inline]
+    fn read_stark_degree_bits<F, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> IoResult<usize>
+    where
+        F: RichField + Extendable<D>,
+        S: Stark<F, D>,
+    {
+        let degree_bits = self.read_u8()? as usize;
+        let lde_bits = config.committed_degree_bits(degree_bits, stark.num_opening_points())
+            + config.fri_config.rate_bits;
+        if lde_bits > F::TWO_ADICITY {
+            return Err(IoError);
+        }
+        Ok(degree_bits)
+    }
+
+    /// Reads a value of type [`StarkProof`] from `self`, for a proof of `stark` with `num_ctl_zs`
+    /// cross-table lookup `Z` polynomials.
+    #[inline]
+    fn read_stark_proof<F, C, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        config: &StarkConfig,
+        num_ctl_zs: usize,
+    ) -> IoResult<StarkProof<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    {
+        let degree_bits = self.read_stark_degree_bits::<F, S, D>(stark, config)?;
+        let cap_height = config.fri_config.cap_height;
+        let num_zs = stark.num_permutation_lookup_zs(config) + num_ctl_zs;
+        let trace_cap = self.read_merkle_cap(cap_height)?;
+        let permutation_ctl_zs_cap = if num_zs > 0 {
+            Some(self.read_merkle_cap(cap_height)?)
+        } else {
+            None
+        };
+        let quotient_polys_cap = self.read_merkle_cap(cap_height)?;
+        let openings = self.read_stark_opening_set(stark, config, num_ctl_zs)?;
+        let opening_proof = self.read_stark_fri_proof::<F, C, D>(
+            &num_leaves_per_oracle(stark, config, num_ctl_zs),
+            &config.fri_params(degree_bits, stark.num_opening_points()),
+        )?;
+        Ok(StarkProof {
+            degree_bits,
+            trace_cap,
+            permutation_ctl_zs_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof,
+        })
+    }
+
+    /// Reads a value of type [`StarkProofWithPublicInputs`] from `self`, for a proof of `stark`.
+    #[inline]
+    fn read_stark_proof_with_public_inputs<F, C, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> IoResult<StarkProofWithPublicInputs<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    {
+        let proof = self.read_stark_proof(stark, config, 0)?;
+        let public_inputs = self.read_field_vec(stark.num_public_inputs())?;
+        Ok(StarkProofWithPublicInputs {
+            proof,
+            public_inputs,
+        })
+    }
+
+    /// Reads a value of type [`CompressedFriQueryRounds`] from `self`, for oracles of the given
+    /// sizes.
+    #[inline]
+    fn read_compressed_stark_fri_query_rounds<F, C, const D: usize>(
+        &mut self,
+        num_leaves_per_oracle: &[usize],
+        params: &FriParams,
+    ) -> IoResult<CompressedFriQueryRounds<F, C::Hasher, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let original_indices = (0..params.config.num_query_rounds)
+            .map(|_| self.read_u32().map(|i| i as usize))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut indices = original_indices.clone();
+        indices.sort_unstable();
+        indices.dedup();
+        let initial_trees_proofs = indices
+            .iter()
+            .map(|&i| {
+                let proof = self.read_stark_fri_initial_proof::<F, C, D>(num_leaves_per_oracle)?;
+                Ok((i, proof))
+            })
+            .collect::<IoResult<_>>()?;
+
+        let mut steps = Vec::with_capacity(params.reduction_arity_bits.len());
+        for &arity_bits in &params.reduction_arity_bits {
+            indices.iter_mut().for_each(|x| *x >>= arity_bits);
+            indices.dedup();
+            let query_steps = (0..indices.len())
+                .map(|_| self.read_fri_query_step::<F, C, D>(1 << arity_bits, true))
+                .collect::<Result<Vec<_>, _>>()?;
+            steps.push(indices.iter().copied().zip(query_steps).collect());
+        }
+
+        Ok(CompressedFriQueryRounds {
+            indices: original_indices,
+            initial_trees_proofs,
+            steps,
+        })
+    }
+
+    /// Reads a value of type [`CompressedFriProof`] from `self`, for oracles of the given sizes.
+    #[inline]
+    fn read_compressed_stark_fri_proof<F, C, const D: usize>(
+        &mut self,
+        num_leaves_per_oracle: &[usize],
+        params: &FriParams,
+    ) -> IoResult<CompressedFriProof<F, C::Hasher, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let commit_phase_merkle_caps = (0..params.reduction_arity_bits.len())
+            .map(|_| self.read_merkle_cap(params.config.cap_height))
+            .collect::<Result<Vec<_>, _>>()?;
+        let query_round_proofs =
+            self.read_compressed_stark_fri_query_rounds::<F, C, D>(num_leaves_per_oracle, params)?;
+        let final_poly =
+            PolynomialCoeffs::new(self.read_field_ext_vec::<F, D>(params.final_poly_len())?);
+        let pow_witness = self.read_field()?;
+        Ok(CompressedFriProof {
+            commit_phase_merkle_caps,
+            query_round_proofs,
+            final_poly,
+            pow_witness,
+        })
+    }
+
+    /// Reads a value of type [`CompressedStarkProof`] from `self`, for a proof of `stark` with
+    /// `num_ctl_zs` cross-table lookup `Z` polynomials.
+    #[inline]
+    fn read_compressed_stark_proof<F, C, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        config: &StarkConfig,
+        num_ctl_zs: usize,
+    ) -> IoResult<CompressedStarkProof<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    {
+        let degree_bits = self.read_stark_degree_bits::<F, S, D>(stark, config)?;
+        let cap_height = config.fri_config.cap_height;
+        let num_zs = stark.num_permutation_lookup_zs(config) + num_ctl_zs;
+        let trace_cap = self.read_merkle_cap(cap_height)?;
+        let permutation_ctl_zs_cap = if num_zs > 0 {
+            Some(self.read_merkle_cap(cap_height)?)
+        } else {
+            None
+        };
+        let quotient_polys_cap = self.read_merkle_cap(cap_height)?;
+        let openings = self.read_stark_opening_set(stark, config, num_ctl_zs)?;
+        let opening_proof = self.read_compressed_stark_fri_proof::<F, C, D>(
+            &num_leaves_per_oracle(stark, config, num_ctl_zs),
+            &config.fri_params(degree_bits, stark.num_opening_points()),
+        )?;
+        Ok(CompressedStarkProof {
+            degree_bits,
+            trace_cap,
+            permutation_ctl_zs_cap,
+            quotient_polys_cap,
+            openings,
+            opening_proof,
+        })
+    }
+
+    /// Reads a value of type [`CompressedStarkProofWithPublicInputs`] from `self`, for a proof of
+    /// `stark`.
+    #[inline]
+    fn read_compressed_stark_proof_with_public_inputs<F, C, S, const D: usize>(
+        &mut self,
+        stark: &S,
+        config: &StarkConfig,
+    ) -> IoResult<CompressedStarkProofWithPublicInputs<F, C, D>>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+        S: Stark<F, D>,
+    {
+        let proof = self.read_compressed_stark_proof(stark, config, 0)?;
+        let public_inputs = self.read_field_vec(stark.num_public_inputs())?;
+        Ok(CompressedStarkProofWithPublicInputs {
+            proof,
+            public_inputs,
+        })
+    }
+}
+
+impl<R: Read + ?Sized> StarkRead for R {}
+
+/// Writing STARK proofs and configurations.
+pub trait StarkWrite: Write {
+    /// Writes a value `config` of type [`StarkConfig`] to `self`.
+    #[inline]
+    fn write_stark_config(&mut self, config: &StarkConfig) -> IoResult<()> {
+        self.write_u32(config.security_bits as u32)?;
+        self.write_u32(config.num_challenges as u32)?;
+        self.write_fri_config(&config.fri_config)?;
+        self.write_u8(u8::from(config.zero_knowledge))
+    }
+
+    /// Writes a value `os` of type [`StarkOpeningSet`] to `self`.
+    #[inline]
+    fn write_stark_opening_set<F, const D: usize>(
+        &mut self,
+        os: &StarkOpeningSet<F, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+    {
+        self.write_field_ext_vec::<F, D>(&os.local_values)?;
+        self.write_field_ext_vec::<F, D>(&os.next_values)?;
+        if let Some(values) = &os.preprocessed_local_values {
+            self.write_field_ext_vec::<F, D>(values)?;
+        }
+        if let Some(values) = &os.preprocessed_next_values {
+            self.write_field_ext_vec::<F, D>(values)?;
+        }
+        for values in &os.extra_row_values {
+            self.write_field_ext_vec::<F, D>(values)?;
+        }
+        if let Some(zs) = &os.permutation_ctl_zs {
+            self.write_field_ext_vec::<F, D>(zs)?;
+        }
+        if let Some(zs) = &os.permutation_ctl_zs_next {
+            self.write_field_ext_vec::<F, D>(zs)?;
+        }
+        if let Some(zs) = &os.ctl_zs_last {
+            self.write_field_vec(zs)?;
+        }
+        self.write_field_ext_vec::<F, D>(&os.quotient_polys)
+    }
+
+    /// Writes a value `proof` of type [`StarkProof`] to `self`.
+    #[inline]
+    fn write_stark_proof<F, C, const D: usize>(
+        &mut self,
+        proof: &StarkProof<F, C, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        self.write_u8(proof.degree_bits as u8)?;
+        self.write_merkle_cap(&proof.trace_cap)?;
+        if let Some(cap) = &proof.permutation_ctl_zs_cap {
+            self.write_merkle_cap(cap)?;
+        }
+        self.write_merkle_cap(&proof.quotient_polys_cap)?;
+        self.write_stark_opening_set(&proof.openings)?;
+        self.write_fri_proof::<F, C, D>(&proof.opening_proof)
+    }
+
+    /// Writes a value `proof_with_pis` of type [`StarkProofWithPublicInputs`] to `self`.
+    #[inline]
+    fn write_stark_proof_with_public_inputs<F, C, const D: usize>(
+        &mut self,
+        proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let StarkProofWithPublicInputs {
+            proof,
+            public_inputs,
+        } = proof_with_pis;
+        self.write_stark_proof(proof)?;
+        self.write_field_vec(public_inputs)
+    }
+
+    /// Writes a value `proof` of type [`CompressedStarkProof`] to `self`.
+    #[inline]
+    fn write_compressed_stark_proof<F, C, const D: usize>(
+        &mut self,
+        proof: &CompressedStarkProof<F, C, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        self.write_u8(proof.degree_bits as u8)?;
+        self.write_merkle_cap(&proof.trace_cap)?;
+        if let Some(cap) = &proof.permutation_ctl_zs_cap {
+            self.write_merkle_cap(cap)?;
+        }
+        self.write_merkle_cap(&proof.quotient_polys_cap)?;
+        self.write_stark_opening_set(&proof.openings)?;
+        self.write_compressed_fri_proof::<F, C, D>(&proof.opening_proof)
+    }
+
+    /// Writes a value `proof_with_pis` of type [`CompressedStarkProofWithPublicInputs`] to `self`.
+    #[inline]
+    fn write_compressed_stark_proof_with_public_inputs<F, C, const D: usize>(
+        &mut self,
+        proof_with_pis: &CompressedStarkProofWithPublicInputs<F, C, D>,
+    ) -> IoResult<()>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let CompressedStarkProofWithPublicInputs {
+            proof,
+            public_inputs,
+        } = proof_with_pis;
+        self.write_compressed_stark_proof(proof)?;
+        self.write_field_vec(public_inputs)
+    }
+}
+
+impl<W: Write + ?Sized> StarkWrite for W {}
@@ -3,6 +3,7 @@ use alloc::vec::Vec;
 
 use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::packed::PackedField;
+use plonky2::field::polynomial::PolynomialValues;
 use plonky2::fri::structure::{
     FriBatchInfo, FriBatchInfoTarget, FriInstanceInfo, FriInstanceInfoTarget, FriOracleInfo,
     FriPolynomialInfo,
@@ -14,15 +15,41 @@ use plonky2::util::ceil_div_usize;
 
 use crate::config::StarkConfig;
 use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::lookup::Lookup;
 use crate::permutation::PermutationPair;
+use crate::util::row_shift;
 use crate::vars::{StarkEvaluationTargets, StarkEvaluationVars};
 
 /// Represents a STARK system.
 pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
     /// The total number of columns in the trace.
-    const COLUMNS: usize;
+    fn num_columns(&self) -> usize;
     /// The number of public inputs.
-    const PUBLIC_INPUTS: usize;
+    fn num_public_inputs(&self) -> usize;
+
+    /// The number of preprocessed columns, which hold values fixed by the STARK itself rather than
+    /// by the witness. They are committed to once, in the verifying key. Zero by default.
+    fn num_preprocessed_columns(&self) -> usize {
+        0
+    }
+
+    /// Generates the `num_preprocessed_columns()` preprocessed columns for a trace of `num_rows`
+    /// rows.
+    fn preprocessed_columns(&self, _num_rows: usize) -> Vec<PolynomialValues<F>> {
+        vec![]
+    }
+
+    fn uses_preprocessed_columns(&self) -> bool {
+        self.num_preprocessed_columns() > 0
+    }
+
+    /// Row offsets, other than `0` and `1`, at which the constraints read the trace, e.g. `[2]` for
+    /// a window of three rows or `[-1]` to also read the previous row. The trace values at these
+    /// rows are given in `extra_row_values`, in the same order. Like `next_values` on the last row,
+    /// shifted rows wrap around the trace.
+    fn extra_row_offsets(&self) -> Vec<isize> {
+        vec![]
+    }
 
     /// Evaluate constraints at a vector of points.
     ///
@@ -32,7 +59,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
     /// constraints over `F`.
     fn eval_packed_generic<FE, P, const D2: usize>(
         &self,
-        vars: StarkEvaluationVars<FE, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        vars: StarkEvaluationVars<FE, P>,
         yield_constr: &mut ConstraintConsumer<P>,
     ) where
         FE: FieldExtension<D2, BaseField = F>,
@@ -41,7 +68,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
     /// Evaluate constraints at a vector of points from the base field `F`.
     fn eval_packed_base<P: PackedField<Scalar = F>>(
         &self,
-        vars: StarkEvaluationVars<F, P, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        vars: StarkEvaluationVars<F, P>,
         yield_constr: &mut ConstraintConsumer<P>,
     ) {
         self.eval_packed_generic(vars, yield_constr)
@@ -50,12 +77,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
     /// Evaluate constraints at a single point from the degree `D` extension field.
     fn eval_ext(
         &self,
-        vars: StarkEvaluationVars<
-            F::Extension,
-            F::Extension,
-            { Self::COLUMNS },
-            { Self::PUBLIC_INPUTS },
-        >,
+        vars: StarkEvaluationVars<F::Extension, F::Extension>,
         yield_constr: &mut ConstraintConsumer<F::Extension>,
     ) {
         self.eval_packed_generic(vars, yield_constr)
@@ -68,7 +90,7 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
     fn eval_ext_circuit(
         &self,
         builder: &mut CircuitBuilder<F, D>,
-        vars: StarkEvaluationTargets<D, { Self::COLUMNS }, { Self::PUBLIC_INPUTS }>,
+        vars: StarkEvaluationTargets<D>,
         yield_constr: &mut RecursiveConstraintConsumer<F, D>,
     );
 
@@ -80,102 +102,184 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         1.max(self.constraint_degree() - 1)
     }
 
+    /// The number of chunks that each quotient polynomial is split into, each of the degree bound
+    /// given by `StarkConfig::quotient_chunk_degree_bits`.
+    fn num_quotient_chunks(&self, config: &StarkConfig) -> usize {
+        if config.zero_knowledge {
+            // Randomized trace polynomials have degree `< m` for the committed degree bound `m > n`,
+            // so a quotient has degree `< d m - n` and fits into `2 d` chunks of degree `< m / 2`.
+            2 * self.constraint_degree().max(1)
+        } else {
+            self.quotient_degree_factor()
+        }
+    }
+
     fn num_quotient_polys(&self, config: &StarkConfig) -> usize {
-        self.quotient_degree_factor() * config.num_challenges
+        self.num_quotient_chunks(config) * config.num_challenges
+    }
+
+    /// The number of points outside of the trace domain at which the trace polynomials are opened:
+    /// `zeta`, `g * zeta` and one point per extra row offset. Cross-table lookup `Z`s are also
+    /// opened at `g^-1`, but this point lies in the trace domain.
+    fn num_opening_points(&self) -> usize {
+        2 + self.extra_row_offsets().len()
     }
 
-    /// Computes the FRI instance used to prove this Stark.
+    /// Computes the FRI instance used to prove this Stark, given the number of cross-table lookup
+    /// `Z` polynomials of its trace.
     fn fri_instance(
         &self,
         zeta: F::Extension,
         g: F,
+        num_ctl_zs: usize,
         config: &StarkConfig,
     ) -> FriInstanceInfo<F, D> {
         let mut oracles = vec![];
 
-        let trace_info = FriPolynomialInfo::from_range(oracles.len(), 0..Self::COLUMNS);
+        let num_preprocessed_columns = self.num_preprocessed_columns();
+        let preprocessed_info = if num_preprocessed_columns > 0 {
+            let oracle_index = oracles.len();
+            oracles.push(FriOracleInfo {
+                num_polys: num_preprocessed_columns,
+                blinding: false,
+            });
+            FriPolynomialInfo::from_range(oracle_index, 0..num_preprocessed_columns)
+        } else {
+            vec![]
+        };
+
+        let trace_info = FriPolynomialInfo::from_range(oracles.len(), 0..self.num_columns());
         oracles.push(FriOracleInfo {
-            num_polys: Self::COLUMNS,
-            blinding: false,
+            num_polys: self.num_columns(),
+            blinding: config.zero_knowledge,
         });
 
-        let permutation_zs_info = if self.uses_permutation_args() {
-            let num_z_polys = self.num_permutation_batches(config);
-            let polys = FriPolynomialInfo::from_range(oracles.len(), 0..num_z_polys);
+        let num_permutation_lookup_zs = self.num_permutation_lookup_zs(config);
+        let num_permutation_ctl_zs = num_permutation_lookup_zs + num_ctl_zs;
+        let (permutation_ctl_zs_info, ctl_zs_info) = if num_permutation_ctl_zs > 0 {
+            let oracle_index = oracles.len();
             oracles.push(FriOracleInfo {
-                num_polys: num_z_polys,
-                blinding: false,
+                num_polys: num_permutation_ctl_zs,
+                blinding: config.zero_knowledge,
             });
-            polys
+            (
+                FriPolynomialInfo::from_range(oracle_index, 0..num_permutation_ctl_zs),
+                FriPolynomialInfo::from_range(
+                    oracle_index,
+                    num_permutation_lookup_zs..num_permutation_ctl_zs,
+                ),
+            )
         } else {
-            vec![]
+            (vec![], vec![])
         };
 
-        let num_quotient_polys = self.quotient_degree_factor() * config.num_challenges;
+        let num_quotient_polys = self.num_quotient_polys(config);
         let quotient_info = FriPolynomialInfo::from_range(oracles.len(), 0..num_quotient_polys);
         oracles.push(FriOracleInfo {
             num_polys: num_quotient_polys,
-            blinding: false,
+            blinding: config.zero_knowledge,
         });
 
         let zeta_batch = FriBatchInfo {
             point: zeta,
             polynomials: [
+                preprocessed_info.clone(),
                 trace_info.clone(),
-                permutation_zs_info.clone(),
+                permutation_ctl_zs_info.clone(),
                 quotient_info,
             ]
             .concat(),
         };
         let zeta_next_batch = FriBatchInfo {
             point: zeta.scalar_mul(g),
-            polynomials: [trace_info, permutation_zs_info].concat(),
+            polynomials: [
+                preprocessed_info,
+                trace_info.clone(),
+                permutation_ctl_zs_info,
+            ]
+            .concat(),
         };
-        let batches = vec![zeta_batch, zeta_next_batch];
+        let mut batches = vec![zeta_batch, zeta_next_batch];
+        batches.extend(
+            self.extra_row_offsets()
+                .into_iter()
+                .map(|offset| FriBatchInfo {
+                    point: zeta.scalar_mul(row_shift(g, offset)),
+                    polynomials: trace_info.clone(),
+                }),
+        );
+        if num_ctl_zs > 0 {
+            batches.push(FriBatchInfo {
+                point: F::Extension::from_basefield(g.inverse()),
+                polynomials: ctl_zs_info,
+            });
+        }
 
         FriInstanceInfo { oracles, batches }
     }
 
-    /// Computes the FRI instance used to prove this Stark.
+    /// Computes the FRI instance used to prove this Stark, given the number of cross-table lookup
+    /// `Z` polynomials of its trace.
     fn fri_instance_target(
         &self,
         builder: &mut CircuitBuilder<F, D>,
         zeta: ExtensionTarget<D>,
         g: F,
+        num_ctl_zs: usize,
         config: &StarkConfig,
     ) -> FriInstanceInfoTarget<D> {
         let mut oracles = vec![];
 
-        let trace_info = FriPolynomialInfo::from_range(oracles.len(), 0..Self::COLUMNS);
+        let num_preprocessed_columns = self.num_preprocessed_columns();
+        let preprocessed_info = if num_preprocessed_columns > 0 {
+            let oracle_index = oracles.len();
+            oracles.push(FriOracleInfo {
+                num_polys: num_preprocessed_columns,
+                blinding: false,
+            });
+            FriPolynomialInfo::from_range(oracle_index, 0..num_preprocessed_columns)
+        } else {
+            vec![]
+        };
+
+        let trace_info = FriPolynomialInfo::from_range(oracles.len(), 0..self.num_columns());
         oracles.push(FriOracleInfo {
-            num_polys: Self::COLUMNS,
-            blinding: false,
+            num_polys: self.num_columns(),
+            blinding: config.zero_knowledge,
         });
 
-        let permutation_zs_info = if self.uses_permutation_args() {
-            let num_z_polys = self.num_permutation_batches(config);
-            let polys = FriPolynomialInfo::from_range(oracles.len(), 0..num_z_polys);
+        let num_permutation_lookup_zs = self.num_permutation_lookup_zs(config);
+        let num_permutation_ctl_zs = num_permutation_lookup_zs + num_ctl_zs;
+        let (permutation_ctl_zs_info, ctl_zs_info) = if num_permutation_ctl_zs > 0 {
+            let oracle_index = oracles.len();
             oracles.push(FriOracleInfo {
-                num_polys: num_z_polys,
-                blinding: false,
+                num_polys: num_permutation_ctl_zs,
+                blinding: config.zero_knowledge,
             });
-            polys
+            (
+                FriPolynomialInfo::from_range(oracle_index, 0..num_permutation_ctl_zs),
+                FriPolynomialInfo::from_range(
+                    oracle_index,
+                    num_permutation_lookup_zs..num_permutation_ctl_zs,
+                ),
+            )
         } else {
-            vec![]
+            (vec![], vec![])
         };
 
-        let num_quotient_polys = self.quotient_degree_factor() * config.num_challenges;
+        let num_quotient_polys = self.num_quotient_polys(config);
         let quotient_info = FriPolynomialInfo::from_range(oracles.len(), 0..num_quotient_polys);
         oracles.push(FriOracleInfo {
             num_polys: num_quotient_polys,
-            blinding: false,
+            blinding: config.zero_knowledge,
         });
 
         let zeta_batch = FriBatchInfoTarget {
             point: zeta,
             polynomials: [
+                preprocessed_info.clone(),
                 trace_info.clone(),
-                permutation_zs_info.clone(),
+                permutation_ctl_zs_info.clone(),
                 quotient_info,
             ]
             .concat(),
@@ -183,9 +287,28 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
         let zeta_next = builder.mul_const_extension(g, zeta);
         let zeta_next_batch = FriBatchInfoTarget {
             point: zeta_next,
-            polynomials: [trace_info, permutation_zs_info].concat(),
+            polynomials: [
+                preprocessed_info,
+                trace_info.clone(),
+                permutation_ctl_zs_info,
+            ]
+            .concat(),
         };
-        let batches = vec![zeta_batch, zeta_next_batch];
+        let mut batches = vec![zeta_batch, zeta_next_batch];
+        for offset in self.extra_row_offsets() {
+            let point = builder.mul_const_extension(row_shift(g, offset), zeta);
+            batches.push(FriBatchInfoTarget {
+                point,
+                polynomials: trace_info.clone(),
+            });
+        }
+        if num_ctl_zs > 0 {
+            let g_inv = builder.constant_extension(F::Extension::from_basefield(g.inverse()));
+            batches.push(FriBatchInfoTarget {
+                point: g_inv,
+                polynomials: ctl_zs_info,
+            });
+        }
 
         FriInstanceInfoTarget { oracles, batches }
     }
@@ -219,4 +342,36 @@ pub trait Stark<F: RichField + Extendable<D>, const D: usize>: Sync {
             self.permutation_batch_size(),
         )
     }
+
+    /// Lookups of some columns of the trace into a table column of the same trace, proven with the
+    /// logUp argument. Empty by default.
+    fn lookups(&self) -> Vec<Lookup<F>> {
+        vec![]
+    }
+
+    fn uses_lookups(&self) -> bool {
+        !self.lookups().is_empty()
+    }
+
+    /// The number of looking columns whose logUp terms are summed into a single helper column.
+    fn lookup_batch_size(&self) -> usize {
+        // A helper column `h` of a batch of `k` columns is checked by a constraint
+        //     h \prod(...) = \sum \prod(...)
+        // of degree `k + 1`, so the batch size is one less than our constraint degree.
+        self.quotient_degree_factor()
+    }
+
+    fn num_lookup_helper_columns(&self, config: &StarkConfig) -> usize {
+        self.lookups()
+            .iter()
+            .map(|lookup| lookup.num_helper_columns(self.lookup_batch_size()))
+            .sum::<usize>()
+            * config.num_challenges
+    }
+
+    /// The number of permutation `Z` polynomials and lookup helper columns, which precede the
+    /// cross-table lookup `Z` polynomials in the same oracle.
+    fn num_permutation_lookup_zs(&self, config: &StarkConfig) -> usize {
+        self.num_permutation_batches(config) + self.num_lookup_helper_columns(config)
+    }
 }
@@ -1,18 +1,22 @@
+use alloc::collections::BTreeMap;
 use alloc::vec;
 use alloc::vec::Vec;
 
-use anyhow::{ensure, Result};
+use anyhow::{anyhow, bail, ensure, Result};
+use itertools::Itertools;
 use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::interpolation::interpolant;
 use plonky2::field::polynomial::{PolynomialCoeffs, PolynomialValues};
 use plonky2::field::types::{Field, Sample};
 use plonky2::hash::hash_types::RichField;
 use plonky2::iop::witness::{PartialWitness, WitnessWrite};
 use plonky2::plonk::circuit_builder::CircuitBuilder;
 use plonky2::plonk::circuit_data::CircuitConfig;
-use plonky2::plonk::config::{GenericConfig, Hasher};
+use plonky2::plonk::config::GenericConfig;
 use plonky2::util::{log2_ceil, log2_strict, transpose};
 
 use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::cross_table_lookup::Column;
 use crate::stark::Stark;
 use crate::vars::{StarkEvaluationTargets, StarkEvaluationVars};
 
@@ -22,16 +26,17 @@ const WITNESS_SIZE: usize = 1 << 5;
 /// low-degree witness polynomials.
 pub fn test_stark_low_degree<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
     stark: S,
-) -> Result<()>
-where
-    [(); S::COLUMNS]:,
-    [(); S::PUBLIC_INPUTS]:,
-{
+) -> Result<()> {
     let rate_bits = log2_ceil(stark.constraint_degree() + 1);
 
-    let trace_ldes = random_low_degree_matrix::<F>(S::COLUMNS, rate_bits);
+    let trace_ldes = random_low_degree_matrix::<F>(stark.num_columns(), rate_bits);
     let size = trace_ldes.len();
-    let public_inputs = F::rand_array::<{ S::PUBLIC_INPUTS }>();
+    let preprocessed_ldes = if stark.uses_preprocessed_columns() {
+        random_low_degree_matrix::<F>(stark.num_preprocessed_columns(), rate_bits)
+    } else {
+        vec![vec![]; size]
+    };
+    let public_inputs = F::rand_vec(stark.num_public_inputs());
 
     let lagrange_first = PolynomialValues::selector(WITNESS_SIZE, 0).lde(rate_bits);
     let lagrange_last = PolynomialValues::selector(WITNESS_SIZE, WITNESS_SIZE - 1).lde(rate_bits);
@@ -40,14 +45,22 @@ where
     let subgroup =
         F::cyclic_subgroup_known_order(F::primitive_root_of_unity(log2_strict(size)), size);
     let alpha = F::rand();
+    let extra_row_offsets = stark.extra_row_offsets();
     let constraint_evals = (0..size)
         .map(|i| {
+            let extra_row_values = extra_row_offsets
+                .iter()
+                .map(|&offset| {
+                    let shifted = (i as isize + (offset << rate_bits)).rem_euclid(size as isize);
+                    trace_ldes[shifted as usize].clone()
+                })
+                .collect::<Vec<_>>();
             let vars = StarkEvaluationVars {
-                local_values: &trace_ldes[i].clone().try_into().unwrap(),
-                next_values: &trace_ldes[(i + (1 << rate_bits)) % size]
-                    .clone()
-                    .try_into()
-                    .unwrap(),
+                local_values: &trace_ldes[i],
+                next_values: &trace_ldes[(i + (1 << rate_bits)) % size],
+                preprocessed_local_values: &preprocessed_ldes[i],
+                preprocessed_next_values: &preprocessed_ldes[(i + (1 << rate_bits)) % size],
+                extra_row_values: &extra_row_values,
                 public_inputs: &public_inputs,
             };
 
@@ -85,17 +98,25 @@ pub fn test_stark_circuit_constraints<
     const D: usize,
 >(
     stark: S,
-) -> Result<()>
-where
-    [(); S::COLUMNS]:,
-    [(); S::PUBLIC_INPUTS]:,
-    [(); C::Hasher::HASH_SIZE]:,
-{
+) -> Result<()> {
     // Compute native constraint evaluation on random values.
+    let local_values = F::Extension::rand_vec(stark.num_columns());
+    let next_values = F::Extension::rand_vec(stark.num_columns());
+    let preprocessed_local_values = F::Extension::rand_vec(stark.num_preprocessed_columns());
+    let preprocessed_next_values = F::Extension::rand_vec(stark.num_preprocessed_columns());
+    let extra_row_values = stark
+        .extra_row_offsets()
+        .iter()
+        .map(|_| F::Extension::rand_vec(stark.num_columns()))
+        .collect::<Vec<_>>();
+    let public_inputs = F::Extension::rand_vec(stark.num_public_inputs());
     let vars = StarkEvaluationVars {
-        local_values: &F::Extension::rand_array::<{ S::COLUMNS }>(),
-        next_values: &F::Extension::rand_array::<{ S::COLUMNS }>(),
-        public_inputs: &F::Extension::rand_array::<{ S::PUBLIC_INPUTS }>(),
+        local_values: &local_values,
+        next_values: &next_values,
+        preprocessed_local_values: &preprocessed_local_values,
+        preprocessed_next_values: &preprocessed_next_values,
+        extra_row_values: &extra_row_values,
+        public_inputs: &public_inputs,
     };
     let alphas = F::rand_vec(1);
     let z_last = F::Extension::rand();
@@ -119,11 +140,26 @@ where
     let mut builder = CircuitBuilder::<F, D>::new(circuit_config);
     let mut pw = PartialWitness::<F>::new();
 
-    let locals_t = builder.add_virtual_extension_targets(S::COLUMNS);
+    let locals_t = builder.add_virtual_extension_targets(stark.num_columns());
     pw.set_extension_targets(&locals_t, vars.local_values);
-    let nexts_t = builder.add_virtual_extension_targets(S::COLUMNS);
+    let nexts_t = builder.add_virtual_extension_targets(stark.num_columns());
     pw.set_extension_targets(&nexts_t, vars.next_values);
-    let pis_t = builder.add_virtual_extension_targets(S::PUBLIC_INPUTS);
+    let preprocessed_locals_t =
+        builder.add_virtual_extension_targets(stark.num_preprocessed_columns());
+    pw.set_extension_targets(&preprocessed_locals_t, vars.preprocessed_local_values);
+    let preprocessed_nexts_t =
+        builder.add_virtual_extension_targets(stark.num_preprocessed_columns());
+    pw.set_extension_targets(&preprocessed_nexts_t, vars.preprocessed_next_values);
+    let extra_rows_t = vars
+        .extra_row_values
+        .iter()
+        .map(|values| {
+            let values_t = builder.add_virtual_extension_targets(stark.num_columns());
+            pw.set_extension_targets(&values_t, values);
+            values_t
+        })
+        .collect::<Vec<_>>();
+    let pis_t = builder.add_virtual_extension_targets(stark.num_public_inputs());
     pw.set_extension_targets(&pis_t, vars.public_inputs);
     let alphas_t = builder.add_virtual_targets(1);
     pw.set_target(alphas_t[0], alphas[0]);
@@ -134,10 +170,13 @@ where
     let lagrange_last_t = builder.add_virtual_extension_target();
     pw.set_extension_target(lagrange_last_t, lagrange_last);
 
-    let vars = StarkEvaluationTargets::<D, { S::COLUMNS }, { S::PUBLIC_INPUTS }> {
-        local_values: &locals_t.try_into().unwrap(),
-        next_values: &nexts_t.try_into().unwrap(),
-        public_inputs: &pis_t.try_into().unwrap(),
+    let vars = StarkEvaluationTargets::<D> {
+        local_values: &locals_t,
+        next_values: &nexts_t,
+        preprocessed_local_values: &preprocessed_locals_t,
+        preprocessed_next_values: &preprocessed_nexts_t,
+        extra_row_values: &extra_rows_t,
+        public_inputs: &pis_t,
     };
     let mut consumer = RecursiveConstraintConsumer::<F, D>::new(
         builder.zero_extension(),
@@ -156,6 +195,257 @@ where
     data.verify(proof)
 }
 
+/// Evaluates the constraints of `stark` row by row on a raw trace, rather than on its low-degree
+/// extension, and reports the first failing row and constraint along with the values it reads.
+/// Also checks that the permutation pairs and lookups of `stark` hold on the trace. This locates
+/// the bug when `prove` fails because a quotient polynomial has too high a degree.
+pub fn debug_constraints<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
+    stark: &S,
+    trace_poly_values: &[PolynomialValues<F>],
+    public_inputs: &[F],
+) -> Result<()> {
+    ensure!(
+        trace_poly_values.len() == stark.num_columns(),
+        "Expected {} columns, got {}",
+        stark.num_columns(),
+        trace_poly_values.len()
+    );
+    ensure!(
+        public_inputs.len() == stark.num_public_inputs(),
+        "Expected {} public inputs, got {}",
+        stark.num_public_inputs(),
+        public_inputs.len()
+    );
+    let num_rows = trace_poly_values[0].len();
+    ensure!(
+        trace_poly_values
+            .iter()
+            .all(|column| column.len() == num_rows),
+        "All columns must have the same length"
+    );
+
+    let preprocessed_columns = stark.preprocessed_columns(num_rows);
+    let extra_row_offsets = stark.extra_row_offsets();
+    // The values of the given columns on a row, wrapping around the trace.
+    let row_values = |columns: &[PolynomialValues<F>], row: isize| {
+        let row = row.rem_euclid(num_rows as isize) as usize;
+        columns
+            .iter()
+            .map(|column| column.values[row])
+            .collect::<Vec<_>>()
+    };
+    for row in 0..num_rows {
+        let local_values = row_values(trace_poly_values, row as isize);
+        let next_values = row_values(trace_poly_values, row as isize + 1);
+        let preprocessed_local_values = row_values(&preprocessed_columns, row as isize);
+        let preprocessed_next_values = row_values(&preprocessed_columns, row as isize + 1);
+        let extra_row_values = extra_row_offsets
+            .iter()
+            .map(|&offset| row_values(trace_poly_values, row as isize + offset))
+            .collect::<Vec<_>>();
+        let vars = StarkEvaluationVars {
+            local_values: &local_values,
+            next_values: &next_values,
+            preprocessed_local_values: &preprocessed_local_values,
+            preprocessed_next_values: &preprocessed_next_values,
+            extra_row_values: &extra_row_values,
+            public_inputs,
+        };
+
+        // On the trace domain, the transition filter is nonzero everywhere but on the last row and
+        // the Lagrange selectors are zero everywhere but on the first and last rows, so we use 0/1
+        // filters to report unscaled constraint values.
+        let is_last_row = row == num_rows - 1;
+        let filters = [
+            F::from_bool(!is_last_row),
+            F::from_bool(row == 0),
+            F::from_bool(is_last_row),
+        ];
+        // Two random combinations of the constraints vanish only if every constraint does, except
+        // with negligible probability.
+        let combinations = eval_combined_constraints(stark, vars, filters, F::rand_vec(2), F::ZERO);
+        if combinations.iter().all(|c| c.is_zero()) {
+            continue;
+        }
+        let constraint_values = eval_constraints(stark, vars, filters);
+        if let Some((index, value)) = constraint_values
+            .iter()
+            .enumerate()
+            .find(|(_, value)| value.is_nonzero())
+        {
+            bail!(
+                "Constraint {} fails on row {}: it evaluates to {}, with local values [{}] and \
+                 next values [{}]",
+                index,
+                row,
+                value,
+                local_values.iter().format(", "),
+                next_values.iter().format(", ")
+            );
+        }
+    }
+
+    for (i, pair) in stark.permutation_pairs().iter().enumerate() {
+        let (lhs, rhs): (Vec<_>, Vec<_>) = pair.column_pairs.iter().copied().unzip();
+        let lhs_columns = lhs.into_iter().map(Column::single).collect::<Vec<_>>();
+        let rhs_columns = rhs.into_iter().map(Column::single).collect::<Vec<_>>();
+        let lhs_rows = (0..num_rows).map(|row| (row, &lhs_columns));
+        let rhs_rows = (0..num_rows).map(|row| (row, &rhs_columns));
+        check_multiset_equality(trace_poly_values, lhs_rows, rhs_rows).map_err(
+            |(row, values)| {
+                anyhow!(
+                    "Permutation pair {} fails: the values [{}] of row {} do not appear as often \
+                     in the left-hand columns {:?} as in the right-hand ones",
+                    i,
+                    values.iter().format(", "),
+                    row,
+                    pair.column_pairs
+                )
+            },
+        )?;
+    }
+
+    for (i, lookup) in stark.lookups().iter().enumerate() {
+        let table = (0..num_rows)
+            .map(|row| lookup.table_column.eval_table(trace_poly_values, row))
+            .collect::<Vec<_>>();
+        // The multiplicities claimed for each table value, summed over its rows.
+        let mut table_frequencies = BTreeMap::new();
+        for (row, value) in table.iter().enumerate() {
+            let frequency = lookup.frequencies_column.eval_table(trace_poly_values, row);
+            *table_frequencies
+                .entry(value.to_canonical_u64())
+                .or_insert(F::ZERO) += frequency;
+        }
+        let mut frequencies = BTreeMap::new();
+        for (column_index, column) in lookup.columns.iter().enumerate() {
+            for row in 0..num_rows {
+                let value = column.eval_table(trace_poly_values, row);
+                ensure!(
+                    table_frequencies.contains_key(&value.to_canonical_u64()),
+                    "Lookup {} fails: the value {} of its column {} on row {} is not in the table",
+                    i,
+                    value,
+                    column_index,
+                    row
+                );
+                *frequencies.entry(value.to_canonical_u64()).or_insert(0u64) += 1;
+            }
+        }
+        for (row, value) in table.iter().enumerate() {
+            let expected = frequencies
+                .get(&value.to_canonical_u64())
+                .copied()
+                .unwrap_or_default();
+            let actual = table_frequencies[&value.to_canonical_u64()];
+            ensure!(
+                actual == F::from_canonical_u64(expected),
+                "Lookup {} fails: the table value {} on row {} is looked up {} times, but its \
+                 frequencies sum to {}",
+                i,
+                value,
+                row,
+                expected,
+                actual
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Combines the constraints of `stark` on `vars` with each of the given `alphas`, starting from an
+/// accumulator equal to `init`. With `m` constraints `c_0, ..., c_{m-1}`, each combination is the
+/// polynomial `init * alpha^m + sum_i c_i alpha^(m - 1 - i)` evaluated at its `alpha`.
+fn eval_combined_constraints<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
+    stark: &S,
+    vars: StarkEvaluationVars<F, F>,
+    [z_last, lagrange_first, lagrange_last]: [F; 3],
+    alphas: Vec<F>,
+    init: F,
+) -> Vec<F> {
+    let mut consumer = ConstraintConsumer::new(alphas, z_last, lagrange_first, lagrange_last);
+    consumer.constraint_accs.fill(init);
+    stark.eval_packed_base(vars, &mut consumer);
+    consumer.accumulators()
+}
+
+/// Computes the individual values of the constraints of `stark` on `vars`. The constraint consumer
+/// only exposes random combinations of the constraints, so we combine them with a leading 1 at
+/// more and more points until the interpolated polynomial is monic of degree `m`, the number of
+/// constraints, and read the constraint values off its coefficients.
+fn eval_constraints<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
+    stark: &S,
+    vars: StarkEvaluationVars<F, F>,
+    filters: [F; 3],
+) -> Vec<F> {
+    let mut num_points = 2;
+    loop {
+        // An extra random point checks that `num_points` points determine the polynomial.
+        let mut alphas = (0..num_points)
+            .map(F::from_canonical_usize)
+            .collect::<Vec<_>>();
+        alphas.push(F::rand());
+        let combinations = eval_combined_constraints(stark, vars, filters, alphas.clone(), F::ONE);
+        let points = alphas.into_iter().zip(combinations).collect::<Vec<_>>();
+        let poly = interpolant(&points[..num_points]);
+        let (check_alpha, check_value) = points[num_points];
+        if poly.eval(check_alpha) == check_value {
+            let num_constraints = poly.degree_plus_one() - 1;
+            return (0..num_constraints)
+                .map(|i| poly.coeffs[num_constraints - 1 - i])
+                .collect();
+        }
+        num_points *= 2;
+    }
+}
+
+/// Checks that the rows selected on the left appear as often as those selected on the right, where
+/// each selected row is evaluated on the given columns. Returns the first row, from the left if
+/// any, whose values appear a different number of times, together with these values.
+pub(crate) fn check_multiset_equality<'a, F: RichField>(
+    trace_poly_values: &[PolynomialValues<F>],
+    lhs_rows: impl IntoIterator<Item = (usize, &'a Vec<Column<F>>)>,
+    rhs_rows: impl IntoIterator<Item = (usize, &'a Vec<Column<F>>)>,
+) -> core::result::Result<(), (usize, Vec<F>)> {
+    let eval_row = |row: usize, columns: &[Column<F>]| {
+        columns
+            .iter()
+            .map(|column| column.eval_table(trace_poly_values, row))
+            .collect::<Vec<_>>()
+    };
+    let key = |values: &[F]| {
+        values
+            .iter()
+            .map(|v| v.to_canonical_u64())
+            .collect::<Vec<_>>()
+    };
+
+    let lhs_rows = lhs_rows
+        .into_iter()
+        .map(|(row, columns)| (row, eval_row(row, columns)))
+        .collect::<Vec<_>>();
+    let rhs_rows = rhs_rows
+        .into_iter()
+        .map(|(row, columns)| (row, eval_row(row, columns)))
+        .collect::<Vec<_>>();
+    let mut counts = BTreeMap::<Vec<u64>, i64>::new();
+    for (_, values) in &lhs_rows {
+        *counts.entry(key(values)).or_default() += 1;
+    }
+    for (_, values) in &rhs_rows {
+        *counts.entry(key(values)).or_default() -= 1;
+    }
+    match lhs_rows
+        .into_iter()
+        .chain(rhs_rows)
+        .find(|(_, values)| counts[&key(values)] != 0)
+    {
+        Some(mismatch) => Err(mismatch),
+        None => Ok(()),
+    }
+}
+
 fn random_low_degree_matrix<F: Field>(num_polys: usize, rate_bits: usize) -> Vec<Vec<F>> {
     let polys = (0..num_polys)
         .map(|_| random_low_degree_values(rate_bits))
@@ -0,0 +1,516 @@
+//! Symbolic constraints, from which both the native and the recursive constraint evaluators of a
+//! STARK are derived, so that the two cannot drift apart.
+//!
+//! Constraints are written once as `Expr`s over the trace, the preprocessed columns and the public
+//! inputs, and collected in `SymbolicConstraints`. Its `eval_packed_generic`, `eval_ext_circuit`
+//! and `constraint_degree` methods can then back the corresponding `Stark` methods.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::iter::Sum;
+use core::ops::{Add, Mul, Neg, Sub};
+
+use plonky2::field::extension::{Extendable, FieldExtension};
+use plonky2::field::packed::PackedField;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+
+use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+
+/// A polynomial expression over the values a STARK's constraints can read.
+#[derive(Clone, Debug)]
+pub enum Expr<F: Field> {
+    Constant(F),
+    /// A trace column on the current row.
+    Local(usize),
+    /// A trace column on the next row.
+    Next(usize),
+    /// A preprocessed column on the current row.
+    PreprocessedLocal(usize),
+    /// A preprocessed column on the next row.
+    PreprocessedNext(usize),
+    /// A trace column, given second, on the row at the STARK's `i`-th extra row offset, given
+    /// first.
+    ExtraRow(usize, usize),
+    PublicInput(usize),
+    Add(Box<Self>, Box<Self>),
+    Sub(Box<Self>, Box<Self>),
+    Mul(Box<Self>, Box<Self>),
+    Neg(Box<Self>),
+}
+
+impl<F: Field> Expr<F> {
+    pub fn constant(c: F) -> Self {
+        Self::Constant(c)
+    }
+
+    pub fn local(column: usize) -> Self {
+        Self::Local(column)
+    }
+
+    pub fn next(column: usize) -> Self {
+        Self::Next(column)
+    }
+
+    pub fn preprocessed_local(column: usize) -> Self {
+        Self::PreprocessedLocal(column)
+    }
+
+    pub fn preprocessed_next(column: usize) -> Self {
+        Self::PreprocessedNext(column)
+    }
+
+    pub fn extra_row(offset_index: usize, column: usize) -> Self {
+        Self::ExtraRow(offset_index, column)
+    }
+
+    pub fn public_input(index: usize) -> Self {
+        Self::PublicInput(index)
+    }
+
+    /// The degree of this expression, as a polynomial in the trace and preprocessed columns.
+    pub fn degree(&self) -> usize {
+        match self {
+            Self::Constant(_) | Self::PublicInput(_) => 0,
+            Self::Local(_)
+            | Self::Next(_)
+            | Self::PreprocessedLocal(_)
+            | Self::PreprocessedNext(_)
+            | Self::ExtraRow(_, _) => 1,
+            Self::Add(a, b) | Self::Sub(a, b) => a.degree().max(b.degree()),
+            Self::Mul(a, b) => a.degree() + b.degree(),
+            Self::Neg(a) => a.degree(),
+        }
+    }
+
+    /// Evaluates this expression on the given packed values.
+    pub fn eval<FE, P, const D2: usize>(&self, vars: &StarkEvaluationVars<FE, P>) -> P
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        match self {
+            Self::Constant(c) => P::from(FE::from_basefield(*c)),
+            Self::Local(i) => vars.local_values[*i],
+            Self::Next(i) => vars.next_values[*i],
+            Self::PreprocessedLocal(i) => vars.preprocessed_local_values[*i],
+            Self::PreprocessedNext(i) => vars.preprocessed_next_values[*i],
+            Self::ExtraRow(row, i) => vars.extra_row_values[*row][*i],
+            Self::PublicInput(i) => P::from(vars.public_inputs[*i]),
+            Self::Add(a, b) => a.eval(vars) + b.eval(vars),
+            Self::Sub(a, b) => a.eval(vars) - b.eval(vars),
+            Self::Mul(a, b) => a.eval(vars) * b.eval(vars),
+            Self::Neg(a) => -a.eval(vars),
+        }
+    }
+
+    /// Evaluates this expression in a circuit, on the given targets.
+    pub fn eval_circuit<const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &StarkEvaluationTargets<D>,
+    ) -> ExtensionTarget<D>
+    where
+        F: RichField + Extendable<D>,
+    {
+        match self {
+            Self::Constant(c) => builder.constant_extension(F::Extension::from_basefield(*c)),
+            Self::Local(i) => vars.local_values[*i],
+            Self::Next(i) => vars.next_values[*i],
+            Self::PreprocessedLocal(i) => vars.preprocessed_local_values[*i],
+            Self::PreprocessedNext(i) => vars.preprocessed_next_values[*i],
+            Self::ExtraRow(row, i) => vars.extra_row_values[*row][*i],
+            Self::PublicInput(i) => vars.public_inputs[*i],
+            // Operations with a constant operand don't need to materialize the constant.
+            Self::Add(a, b) => match (&**a, &**b) {
+                (Self::Constant(c), e) | (e, Self::Constant(c)) => {
+                    let e = e.eval_circuit(builder, vars);
+                    builder.add_const_extension(e, *c)
+                }
+                _ => {
+                    let a = a.eval_circuit(builder, vars);
+                    let b = b.eval_circuit(builder, vars);
+                    builder.add_extension(a, b)
+                }
+            },
+            Self::Sub(a, b) => match &**b {
+                Self::Constant(c) => {
+                    let a = a.eval_circuit(builder, vars);
+                    builder.add_const_extension(a, -*c)
+                }
+                _ => {
+                    let a = a.eval_circuit(builder, vars);
+                    let b = b.eval_circuit(builder, vars);
+                    builder.sub_extension(a, b)
+                }
+            },
+            Self::Mul(a, b) => match (&**a, &**b) {
+                (Self::Constant(c), e) | (e, Self::Constant(c)) => {
+                    let e = e.eval_circuit(builder, vars);
+                    builder.mul_const_extension(*c, e)
+                }
+                _ => {
+                    let a = a.eval_circuit(builder, vars);
+                    let b = b.eval_circuit(builder, vars);
+                    builder.mul_extension(a, b)
+                }
+            },
+            Self::Neg(a) => {
+                let a = a.eval_circuit(builder, vars);
+                builder.mul_const_extension(F::NEG_ONE, a)
+            }
+        }
+    }
+}
+
+impl<F: Field> Add for Expr<F> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::Add(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<F: Field> Add<F> for Expr<F> {
+    type Output = Self;
+
+    fn add(self, rhs: F) -> Self {
+        self + Self::Constant(rhs)
+    }
+}
+
+impl<F: Field> Sub for Expr<F> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::Sub(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<F: Field> Sub<F> for Expr<F> {
+    type Output = Self;
+
+    fn sub(self, rhs: F) -> Self {
+        self - Self::Constant(rhs)
+    }
+}
+
+impl<F: Field> Mul for Expr<F> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::Mul(Box::new(self), Box::new(rhs))
+    }
+}
+
+impl<F: Field> Mul<F> for Expr<F> {
+    type Output = Self;
+
+    fn mul(self, rhs: F) -> Self {
+        self * Self::Constant(rhs)
+    }
+}
+
+impl<F: Field> Neg for Expr<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::Neg(Box::new(self))
+    }
+}
+
+impl<F: Field> Sum for Expr<F> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(|acc, x| acc + x)
+            .unwrap_or(Self::Constant(F::ZERO))
+    }
+}
+
+/// The rows on which a constraint is enforced.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum ConstraintFilter {
+    AllRows,
+    FirstRow,
+    LastRow,
+    Transition,
+}
+
+/// A set of symbolic constraints, which can be evaluated natively or in a circuit.
+#[derive(Clone, Debug, Default)]
+pub struct SymbolicConstraints<F: Field> {
+    constraints: Vec<(ConstraintFilter, Expr<F>)>,
+}
+
+impl<F: Field> SymbolicConstraints<F> {
+    pub fn new() -> Self {
+        Self {
+            constraints: Vec::new(),
+        }
+    }
+
+    /// Adds a constraint which must hold on every row.
+    pub fn constraint(&mut self, expr: Expr<F>) {
+        self.constraints.push((ConstraintFilter::AllRows, expr));
+    }
+
+    /// Adds a constraint which must hold on the first row.
+    pub fn constraint_first_row(&mut self, expr: Expr<F>) {
+        self.constraints.push((ConstraintFilter::FirstRow, expr));
+    }
+
+    /// Adds a constraint which must hold on the last row.
+    pub fn constraint_last_row(&mut self, expr: Expr<F>) {
+        self.constraints.push((ConstraintFilter::LastRow, expr));
+    }
+
+    /// Adds a constraint which must hold on every row except the last.
+    pub fn constraint_transition(&mut self, expr: Expr<F>) {
+        self.constraints.push((ConstraintFilter::Transition, expr));
+    }
+
+    /// The maximum degree of the constraints, where restricting a constraint to some rows adds one
+    /// to its degree, as expected by `Stark::constraint_degree`.
+    pub fn constraint_degree(&self) -> usize {
+        self.constraints
+            .iter()
+            .map(|(filter, expr)| expr.degree() + usize::from(*filter != ConstraintFilter::AllRows))
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: StarkEvaluationVars<FE, P>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        for (filter, expr) in &self.constraints {
+            let value = expr.eval(&vars);
+            match filter {
+                ConstraintFilter::AllRows => yield_constr.constraint(value),
+                ConstraintFilter::FirstRow => yield_constr.constraint_first_row(value),
+                ConstraintFilter::LastRow => yield_constr.constraint_last_row(value),
+                ConstraintFilter::Transition => yield_constr.constraint_transition(value),
+            }
+        }
+    }
+
+    pub fn eval_ext_circuit<const D: usize>(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: StarkEvaluationTargets<D>,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) where
+        F: RichField + Extendable<D>,
+    {
+        for (filter, expr) in &self.constraints {
+            let value = expr.eval_circuit(builder, &vars);
+            match filter {
+                ConstraintFilter::AllRows => yield_constr.constraint(builder, value),
+                ConstraintFilter::FirstRow => yield_constr.constraint_first_row(builder, value),
+                ConstraintFilter::LastRow => yield_constr.constraint_last_row(builder, value),
+                ConstraintFilter::Transition => yield_constr.constraint_transition(builder, value),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::marker::PhantomData;
+
+    use anyhow::Result;
+    use plonky2::field::extension::{Extendable, FieldExtension};
+    use plonky2::field::packed::PackedField;
+    use plonky2::field::polynomial::PolynomialValues;
+    use plonky2::field::types::Field;
+    use plonky2::hash::hash_types::RichField;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_builder::CircuitBuilder;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use plonky2::util::timing::TimingTree;
+
+    use crate::config::StarkConfig;
+    use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+    use crate::prover::prove;
+    use crate::recursive_verifier::{
+        add_virtual_stark_proof_with_pis, set_stark_proof_with_pis_target,
+        verify_stark_proof_circuit,
+    };
+    use crate::stark::Stark;
+    use crate::stark_testing::{test_stark_circuit_constraints, test_stark_low_degree};
+    use crate::symbolic::{Expr, SymbolicConstraints};
+    use crate::util::trace_rows_to_poly_values;
+    use crate::vars::{StarkEvaluationTargets, StarkEvaluationVars};
+    use crate::verifier::verify_stark_proof;
+
+    /// Computes the sequence `x' <- x^2 + c * y, y' <- x + y + 1` from public initial values, and
+    /// exposes the last `x` as a public input. All constraints are written symbolically.
+    #[derive(Clone)]
+    struct SymbolicStark<F: RichField + Extendable<D>, const D: usize> {
+        constraints: SymbolicConstraints<F>,
+        _phantom: PhantomData<F>,
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> SymbolicStark<F, D> {
+        const C: u64 = 7;
+
+        fn new() -> Self {
+            let x = || Expr::local(0);
+            let y = || Expr::local(1);
+            let c = F::from_canonical_u64(Self::C);
+
+            let mut constraints = SymbolicConstraints::new();
+            constraints.constraint_first_row(x() - Expr::public_input(0));
+            constraints.constraint_first_row(y() - Expr::public_input(1));
+            constraints.constraint_last_row(x() - Expr::public_input(2));
+            constraints.constraint_transition(Expr::next(0) - (x() * x() + y() * c));
+            let x_plus_y: Expr<F> = [x(), y()].into_iter().sum();
+            constraints.constraint_transition(Expr::next(1) - x_plus_y - F::ONE);
+            Self {
+                constraints,
+                _phantom: PhantomData,
+            }
+        }
+
+        /// Generates the trace, along with the public inputs.
+        fn generate_trace(
+            &self,
+            num_rows: usize,
+            x0: F,
+            y0: F,
+        ) -> (Vec<PolynomialValues<F>>, [F; 3]) {
+            let c = F::from_canonical_u64(Self::C);
+            let rows = (0..num_rows)
+                .scan([x0, y0], |acc, _| {
+                    let row = *acc;
+                    *acc = [row[0].square() + c * row[1], row[0] + row[1] + F::ONE];
+                    Some(row)
+                })
+                .collect::<Vec<_>>();
+            let last = rows[num_rows - 1][0];
+            (trace_rows_to_poly_values(rows), [x0, y0, last])
+        }
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for SymbolicStark<F, D> {
+        fn num_columns(&self) -> usize {
+            2
+        }
+
+        fn num_public_inputs(&self) -> usize {
+            3
+        }
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            vars: StarkEvaluationVars<FE, P>,
+            yield_constr: &mut ConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            self.constraints.eval_packed_generic(vars, yield_constr);
+        }
+
+        fn eval_ext_circuit(
+            &self,
+            builder: &mut CircuitBuilder<F, D>,
+            vars: StarkEvaluationTargets<D>,
+            yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+        ) {
+            self.constraints
+                .eval_ext_circuit(builder, vars, yield_constr);
+        }
+
+        fn constraint_degree(&self) -> usize {
+            self.constraints.constraint_degree()
+        }
+    }
+
+    #[test]
+    fn test_constraint_degree() {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+
+        let x = Expr::<F>::local(0);
+        assert_eq!((x.clone() * x.clone() * F::TWO - Expr::next(0)).degree(), 2);
+        assert_eq!((Expr::public_input(0) * Expr::constant(F::TWO)).degree(), 0);
+
+        let mut constraints = SymbolicConstraints::new();
+        assert_eq!(constraints.constraint_degree(), 0);
+        constraints.constraint(x.clone() * x.clone());
+        assert_eq!(constraints.constraint_degree(), 2);
+        constraints.constraint_transition(x.clone() * x - Expr::next(0));
+        assert_eq!(constraints.constraint_degree(), 3);
+        assert_eq!(SymbolicStark::<F, 2>::new().constraint_degree(), 3);
+    }
+
+    #[test]
+    fn test_symbolic_stark() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = SymbolicStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new();
+        let (trace, public_inputs) = stark.generate_trace(1 << 5, F::ONE, F::TWO);
+        let proof = prove::<F, C, S, D>(
+            stark.clone(),
+            &config,
+            trace,
+            &public_inputs,
+            &mut TimingTree::default(),
+        )?;
+        verify_stark_proof(stark, proof, &config)
+    }
+
+    #[test]
+    fn test_symbolic_stark_degree() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = SymbolicStark<F, D>;
+
+        test_stark_low_degree(S::new())?;
+        test_stark_circuit_constraints::<F, C, S, D>(S::new())
+    }
+
+    #[test]
+    fn test_recursive_symbolic_stark_verifier() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = SymbolicStark<F, D>;
+
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new();
+        let (trace, public_inputs) = stark.generate_trace(1 << 5, F::ONE, F::TWO);
+        let proof = prove::<F, C, S, D>(
+            stark.clone(),
+            &config,
+            trace,
+            &public_inputs,
+            &mut TimingTree::default(),
+        )?;
+
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let mut pw = PartialWitness::new();
+        let degree_bits = proof.proof.degree_bits;
+        let pt =
+            add_virtual_stark_proof_with_pis(&mut builder, stark.clone(), &config, degree_bits);
+        set_stark_proof_with_pis_target(&mut pw, &pt, &proof);
+        verify_stark_proof_circuit::<F, C, S, D>(&mut builder, stark, pt, &config);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+}
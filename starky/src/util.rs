@@ -1,10 +1,17 @@
 use alloc::vec::Vec;
+use core::iter::once;
 
 use itertools::Itertools;
+use plonky2::field::extension::Extendable;
 use plonky2::field::polynomial::PolynomialValues;
 use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::plonk_common::salt_size;
 use plonky2::util::transpose;
 
+use crate::config::StarkConfig;
+use crate::stark::Stark;
+
 /// A helper function to transpose a row-wise trace and put it in the format that `prove` expects.
 pub fn trace_rows_to_poly_values<F: Field, const COLUMNS: usize>(
     trace_rows: Vec<[F; COLUMNS]>,
@@ -16,3 +23,37 @@ pub fn trace_rows_to_poly_values<F: Field, const COLUMNS: usize>(
         .map(|column| PolynomialValues::new(column))
         .collect()
 }
+
+/// Returns `g^offset`, which shifts a point of the trace domain generated by `g` by `offset` rows.
+pub(crate) fn row_shift<F: Field>(g: F, offset: isize) -> F {
+    let shift = g.exp_u64(offset.unsigned_abs() as u64);
+    if offset < 0 {
+        shift.inverse()
+    } else {
+        shift
+    }
+}
+
+/// The number of leaves of each oracle of a proof of `stark` with `num_ctl_zs` cross-table lookup
+/// `Z` polynomials, in the order the oracles are opened. These are the committed polynomials, plus
+/// the salt of witness-dependent oracles in zero-knowledge mode.
+pub(crate) fn num_leaves_per_oracle<
+    F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+    const D: usize,
+>(
+    stark: &S,
+    config: &StarkConfig,
+    num_ctl_zs: usize,
+) -> Vec<usize> {
+    let num_zs = stark.num_permutation_lookup_zs(config) + num_ctl_zs;
+    let num_preprocessed_columns = stark.num_preprocessed_columns();
+    let salt = salt_size(config.zero_knowledge);
+    (num_preprocessed_columns > 0)
+        .then_some(num_preprocessed_columns)
+        .into_iter()
+        .chain(once(stark.num_columns() + salt))
+        .chain((num_zs > 0).then_some(num_zs + salt))
+        .chain(once(stark.num_quotient_polys(config) + salt))
+        .collect_vec()
+}
@@ -6,6 +6,13 @@ use plonky2::plonk::config::GenericConfig;
 
 use crate::config::StarkConfig;
 use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
+use crate::cross_table_lookup::{
+    eval_cross_table_lookup_checks, eval_cross_table_lookup_checks_circuit, CtlCheckVars,
+    CtlCheckVarsTarget,
+};
+use crate::lookup::{
+    eval_ext_lookups_circuit, eval_packed_lookups_generic, LookupCheckVars, LookupCheckVarsTarget,
+};
 use crate::permutation::{
     eval_permutation_checks, eval_permutation_checks_circuit, PermutationCheckDataTarget,
     PermutationCheckVars,
@@ -16,8 +23,10 @@ use crate::vars::{StarkEvaluationTargets, StarkEvaluationVars};
 pub(crate) fn eval_vanishing_poly<F, FE, P, C, S, const D: usize, const D2: usize>(
     stark: &S,
     config: &StarkConfig,
-    vars: StarkEvaluationVars<FE, P, { S::COLUMNS }, { S::PUBLIC_INPUTS }>,
+    vars: StarkEvaluationVars<FE, P>,
     permutation_data: Option<PermutationCheckVars<F, FE, P, D2>>,
+    lookup_vars: Option<LookupCheckVars<F, FE, P, D2>>,
+    ctl_vars: &[CtlCheckVars<F, FE, P, D2>],
     consumer: &mut ConstraintConsumer<P>,
 ) where
     F: RichField + Extendable<D>,
@@ -25,8 +34,6 @@ pub(crate) fn eval_vanishing_poly<F, FE, P, C, S, const D: usize, const D2: usiz
     P: PackedField<Scalar = FE>,
     C: GenericConfig<D, F = F>,
     S: Stark<F, D>,
-    [(); S::COLUMNS]:,
-    [(); S::PUBLIC_INPUTS]:,
 {
     stark.eval_packed_generic(vars, consumer);
     if let Some(permutation_data) = permutation_data {
@@ -38,21 +45,25 @@ pub(crate) fn eval_vanishing_poly<F, FE, P, C, S, const D: usize, const D2: usiz
             consumer,
         );
     }
+    if let Some(lookup_vars) = lookup_vars {
+        eval_packed_lookups_generic::<F, FE, P, S, D, D2>(stark, vars, lookup_vars, consumer);
+    }
+    eval_cross_table_lookup_checks::<F, FE, P, D, D2>(vars, ctl_vars, consumer);
 }
 
 pub(crate) fn eval_vanishing_poly_circuit<F, C, S, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
     stark: &S,
     config: &StarkConfig,
-    vars: StarkEvaluationTargets<D, { S::COLUMNS }, { S::PUBLIC_INPUTS }>,
+    vars: StarkEvaluationTargets<D>,
     permutation_data: Option<PermutationCheckDataTarget<D>>,
+    lookup_vars: Option<LookupCheckVarsTarget<D>>,
+    ctl_vars: &[CtlCheckVarsTarget<F, D>],
     consumer: &mut RecursiveConstraintConsumer<F, D>,
 ) where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
     S: Stark<F, D>,
-    [(); S::COLUMNS]:,
-    [(); S::PUBLIC_INPUTS]:,
 {
     stark.eval_ext_circuit(builder, vars, consumer);
     if let Some(permutation_data) = permutation_data {
@@ -65,4 +76,8 @@ pub(crate) fn eval_vanishing_poly_circuit<F, C, S, const D: usize>(
             consumer,
         );
     }
+    if let Some(lookup_vars) = lookup_vars {
+        eval_ext_lookups_circuit::<F, S, D>(builder, stark, vars, lookup_vars, consumer);
+    }
+    eval_cross_table_lookup_checks_circuit::<F, D>(builder, vars, ctl_vars, consumer);
 }
@@ -1,26 +1,33 @@
+use alloc::vec::Vec;
+
 use plonky2::field::packed::PackedField;
 use plonky2::field::types::Field;
 use plonky2::iop::ext_target::ExtensionTarget;
 
+/// The values of a STARK's trace and preprocessed columns on the current and next rows, along with
+/// its public inputs. The slices hold `num_columns()`, `num_preprocessed_columns()` and
+/// `num_public_inputs()` values respectively. `extra_row_values` holds the trace values at each of
+/// the STARK's `extra_row_offsets()`.
 #[derive(Debug, Copy, Clone)]
-pub struct StarkEvaluationVars<'a, F, P, const COLUMNS: usize, const PUBLIC_INPUTS: usize>
+pub struct StarkEvaluationVars<'a, F, P>
 where
     F: Field,
     P: PackedField<Scalar = F>,
 {
-    pub local_values: &'a [P; COLUMNS],
-    pub next_values: &'a [P; COLUMNS],
-    pub public_inputs: &'a [P::Scalar; PUBLIC_INPUTS],
+    pub local_values: &'a [P],
+    pub next_values: &'a [P],
+    pub preprocessed_local_values: &'a [P],
+    pub preprocessed_next_values: &'a [P],
+    pub extra_row_values: &'a [Vec<P>],
+    pub public_inputs: &'a [P::Scalar],
 }
 
 #[derive(Debug, Copy, Clone)]
-pub struct StarkEvaluationTargets<
-    'a,
-    const D: usize,
-    const COLUMNS: usize,
-    const PUBLIC_INPUTS: usize,
-> {
-    pub local_values: &'a [ExtensionTarget<D>; COLUMNS],
-    pub next_values: &'a [ExtensionTarget<D>; COLUMNS],
-    pub public_inputs: &'a [ExtensionTarget<D>; PUBLIC_INPUTS],
+pub struct StarkEvaluationTargets<'a, const D: usize> {
+    pub local_values: &'a [ExtensionTarget<D>],
+    pub next_values: &'a [ExtensionTarget<D>],
+    pub preprocessed_local_values: &'a [ExtensionTarget<D>],
+    pub preprocessed_next_values: &'a [ExtensionTarget<D>],
+    pub extra_row_values: &'a [Vec<ExtensionTarget<D>>],
+    pub public_inputs: &'a [ExtensionTarget<D>],
 }
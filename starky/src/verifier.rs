@@ -7,13 +7,21 @@ use plonky2::field::extension::{Extendable, FieldExtension};
 use plonky2::field::types::Field;
 use plonky2::fri::verifier::verify_fri_proof;
 use plonky2::hash::hash_types::RichField;
-use plonky2::plonk::config::{GenericConfig, Hasher};
+use plonky2::hash::merkle_tree::MerkleCap;
+use plonky2::iop::challenger::Challenger;
+use plonky2::plonk::config::GenericConfig;
 use plonky2::plonk::plonk_common::reduce_with_powers;
 
 use crate::config::StarkConfig;
 use crate::constraint_consumer::ConstraintConsumer;
-use crate::permutation::PermutationCheckVars;
-use crate::proof::{StarkOpeningSet, StarkProof, StarkProofChallenges, StarkProofWithPublicInputs};
+use crate::cross_table_lookup::{verify_cross_table_lookups, CrossTableLookup, CtlCheckVars};
+use crate::lookup::LookupCheckVars;
+use crate::multi_stark::DynStark;
+use crate::permutation::{get_permutation_challenge_set, PermutationCheckVars};
+use crate::preprocessed::StarkVerifyingKey;
+use crate::proof::{
+    MultiStarkProof, StarkOpeningSet, StarkProof, StarkProofChallenges, StarkProofWithPublicInputs,
+};
 use crate::stark::Stark;
 use crate::vanishing_poly::eval_vanishing_poly;
 use crate::vars::StarkEvaluationVars;
@@ -27,16 +35,138 @@ pub fn verify_stark_proof<
     stark: S,
     proof_with_pis: StarkProofWithPublicInputs<F, C, D>,
     config: &StarkConfig,
+) -> Result<()> {
+    let degree_bits = proof_with_pis.proof.degree_bits;
+    let verifying_key = StarkVerifyingKey::new(&stark, config, degree_bits);
+    verify_stark_proof_with_key(stark, proof_with_pis, &verifying_key, config)
+}
+
+/// Verifies a STARK proof against a verifying key which was computed ahead of time, so that the
+/// preprocessed columns need not be committed to again.
+pub fn verify_stark_proof_with_key<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+    const D: usize,
+>(
+    stark: S,
+    proof_with_pis: StarkProofWithPublicInputs<F, C, D>,
+    verifying_key: &StarkVerifyingKey<F, C, D>,
+    config: &StarkConfig,
+) -> Result<()> {
+    ensure!(proof_with_pis.public_inputs.len() == stark.num_public_inputs());
+    let degree_bits = proof_with_pis.proof.degree_bits;
+    ensure!(
+        verifying_key.degree_bits == degree_bits,
+        "Verifying key is for traces of 2^{} rows, got 2^{}",
+        verifying_key.degree_bits,
+        degree_bits
+    );
+    ensure!(
+        verifying_key.preprocessed_cap.is_some() == stark.uses_preprocessed_columns(),
+        "Verifying key does not match the STARK's preprocessed columns"
+    );
+    let preprocessed_cap = verifying_key.preprocessed_cap.as_ref();
+    let challenges = proof_with_pis.get_challenges(&stark, preprocessed_cap, config, degree_bits);
+    verify_stark_proof_with_challenges(
+        &stark,
+        &proof_with_pis,
+        preprocessed_cap,
+        challenges,
+        &[],
+        degree_bits,
+        config,
+    )
+}
+
+/// Verifies a multi-STARK proof produced by `prove_multi_stark` for the same STARKs and
+/// cross-table lookups.
+pub fn verify_multi_stark_proof<F, C, const D: usize>(
+    starks: &[&dyn DynStark<F, C, D>],
+    cross_table_lookups: &[CrossTableLookup<F>],
+    proof: MultiStarkProof<F, C, D>,
+    config: &StarkConfig,
 ) -> Result<()>
 where
-    [(); S::COLUMNS]:,
-    [(); S::PUBLIC_INPUTS]:,
-    [(); C::Hasher::HASH_SIZE]:,
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
 {
-    ensure!(proof_with_pis.public_inputs.len() == S::PUBLIC_INPUTS);
-    let degree_bits = proof_with_pis.proof.recover_degree_bits(config);
-    let challenges = proof_with_pis.get_challenges(&stark, config, degree_bits);
-    verify_stark_proof_with_challenges(stark, proof_with_pis, challenges, degree_bits, config)
+    ensure!(
+        proof.stark_proofs.len() == starks.len(),
+        "Expected {} STARK proofs, got {}",
+        starks.len(),
+        proof.stark_proofs.len()
+    );
+    ensure!(
+        CrossTableLookup::min_num_tables(cross_table_lookups) <= starks.len(),
+        "Cross-table lookup refers to a missing table"
+    );
+
+    let num_permutation_lookup_zs = starks
+        .iter()
+        .map(|stark| stark.num_permutation_lookup_zs(config))
+        .collect_vec();
+    for (i, (proof_with_pis, &num_skipped)) in proof
+        .stark_proofs
+        .iter()
+        .zip(&num_permutation_lookup_zs)
+        .enumerate()
+    {
+        let num_ctl_zs =
+            CrossTableLookup::num_ctl_zs(cross_table_lookups, i, config.num_challenges);
+        check_ctl_openings_len(&proof_with_pis.proof.openings, num_skipped, num_ctl_zs)?;
+    }
+
+    let degrees_bits = proof.degrees_bits();
+    let mut challenger = Challenger::<F, C::Hasher>::new();
+    for proof_with_pis in &proof.stark_proofs {
+        challenger.observe_cap(&proof_with_pis.proof.trace_cap);
+    }
+    let ctl_challenges = get_permutation_challenge_set(&mut challenger, config.num_challenges);
+
+    let ctl_vars_per_table = CtlCheckVars::from_proofs(
+        &proof.stark_proofs,
+        cross_table_lookups,
+        &ctl_challenges,
+        &num_permutation_lookup_zs,
+    );
+    for ((stark, proof_with_pis), ctl_vars) in starks
+        .iter()
+        .zip(&proof.stark_proofs)
+        .zip(&ctl_vars_per_table)
+    {
+        stark.verify_with_challenger(config, proof_with_pis, ctl_vars, &mut challenger)?;
+    }
+
+    let ctl_zs_lasts = proof
+        .stark_proofs
+        .iter()
+        .map(|p| p.proof.openings.ctl_zs_last.as_deref().unwrap_or_default())
+        .collect_vec();
+    verify_cross_table_lookups(
+        cross_table_lookups,
+        &ctl_zs_lasts,
+        &degrees_bits,
+        &ctl_challenges,
+    )
+}
+
+/// Checks that a proof opens as many cross-table lookup `Z` polynomials as expected, so that they
+/// can be safely split among the lookups.
+fn check_ctl_openings_len<F: RichField + Extendable<D>, const D: usize>(
+    openings: &StarkOpeningSet<F, D>,
+    num_permutation_lookup_zs: usize,
+    num_ctl_zs: usize,
+) -> Result<()> {
+    let num_zs = num_permutation_lookup_zs + num_ctl_zs;
+    let len = |zs: &Option<Vec<F::Extension>>| zs.as_ref().map_or(0, Vec::len);
+    ensure!(
+        len(&openings.permutation_ctl_zs) == num_zs
+            && len(&openings.permutation_ctl_zs_next) == num_zs
+            && openings.ctl_zs_last.as_ref().map_or(0, Vec::len) == num_ctl_zs,
+        "Wrong number of cross-table lookup openings"
+    );
+    Ok(())
 }
 
 pub(crate) fn verify_stark_proof_with_challenges<
@@ -45,19 +175,16 @@ pub(crate) fn verify_stark_proof_with_challenges<
     S: Stark<F, D>,
     const D: usize,
 >(
-    stark: S,
-    proof_with_pis: StarkProofWithPublicInputs<F, C, D>,
+    stark: &S,
+    proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
+    preprocessed_cap: Option<&MerkleCap<F, C::Hasher>>,
     challenges: StarkProofChallenges<F, D>,
+    ctl_vars: &[CtlCheckVars<F, F::Extension, F::Extension, D>],
     degree_bits: usize,
     config: &StarkConfig,
-) -> Result<()>
-where
-    [(); S::COLUMNS]:,
-    [(); S::PUBLIC_INPUTS]:,
-    [(); C::Hasher::HASH_SIZE]:,
-{
-    validate_proof_shape(&stark, &proof_with_pis, config)?;
-    check_permutation_options(&stark, &proof_with_pis, &challenges)?;
+) -> Result<()> {
+    validate_proof_shape(stark, proof_with_pis, ctl_vars.len(), config)?;
+    check_permutation_options(stark, &challenges)?;
     let StarkProofWithPublicInputs {
         proof,
         public_inputs,
@@ -65,19 +192,26 @@ where
     let StarkOpeningSet {
         local_values,
         next_values,
-        permutation_zs,
-        permutation_zs_next,
+        preprocessed_local_values,
+        preprocessed_next_values,
+        extra_row_values,
+        permutation_ctl_zs,
+        permutation_ctl_zs_next,
+        ctl_zs_last: _,
         quotient_polys,
     } = &proof.openings;
+    let public_inputs = public_inputs
+        .iter()
+        .copied()
+        .map(F::Extension::from_basefield)
+        .collect::<Vec<_>>();
     let vars = StarkEvaluationVars {
-        local_values: &local_values.to_vec().try_into().unwrap(),
-        next_values: &next_values.to_vec().try_into().unwrap(),
-        public_inputs: &public_inputs
-            .into_iter()
-            .map(F::Extension::from_basefield)
-            .collect::<Vec<_>>()
-            .try_into()
-            .unwrap(),
+        local_values,
+        next_values,
+        preprocessed_local_values: preprocessed_local_values.as_deref().unwrap_or_default(),
+        preprocessed_next_values: preprocessed_next_values.as_deref().unwrap_or_default(),
+        extra_row_values,
+        public_inputs: &public_inputs,
     };
 
     let (l_0, l_last) = eval_l_0_and_l_last(degree_bits, challenges.stark_zeta);
@@ -93,16 +227,29 @@ where
         l_0,
         l_last,
     );
+    let num_permutation_zs = stark.num_permutation_batches(config);
+    let num_permutation_lookup_zs = stark.num_permutation_lookup_zs(config);
     let permutation_data = stark.uses_permutation_args().then(|| PermutationCheckVars {
-        local_zs: permutation_zs.as_ref().unwrap().clone(),
-        next_zs: permutation_zs_next.as_ref().unwrap().clone(),
+        local_zs: permutation_ctl_zs.as_ref().unwrap()[..num_permutation_zs].to_vec(),
+        next_zs: permutation_ctl_zs_next.as_ref().unwrap()[..num_permutation_zs].to_vec(),
         permutation_challenge_sets: challenges.permutation_challenge_sets.unwrap(),
     });
+    let lookup_vars = stark.uses_lookups().then(|| LookupCheckVars {
+        local_values: permutation_ctl_zs.as_ref().unwrap()
+            [num_permutation_zs..num_permutation_lookup_zs]
+            .to_vec(),
+        next_values: permutation_ctl_zs_next.as_ref().unwrap()
+            [num_permutation_zs..num_permutation_lookup_zs]
+            .to_vec(),
+        challenges: challenges.lookup_challenges.unwrap(),
+    });
     eval_vanishing_poly::<F, F::Extension, F::Extension, C, S, D, D>(
-        &stark,
+        stark,
         config,
         vars,
         permutation_data,
+        lookup_vars,
+        ctl_vars,
         &mut consumer,
     );
     let vanishing_polys_zeta = consumer.accumulators();
@@ -110,37 +257,46 @@ where
     // Check each polynomial identity, of the form `vanishing(x) = Z_H(x) quotient(x)`, at zeta.
     let zeta_pow_deg = challenges.stark_zeta.exp_power_of_2(degree_bits);
     let z_h_zeta = zeta_pow_deg - F::Extension::ONE;
-    // `quotient_polys_zeta` holds `num_challenges * quotient_degree_factor` evaluations.
-    // Each chunk of `quotient_degree_factor` holds the evaluations of `t_0(zeta),...,t_{quotient_degree_factor-1}(zeta)`
-    // where the "real" quotient polynomial is `t(X) = t_0(X) + t_1(X)*X^n + t_2(X)*X^{2n} + ...`.
-    // So to reconstruct `t(zeta)` we can compute `reduce_with_powers(chunk, zeta^n)` for each
-    // `quotient_degree_factor`-sized chunk of the original evaluations.
+    // `quotient_polys_zeta` holds `num_challenges * num_quotient_chunks` evaluations.
+    // Each chunk of `num_quotient_chunks` holds the evaluations of `t_0(zeta),...,t_{num_quotient_chunks-1}(zeta)`
+    // where the "real" quotient polynomial is `t(X) = t_0(X) + t_1(X)*X^m + t_2(X)*X^{2m} + ...`,
+    // `m` being the degree bound of quotient chunks: `n`, or more in zero-knowledge mode, where the
+    // chunk randomizers cancel out in this sum.
+    // So to reconstruct `t(zeta)` we can compute `reduce_with_powers(chunk, zeta^m)` for each
+    // `num_quotient_chunks`-sized chunk of the original evaluations.
+    let zeta_pow_chunk_deg = challenges
+        .stark_zeta
+        .exp_power_of_2(config.quotient_chunk_degree_bits(degree_bits, stark.num_opening_points()));
     for (i, chunk) in quotient_polys
-        .chunks(stark.quotient_degree_factor())
+        .chunks(stark.num_quotient_chunks(config))
         .enumerate()
     {
         ensure!(
-            vanishing_polys_zeta[i] == z_h_zeta * reduce_with_powers(chunk, zeta_pow_deg),
+            vanishing_polys_zeta[i] == z_h_zeta * reduce_with_powers(chunk, zeta_pow_chunk_deg),
             "Mismatch between evaluation and opening of quotient polynomial"
         );
     }
 
-    let merkle_caps = once(proof.trace_cap)
-        .chain(proof.permutation_zs_cap)
-        .chain(once(proof.quotient_polys_cap))
+    let merkle_caps = preprocessed_cap
+        .cloned()
+        .into_iter()
+        .chain(once(proof.trace_cap.clone()))
+        .chain(proof.permutation_ctl_zs_cap.clone())
+        .chain(once(proof.quotient_polys_cap.clone()))
         .collect_vec();
 
     verify_fri_proof::<F, C, D>(
         &stark.fri_instance(
             challenges.stark_zeta,
             F::primitive_root_of_unity(degree_bits),
+            ctl_vars.len(),
             config,
         ),
         &proof.openings.to_fri_openings(),
         &challenges.fri_challenges,
         &merkle_caps,
         &proof.opening_proof,
-        &config.fri_params(degree_bits),
+        &config.fri_params(degree_bits, stark.num_opening_points()),
     )?;
 
     Ok(())
@@ -149,24 +305,24 @@ where
 fn validate_proof_shape<F, C, S, const D: usize>(
     stark: &S,
     proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
+    num_ctl_zs: usize,
     config: &StarkConfig,
 ) -> anyhow::Result<()>
 where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
     S: Stark<F, D>,
-    [(); S::COLUMNS]:,
-    [(); C::Hasher::HASH_SIZE]:,
 {
     let StarkProofWithPublicInputs {
         proof,
         public_inputs,
     } = proof_with_pis;
-    let degree_bits = proof.recover_degree_bits(config);
+    let degree_bits = proof.degree_bits;
 
     let StarkProof {
+        degree_bits: _,
         trace_cap,
-        permutation_zs_cap,
+        permutation_ctl_zs_cap,
         quotient_polys_cap,
         openings,
         // The shape of the opening proof will be checked in the FRI verifier (see
@@ -177,42 +333,74 @@ where
     let StarkOpeningSet {
         local_values,
         next_values,
-        permutation_zs,
-        permutation_zs_next,
+        preprocessed_local_values,
+        preprocessed_next_values,
+        extra_row_values,
+        permutation_ctl_zs,
+        permutation_ctl_zs_next,
+        ctl_zs_last,
         quotient_polys,
     } = openings;
 
-    ensure!(public_inputs.len() == S::PUBLIC_INPUTS);
+    ensure!(public_inputs.len() == stark.num_public_inputs());
 
-    let fri_params = config.fri_params(degree_bits);
+    let fri_params = config.fri_params(degree_bits, stark.num_opening_points());
     let cap_height = fri_params.config.cap_height;
-    let num_zs = stark.num_permutation_batches(config);
+    let num_zs = stark.num_permutation_lookup_zs(config) + num_ctl_zs;
 
     ensure!(trace_cap.height() == cap_height);
     ensure!(quotient_polys_cap.height() == cap_height);
 
-    ensure!(local_values.len() == S::COLUMNS);
-    ensure!(next_values.len() == S::COLUMNS);
+    ensure!(local_values.len() == stark.num_columns());
+    ensure!(next_values.len() == stark.num_columns());
     ensure!(quotient_polys.len() == stark.num_quotient_polys(config));
+    ensure!(extra_row_values.len() == stark.extra_row_offsets().len());
+    ensure!(extra_row_values
+        .iter()
+        .all(|values| values.len() == stark.num_columns()));
+
+    if stark.uses_preprocessed_columns() {
+        let preprocessed_local_values = preprocessed_local_values
+            .as_ref()
+            .ok_or_else(|| anyhow!("Missing preprocessed_local_values"))?;
+        let preprocessed_next_values = preprocessed_next_values
+            .as_ref()
+            .ok_or_else(|| anyhow!("Missing preprocessed_next_values"))?;
+
+        ensure!(preprocessed_local_values.len() == stark.num_preprocessed_columns());
+        ensure!(preprocessed_next_values.len() == stark.num_preprocessed_columns());
+    } else {
+        ensure!(preprocessed_local_values.is_none());
+        ensure!(preprocessed_next_values.is_none());
+    }
 
-    if stark.uses_permutation_args() {
-        let permutation_zs_cap = permutation_zs_cap
+    if num_zs > 0 {
+        let permutation_ctl_zs_cap = permutation_ctl_zs_cap
             .as_ref()
             .ok_or_else(|| anyhow!("Missing Zs cap"))?;
-        let permutation_zs = permutation_zs
+        let permutation_ctl_zs = permutation_ctl_zs
             .as_ref()
-            .ok_or_else(|| anyhow!("Missing permutation_zs"))?;
-        let permutation_zs_next = permutation_zs_next
+            .ok_or_else(|| anyhow!("Missing permutation_ctl_zs"))?;
+        let permutation_ctl_zs_next = permutation_ctl_zs_next
             .as_ref()
-            .ok_or_else(|| anyhow!("Missing permutation_zs_next"))?;
+            .ok_or_else(|| anyhow!("Missing permutation_ctl_zs_next"))?;
 
-        ensure!(permutation_zs_cap.height() == cap_height);
-        ensure!(permutation_zs.len() == num_zs);
-        ensure!(permutation_zs_next.len() == num_zs);
+        ensure!(permutation_ctl_zs_cap.height() == cap_height);
+        ensure!(permutation_ctl_zs.len() == num_zs);
+        ensure!(permutation_ctl_zs_next.len() == num_zs);
     } else {
-        ensure!(permutation_zs_cap.is_none());
-        ensure!(permutation_zs.is_none());
-        ensure!(permutation_zs_next.is_none());
+        ensure!(permutation_ctl_zs_cap.is_none());
+        ensure!(permutation_ctl_zs.is_none());
+        ensure!(permutation_ctl_zs_next.is_none());
+    }
+
+    if num_ctl_zs > 0 {
+        let ctl_zs_last = ctl_zs_last
+            .as_ref()
+            .ok_or_else(|| anyhow!("Missing ctl_zs_last"))?;
+        ensure!(ctl_zs_last.len() == num_ctl_zs);
+    } else {
+        ensure!(ctl_zs_last.is_none());
     }
 
     Ok(())
@@ -230,30 +418,20 @@ fn eval_l_0_and_l_last<F: Field>(log_n: usize, x: F) -> (F, F) {
     (z_x * invs[0], z_x * invs[1])
 }
 
-/// Utility function to check that all permutation data wrapped in `Option`s are `Some` iff
-/// the Stark uses a permutation argument.
-fn check_permutation_options<
-    F: RichField + Extendable<D>,
-    C: GenericConfig<D, F = F>,
-    S: Stark<F, D>,
-    const D: usize,
->(
+/// Utility function to check that the permutation and lookup challenges are `Some` iff the Stark
+/// uses permutation and lookup arguments respectively.
+fn check_permutation_options<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(
     stark: &S,
-    proof_with_pis: &StarkProofWithPublicInputs<F, C, D>,
     challenges: &StarkProofChallenges<F, D>,
 ) -> Result<()> {
-    let options_is_some = [
-        proof_with_pis.proof.permutation_zs_cap.is_some(),
-        proof_with_pis.proof.openings.permutation_zs.is_some(),
-        proof_with_pis.proof.openings.permutation_zs_next.is_some(),
-        challenges.permutation_challenge_sets.is_some(),
-    ];
     ensure!(
-        options_is_some
-            .into_iter()
-            .all(|b| b == stark.uses_permutation_args()),
+        challenges.permutation_challenge_sets.is_some() == stark.uses_permutation_args(),
         "Permutation data doesn't match with Stark configuration."
     );
+    ensure!(
+        challenges.lookup_challenges.is_some() == stark.uses_lookups(),
+        "Lookup data doesn't match with Stark configuration."
+    );
     Ok(())
 }
 
@@ -0,0 +1,311 @@
+//! Wrapping of STARK proofs in plonky2 proofs, which can then be verified or aggregated like any
+//! other plonky2 proof.
+//!
+//! A `StarkWrapper` builds, on first use, one wrapper circuit per trace length. It recursively
+//! verifies a STARK proof, then shrinks the result through a chain of plonky2 recursive verifiers
+//! until it reaches a fixed size, so that wrapper proofs of every trace length share the same
+//! `CommonCircuitData` and only differ in their verifier key. A range circuit can thus verify a
+//! wrapper proof of any trace length in a range with a single recursive verifier, whose key is
+//! selected among the wrapper circuits' keys.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+use anyhow::{ensure, Result};
+use itertools::Itertools;
+use plonky2::field::extension::Extendable;
+use plonky2::gates::noop::NoopGate;
+use plonky2::hash::hash_types::{HashOutTarget, MerkleCapTarget, RichField};
+use plonky2::iop::target::Target;
+use plonky2::iop::witness::{PartialWitness, WitnessWrite};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::{CircuitConfig, CircuitData, VerifierCircuitTarget};
+use plonky2::plonk::config::{AlgebraicHasher, GenericConfig};
+use plonky2::plonk::proof::{ProofWithPublicInputs, ProofWithPublicInputsTarget};
+
+use crate::config::StarkConfig;
+use crate::proof::{StarkProofWithPublicInputs, StarkProofWithPublicInputsTarget};
+use crate::recursive_verifier::{
+    add_virtual_stark_proof_with_pis, set_stark_proof_with_pis_target, verify_stark_proof_circuit,
+};
+use crate::stark::Stark;
+
+/// Wrapper circuits are shrunk until their degree is at most `2^SHRUNK_DEGREE_BITS`, and the last
+/// one is padded to more than half of that, so that all of them end with the same degree.
+const SHRUNK_DEGREE_BITS: usize = 13;
+
+/// The circuits wrapping proofs of a STARK for traces of `2^degree_bits` rows. Their public inputs
+/// are those of the STARK.
+pub struct StarkWrapperCircuit<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+> {
+    /// The circuit verifying STARK proofs, whose size depends on the trace length.
+    stark_verifier: CircuitData<F, C, D>,
+    stark_proof_target: StarkProofWithPublicInputsTarget<D>,
+    /// Circuits each verifying a proof of the previous one. The last one has the same
+    /// `CommonCircuitData` for every trace length.
+    shrinking_circuits: Vec<ShrinkingCircuit<F, C, D>>,
+}
+
+struct ShrinkingCircuit<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+    data: CircuitData<F, C, D>,
+    proof_target: ProofWithPublicInputsTarget<D>,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
+    StarkWrapperCircuit<F, C, D>
+{
+    /// The circuit which wrapper proofs are proofs of.
+    pub fn data(&self) -> &CircuitData<F, C, D> {
+        &self.shrinking_circuits.last().unwrap().data
+    }
+}
+
+/// A circuit verifying a wrapper proof of any trace length in a range, against the verifier key of
+/// the wrapper circuit of index `wrapper_index` in the range. Its public inputs are those of the
+/// wrapper proof.
+pub struct StarkWrapperRangeCircuit<
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    const D: usize,
+> {
+    pub data: CircuitData<F, C, D>,
+    proof_target: ProofWithPublicInputsTarget<D>,
+    wrapper_index: Target,
+}
+
+/// Builds and caches the circuits wrapping proofs of `stark` for traces of `2^degree_bits` rows,
+/// for each `degree_bits` in a range.
+pub struct StarkWrapper<F, C, S, const D: usize>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    S: Stark<F, D>,
+{
+    stark: S,
+    stark_config: StarkConfig,
+    circuit_config: CircuitConfig,
+    degree_bits_range: RangeInclusive<usize>,
+    wrapper_circuits: BTreeMap<usize, StarkWrapperCircuit<F, C, D>>,
+    range_circuit: Option<StarkWrapperRangeCircuit<F, C, D>>,
+}
+
+impl<F, C, S, const D: usize> StarkWrapper<F, C, S, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+    C::Hasher: AlgebraicHasher<F>,
+    S: Stark<F, D> + Copy,
+{
+    pub fn new(
+        stark: S,
+        stark_config: StarkConfig,
+        circuit_config: CircuitConfig,
+        degree_bits_range: RangeInclusive<usize>,
+    ) -> Self {
+        assert!(
+            !degree_bits_range.is_empty(),
+            "The range of trace lengths is empty."
+        );
+        Self {
+            stark,
+            stark_config,
+            circuit_config,
+            degree_bits_range,
+            wrapper_circuits: BTreeMap::new(),
+            range_circuit: None,
+        }
+    }
+
+    /// Returns the circuits wrapping proofs for traces of `2^degree_bits` rows, building them if
+    /// needed.
+    pub fn wrapper_circuit(&mut self, degree_bits: usize) -> Result<&StarkWrapperCircuit<F, C, D>> {
+        ensure!(
+            self.degree_bits_range.contains(&degree_bits),
+            "Trace length 2^{} is outside of the wrapper's range 2^{:?}",
+            degree_bits,
+            self.degree_bits_range
+        );
+        if !self.wrapper_circuits.contains_key(&degree_bits) {
+            let wrapper_circuit = self.build_wrapper_circuit(degree_bits)?;
+            self.wrapper_circuits.insert(degree_bits, wrapper_circuit);
+        }
+        Ok(&self.wrapper_circuits[&degree_bits])
+    }
+
+    fn build_wrapper_circuit(&self, degree_bits: usize) -> Result<StarkWrapperCircuit<F, C, D>> {
+        let mut builder = CircuitBuilder::<F, D>::new(self.circuit_config.clone());
+        let stark_proof_target = add_virtual_stark_proof_with_pis(
+            &mut builder,
+            self.stark,
+            &self.stark_config,
+            degree_bits,
+        );
+        builder.register_public_inputs(&stark_proof_target.public_inputs);
+        verify_stark_proof_circuit::<F, C, S, D>(
+            &mut builder,
+            self.stark,
+            stark_proof_target.clone(),
+            &self.stark_config,
+        );
+        let stark_verifier = builder.build::<C>();
+
+        // Shrink the circuit until its degree is at most `2^SHRUNK_DEGREE_BITS`, then wrap it once
+        // more with padding.
+        let mut shrinking_circuits: Vec<ShrinkingCircuit<F, C, D>> = Vec::new();
+        loop {
+            let last = shrinking_circuits
+                .last()
+                .map_or(&stark_verifier, |circuit| &circuit.data);
+            let last_degree_bits = last.common.degree_bits();
+            let pad = last_degree_bits <= SHRUNK_DEGREE_BITS;
+
+            let mut builder = CircuitBuilder::<F, D>::new(self.circuit_config.clone());
+            let proof_target = builder.add_virtual_proof_with_pis::<C>(&last.common);
+            builder.register_public_inputs(&proof_target.public_inputs);
+            let verifier_key = builder.constant_verifier_data(&last.verifier_only);
+            builder.verify_proof::<C>(&proof_target, &verifier_key, &last.common);
+            if pad {
+                while builder.num_gates() < 1 << (SHRUNK_DEGREE_BITS - 1) {
+                    builder.add_gate(NoopGate, vec![]);
+                }
+            }
+            let data = builder.build::<C>();
+            ensure!(
+                pad || data.common.degree_bits() < last_degree_bits,
+                "Wrapper circuit for traces of 2^{} rows stalled at 2^{} gates",
+                degree_bits,
+                last_degree_bits
+            );
+            shrinking_circuits.push(ShrinkingCircuit { data, proof_target });
+            if pad {
+                break;
+            }
+        }
+        Ok(StarkWrapperCircuit {
+            stark_verifier,
+            stark_proof_target,
+            shrinking_circuits,
+        })
+    }
+
+    /// Wraps a STARK proof in a proof of the wrapper circuit of its trace length.
+    pub fn wrap(
+        &mut self,
+        proof: &StarkProofWithPublicInputs<F, C, D>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        let circuit = self.wrapper_circuit(proof.proof.degree_bits)?;
+        let mut pw = PartialWitness::new();
+        set_stark_proof_with_pis_target(&mut pw, &circuit.stark_proof_target, proof);
+        let mut wrapper_proof = circuit.stark_verifier.prove(pw)?;
+        for shrinking_circuit in &circuit.shrinking_circuits {
+            let mut pw = PartialWitness::new();
+            pw.set_proof_with_pis_target(&shrinking_circuit.proof_target, &wrapper_proof);
+            wrapper_proof = shrinking_circuit.data.prove(pw)?;
+        }
+        Ok(wrapper_proof)
+    }
+
+    /// Returns the circuit verifying wrapper proofs of any trace length in the wrapper's range,
+    /// building it and all wrapper circuits if needed.
+    pub fn range_circuit(&mut self) -> Result<&StarkWrapperRangeCircuit<F, C, D>> {
+        if self.range_circuit.is_none() {
+            for degree_bits in self.degree_bits_range.clone() {
+                self.wrapper_circuit(degree_bits)?;
+            }
+            self.range_circuit = Some(self.build_range_circuit()?);
+        }
+        Ok(self.range_circuit.as_ref().unwrap())
+    }
+
+    fn build_range_circuit(&self) -> Result<StarkWrapperRangeCircuit<F, C, D>> {
+        let wrapper_circuits = self
+            .wrapper_circuits
+            .values()
+            .map(StarkWrapperCircuit::data)
+            .collect_vec();
+        let common_data = &wrapper_circuits[0].common;
+        ensure!(
+            wrapper_circuits
+                .iter()
+                .all(|circuit| &circuit.common == common_data),
+            "Wrapper circuits of different trace lengths have different shapes"
+        );
+
+        let mut builder = CircuitBuilder::<F, D>::new(self.circuit_config.clone());
+        let proof_target = builder.add_virtual_proof_with_pis::<C>(common_data);
+        builder.register_public_inputs(&proof_target.public_inputs);
+        let wrapper_index = builder.add_virtual_target();
+        let mut verifier_keys = wrapper_circuits
+            .iter()
+            .map(|circuit| builder.constant_verifier_data(&circuit.verifier_only))
+            .collect_vec();
+        // Pad the keys to a power of two with valid keys, so that every index selects one.
+        verifier_keys.resize(
+            verifier_keys.len().next_power_of_two(),
+            verifier_keys[0].clone(),
+        );
+        let verifier_key = random_access_verifier_data(&mut builder, wrapper_index, &verifier_keys);
+        builder.verify_proof::<C>(&proof_target, &verifier_key, common_data);
+
+        Ok(StarkWrapperRangeCircuit {
+            data: builder.build::<C>(),
+            proof_target,
+            wrapper_index,
+        })
+    }
+
+    /// Wraps a STARK proof in a proof of the range circuit, going through the wrapper circuit of its
+    /// trace length.
+    pub fn wrap_in_range(
+        &mut self,
+        proof: &StarkProofWithPublicInputs<F, C, D>,
+    ) -> Result<ProofWithPublicInputs<F, C, D>> {
+        let wrapper_proof = self.wrap(proof)?;
+        let wrapper_index = proof.proof.degree_bits - self.degree_bits_range.start();
+        let range_circuit = self.range_circuit()?;
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(
+            range_circuit.wrapper_index,
+            F::from_canonical_usize(wrapper_index),
+        );
+        pw.set_proof_with_pis_target(&range_circuit.proof_target, &wrapper_proof);
+        range_circuit.data.prove(pw)
+    }
+}
+
+/// Computes `verifier_data[index]`. The number of keys must be a power of two.
+fn random_access_verifier_data<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    index: Target,
+    verifier_data: &[VerifierCircuitTarget],
+) -> VerifierCircuitTarget {
+    let mut random_access_hash = |hashes: Vec<HashOutTarget>| HashOutTarget {
+        elements: core::array::from_fn(|i| {
+            let elements = hashes.iter().map(|h| h.elements[i]).collect();
+            builder.random_access_padded(index, elements)
+        }),
+    };
+    let cap_len = verifier_data[0].constants_sigmas_cap.0.len();
+    VerifierCircuitTarget {
+        constants_sigmas_cap: MerkleCapTarget(
+            (0..cap_len)
+                .map(|i| {
+                    random_access_hash(
+                        verifier_data
+                            .iter()
+                            .map(|vd| vd.constants_sigmas_cap.0[i])
+                            .collect(),
+                    )
+                })
+                .collect(),
+        ),
+        circuit_digest: random_access_hash(
+            verifier_data.iter().map(|vd| vd.circuit_digest).collect(),
+        ),
+    }
+}
@@ -22,7 +22,7 @@ pub(crate) fn generate_addition<F: PrimeField64>(values: &mut [F; NUM_COLUMNS])
 }
 
 pub(crate) fn eval_addition<F: Field, P: PackedField<Scalar = F>>(
-    local_values: &[P; NUM_COLUMNS],
+    local_values: &[P],
     yield_constr: &mut ConstraintConsumer<P>,
 ) {
     let is_add = local_values[IS_ADD];
@@ -46,7 +46,7 @@ pub(crate) fn eval_addition<F: Field, P: PackedField<Scalar = F>>(
 
 pub(crate) fn eval_addition_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    local_values: &[ExtensionTarget<D>; NUM_COLUMNS],
+    local_values: &[ExtensionTarget<D>],
     yield_constr: &mut RecursiveConstraintConsumer<F, D>,
 ) {
     let is_add = local_values[IS_ADD];
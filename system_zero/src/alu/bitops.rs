@@ -71,7 +71,7 @@ pub(crate) fn generate_bitop<F: PrimeField64>(values: &mut [F; NUM_COLUMNS], bit
 }
 
 fn eval_bitop_32<F: Field, P: PackedField<Scalar = F>>(
-    lv: &[P; NUM_COLUMNS],
+    lv: &[P],
     input_a_regs: [usize; 32],
     input_b_regs: [usize; 32],
     output_reg: usize,
@@ -97,7 +97,7 @@ fn eval_bitop_32<F: Field, P: PackedField<Scalar = F>>(
 
     let a = binary_to_u32(a_bits);
     let b = binary_to_u32(b_bits);
-    let a_and_b = binary_to_u32(a_bits.zip(b_bits).map(|(b0, b1)| b0 * b1));
+    let a_and_b = binary_to_u32(core::array::from_fn(|i| a_bits[i] * b_bits[i]));
 
     let constraint = is_and * (a_and_b - output)
         + is_ior * (a + b - a_and_b - output)
@@ -109,7 +109,7 @@ fn eval_bitop_32<F: Field, P: PackedField<Scalar = F>>(
 
 /// Verify an AND, IOR, XOR, or ANDNOT  instruction.
 pub(crate) fn eval_bitop<F: Field, P: PackedField<Scalar = F>>(
-    lv: &[P; NUM_COLUMNS],
+    lv: &[P],
     yield_constr: &mut ConstraintConsumer<P>,
 ) {
     // Constraint for lo half
@@ -146,7 +146,7 @@ pub(crate) fn constrain_all_to_bits_circuit<F: RichField + Extendable<D>, const
 /// As for `eval_bitop`, but build with `builder`.
 fn eval_bitop_32_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    lv: &[ExtensionTarget<D>; NUM_COLUMNS],
+    lv: &[ExtensionTarget<D>],
     input_a_regs: [usize; 32],
     input_b_regs: [usize; 32],
     output_reg: usize,
@@ -173,9 +173,8 @@ fn eval_bitop_32_circuit<F: RichField + Extendable<D>, const D: usize>(
     let limb_base = builder.constant(F::TWO);
     let a = reduce_with_powers_ext_circuit(builder, &a_bits, limb_base);
     let b = reduce_with_powers_ext_circuit(builder, &b_bits, limb_base);
-    let a_and_b_bits = a_bits
-        .zip(b_bits)
-        .map(|(b0, b1)| builder.mul_extension(b0, b1));
+    let a_and_b_bits: [_; 32] =
+        core::array::from_fn(|i| builder.mul_extension(a_bits[i], b_bits[i]));
     let a_and_b = reduce_with_powers_ext_circuit(builder, &a_and_b_bits, limb_base);
 
     let and_constr = {
@@ -211,7 +210,7 @@ fn eval_bitop_32_circuit<F: RichField + Extendable<D>, const D: usize>(
 /// As for `eval_bitop` but with a builder.
 pub(crate) fn eval_bitop_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    lv: &[ExtensionTarget<D>; NUM_COLUMNS],
+    lv: &[ExtensionTarget<D>],
     yield_constr: &mut RecursiveConstraintConsumer<F, D>,
 ) {
     // Recursive constraint for lo half
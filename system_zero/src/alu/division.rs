@@ -60,7 +60,7 @@ pub(crate) fn generate_division<F: PrimeField64>(values: &mut [F; NUM_COLUMNS])
 }
 
 pub(crate) fn eval_division<F: Field, P: PackedField<Scalar = F>>(
-    lv: &[P; NUM_COLUMNS],
+    lv: &[P],
     yield_constr: &mut ConstraintConsumer<P>,
 ) {
     let base = F::from_canonical_u64(1 << 16);
@@ -92,7 +92,7 @@ pub(crate) fn eval_division<F: Field, P: PackedField<Scalar = F>>(
 
 pub(crate) fn eval_division_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    lv: &[ExtensionTarget<D>; NUM_COLUMNS],
+    lv: &[ExtensionTarget<D>],
     yield_constr: &mut RecursiveConstraintConsumer<F, D>,
 ) {
     let base = builder.constant_extension(F::Extension::from_canonical_u64(1 << 16));
@@ -16,7 +16,6 @@ use crate::alu::rotate_shift::{
     generate_rotate_shift,
 };
 use crate::alu::subtraction::{eval_subtraction, eval_subtraction_circuit, generate_subtraction};
-use crate::public_input_layout::NUM_PUBLIC_INPUTS;
 use crate::registers::alu::*;
 use crate::registers::NUM_COLUMNS;
 
@@ -59,7 +58,7 @@ pub(crate) fn generate_alu<F: PrimeField64>(values: &mut [F; NUM_COLUMNS]) {
 }
 
 pub(crate) fn eval_alu<F: Field, P: PackedField<Scalar = F>>(
-    vars: StarkEvaluationVars<F, P, NUM_COLUMNS, NUM_PUBLIC_INPUTS>,
+    vars: StarkEvaluationVars<F, P>,
     yield_constr: &mut ConstraintConsumer<P>,
 ) {
     let local_values = &vars.local_values;
@@ -83,7 +82,7 @@ pub(crate) fn eval_alu<F: Field, P: PackedField<Scalar = F>>(
 
 pub(crate) fn eval_alu_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    vars: StarkEvaluationTargets<D, NUM_COLUMNS, NUM_PUBLIC_INPUTS>,
+    vars: StarkEvaluationTargets<D>,
     yield_constr: &mut RecursiveConstraintConsumer<F, D>,
 ) {
     let local_values = &vars.local_values;
@@ -33,7 +33,7 @@ pub(crate) fn generate_mul_add<F: PrimeField64>(values: &mut [F; NUM_COLUMNS]) {
 }
 
 pub(crate) fn eval_mul_add<F: Field, P: PackedField<Scalar = F>>(
-    local_values: &[P; NUM_COLUMNS],
+    local_values: &[P],
     yield_constr: &mut ConstraintConsumer<P>,
 ) {
     let is_mul = local_values[IS_MUL_ADD];
@@ -61,7 +61,7 @@ pub(crate) fn eval_mul_add<F: Field, P: PackedField<Scalar = F>>(
 
 pub(crate) fn eval_mul_add_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    local_values: &[ExtensionTarget<D>; NUM_COLUMNS],
+    local_values: &[ExtensionTarget<D>],
     yield_constr: &mut RecursiveConstraintConsumer<F, D>,
 ) {
     let is_mul = local_values[IS_MUL_ADD];
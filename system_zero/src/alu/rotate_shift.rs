@@ -125,7 +125,7 @@ pub(crate) fn generate_rotate_shift<F: PrimeField64>(values: &mut [F; NUM_COLUMN
 ///
 /// Also check that every "bit" of exp_bits and exp_ge32_bit is 0 or 1.
 fn constrain_pow_exp<F: Field, P: PackedField<Scalar = F>>(
-    lv: &[P; NUM_COLUMNS],
+    lv: &[P],
     yield_constr: &mut ConstraintConsumer<P>,
     filter: P,
 ) {
@@ -168,7 +168,7 @@ fn constrain_pow_exp<F: Field, P: PackedField<Scalar = F>>(
 ///   v_lo * (one - aux * (u32_max - v_hi)) == 0
 /// where aux = (m32_max - v_hi)^-1 if it exists.
 fn constrain_shifted_are_valid<F: Field, P: PackedField<Scalar = F>>(
-    lv: &[P; NUM_COLUMNS],
+    lv: &[P],
     yield_constr: &mut ConstraintConsumer<P>,
     filter: P,
 ) {
@@ -197,7 +197,7 @@ fn constrain_shifted_are_valid<F: Field, P: PackedField<Scalar = F>>(
 }
 
 fn eval_rotate_shift<F: Field, P: PackedField<Scalar = F>>(
-    lv: &[P; NUM_COLUMNS],
+    lv: &[P],
     yield_constr: &mut ConstraintConsumer<P>,
     filter: P,
 ) -> (P, P, P, P, P, P, P) {
@@ -252,7 +252,7 @@ fn eval_rotate_shift<F: Field, P: PackedField<Scalar = F>>(
 }
 
 pub(crate) fn eval_rotate_left<F: Field, P: PackedField<Scalar = F>>(
-    lv: &[P; NUM_COLUMNS],
+    lv: &[P],
     yield_constr: &mut ConstraintConsumer<P>,
 ) {
     let is_rol = lv[IS_ROTATE_LEFT];
@@ -278,7 +278,7 @@ pub(crate) fn eval_rotate_left<F: Field, P: PackedField<Scalar = F>>(
 }
 
 pub(crate) fn eval_rotate_right<F: Field, P: PackedField<Scalar = F>>(
-    lv: &[P; NUM_COLUMNS],
+    lv: &[P],
     yield_constr: &mut ConstraintConsumer<P>,
 ) {
     let is_ror = lv[IS_ROTATE_RIGHT];
@@ -300,7 +300,7 @@ pub(crate) fn eval_rotate_right<F: Field, P: PackedField<Scalar = F>>(
 }
 
 pub(crate) fn eval_shift_left<F: Field, P: PackedField<Scalar = F>>(
-    lv: &[P; NUM_COLUMNS],
+    lv: &[P],
     yield_constr: &mut ConstraintConsumer<P>,
 ) {
     let is_shl = lv[IS_SHIFT_LEFT];
@@ -322,7 +322,7 @@ pub(crate) fn eval_shift_left<F: Field, P: PackedField<Scalar = F>>(
 }
 
 pub(crate) fn eval_shift_right<F: Field, P: PackedField<Scalar = F>>(
-    lv: &[P; NUM_COLUMNS],
+    lv: &[P],
     yield_constr: &mut ConstraintConsumer<P>,
 ) {
     let is_shl = lv[IS_SHIFT_LEFT];
@@ -345,7 +345,7 @@ pub(crate) fn eval_shift_right<F: Field, P: PackedField<Scalar = F>>(
 
 fn constrain_pow_exp_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    lv: &[ExtensionTarget<D>; NUM_COLUMNS],
+    lv: &[ExtensionTarget<D>],
     yield_constr: &mut RecursiveConstraintConsumer<F, D>,
     filter: ExtensionTarget<D>,
 ) {
@@ -394,7 +394,7 @@ fn constrain_pow_exp_circuit<F: RichField + Extendable<D>, const D: usize>(
 
 fn constrain_shifted_are_valid_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    lv: &[ExtensionTarget<D>; NUM_COLUMNS],
+    lv: &[ExtensionTarget<D>],
     yield_constr: &mut RecursiveConstraintConsumer<F, D>,
     filter: ExtensionTarget<D>,
 ) {
@@ -440,7 +440,7 @@ fn constrain_shifted_are_valid_circuit<F: RichField + Extendable<D>, const D: us
 
 fn eval_rotate_shift_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    lv: &[ExtensionTarget<D>; NUM_COLUMNS],
+    lv: &[ExtensionTarget<D>],
     yield_constr: &mut RecursiveConstraintConsumer<F, D>,
     filter: ExtensionTarget<D>,
 ) -> (
@@ -502,7 +502,7 @@ fn eval_rotate_shift_circuit<F: RichField + Extendable<D>, const D: usize>(
 
 pub(crate) fn eval_rotate_left_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    lv: &[ExtensionTarget<D>; NUM_COLUMNS],
+    lv: &[ExtensionTarget<D>],
     yield_constr: &mut RecursiveConstraintConsumer<F, D>,
 ) {
     let is_rol = lv[IS_ROTATE_LEFT];
@@ -539,7 +539,7 @@ pub(crate) fn eval_rotate_left_circuit<F: RichField + Extendable<D>, const D: us
 
 pub(crate) fn eval_rotate_right_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    lv: &[ExtensionTarget<D>; NUM_COLUMNS],
+    lv: &[ExtensionTarget<D>],
     yield_constr: &mut RecursiveConstraintConsumer<F, D>,
 ) {
     let is_ror = lv[IS_ROTATE_RIGHT];
@@ -576,7 +576,7 @@ pub(crate) fn eval_rotate_right_circuit<F: RichField + Extendable<D>, const D: u
 
 pub(crate) fn eval_shift_left_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    lv: &[ExtensionTarget<D>; NUM_COLUMNS],
+    lv: &[ExtensionTarget<D>],
     yield_constr: &mut RecursiveConstraintConsumer<F, D>,
 ) {
     let is_shl = lv[IS_SHIFT_LEFT];
@@ -611,7 +611,7 @@ pub(crate) fn eval_shift_left_circuit<F: RichField + Extendable<D>, const D: usi
 
 pub(crate) fn eval_shift_right_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    lv: &[ExtensionTarget<D>; NUM_COLUMNS],
+    lv: &[ExtensionTarget<D>],
     yield_constr: &mut RecursiveConstraintConsumer<F, D>,
 ) {
     let is_shr = lv[IS_SHIFT_RIGHT];
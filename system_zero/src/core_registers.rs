@@ -6,7 +6,6 @@ use plonky2::plonk::circuit_builder::CircuitBuilder;
 use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
 use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
 
-use crate::public_input_layout::NUM_PUBLIC_INPUTS;
 use crate::registers::core::*;
 use crate::registers::NUM_COLUMNS;
 
@@ -40,7 +39,7 @@ pub(crate) fn generate_next_row_core_registers<F: PrimeField64>(
 
 #[inline]
 pub(crate) fn eval_core_registers<F: Field, P: PackedField<Scalar = F>>(
-    vars: StarkEvaluationVars<F, P, NUM_COLUMNS, NUM_PUBLIC_INPUTS>,
+    vars: StarkEvaluationVars<F, P>,
     yield_constr: &mut ConstraintConsumer<P>,
 ) {
     // The clock must start with 0, and increment by 1.
@@ -63,7 +62,7 @@ pub(crate) fn eval_core_registers<F: Field, P: PackedField<Scalar = F>>(
 
 pub(crate) fn eval_core_registers_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    vars: StarkEvaluationTargets<D, NUM_COLUMNS, NUM_PUBLIC_INPUTS>,
+    vars: StarkEvaluationTargets<D>,
     yield_constr: &mut RecursiveConstraintConsumer<F, D>,
 ) {
     let one_ext = builder.one_extension();
@@ -1,5 +1,3 @@
-#![feature(array_zip)]
-
 mod alu;
 mod core_registers;
 pub mod lookup;
@@ -15,9 +15,7 @@ use plonky2::plonk::circuit_builder::CircuitBuilder;
 use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
 use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
 
-use crate::public_input_layout::NUM_PUBLIC_INPUTS;
 use crate::registers::lookup::*;
-use crate::registers::NUM_COLUMNS;
 
 pub(crate) fn generate_lookups<F: PrimeField64>(trace_cols: &mut [Vec<F>]) {
     for i in 0..NUM_LOOKUPS {
@@ -98,7 +96,7 @@ pub fn permuted_cols<F: PrimeField64>(inputs: &[F], table: &[F]) -> (Vec<F>, Vec
 }
 
 pub(crate) fn eval_lookups<F: Field, P: PackedField<Scalar = F>>(
-    vars: StarkEvaluationVars<F, P, NUM_COLUMNS, NUM_PUBLIC_INPUTS>,
+    vars: StarkEvaluationVars<F, P>,
     yield_constr: &mut ConstraintConsumer<P>,
 ) {
     for i in 0..NUM_LOOKUPS {
@@ -122,7 +120,7 @@ pub(crate) fn eval_lookups<F: Field, P: PackedField<Scalar = F>>(
 
 pub(crate) fn eval_lookups_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    vars: StarkEvaluationTargets<D, NUM_COLUMNS, NUM_PUBLIC_INPUTS>,
+    vars: StarkEvaluationTargets<D>,
     yield_constr: &mut RecursiveConstraintConsumer<F, D>,
 ) {
     for i in 0..NUM_LOOKUPS {
@@ -7,7 +7,6 @@ use plonky2::plonk::circuit_builder::CircuitBuilder;
 use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
 use starky::vars::{StarkEvaluationTargets, StarkEvaluationVars};
 
-use crate::public_input_layout::NUM_PUBLIC_INPUTS;
 use crate::registers::permutation::*;
 use crate::registers::NUM_COLUMNS;
 
@@ -106,7 +105,7 @@ pub(crate) fn generate_permutation_unit<F: Poseidon>(values: &mut [F; NUM_COLUMN
 
 #[inline]
 pub(crate) fn eval_permutation_unit<F, FE, P, const D: usize>(
-    vars: StarkEvaluationVars<FE, P, NUM_COLUMNS, NUM_PUBLIC_INPUTS>,
+    vars: StarkEvaluationVars<FE, P>,
     yield_constr: &mut ConstraintConsumer<P>,
 ) where
     F: Poseidon,
@@ -173,7 +172,7 @@ pub(crate) fn eval_permutation_unit<F, FE, P, const D: usize>(
 
 pub(crate) fn eval_permutation_unit_circuit<F: RichField + Extendable<D>, const D: usize>(
     builder: &mut CircuitBuilder<F, D>,
-    vars: StarkEvaluationTargets<D, NUM_COLUMNS, NUM_PUBLIC_INPUTS>,
+    vars: StarkEvaluationTargets<D>,
     yield_constr: &mut RecursiveConstraintConsumer<F, D>,
 ) {
     let zero = builder.zero_extension();
@@ -276,6 +275,9 @@ mod tests {
         let vars = StarkEvaluationVars {
             local_values: &values,
             next_values: &[F::default(); NUM_COLUMNS],
+            preprocessed_local_values: &[],
+            preprocessed_next_values: &[],
+            extra_row_values: &[],
             public_inputs: &[F::default(); NUM_PUBLIC_INPUTS],
         };
 
@@ -116,12 +116,17 @@ impl<F: RichField + Extendable<D>, const D: usize> Default for SystemZero<F, D>
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for SystemZero<F, D> {
-    const COLUMNS: usize = NUM_COLUMNS;
-    const PUBLIC_INPUTS: usize = NUM_PUBLIC_INPUTS;
+    fn num_columns(&self) -> usize {
+        NUM_COLUMNS
+    }
+
+    fn num_public_inputs(&self) -> usize {
+        NUM_PUBLIC_INPUTS
+    }
 
     fn eval_packed_generic<FE, P, const D2: usize>(
         &self,
-        vars: StarkEvaluationVars<FE, P, NUM_COLUMNS, NUM_PUBLIC_INPUTS>,
+        vars: StarkEvaluationVars<FE, P>,
         yield_constr: &mut ConstraintConsumer<P>,
     ) where
         FE: FieldExtension<D2, BaseField = F>,
@@ -137,7 +142,7 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for SystemZero<F,
     fn eval_ext_circuit(
         &self,
         builder: &mut CircuitBuilder<F, D>,
-        vars: StarkEvaluationTargets<D, NUM_COLUMNS, NUM_PUBLIC_INPUTS>,
+        vars: StarkEvaluationTargets<D>,
         yield_constr: &mut RecursiveConstraintConsumer<F, D>,
     ) {
         eval_core_registers_circuit(builder, vars, yield_constr);
@@ -197,11 +202,11 @@ mod tests {
 
         type S = SystemZero<F, D>;
         let system = S::default();
-        let public_inputs = [F::ZERO; S::PUBLIC_INPUTS];
+        let public_inputs = vec![F::ZERO; system.num_public_inputs()];
         let config = StarkConfig::standard_fast_config();
         let mut timing = TimingTree::new("prove", Level::Debug);
         let trace = system.generate_trace();
-        let proof = prove::<F, C, S, D>(system, &config, trace, public_inputs, &mut timing)?;
+        let proof = prove::<F, C, S, D>(system, &config, trace, &public_inputs, &mut timing)?;
 
         verify_stark_proof(system, proof, &config)
     }
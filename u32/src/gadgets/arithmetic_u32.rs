@@ -6,17 +6,47 @@ use plonky2::field::extension::Extendable;
 use plonky2::hash::hash_types::RichField;
 use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
 use plonky2::iop::target::Target;
-use plonky2::iop::witness::{PartitionWitness, Witness};
+use plonky2::iop::variable::CircuitVariable;
+use plonky2::iop::witness::{PartitionWitness, Witness, WitnessWrite};
 use plonky2::plonk::circuit_builder::CircuitBuilder;
 
+use crate::gadgets::range_check::range_check_u32_circuit;
 use crate::gates::add_many_u32::U32AddManyGate;
 use crate::gates::arithmetic_u32::U32ArithmeticGate;
 use crate::gates::subtraction_u32::U32SubtractionGate;
-use crate::witness::GeneratedValuesU32;
+use crate::witness::{GeneratedValuesU32, WitnessU32};
 
 #[derive(Clone, Copy, Debug)]
 pub struct U32Target(pub Target);
 
+impl CircuitVariable for U32Target {
+    type Value<F: RichField> = u32;
+    type Layout = ();
+
+    fn add_virtual<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        _layout: &(),
+    ) -> Self {
+        let x = builder.add_virtual_u32_target();
+        range_check_u32_circuit(builder, vec![x]);
+        x
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        vec![self.0]
+    }
+
+    fn set<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: &u32) {
+        witness.set_target(self.0, F::from_canonical_u32(*value));
+    }
+
+    fn get<F: RichField, W: Witness<F>>(&self, witness: &W) -> u32 {
+        let (low, high) = witness.get_u32_target(*self);
+        debug_assert_eq!(high, 0);
+        low
+    }
+}
+
 pub trait CircuitBuilderU32<F: RichField + Extendable<D>, const D: usize> {
     fn add_virtual_u32_target(&mut self) -> U32Target;
 
@@ -259,6 +289,7 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F>
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
+    use plonky2::field::types::Field;
     use plonky2::iop::witness::PartialWitness;
     use plonky2::plonk::circuit_data::CircuitConfig;
     use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
@@ -300,4 +331,22 @@ mod tests {
         let proof = data.prove(pw).unwrap();
         data.verify(proof)
     }
+
+    #[test]
+    fn test_add_virtual_u32_is_range_checked() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = U32Target::add_virtual(&mut builder, &());
+        let data = builder.build::<C>();
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x.0, F::from_canonical_u64(1 << 32));
+        // The prover doesn't check constraints, so the proof may be generated, but must not verify.
+        let proof = data.prove(pw);
+        assert!(proof.map_or(true, |proof| data.verify(proof).is_err()));
+    }
 }
@@ -0,0 +1,456 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::generator::{GeneratedValues, SimpleGenerator};
+use plonky2::iop::target::{BoolTarget, Target};
+use plonky2::iop::variable::CircuitVariable;
+use plonky2::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+
+use crate::gadgets::arithmetic_u32::U32Target;
+use crate::gates::bitwise_u8::U8BitwiseGate;
+use crate::witness::WitnessU8;
+
+#[derive(Clone, Copy, Debug)]
+pub struct U8Target(pub Target);
+
+impl CircuitVariable for U8Target {
+    type Value<F: RichField> = u8;
+    type Layout = ();
+
+    fn add_virtual<F: RichField + Extendable<D>, const D: usize>(
+        builder: &mut CircuitBuilder<F, D>,
+        _layout: &(),
+    ) -> Self {
+        builder.add_virtual_u8_target_safe()
+    }
+
+    fn targets(&self) -> Vec<Target> {
+        vec![self.0]
+    }
+
+    fn set<F: RichField, W: WitnessWrite<F>>(&self, witness: &mut W, value: &u8) {
+        witness.set_target(self.0, F::from_canonical_u8(*value));
+    }
+
+    fn get<F: RichField, W: Witness<F>>(&self, witness: &W) -> u8 {
+        witness.get_u8_target(*self)
+    }
+}
+
+pub trait CircuitBuilderU8<F: RichField + Extendable<D>, const D: usize> {
+    /// Returns a `U8Target` which is not range-checked.
+    fn add_virtual_u8_target_unsafe(&mut self) -> U8Target;
+
+    fn add_virtual_u8_target_safe(&mut self) -> U8Target;
+
+    fn add_virtual_u8_targets_safe(&mut self, n: usize) -> Vec<U8Target>;
+
+    fn constant_u8(&mut self, c: u8) -> U8Target;
+
+    fn connect_u8(&mut self, x: U8Target, y: U8Target);
+
+    /// Checks that `x` is a byte, and returns it as a `U8Target`.
+    fn target_as_u8(&mut self, x: Target) -> U8Target;
+
+    /// Checks that each of `vals` is a byte. Two bytes are checked per `U8BitwiseGate` operation.
+    fn range_check_u8(&mut self, vals: &[U8Target]);
+
+    fn and_u8(&mut self, x: U8Target, y: U8Target) -> U8Target;
+
+    fn or_u8(&mut self, x: U8Target, y: U8Target) -> U8Target;
+
+    fn xor_u8(&mut self, x: U8Target, y: U8Target) -> U8Target;
+
+    fn not_u8(&mut self, x: U8Target) -> U8Target;
+
+    /// Returns the little-endian bits of `x`.
+    fn split_u8_to_bits(&mut self, x: U8Target) -> Vec<BoolTarget>;
+
+    /// Returns the little-endian bits of the little-endian byte string `xs`, i.e. the bits of
+    /// `xs[0]`, then those of `xs[1]`, etc.
+    fn split_u8s_to_bits(&mut self, xs: &[U8Target]) -> Vec<BoolTarget>;
+
+    /// Packs little-endian bits into bytes, the last of which may be partial.
+    fn u8s_from_bits(&mut self, bits: &[BoolTarget]) -> Vec<U8Target>;
+
+    fn shl_u8(&mut self, x: U8Target, n: usize) -> U8Target;
+
+    fn shr_u8(&mut self, x: U8Target, n: usize) -> U8Target;
+
+    fn rotl_u8(&mut self, x: U8Target, n: usize) -> U8Target;
+
+    fn rotr_u8(&mut self, x: U8Target, n: usize) -> U8Target;
+
+    /// Shifts the integer with little-endian bytes `xs` left by `n` bits, dropping the bits
+    /// shifted past the last byte.
+    fn shl_u8s_le(&mut self, xs: &[U8Target], n: usize) -> Vec<U8Target>;
+
+    /// Shifts the integer with little-endian bytes `xs` right by `n` bits.
+    fn shr_u8s_le(&mut self, xs: &[U8Target], n: usize) -> Vec<U8Target>;
+
+    /// Rotates the integer with little-endian bytes `xs` left by `n` bits.
+    fn rotl_u8s_le(&mut self, xs: &[U8Target], n: usize) -> Vec<U8Target>;
+
+    /// Rotates the integer with little-endian bytes `xs` right by `n` bits.
+    fn rotr_u8s_le(&mut self, xs: &[U8Target], n: usize) -> Vec<U8Target>;
+
+    /// Returns the integer with little-endian bytes `xs`. Panics if it may not fit in a field
+    /// element, i.e. if `xs` has more than 7 bytes for a 64-bit field.
+    fn u8s_le_to_target(&mut self, xs: &[U8Target]) -> Target;
+
+    /// Returns the `num_bytes` little-endian bytes of `x`, checking that `x` fits in them. Panics
+    /// if the decomposition would not be unique, i.e. if `num_bytes` is more than 7 for a 64-bit
+    /// field.
+    fn target_to_u8s_le(&mut self, x: Target, num_bytes: usize) -> Vec<U8Target>;
+
+    fn u8s_le_to_u32(&mut self, xs: [U8Target; 4]) -> U32Target;
+
+    fn u8s_be_to_u32(&mut self, xs: [U8Target; 4]) -> U32Target;
+
+    fn u32_to_u8s_le(&mut self, x: U32Target) -> [U8Target; 4];
+
+    fn u32_to_u8s_be(&mut self, x: U32Target) -> [U8Target; 4];
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilderU8<F, D> for CircuitBuilder<F, D> {
+    fn add_virtual_u8_target_unsafe(&mut self) -> U8Target {
+        U8Target(self.add_virtual_target())
+    }
+
+    fn add_virtual_u8_target_safe(&mut self) -> U8Target {
+        let x = self.add_virtual_u8_target_unsafe();
+        self.range_check_u8(&[x]);
+        x
+    }
+
+    fn add_virtual_u8_targets_safe(&mut self, n: usize) -> Vec<U8Target> {
+        let xs = (0..n)
+            .map(|_| self.add_virtual_u8_target_unsafe())
+            .collect::<Vec<_>>();
+        self.range_check_u8(&xs);
+        xs
+    }
+
+    fn constant_u8(&mut self, c: u8) -> U8Target {
+        U8Target(self.constant(F::from_canonical_u8(c)))
+    }
+
+    fn connect_u8(&mut self, x: U8Target, y: U8Target) {
+        self.connect(x.0, y.0)
+    }
+
+    fn target_as_u8(&mut self, x: Target) -> U8Target {
+        let x = U8Target(x);
+        self.range_check_u8(&[x]);
+        x
+    }
+
+    fn range_check_u8(&mut self, vals: &[U8Target]) {
+        let zero = U8Target(self.zero());
+        for pair in vals.chunks(2) {
+            let y = pair.get(1).copied().unwrap_or(zero);
+            bitwise_u8_op(self, pair[0], y);
+        }
+    }
+
+    fn and_u8(&mut self, x: U8Target, y: U8Target) -> U8Target {
+        let (gate, row, copy) = bitwise_u8_op(self, x, y);
+        U8Target(Target::wire(row, gate.wire_ith_output_and(copy)))
+    }
+
+    fn or_u8(&mut self, x: U8Target, y: U8Target) -> U8Target {
+        // x | y = x + y - (x & y)
+        let and = self.and_u8(x, y);
+        let sum = self.add(x.0, y.0);
+        U8Target(self.sub(sum, and.0))
+    }
+
+    fn xor_u8(&mut self, x: U8Target, y: U8Target) -> U8Target {
+        let (gate, row, copy) = bitwise_u8_op(self, x, y);
+        U8Target(Target::wire(row, gate.wire_ith_output_xor(copy)))
+    }
+
+    fn not_u8(&mut self, x: U8Target) -> U8Target {
+        let max = self.constant(F::from_canonical_u8(u8::MAX));
+        U8Target(self.sub(max, x.0))
+    }
+
+    fn split_u8_to_bits(&mut self, x: U8Target) -> Vec<BoolTarget> {
+        self.split_u8s_to_bits(&[x])
+    }
+
+    fn split_u8s_to_bits(&mut self, xs: &[U8Target]) -> Vec<BoolTarget> {
+        let zero = U8Target(self.zero());
+        let mut bits = Vec::with_capacity(8 * xs.len());
+        for pair in xs.chunks(2) {
+            let y = pair.get(1).copied().unwrap_or(zero);
+            let (gate, row, copy) = bitwise_u8_op(self, pair[0], y);
+            let x_bits = (0..8).map(|j| gate.wire_ith_x_jth_bit(copy, j));
+            let y_bits = (0..8).map(|j| gate.wire_ith_y_jth_bit(copy, j));
+            let wires = x_bits.chain(y_bits).take(8 * pair.len());
+            bits.extend(wires.map(|w| BoolTarget::new_unsafe(Target::wire(row, w))));
+        }
+        bits
+    }
+
+    fn u8s_from_bits(&mut self, bits: &[BoolTarget]) -> Vec<U8Target> {
+        let two = F::TWO;
+        bits.chunks(8)
+            .map(|byte_bits| {
+                let mut byte = self.zero();
+                for bit in byte_bits.iter().rev() {
+                    byte = self.mul_const_add(two, byte, bit.target);
+                }
+                U8Target(byte)
+            })
+            .collect()
+    }
+
+    fn shl_u8(&mut self, x: U8Target, n: usize) -> U8Target {
+        self.shl_u8s_le(&[x], n)[0]
+    }
+
+    fn shr_u8(&mut self, x: U8Target, n: usize) -> U8Target {
+        self.shr_u8s_le(&[x], n)[0]
+    }
+
+    fn rotl_u8(&mut self, x: U8Target, n: usize) -> U8Target {
+        self.rotl_u8s_le(&[x], n)[0]
+    }
+
+    fn rotr_u8(&mut self, x: U8Target, n: usize) -> U8Target {
+        self.rotr_u8s_le(&[x], n)[0]
+    }
+
+    fn shl_u8s_le(&mut self, xs: &[U8Target], n: usize) -> Vec<U8Target> {
+        let bits = self.split_u8s_to_bits(xs);
+        let n = n.min(bits.len());
+        let zero = self._false();
+        let shifted = [vec![zero; n], bits[..bits.len() - n].to_vec()].concat();
+        self.u8s_from_bits(&shifted)
+    }
+
+    fn shr_u8s_le(&mut self, xs: &[U8Target], n: usize) -> Vec<U8Target> {
+        let bits = self.split_u8s_to_bits(xs);
+        let n = n.min(bits.len());
+        let zero = self._false();
+        let shifted = [bits[n..].to_vec(), vec![zero; n]].concat();
+        self.u8s_from_bits(&shifted)
+    }
+
+    fn rotl_u8s_le(&mut self, xs: &[U8Target], n: usize) -> Vec<U8Target> {
+        let mut bits = self.split_u8s_to_bits(xs);
+        let len = bits.len();
+        bits.rotate_right(n % len);
+        self.u8s_from_bits(&bits)
+    }
+
+    fn rotr_u8s_le(&mut self, xs: &[U8Target], n: usize) -> Vec<U8Target> {
+        let mut bits = self.split_u8s_to_bits(xs);
+        let len = bits.len();
+        bits.rotate_left(n % len);
+        self.u8s_from_bits(&bits)
+    }
+
+    fn u8s_le_to_target(&mut self, xs: &[U8Target]) -> Target {
+        assert!(
+            8 * xs.len() < F::BITS,
+            "Too many bytes to fit in a field element"
+        );
+        let base = F::from_canonical_u64(1 << 8);
+        let mut result = self.zero();
+        for x in xs.iter().rev() {
+            result = self.mul_const_add(base, result, x.0);
+        }
+        result
+    }
+
+    fn target_to_u8s_le(&mut self, x: Target, num_bytes: usize) -> Vec<U8Target> {
+        let bytes = (0..num_bytes)
+            .map(|_| self.add_virtual_u8_target_unsafe())
+            .collect::<Vec<_>>();
+        self.add_simple_generator(SplitToU8sGenerator::<F, D> {
+            x,
+            bytes: bytes.clone(),
+            _phantom: PhantomData,
+        });
+        self.range_check_u8(&bytes);
+        let recombined = self.u8s_le_to_target(&bytes);
+        self.connect(recombined, x);
+        bytes
+    }
+
+    fn u8s_le_to_u32(&mut self, xs: [U8Target; 4]) -> U32Target {
+        U32Target(self.u8s_le_to_target(&xs))
+    }
+
+    fn u8s_be_to_u32(&mut self, mut xs: [U8Target; 4]) -> U32Target {
+        xs.reverse();
+        self.u8s_le_to_u32(xs)
+    }
+
+    fn u32_to_u8s_le(&mut self, x: U32Target) -> [U8Target; 4] {
+        self.target_to_u8s_le(x.0, 4).try_into().unwrap()
+    }
+
+    fn u32_to_u8s_be(&mut self, x: U32Target) -> [U8Target; 4] {
+        let mut bytes = self.u32_to_u8s_le(x);
+        bytes.reverse();
+        bytes
+    }
+}
+
+/// Adds a `U8BitwiseGate` operation on `x` and `y`, returning the gate and its location.
+fn bitwise_u8_op<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    x: U8Target,
+    y: U8Target,
+) -> (U8BitwiseGate<F, D>, usize, usize) {
+    let gate = U8BitwiseGate::<F, D>::new_from_config(&builder.config);
+    let (row, copy) = builder.find_slot(gate, &[], &[]);
+
+    builder.connect(Target::wire(row, gate.wire_ith_input_x(copy)), x.0);
+    builder.connect(Target::wire(row, gate.wire_ith_input_y(copy)), y.0);
+
+    (gate, row, copy)
+}
+
+#[derive(Debug)]
+struct SplitToU8sGenerator<F: RichField + Extendable<D>, const D: usize> {
+    x: Target,
+    bytes: Vec<U8Target>,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F>
+    for SplitToU8sGenerator<F, D>
+{
+    fn dependencies(&self) -> Vec<Target> {
+        vec![self.x]
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let x = witness.get_target(self.x).to_canonical_u64();
+        for (i, byte) in self.bytes.iter().enumerate() {
+            let value = (x >> (8 * i)) as u8;
+            out_buffer.set_target(byte.0, F::from_canonical_u8(value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::field::types::Field;
+    use plonky2::iop::witness::PartialWitness;
+    use plonky2::plonk::circuit_data::CircuitConfig;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::gadgets::arithmetic_u32::CircuitBuilderU32;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_bitwise_u8() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let mut rng = OsRng;
+        let (x_value, y_value): (u8, u8) = (rng.gen(), rng.gen());
+        let n = rng.gen_range(0..8);
+        let x = builder.add_virtual_u8_target_safe();
+        let y = builder.add_virtual_u8_target_safe();
+        pw.set_u8_target(x, x_value);
+        pw.set_u8_target(y, y_value);
+
+        let results = [
+            (builder.and_u8(x, y), x_value & y_value),
+            (builder.or_u8(x, y), x_value | y_value),
+            (builder.xor_u8(x, y), x_value ^ y_value),
+            (builder.not_u8(x), !x_value),
+            (builder.shl_u8(x, n), x_value << n),
+            (builder.shr_u8(x, n), x_value >> n),
+            (builder.rotl_u8(x, n), x_value.rotate_left(n as u32)),
+            (builder.rotr_u8(x, n), x_value.rotate_right(n as u32)),
+        ];
+        for (result, expected) in results {
+            let expected = builder.constant_u8(expected);
+            builder.connect_u8(result, expected);
+        }
+
+        let bits = builder.split_u8_to_bits(x);
+        for (j, bit) in bits.into_iter().enumerate() {
+            let expected = builder.constant_bool((x_value >> j) & 1 == 1);
+            builder.connect(bit.target, expected.target);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+
+    #[test]
+    fn test_u8_conversions() -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let mut rng = OsRng;
+        let value: u32 = rng.gen();
+        let n = rng.gen_range(0..32);
+        let x = builder.constant_u32(value);
+
+        let le_bytes = builder.u32_to_u8s_le(x);
+        let be_bytes = builder.u32_to_u8s_be(x);
+        for i in 0..4 {
+            let expected_le = builder.constant_u8(value.to_le_bytes()[i]);
+            builder.connect_u8(le_bytes[i], expected_le);
+            let expected_be = builder.constant_u8(value.to_be_bytes()[i]);
+            builder.connect_u8(be_bytes[i], expected_be);
+        }
+        let from_le = builder.u8s_le_to_u32(le_bytes);
+        builder.connect_u32(from_le, x);
+        let from_be = builder.u8s_be_to_u32(be_bytes);
+        builder.connect_u32(from_be, x);
+
+        let word_results = [
+            (builder.shl_u8s_le(&le_bytes, n), value << n),
+            (builder.shr_u8s_le(&le_bytes, n), value >> n),
+            (
+                builder.rotl_u8s_le(&le_bytes, n),
+                value.rotate_left(n as u32),
+            ),
+            (
+                builder.rotr_u8s_le(&le_bytes, n),
+                value.rotate_right(n as u32),
+            ),
+        ];
+        for (result, expected) in word_results {
+            let result = builder.u8s_le_to_u32(result.try_into().unwrap());
+            let expected = builder.constant_u32(expected);
+            builder.connect_u32(result, expected);
+        }
+
+        let wide_value = rng.gen::<u64>() >> 8;
+        let wide = builder.constant(F::from_canonical_u64(wide_value));
+        let wide_bytes = builder.target_to_u8s_le(wide, 7);
+        for (i, &byte) in wide_bytes.iter().enumerate() {
+            let expected = builder.constant_u8(wide_value.to_le_bytes()[i]);
+            builder.connect_u8(byte, expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        data.verify(proof)
+    }
+}
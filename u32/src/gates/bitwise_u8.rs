@@ -0,0 +1,397 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::marker::PhantomData;
+
+use plonky2::field::extension::Extendable;
+use plonky2::field::packed::PackedField;
+use plonky2::field::types::Field;
+use plonky2::gates::gate::Gate;
+use plonky2::gates::packed_util::PackedEvaluableBase;
+use plonky2::gates::util::StridedConstraintConsumer;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGenerator};
+use plonky2::iop::target::Target;
+use plonky2::iop::wire::Wire;
+use plonky2::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use plonky2::plonk::circuit_builder::CircuitBuilder;
+use plonky2::plonk::circuit_data::CircuitConfig;
+use plonky2::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+    EvaluationVarsBasePacked,
+};
+
+/// A gate to decompose two bytes `x` and `y` into bits, and compute `x & y` and `x ^ y`. As the
+/// bits are constrained to be boolean, this also range-checks `x` and `y` to 8 bits. The bits are
+/// routed, so that shifts and rotations can be computed from them without further gates.
+#[derive(Copy, Clone, Debug)]
+pub struct U8BitwiseGate<F: RichField + Extendable<D>, const D: usize> {
+    pub num_ops: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> U8BitwiseGate<F, D> {
+    pub fn new_from_config(config: &CircuitConfig) -> Self {
+        Self {
+            num_ops: Self::num_ops(config),
+            _phantom: PhantomData,
+        }
+    }
+
+    pub(crate) fn num_ops(config: &CircuitConfig) -> usize {
+        let wires_per_op = Self::wires_per_op();
+        (config.num_wires / wires_per_op).min(config.num_routed_wires / wires_per_op)
+    }
+
+    pub const NUM_BITS: usize = 8;
+
+    fn wires_per_op() -> usize {
+        4 + 2 * Self::NUM_BITS
+    }
+
+    pub fn wire_ith_input_x(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        Self::wires_per_op() * i
+    }
+    pub fn wire_ith_input_y(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        Self::wires_per_op() * i + 1
+    }
+
+    pub fn wire_ith_output_and(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        Self::wires_per_op() * i + 2
+    }
+    pub fn wire_ith_output_xor(&self, i: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        Self::wires_per_op() * i + 3
+    }
+
+    /// The `j`-th least significant bit of `x`.
+    pub fn wire_ith_x_jth_bit(&self, i: usize, j: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        debug_assert!(j < Self::NUM_BITS);
+        Self::wires_per_op() * i + 4 + j
+    }
+    /// The `j`-th least significant bit of `y`.
+    pub fn wire_ith_y_jth_bit(&self, i: usize, j: usize) -> usize {
+        debug_assert!(i < self.num_ops);
+        debug_assert!(j < Self::NUM_BITS);
+        Self::wires_per_op() * i + 4 + Self::NUM_BITS + j
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for U8BitwiseGate<F, D> {
+    fn id(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn export_circom_verification_code(&self) -> String {
+        todo!()
+    }
+    fn export_solidity_verification_code(&self) -> String {
+        todo!()
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        for i in 0..self.num_ops {
+            let input_x = vars.local_wires[self.wire_ith_input_x(i)];
+            let input_y = vars.local_wires[self.wire_ith_input_y(i)];
+            let output_and = vars.local_wires[self.wire_ith_output_and(i)];
+            let output_xor = vars.local_wires[self.wire_ith_output_xor(i)];
+
+            let mut combined_x = F::Extension::ZERO;
+            let mut combined_y = F::Extension::ZERO;
+            let mut combined_and = F::Extension::ZERO;
+            let mut combined_xor = F::Extension::ZERO;
+            let two = F::Extension::TWO;
+            for j in (0..Self::NUM_BITS).rev() {
+                let x_bit = vars.local_wires[self.wire_ith_x_jth_bit(i, j)];
+                let y_bit = vars.local_wires[self.wire_ith_y_jth_bit(i, j)];
+                constraints.push(x_bit * (x_bit - F::Extension::ONE));
+                constraints.push(y_bit * (y_bit - F::Extension::ONE));
+
+                let and_bit = x_bit * y_bit;
+                combined_x = two * combined_x + x_bit;
+                combined_y = two * combined_y + y_bit;
+                combined_and = two * combined_and + and_bit;
+                combined_xor = two * combined_xor + x_bit + y_bit - two * and_bit;
+            }
+            constraints.push(combined_x - input_x);
+            constraints.push(combined_y - input_y);
+            constraints.push(combined_and - output_and);
+            constraints.push(combined_xor - output_xor);
+        }
+
+        constraints
+    }
+
+    fn eval_unfiltered_base_one(
+        &self,
+        _vars: EvaluationVarsBase<F>,
+        _yield_constr: StridedConstraintConsumer<F>,
+    ) {
+        panic!("use eval_unfiltered_base_packed instead");
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        self.eval_unfiltered_base_batch_packed(vars_base)
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        let one = builder.one_extension();
+        let two = builder.constant_extension(F::Extension::TWO);
+        for i in 0..self.num_ops {
+            let input_x = vars.local_wires[self.wire_ith_input_x(i)];
+            let input_y = vars.local_wires[self.wire_ith_input_y(i)];
+            let output_and = vars.local_wires[self.wire_ith_output_and(i)];
+            let output_xor = vars.local_wires[self.wire_ith_output_xor(i)];
+
+            let mut combined_x = builder.zero_extension();
+            let mut combined_y = builder.zero_extension();
+            let mut combined_and = builder.zero_extension();
+            let mut combined_xor = builder.zero_extension();
+            for j in (0..Self::NUM_BITS).rev() {
+                let x_bit = vars.local_wires[self.wire_ith_x_jth_bit(i, j)];
+                let y_bit = vars.local_wires[self.wire_ith_y_jth_bit(i, j)];
+                constraints.push(builder.mul_sub_extension(x_bit, x_bit, x_bit));
+                constraints.push(builder.mul_sub_extension(y_bit, y_bit, y_bit));
+
+                let and_bit = builder.mul_extension(x_bit, y_bit);
+                combined_x = builder.mul_add_extension(two, combined_x, x_bit);
+                combined_y = builder.mul_add_extension(two, combined_y, y_bit);
+                combined_and = builder.mul_add_extension(two, combined_and, and_bit);
+                // xor_bit = x_bit + y_bit - 2 * and_bit
+                let xor_bit = builder.add_extension(x_bit, y_bit);
+                let xor_bit = builder.arithmetic_extension(-F::TWO, F::ONE, and_bit, one, xor_bit);
+                combined_xor = builder.mul_add_extension(two, combined_xor, xor_bit);
+            }
+            constraints.push(builder.sub_extension(combined_x, input_x));
+            constraints.push(builder.sub_extension(combined_y, input_y));
+            constraints.push(builder.sub_extension(combined_and, output_and));
+            constraints.push(builder.sub_extension(combined_xor, output_xor));
+        }
+
+        constraints
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<Box<dyn WitnessGenerator<F>>> {
+        (0..self.num_ops)
+            .map(|i| {
+                let g: Box<dyn WitnessGenerator<F>> = Box::new(
+                    U8BitwiseGenerator {
+                        gate: *self,
+                        row,
+                        i,
+                        _phantom: PhantomData,
+                    }
+                    .adapter(),
+                );
+                g
+            })
+            .collect()
+    }
+
+    fn num_wires(&self) -> usize {
+        self.num_ops * Self::wires_per_op()
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    // Bounded by the boolean checks and the products of bits.
+    fn degree(&self) -> usize {
+        2
+    }
+
+    // A boolean check for each bit, plus one each for `x`, `y`, `x & y` and `x ^ y`.
+    fn num_constraints(&self) -> usize {
+        self.num_ops * (2 * Self::NUM_BITS + 4)
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> PackedEvaluableBase<F, D>
+    for U8BitwiseGate<F, D>
+{
+    fn eval_unfiltered_base_packed<P: PackedField<Scalar = F>>(
+        &self,
+        vars: EvaluationVarsBasePacked<P>,
+        mut yield_constr: StridedConstraintConsumer<P>,
+    ) {
+        for i in 0..self.num_ops {
+            let input_x = vars.local_wires[self.wire_ith_input_x(i)];
+            let input_y = vars.local_wires[self.wire_ith_input_y(i)];
+            let output_and = vars.local_wires[self.wire_ith_output_and(i)];
+            let output_xor = vars.local_wires[self.wire_ith_output_xor(i)];
+
+            let mut combined_x = P::ZEROS;
+            let mut combined_y = P::ZEROS;
+            let mut combined_and = P::ZEROS;
+            let mut combined_xor = P::ZEROS;
+            let two = F::TWO;
+            for j in (0..Self::NUM_BITS).rev() {
+                let x_bit = vars.local_wires[self.wire_ith_x_jth_bit(i, j)];
+                let y_bit = vars.local_wires[self.wire_ith_y_jth_bit(i, j)];
+                yield_constr.one(x_bit * (x_bit - P::ONES));
+                yield_constr.one(y_bit * (y_bit - P::ONES));
+
+                let and_bit = x_bit * y_bit;
+                combined_x = combined_x * two + x_bit;
+                combined_y = combined_y * two + y_bit;
+                combined_and = combined_and * two + and_bit;
+                combined_xor = combined_xor * two + x_bit + y_bit - and_bit * two;
+            }
+            yield_constr.one(combined_x - input_x);
+            yield_constr.one(combined_y - input_y);
+            yield_constr.one(combined_and - output_and);
+            yield_constr.one(combined_xor - output_xor);
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct U8BitwiseGenerator<F: RichField + Extendable<D>, const D: usize> {
+    gate: U8BitwiseGate<F, D>,
+    row: usize,
+    i: usize,
+    _phantom: PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F> for U8BitwiseGenerator<F, D> {
+    fn dependencies(&self) -> Vec<Target> {
+        let local_target = |column| Target::wire(self.row, column);
+
+        vec![
+            local_target(self.gate.wire_ith_input_x(self.i)),
+            local_target(self.gate.wire_ith_input_y(self.i)),
+        ]
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let local_wire = |column| Wire {
+            row: self.row,
+            column,
+        };
+
+        let get_local_wire = |column| witness.get_wire(local_wire(column));
+
+        let input_x = get_local_wire(self.gate.wire_ith_input_x(self.i)).to_canonical_u64();
+        let input_y = get_local_wire(self.gate.wire_ith_input_y(self.i)).to_canonical_u64();
+        debug_assert!(input_x < 256 && input_y < 256, "Inputs are not bytes");
+
+        out_buffer.set_wire(
+            local_wire(self.gate.wire_ith_output_and(self.i)),
+            F::from_canonical_u64(input_x & input_y),
+        );
+        out_buffer.set_wire(
+            local_wire(self.gate.wire_ith_output_xor(self.i)),
+            F::from_canonical_u64(input_x ^ input_y),
+        );
+
+        for j in 0..U8BitwiseGate::<F, D>::NUM_BITS {
+            let x_bit_wire = local_wire(self.gate.wire_ith_x_jth_bit(self.i, j));
+            let y_bit_wire = local_wire(self.gate.wire_ith_y_jth_bit(self.i, j));
+            out_buffer.set_wire(x_bit_wire, F::from_canonical_u64((input_x >> j) & 1));
+            out_buffer.set_wire(y_bit_wire, F::from_canonical_u64((input_y >> j) & 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2::field::extension::quartic::QuarticExtension;
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::field::types::Sample;
+    use plonky2::gates::gate_testing::{test_eval_fns, test_low_degree};
+    use plonky2::hash::hash_types::HashOut;
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn low_degree() {
+        test_low_degree::<GoldilocksField, _, 4>(U8BitwiseGate::<GoldilocksField, 4> {
+            num_ops: 3,
+            _phantom: PhantomData,
+        })
+    }
+
+    #[test]
+    fn eval_fns() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        test_eval_fns::<F, C, _, D>(U8BitwiseGate::<GoldilocksField, D> {
+            num_ops: 3,
+            _phantom: PhantomData,
+        })
+    }
+
+    fn test_gate_constraint(inputs_x: Vec<u64>, inputs_y: Vec<u64>) {
+        type F = GoldilocksField;
+        type FF = QuarticExtension<GoldilocksField>;
+        const D: usize = 4;
+
+        fn get_wires(inputs_x: &[u64], inputs_y: &[u64]) -> Vec<FF> {
+            let mut v = Vec::new();
+            for (&x, &y) in inputs_x.iter().zip(inputs_y) {
+                // Bits are taken modulo 2^8, so larger inputs don't match their bits.
+                let x_bits = (0..8).map(|j| (x >> j) & 1);
+                let y_bits = (0..8).map(|j| (y >> j) & 1);
+                v.extend([x, y, x & y, x ^ y]);
+                v.extend(x_bits);
+                v.extend(y_bits);
+            }
+            v.into_iter()
+                .map(|x| F::from_canonical_u64(x).into())
+                .collect()
+        }
+
+        let gate = U8BitwiseGate::<F, D> {
+            num_ops: inputs_x.len(),
+            _phantom: PhantomData,
+        };
+
+        let vars = EvaluationVars {
+            local_constants: &[],
+            local_wires: &get_wires(&inputs_x, &inputs_y),
+            public_inputs_hash: &HashOut::rand(),
+        };
+
+        assert!(
+            gate.eval_unfiltered(vars).iter().all(|x| x.is_zero()),
+            "Gate constraints are not satisfied."
+        );
+    }
+
+    #[test]
+    fn test_gate_constraint_good() {
+        let mut rng = OsRng;
+        let inputs_x = (0..4).map(|_| rng.gen::<u8>() as u64).collect();
+        let inputs_y = (0..4).map(|_| rng.gen::<u8>() as u64).collect();
+
+        test_gate_constraint(inputs_x, inputs_y);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_gate_constraint_bad() {
+        let mut rng = OsRng;
+        let inputs_x = (0..4).map(|_| 256 + rng.gen::<u8>() as u64).collect();
+        let inputs_y = (0..4).map(|_| rng.gen::<u8>() as u64).collect();
+
+        test_gate_constraint(inputs_x, inputs_y);
+    }
+}
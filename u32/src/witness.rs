@@ -3,6 +3,7 @@ use plonky2::iop::generator::GeneratedValues;
 use plonky2::iop::witness::{Witness, WitnessWrite};
 
 use crate::gadgets::arithmetic_u32::U32Target;
+use crate::gadgets::bytes::U8Target;
 
 pub trait WitnessU32<F: PrimeField64>: Witness<F> {
     fn set_u32_target(&mut self, target: U32Target, value: u32);
@@ -31,3 +32,18 @@ impl<F: Field> GeneratedValuesU32<F> for GeneratedValues<F> {
         self.set_target(target.0, F::from_canonical_u32(value))
     }
 }
+
+pub trait WitnessU8<F: PrimeField64>: Witness<F> {
+    fn set_u8_target(&mut self, target: U8Target, value: u8);
+    fn get_u8_target(&self, target: U8Target) -> u8;
+}
+
+impl<T: Witness<F>, F: PrimeField64> WitnessU8<F> for T {
+    fn set_u8_target(&mut self, target: U8Target, value: u8) {
+        self.set_target(target.0, F::from_canonical_u8(value));
+    }
+
+    fn get_u8_target(&self, target: U8Target) -> u8 {
+        self.get_target(target.0).to_canonical_u64() as u8
+    }
+}